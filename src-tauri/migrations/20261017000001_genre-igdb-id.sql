@@ -0,0 +1,3 @@
+alter table genres add column igdb_id integer;
+
+create unique index unique_genre_igdb_id on genres(igdb_id);
@@ -0,0 +1 @@
+alter table covers add column upscaled_path text;
@@ -0,0 +1,2 @@
+alter table games add column playtime_forever integer;
+alter table games add column playtime_2weeks integer;
@@ -0,0 +1,7 @@
+alter table games add column game_type integer;
+alter table games add column excluded integer not null default 0;
+
+create table if not exists exclusion_rules (
+    id integer primary key autoincrement not null,
+    rule text not null
+);
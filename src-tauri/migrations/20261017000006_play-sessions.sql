@@ -0,0 +1,9 @@
+create table if not exists play_sessions (
+    id integer primary key autoincrement not null,
+    game_id integer not null,
+    started_at integer not null,
+    ended_at integer,
+    duration integer,
+
+    foreign key (game_id) references games(id) on delete cascade on update cascade
+);
@@ -0,0 +1,5 @@
+create table if not exists hooks (
+    id integer primary key autoincrement not null,
+    event text not null,
+    command text not null
+);
@@ -0,0 +1 @@
+alter table games add column last_played_at integer;
@@ -0,0 +1,15 @@
+create table if not exists achievements (
+    id integer primary key autoincrement not null,
+    game_id integer not null,
+    api_name text not null,
+    display_name text not null,
+    description text,
+    icon text,
+    icon_gray text,
+    hidden boolean not null default 0,
+    achieved boolean not null default 0,
+    unlocked_at integer,
+
+    unique (game_id, api_name),
+    foreign key (game_id) references games(id) on delete cascade on update cascade
+);
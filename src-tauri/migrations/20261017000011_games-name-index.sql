@@ -0,0 +1 @@
+create index if not exists games_name_id on games(name, id);
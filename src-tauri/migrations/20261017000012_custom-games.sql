@@ -0,0 +1,14 @@
+create table if not exists custom_games (
+    game_id integer primary key not null,
+    executable text not null,
+
+    foreign key (game_id) references games(id) on delete cascade on update cascade
+);
+
+create table if not exists wine_configs (
+    game_id integer primary key not null,
+    runtime text not null,
+    prefix text not null,
+
+    foreign key (game_id) references games(id) on delete cascade on update cascade
+);
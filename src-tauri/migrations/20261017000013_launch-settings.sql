@@ -0,0 +1,6 @@
+create table if not exists launch_settings (
+    game_id integer primary key not null,
+    controller_profile text,
+
+    foreign key (game_id) references games(id) on delete cascade on update cascade
+);
@@ -0,0 +1,16 @@
+create table if not exists subscriptions (
+    id integer primary key autoincrement not null,
+    name text not null,
+    monthly_cost real not null,
+    started_at integer not null,
+    ended_at integer
+);
+
+create table if not exists subscription_games (
+    subscription_id integer not null,
+    game_id integer not null,
+
+    primary key (subscription_id, game_id),
+    foreign key (subscription_id) references subscriptions(id) on delete cascade on update cascade,
+    foreign key (game_id) references games(id) on delete cascade on update cascade
+);
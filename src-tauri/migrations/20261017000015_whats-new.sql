@@ -0,0 +1,14 @@
+create table if not exists whats_new (
+    id integer primary key autoincrement not null,
+    version integer not null,
+    title text not null,
+    body text not null,
+    seen boolean not null default 0
+);
+
+insert into whats_new (version, title, body) values
+    (20261017000005, 'Exclusion rules', 'Hide bundles, tools, or games by name pattern with rules applied on every refresh.'),
+    (20261017000006, 'Play sessions', 'Games launched from Rocade now record how long you played.'),
+    (20261017000010, 'Achievements', 'The game page shows your Steam achievements and when you unlocked them.'),
+    (20261017000012, 'Custom games', 'Add non-Steam games and run Windows games through Wine or Proton. Custom games are kept across refreshes.'),
+    (20261017000014, 'Subscriptions', 'Record your subscriptions and see how many hours you get out of them each month.');
@@ -0,0 +1,13 @@
+create table if not exists news (
+    gid text primary key not null,
+    store_id text not null,
+    title text not null,
+    url text not null,
+    author text,
+    contents text not null,
+    feed_label text,
+    date integer not null,
+    fetched_at integer not null
+);
+
+create index if not exists news_store_id on news(store_id, date);
@@ -0,0 +1,11 @@
+alter table games add column metacritic_score integer;
+alter table games add column header_image text;
+
+create table if not exists store_categories (
+    game_id integer not null,
+    category_id integer not null,
+    name text not null,
+
+    primary key (game_id, category_id),
+    foreign key (game_id) references games(id) on delete cascade on update cascade
+);
@@ -0,0 +1,17 @@
+create table if not exists tracked_prices (
+    store_id text primary key not null,
+    name text not null,
+    kind text not null,
+    parent_store_id text,
+    currency text,
+    initial_price integer,
+    final_price integer,
+    discount_percent integer not null default 0,
+    historical_low integer,
+    updated_at integer
+);
+
+create table if not exists dlc_lookups (
+    store_id text primary key not null,
+    checked_at integer not null
+);
@@ -0,0 +1,8 @@
+create table if not exists completion_times (
+    name text primary key not null,
+    hltb_id integer,
+    main_hours real,
+    main_extra_hours real,
+    completionist_hours real,
+    fetched_at integer not null
+);
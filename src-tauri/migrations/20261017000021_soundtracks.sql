@@ -0,0 +1,7 @@
+create table if not exists soundtracks (
+    store_id text primary key not null,
+    parent_store_id text not null,
+    name text not null
+);
+
+create index if not exists soundtracks_parent_store_id on soundtracks(parent_store_id);
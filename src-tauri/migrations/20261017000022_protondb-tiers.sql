@@ -0,0 +1,5 @@
+create table if not exists protondb_tiers (
+    store_id text primary key not null,
+    tier text,
+    fetched_at integer not null
+);
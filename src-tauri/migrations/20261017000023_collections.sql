@@ -0,0 +1,13 @@
+create table if not exists collections (
+    id integer primary key autoincrement not null,
+    name text not null unique
+);
+
+create table if not exists collection_games (
+    collection_id integer not null,
+    game_id integer not null,
+
+    primary key (collection_id, game_id),
+    foreign key (collection_id) references collections(id) on delete cascade on update cascade,
+    foreign key (game_id) references games(id) on delete cascade on update cascade
+);
@@ -0,0 +1,10 @@
+alter table games add column storyline text;
+
+create table if not exists summary_translations (
+    store_id text not null,
+    language text not null,
+    summary text,
+    fetched_at integer not null,
+
+    primary key (store_id, language)
+);
@@ -0,0 +1 @@
+alter table games add column is_favorite boolean not null default 0;
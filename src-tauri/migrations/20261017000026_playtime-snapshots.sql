@@ -0,0 +1,7 @@
+create table if not exists playtime_snapshots (
+    store_id text not null,
+    taken_at integer not null,
+    playtime_forever integer not null,
+
+    primary key (store_id, taken_at)
+);
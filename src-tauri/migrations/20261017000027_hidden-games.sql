@@ -0,0 +1 @@
+alter table games add column hidden boolean not null default 0;
@@ -0,0 +1 @@
+alter table games add column user_rating integer check (user_rating between 0 and 10);
@@ -0,0 +1 @@
+alter table games add column status text;
@@ -0,0 +1,7 @@
+create table if not exists metadata_overrides (
+    store_id text primary key not null,
+    name text,
+    summary text,
+    release_date integer,
+    cover_id text
+);
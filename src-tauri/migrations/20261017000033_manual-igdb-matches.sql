@@ -0,0 +1,4 @@
+create table if not exists manual_igdb_matches (
+    store_id text primary key not null,
+    igdb_id integer not null
+);
@@ -0,0 +1,5 @@
+create table if not exists igdb_responses (
+    request text primary key not null,
+    body text not null,
+    fetched_at integer not null
+);
@@ -0,0 +1,7 @@
+create table if not exists screenshots (
+    id integer primary key autoincrement not null,
+    game_id integer not null,
+    screenshot_id text not null,
+    local_path text,
+    foreign key (game_id) references games(id) on delete cascade on update cascade
+);
@@ -0,0 +1,7 @@
+create table if not exists videos (
+    id integer primary key autoincrement not null,
+    game_id integer not null,
+    video_id text not null,
+    name text,
+    foreign key (game_id) references games(id) on delete cascade on update cascade
+);
@@ -0,0 +1,2 @@
+alter table games add column aggregated_rating real;
+alter table games add column rating_count integer;
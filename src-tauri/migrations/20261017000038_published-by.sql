@@ -0,0 +1,7 @@
+create table if not exists published_by (
+    id integer primary key autoincrement not null,
+    game_id integer not null,
+    company_id integer not null,
+    foreign key (game_id) references games(id) on delete cascade on update cascade,
+    foreign key (company_id) references companies(id) on delete cascade on update cascade
+);
@@ -0,0 +1,16 @@
+create table if not exists franchises (
+    id integer primary key autoincrement not null,
+    igdb_id integer not null,
+    -- IGDB `franchise` or `collection` (series); their IDs overlap
+    kind text not null,
+    name text not null,
+    unique (igdb_id, kind)
+);
+
+create table if not exists game_franchises (
+    game_id integer not null,
+    franchise_id integer not null,
+    primary key (game_id, franchise_id),
+    foreign key (game_id) references games(id) on delete cascade on update cascade,
+    foreign key (franchise_id) references franchises(id) on delete cascade on update cascade
+);
@@ -0,0 +1,7 @@
+create table if not exists dlcs (
+    store_id text primary key not null,
+    parent_store_id text not null,
+    name text not null
+);
+
+create index if not exists dlcs_parent_store_id on dlcs(parent_store_id);
@@ -0,0 +1,6 @@
+create table if not exists similar_games (
+    game_id integer not null,
+    igdb_id integer not null,
+    primary key (game_id, igdb_id),
+    foreign key (game_id) references games(id) on delete cascade on update cascade
+);
@@ -0,0 +1,13 @@
+create table if not exists game_modes (
+    game_id integer not null,
+    name text not null,
+    primary key (game_id, name),
+    foreign key (game_id) references games(id) on delete cascade on update cascade
+);
+
+create table if not exists player_perspectives (
+    game_id integer not null,
+    name text not null,
+    primary key (game_id, name),
+    foreign key (game_id) references games(id) on delete cascade on update cascade
+);
@@ -0,0 +1,10 @@
+create table if not exists age_ratings (
+    game_id integer not null,
+    -- Rating board: `ESRB` or `PEGI`
+    organization text not null,
+    rating text not null,
+    -- Minimum player age the rating stands for, null for pending ratings
+    minimum_age integer,
+    primary key (game_id, organization),
+    foreign key (game_id) references games(id) on delete cascade on update cascade
+);
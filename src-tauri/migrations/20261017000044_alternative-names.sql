@@ -0,0 +1,6 @@
+create table if not exists alternative_names (
+    game_id integer not null,
+    name text not null,
+    primary key (game_id, name),
+    foreign key (game_id) references games(id) on delete cascade on update cascade
+);
@@ -0,0 +1,6 @@
+create table if not exists steamgriddb_artworks (
+    store_id text primary key not null,
+    grid_path text,
+    hero_path text,
+    logo_path text
+);
@@ -0,0 +1,3 @@
+alter table covers add column source text not null default 'igdb';
+alter table artworks add column source text not null default 'igdb';
+alter table screenshots add column source text not null default 'igdb';
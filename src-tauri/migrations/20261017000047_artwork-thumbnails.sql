@@ -0,0 +1 @@
+alter table artworks add column thumbnail_path text;
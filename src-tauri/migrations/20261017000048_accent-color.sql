@@ -0,0 +1 @@
+alter table games add column accent_color text;
@@ -0,0 +1,5 @@
+create table if not exists asset_validators (
+    url text primary key not null,
+    etag text,
+    last_modified text
+);
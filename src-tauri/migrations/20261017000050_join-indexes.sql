@@ -0,0 +1,6 @@
+create index if not exists games_store_game_id on games_store(game_id);
+create index if not exists artworks_game_id on artworks(game_id);
+create index if not exists covers_game_id on covers(game_id);
+create index if not exists belongs_to_game_id_genre_id on belongs_to(game_id, genre_id);
+create index if not exists developed_by_game_id_studio_id on developed_by(game_id, studio_id);
+create index if not exists games_name_nocase on games(name collate nocase);
@@ -0,0 +1,4 @@
+create table if not exists refresh_reports (
+    finished_at integer not null,
+    report text not null
+);
@@ -0,0 +1,8 @@
+create table if not exists history (
+    id integer primary key autoincrement,
+    event text not null,
+    game_name text not null,
+    store_id text,
+    detail text,
+    created_at integer not null default (strftime('%s', 'now'))
+);
@@ -0,0 +1,8 @@
+-- Left null for games already in the library, whose purchase date is unknown
+alter table games add column added_at integer;
+
+create trigger if not exists games_added_at after insert on games
+when new.added_at is null
+begin
+    update games set added_at = strftime('%s', 'now') where id = new.id;
+end;
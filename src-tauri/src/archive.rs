@@ -0,0 +1,291 @@
+//! Full backups of the library: the database and the cached images packed
+//! into one zip archive.
+//!
+//! Restoring an archive on another machine spares downloading the images
+//! again. The database stores absolute image paths, so the archive records
+//! the assets directory it was made from, and the paths of the restored
+//! database are rewritten to the assets directory of the restoring machine.
+
+use std::{
+    fs::{self, File},
+    io::{self, BufReader, BufWriter},
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+use sqlx::{sqlite::SqliteConnectOptions, SqlitePool};
+use zip::{result::ZipError, write::SimpleFileOptions, CompressionMethod, ZipArchive, ZipWriter};
+
+use crate::db::DatabaseState;
+
+/// Version written in the manifest of created archives. Archives of other
+/// versions are rejected on restore.
+const ARCHIVE_VERSION: u32 = 1;
+
+/// Name of the manifest entry.
+const MANIFEST_ENTRY: &str = "manifest.json";
+
+/// Name of the database entry.
+const DATABASE_ENTRY: &str = "rocade.db";
+
+/// Directory of the image entries.
+const ASSETS_ENTRY: &str = "assets";
+
+/// Directories of the assets directory left out of archives: superseded
+/// images kept for revalidation, see `AssetManager::clear_all`.
+const EXCLUDED_DIRS: [&str; 1] = ["previous"];
+
+/// Image path columns, rewritten on restore.
+const PATH_COLUMNS: [(&str, &str); 8] = [
+    ("covers", "local_path"),
+    ("covers", "upscaled_path"),
+    ("artworks", "local_path"),
+    ("artworks", "thumbnail_path"),
+    ("screenshots", "local_path"),
+    ("steamgriddb_artworks", "grid_path"),
+    ("steamgriddb_artworks", "hero_path"),
+    ("steamgriddb_artworks", "logo_path"),
+];
+
+/// Errors that can occur while creating or restoring a full backup.
+#[derive(Debug, thiserror::Error)]
+pub enum ArchiveError {
+    /// The archive or a file packed in it could not be read or written.
+    #[error("io error: {0}")]
+    Io(#[from] io::Error),
+
+    /// The archive is not a valid zip file.
+    #[error("invalid archive: {0}")]
+    Zip(#[from] ZipError),
+
+    /// The database could not be copied or its paths rewritten.
+    #[error("database error: {0}")]
+    Database(#[from] sqlx::Error),
+
+    /// The manifest could not be written or read.
+    #[error("invalid manifest: {0}")]
+    Manifest(#[from] serde_json::Error),
+
+    /// The archive was written by an incompatible version of Rocade.
+    #[error("unsupported archive version {0}")]
+    UnsupportedVersion(u32),
+
+    /// The database is in memory, so there is nothing to back up or
+    /// restore.
+    #[error("the database is not stored on disk")]
+    InMemory,
+}
+
+/// Describes the content of an archive.
+#[derive(Serialize, Deserialize)]
+struct Manifest {
+    version: u32,
+    /// Assets directory the archive was made from, prefix of the image
+    /// paths stored in its database.
+    assets_dir: String,
+}
+
+/// Packs the database and the images of `assets_dir` into a zip archive at
+/// `path`. Partial downloads and superseded images are left out.
+pub async fn create(
+    path: &Path,
+    db_state: &DatabaseState,
+    assets_dir: &Path,
+) -> Result<(), ArchiveError> {
+    let snapshot = db_state
+        .pending_restore_path()
+        .ok_or(ArchiveError::InMemory)?
+        .with_extension("snapshot");
+    db_state.copy_to(&snapshot).await?;
+
+    let manifest = Manifest {
+        version: ARCHIVE_VERSION,
+        assets_dir: assets_dir.to_string_lossy().into_owned(),
+    };
+    let path = path.to_path_buf();
+    let assets_dir = assets_dir.to_path_buf();
+    let database = snapshot.clone();
+
+    let result = tokio::task::spawn_blocking(move || {
+        write_archive(&path, &manifest, &database, &assets_dir)
+    })
+    .await
+    .unwrap_or_else(|e| Err(io::Error::other(e).into()));
+
+    let _ = tokio::fs::remove_file(&snapshot).await;
+
+    result
+}
+
+/// Restores the zip archive at `path`.
+///
+/// The images replace the content of `assets_dir` immediately. The database
+/// replaces the current one on the next start, see
+/// [`DatabaseState::restore_backup`].
+pub async fn restore(
+    path: &Path,
+    db_state: &DatabaseState,
+    assets_dir: &Path,
+) -> Result<(), ArchiveError> {
+    let pending_restore = db_state
+        .pending_restore_path()
+        .ok_or(ArchiveError::InMemory)?;
+    let staged = pending_restore.with_extension("staged");
+
+    let manifest = {
+        let path = path.to_path_buf();
+        let assets_dir = assets_dir.to_path_buf();
+        let staged = staged.clone();
+
+        tokio::task::spawn_blocking(move || extract_archive(&path, &staged, &assets_dir))
+            .await
+            .map_err(io::Error::other)??
+    };
+
+    rewrite_paths(&staged, &manifest.assets_dir, &assets_dir.to_string_lossy()).await?;
+
+    // Only a fully restored database is picked up on the next start
+    tokio::fs::rename(&staged, &pending_restore).await?;
+
+    Ok(())
+}
+
+/// Writes the archive at `path`. Images are stored as is, being compressed
+/// already; the database is deflated.
+fn write_archive(
+    path: &Path,
+    manifest: &Manifest,
+    database: &Path,
+    assets_dir: &Path,
+) -> Result<(), ArchiveError> {
+    let mut zip = ZipWriter::new(BufWriter::new(File::create(path)?));
+    let stored = SimpleFileOptions::default()
+        .compression_method(CompressionMethod::Stored)
+        .large_file(true);
+    let deflated = SimpleFileOptions::default()
+        .compression_method(CompressionMethod::Deflated)
+        .large_file(true);
+
+    zip.start_file(MANIFEST_ENTRY, deflated)?;
+    serde_json::to_writer(&mut zip, manifest)?;
+
+    zip.start_file(DATABASE_ENTRY, deflated)?;
+    io::copy(&mut File::open(database)?, &mut zip)?;
+
+    for file in list_assets(assets_dir)? {
+        let Ok(relative) = file.strip_prefix(assets_dir) else {
+            continue;
+        };
+        let components: Vec<_> = relative
+            .components()
+            .map(|component| component.as_os_str().to_string_lossy())
+            .collect();
+
+        zip.start_file(format!("{}/{}", ASSETS_ENTRY, components.join("/")), stored)?;
+        io::copy(&mut File::open(&file)?, &mut zip)?;
+    }
+
+    zip.finish()?;
+
+    Ok(())
+}
+
+/// Returns the files of `assets_dir` to archive, recursively.
+fn list_assets(assets_dir: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    let mut dirs = vec![assets_dir.to_path_buf()];
+
+    while let Some(dir) = dirs.pop() {
+        for entry in fs::read_dir(&dir)? {
+            let path = entry?.path();
+            let name = path
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default();
+
+            if path.is_dir() {
+                if dir != assets_dir || !EXCLUDED_DIRS.contains(&name.as_str()) {
+                    dirs.push(path);
+                }
+            } else if !name.contains(".tmp") {
+                files.push(path);
+            }
+        }
+    }
+
+    Ok(files)
+}
+
+/// Extracts the database of the archive at `path` to `database`, and its
+/// images to `assets_dir`, whose previous content is removed. Returns the
+/// manifest of the archive.
+fn extract_archive(
+    path: &Path,
+    database: &Path,
+    assets_dir: &Path,
+) -> Result<Manifest, ArchiveError> {
+    let mut zip = ZipArchive::new(BufReader::new(File::open(path)?))?;
+
+    let manifest: Manifest = serde_json::from_reader(zip.by_name(MANIFEST_ENTRY)?)?;
+    if manifest.version != ARCHIVE_VERSION {
+        return Err(ArchiveError::UnsupportedVersion(manifest.version));
+    }
+
+    io::copy(
+        &mut zip.by_name(DATABASE_ENTRY)?,
+        &mut File::create(database)?,
+    )?;
+
+    if assets_dir.exists() {
+        fs::remove_dir_all(assets_dir)?;
+    }
+    fs::create_dir_all(assets_dir)?;
+
+    for index in 0..zip.len() {
+        let mut entry = zip.by_index(index)?;
+
+        // Entries leading outside of the assets directory are ignored
+        let Some(relative) = entry
+            .enclosed_name()
+            .and_then(|name| name.strip_prefix(ASSETS_ENTRY).ok().map(Path::to_path_buf))
+        else {
+            continue;
+        };
+        if entry.is_dir() || relative.as_os_str().is_empty() {
+            continue;
+        }
+
+        let destination = assets_dir.join(relative);
+        if let Some(parent) = destination.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        io::copy(&mut entry, &mut File::create(destination)?)?;
+    }
+
+    Ok(manifest)
+}
+
+/// Replaces the `from` prefix of the image paths of the database at
+/// `database` with `to`.
+async fn rewrite_paths(database: &Path, from: &str, to: &str) -> Result<(), sqlx::Error> {
+    if from == to {
+        return Ok(());
+    }
+
+    let pool = SqlitePool::connect_with(SqliteConnectOptions::new().filename(database)).await?;
+
+    for (table, column) in PATH_COLUMNS {
+        sqlx::query(&format!(
+            "update {table} set {column} = ?2 || substr({column}, length(?1) + 1)
+             where substr({column}, 1, length(?1)) = ?1"
+        ))
+        .bind(from)
+        .bind(to)
+        .execute(&pool)
+        .await?;
+    }
+
+    pool.close().await;
+
+    Ok(())
+}
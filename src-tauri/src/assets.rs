@@ -3,13 +3,73 @@
 //! Downloads and stores game cover art and screenshots from IGDB CDN
 //! to the local filesystem for offline access.
 
-use std::path::PathBuf;
-use std::time::Duration;
+use std::collections::HashMap;
+use std::future::Future;
+use std::io::Cursor;
+use std::path::{Component, Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicU8, AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant, UNIX_EPOCH};
 
 use futures::stream::{self, StreamExt};
-use tauri_plugin_http::reqwest::{self, Client};
+use image::{imageops::FilterType, DynamicImage, ImageFormat};
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+use tauri_plugin_http::reqwest::{
+    self,
+    header::{HeaderName, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED, RANGE},
+    Client, Response, StatusCode,
+};
 use tokio::fs;
 use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+
+use crate::{
+    db::asset_validator::{AssetValidatorRepository, AssetValidators},
+    retry::RetryPolicy,
+};
+
+/// Number of images downloaded in parallel when
+/// [`Setting::DownloadConcurrency`](crate::db::settings::Setting::DownloadConcurrency)
+/// is unset.
+pub const DEFAULT_DOWNLOAD_CONCURRENCY: usize = 5;
+
+/// Scale factor applied to covers by [`CoverUpscaling::Lanczos`].
+const UPSCALE_FACTOR: u32 = 4;
+
+/// Bounds of artwork thumbnails, in pixels. The aspect ratio is kept.
+const THUMBNAIL_WIDTH: u32 = 320;
+const THUMBNAIL_HEIGHT: u32 = 180;
+
+/// Side of the image covers are shrunk to before extracting their accent
+/// color, in pixels.
+const ACCENT_SAMPLE_SIZE: u32 = 32;
+
+/// Directories of the images downloaded from IGDB, replaced on refresh.
+const IGDB_DIRS: [&str; 5] = [
+    "covers",
+    "covers_upscaled",
+    "artworks",
+    "artwork_thumbnails",
+    "screenshots",
+];
+
+/// Directory the downloaded IGDB images are moved to on refresh, until the
+/// next one. Images requested again are moved back if unchanged.
+const PREVIOUS_DIR: &str = "previous";
+
+/// Directories of [`IGDB_DIRS`] moved to [`PREVIOUS_DIR`] on refresh. Other
+/// images are cheap to create again.
+const PREVIOUS_DIRS: [&str; 4] = ["covers", "artworks", "artwork_thumbnails", "screenshots"];
+
+/// Directory of the artwork chosen from SteamGridDB, kept across refreshes.
+const STEAMGRIDDB_DIR: &str = "steamgriddb";
+
+/// Directory of the images added by the user, kept across refreshes.
+const CUSTOM_DIR: &str = "custom";
+
+/// Extensions of the image files the user can add.
+const CUSTOM_IMAGE_EXTENSIONS: [&str; 4] = ["jpg", "jpeg", "png", "webp"];
 
 /// Errors that can occur during asset management operations.
 #[derive(Debug, thiserror::Error)]
@@ -23,14 +83,188 @@ pub enum AssetError {
     Filesystem(#[from] std::io::Error),
 
     /// Failed to download image after all retry attempts.
-    #[error("failed to download {0} after 3 attempts")]
-    DownloadFailed(String),
+    #[error("failed to download {0} after {1} attempts")]
+    DownloadFailed(String, u32),
+
+    /// An image could not be decoded or encoded.
+    #[error("image processing failed: {0}")]
+    Image(#[from] image::ImageError),
+
+    /// Upscaling a cover failed.
+    #[error("upscaling failed: {0}")]
+    Upscale(String),
+
+    /// Creating an artwork thumbnail failed.
+    #[error("thumbnail creation failed: {0}")]
+    Thumbnail(String),
+
+    /// Re-encoding a downloaded image to WebP failed.
+    #[error("webp encoding failed: {0}")]
+    WebP(String),
+
+    /// Extracting the accent color of a cover failed.
+    #[error("color extraction failed: {0}")]
+    AccentColor(String),
+
+    /// The download was not started because the app is shutting down.
+    #[error("download cancelled")]
+    Cancelled,
+
+    /// A file added by the user is not a supported image.
+    #[error("unsupported image file: {0}")]
+    UnsupportedImage(String),
+}
+
+/// How low-resolution covers are upscaled for large-format grid displays.
+#[derive(Debug, Clone)]
+pub enum CoverUpscaling {
+    /// Resize locally with a Lanczos3 filter.
+    Lanczos,
+    /// Run an external tool. The command line is split into arguments like a
+    /// POSIX shell does, so arguments with spaces must be quoted. `{input}`
+    /// and `{output}` in the arguments are replaced with the source and
+    /// destination file paths, which may contain spaces.
+    External(String),
+}
+
+impl CoverUpscaling {
+    /// Builds the upscaling option from its stored settings.
+    ///
+    /// `method` is `"lanczos"` or `"external"`; any other value disables
+    /// upscaling. `"external"` also requires a `command`.
+    pub fn from_settings(method: Option<&str>, command: Option<String>) -> Option<Self> {
+        match method {
+            Some("lanczos") => Some(CoverUpscaling::Lanczos),
+            Some("external") => command.map(CoverUpscaling::External),
+            _ => None,
+        }
+    }
+}
+
+/// Disk usage of one category of cached images.
+#[derive(Serialize, Debug)]
+pub struct CacheCategoryStats {
+    /// Directory of the category, e.g. `covers` or `steamgriddb`.
+    pub category: String,
+    pub file_count: u64,
+    /// Total size of the files, in bytes.
+    pub size: u64,
+    /// Unix timestamp of the least recently written file, `None` when the
+    /// category is empty.
+    pub oldest: Option<i64>,
+    /// Unix timestamp of the most recently written file.
+    pub newest: Option<i64>,
+}
+
+/// Disk usage of the cached images, as returned by
+/// [`AssetManager::get_cache_stats`].
+#[derive(Serialize, Debug)]
+pub struct CacheStats {
+    /// Total size of all categories, in bytes.
+    pub total_size: u64,
+    pub categories: Vec<CacheCategoryStats>,
+}
+
+/// Cached images found corrupt, as returned by
+/// [`AssetManager::remove_corrupt_files`].
+#[derive(Debug)]
+pub struct CorruptFiles {
+    /// Number of files checked.
+    pub checked: usize,
+    /// Paths of the corrupt files, which were removed.
+    pub paths: Vec<String>,
+}
+
+/// Name of the event emitted as each image of a batch download is done.
+pub const ASSET_PROGRESS_EVENT: &str = "asset://progress";
+
+/// Payload of the [`ASSET_PROGRESS_EVENT`].
+#[derive(Serialize, Clone)]
+pub struct AssetProgress {
+    /// Category of the batch: `covers`, `artworks`, or `screenshots`.
+    pub category: &'static str,
+    /// Number of images of the batch done, including failed ones.
+    pub completed: usize,
+    /// Number of images in the batch.
+    pub total: usize,
+    /// IGDB image ID of the image just done.
+    pub image_id: String,
+    /// Number of images of the batch that failed so far.
+    pub failed: usize,
+}
+
+/// Spaces the bytes received by all downloads to a shared rate limit.
+///
+/// Each chunk reserves the time it takes at the limit, after the chunks
+/// reserved before it; its download waits until then. Idle time is not
+/// saved up, so downloads starting after a pause are not sent in a burst.
+#[derive(Debug)]
+struct Throttle {
+    /// Bytes per second, `0` for no limit.
+    bytes_per_second: AtomicU64,
+    /// End of the last reserved slot.
+    next: Mutex<Instant>,
+}
+
+impl Throttle {
+    fn new() -> Self {
+        Throttle {
+            bytes_per_second: AtomicU64::new(0),
+            next: Mutex::new(Instant::now()),
+        }
+    }
+
+    /// Waits until `bytes` more bytes fit in the rate limit.
+    async fn consume(&self, bytes: usize) {
+        let bytes_per_second = self.bytes_per_second.load(Ordering::Relaxed);
+
+        if bytes_per_second == 0 {
+            return;
+        }
+
+        let wait = {
+            let mut next = self.next.lock().unwrap_or_else(|e| e.into_inner());
+            let now = Instant::now();
+            *next =
+                (*next).max(now) + Duration::from_secs_f64(bytes as f64 / bytes_per_second as f64);
+            *next - now
+        };
+
+        tokio::time::sleep(wait).await;
+    }
+}
+
+/// Outcome of a download attempt.
+enum Fetched {
+    /// The image was downloaded, with the validators of the response.
+    Downloaded(AssetValidators),
+    /// The server answered a conditional request with `304 Not Modified`.
+    NotModified,
 }
 
 /// Manages downloading and storing game images locally.
 pub struct AssetManager {
     assets_dir: PathBuf,
     client: Client,
+    /// Number of images downloaded in parallel.
+    concurrency: usize,
+    retry_policy: RetryPolicy,
+    /// When set, batch downloads are skipped (low-graphics mode).
+    text_only: AtomicBool,
+    /// Set on shutdown; no download is started afterwards.
+    cancelled: AtomicBool,
+    /// WebP quality covers, artworks, and screenshots are re-encoded at, `0`
+    /// to keep them as JPEG.
+    webp_quality: AtomicU8,
+    /// Validators of the downloaded IGDB images, `None` to never send
+    /// conditional requests.
+    validators: Option<AssetValidatorRepository>,
+    throttle: Throttle,
+    /// Handle progress events are emitted to, `None` to emit none.
+    app: Option<AppHandle>,
+    /// Number of IGDB images downloaded since the last
+    /// [`AssetManager::take_download_count`].
+    downloaded: AtomicUsize,
 }
 
 impl AssetManager {
@@ -39,27 +273,113 @@ impl AssetManager {
     /// # Arguments
     ///
     /// * `app_dir` — Application data directory path.
+    /// * `concurrency` — Number of images downloaded in parallel, at least 1.
+    /// * `retry_policy` — Policy applied to failed downloads.
     ///
     /// # Returns
     ///
     /// Returns `Result<Self, AssetError>` with the initialized manager.
-    pub async fn new(app_dir: PathBuf) -> Result<Self, AssetError> {
+    pub async fn new(
+        app_dir: PathBuf,
+        concurrency: usize,
+        retry_policy: RetryPolicy,
+    ) -> Result<Self, AssetError> {
         let assets_dir = app_dir.join("assets");
 
         // Create assets directories if they don't exist
-        fs::create_dir_all(assets_dir.join("covers")).await?;
-        fs::create_dir_all(assets_dir.join("artworks")).await?;
+        for dir in IGDB_DIRS.into_iter().chain([STEAMGRIDDB_DIR, CUSTOM_DIR]) {
+            fs::create_dir_all(assets_dir.join(dir)).await?;
+        }
 
         Ok(AssetManager {
             assets_dir,
             client: Client::new(),
+            concurrency: concurrency.max(1),
+            retry_policy,
+            text_only: AtomicBool::new(false),
+            cancelled: AtomicBool::new(false),
+            webp_quality: AtomicU8::new(0),
+            validators: None,
+            throttle: Throttle::new(),
+            app: None,
+            downloaded: AtomicUsize::new(0),
         })
     }
 
+    /// Returns the number of IGDB images downloaded since the last call,
+    /// not counting the copies kept from the previous refresh.
+    pub fn take_download_count(&self) -> usize {
+        self.downloaded.swap(0, Ordering::Relaxed)
+    }
+
+    /// Sets the handle download progress events are emitted to.
+    pub fn set_app_handle(&mut self, app: AppHandle) {
+        self.app = Some(app);
+    }
+
+    /// Sets where the validators of downloaded IGDB images are stored.
+    pub fn set_validator_repository(&mut self, repository: AssetValidatorRepository) {
+        self.validators = Some(repository);
+    }
+
+    /// Enables or disables low-graphics mode, in which no image is
+    /// downloaded.
+    pub fn set_text_only(&self, text_only: bool) {
+        self.text_only.store(text_only, Ordering::Relaxed);
+    }
+
+    /// Sets the quality, from 1 to 100, at which covers, artworks, and
+    /// screenshots are re-encoded to WebP once downloaded. `None` or an
+    /// out-of-range quality keeps them as JPEG.
+    pub fn set_webp_quality(&self, quality: Option<u8>) {
+        let quality = quality.filter(|quality| (1..=100).contains(quality));
+        self.webp_quality
+            .store(quality.unwrap_or(0), Ordering::Relaxed);
+    }
+
+    /// Limits the download rate of all images together, in KiB per second.
+    /// `None` or `0` removes the limit. Applies to downloads in progress.
+    pub fn set_download_rate_limit(&self, kib_per_second: Option<u64>) {
+        self.throttle.bytes_per_second.store(
+            kib_per_second.unwrap_or(0).saturating_mul(1024),
+            Ordering::Relaxed,
+        );
+    }
+
+    /// Returns the directory holding the cached images.
+    pub fn assets_dir(&self) -> &Path {
+        &self.assets_dir
+    }
+
+    /// Returns the path of a cached file given as a path inside the assets
+    /// directory, absolute or relative to it.
+    ///
+    /// Returns `None` for paths leading outside the directory.
+    pub fn resolve(&self, path: &Path) -> Option<PathBuf> {
+        let relative = if path.is_absolute() {
+            path.strip_prefix(&self.assets_dir).ok()?
+        } else {
+            path
+        };
+
+        relative
+            .components()
+            .all(|component| matches!(component, Component::Normal(_)))
+            .then(|| self.assets_dir.join(relative))
+    }
+
+    /// Stops starting new downloads. Downloads in progress are left to
+    /// [`AssetManager::remove_partial_files`].
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
     /// Downloads a batch of cover images concurrently.
     ///
-    /// Downloads up to 5 images in parallel with retry logic. Skips images
-    /// that already exist locally.
+    /// Downloads up to the configured number of images in parallel, retrying
+    /// failed downloads. Skips images that already exist locally, and all
+    /// images in low-graphics mode. Emits an [`ASSET_PROGRESS_EVENT`] as
+    /// each image is done.
     ///
     /// # Arguments
     ///
@@ -73,22 +393,18 @@ impl AssetManager {
         &self,
         image_ids: Vec<String>,
     ) -> Result<Vec<(String, String)>, AssetError> {
-        let results: Vec<_> = stream::iter(image_ids)
-            .map(|image_id| self.download_cover(image_id))
-            .buffer_unordered(5) // Limit to 5 concurrent downloads
-            .collect()
-            .await;
-
-        // Filter out errors and collect successful downloads
-        let successful: Vec<_> = results.into_iter().filter_map(Result::ok).collect();
-
-        Ok(successful)
+        self.download_batch("covers", image_ids, |image_id| {
+            self.download_cover(image_id)
+        })
+        .await
     }
 
     /// Downloads a batch of artwork images concurrently.
     ///
-    /// Downloads up to 5 images in parallel with retry logic. Skips images
-    /// that already exist locally.
+    /// Downloads up to the configured number of images in parallel, retrying
+    /// failed downloads. Skips images that already exist locally, and all
+    /// images in low-graphics mode. Emits an [`ASSET_PROGRESS_EVENT`] as
+    /// each image is done.
     ///
     /// # Arguments
     ///
@@ -102,23 +418,197 @@ impl AssetManager {
         &self,
         image_ids: Vec<String>,
     ) -> Result<Vec<(String, String)>, AssetError> {
-        let results: Vec<_> = stream::iter(image_ids)
-            .map(|image_id| self.download_artwork(image_id))
-            .buffer_unordered(5) // Limit to 5 concurrent downloads
+        self.download_batch("artworks", image_ids, |image_id| {
+            self.download_artwork(image_id)
+        })
+        .await
+    }
+
+    /// Downloads a batch of screenshots concurrently.
+    ///
+    /// Downloads up to the configured number of images in parallel, retrying
+    /// failed downloads. Skips images that already exist locally, and all
+    /// images in low-graphics mode. Emits an [`ASSET_PROGRESS_EVENT`] as
+    /// each image is done.
+    ///
+    /// # Arguments
+    ///
+    /// * `image_ids` — List of IGDB image IDs to download.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Vec<(String, String)>` containing tuples of (image_id, local_path)
+    /// for successfully downloaded images.
+    pub async fn download_batch_screenshots(
+        &self,
+        image_ids: Vec<String>,
+    ) -> Result<Vec<(String, String)>, AssetError> {
+        self.download_batch("screenshots", image_ids, |image_id| {
+            self.download_screenshot(image_id)
+        })
+        .await
+    }
+
+    /// Downloads a batch of images of a category with `download`, emitting
+    /// progress events.
+    async fn download_batch<F, Fut>(
+        &self,
+        category: &'static str,
+        image_ids: Vec<String>,
+        download: F,
+    ) -> Result<Vec<(String, String)>, AssetError>
+    where
+        F: Fn(String) -> Fut,
+        Fut: Future<Output = Result<(String, String), AssetError>>,
+    {
+        if self.text_only.load(Ordering::Relaxed) {
+            return Ok(Vec::new());
+        }
+
+        let total = image_ids.len();
+        let mut downloads = stream::iter(image_ids)
+            .map(|image_id| {
+                let download = download(image_id.clone());
+                async move { (image_id, download.await) }
+            })
+            .buffer_unordered(self.concurrency);
+
+        let mut successful = Vec::new();
+        let mut completed = 0;
+        let mut failed = 0;
+
+        while let Some((image_id, result)) = downloads.next().await {
+            completed += 1;
+
+            // Filter out errors and collect successful downloads
+            match result {
+                Ok(download) => successful.push(download),
+                Err(_) => failed += 1,
+            }
+
+            if let Some(app) = &self.app {
+                let _ = app.emit(
+                    ASSET_PROGRESS_EVENT,
+                    AssetProgress {
+                        category,
+                        completed,
+                        total,
+                        image_id,
+                        failed,
+                    },
+                );
+            }
+        }
+
+        Ok(successful)
+    }
+
+    /// Upscales a batch of downloaded covers.
+    ///
+    /// Upscaled covers are cached as a separate variant in the
+    /// `covers_upscaled` directory, leaving the originals untouched. Covers
+    /// that already have an upscaled variant are skipped.
+    ///
+    /// # Arguments
+    ///
+    /// * `covers` — List of (image_id, local_path) tuples of downloaded covers.
+    /// * `method` — Upscaling method to apply.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Vec<(String, String)>` containing tuples of (image_id, upscaled_path)
+    /// for successfully upscaled covers.
+    pub async fn upscale_covers(
+        &self,
+        covers: Vec<(String, String)>,
+        method: &CoverUpscaling,
+    ) -> Result<Vec<(String, String)>, AssetError> {
+        let results: Vec<_> = stream::iter(covers)
+            .map(|(image_id, source)| self.upscale_cover(image_id, source, method))
+            .buffer_unordered(5)
             .collect()
             .await;
 
-        // Filter out errors and collect successful downloads
         let successful: Vec<_> = results.into_iter().filter_map(Result::ok).collect();
 
         Ok(successful)
     }
 
-    /// Downloads a single cover image with retry logic.
-    async fn download_cover(&self, image_id: String) -> Result<(String, String), AssetError> {
+    /// Extracts the accent color of a batch of downloaded covers.
+    ///
+    /// Covers that cannot be decoded are skipped.
+    ///
+    /// # Arguments
+    ///
+    /// * `covers` — List of (image_id, local_path) tuples of downloaded covers.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Vec<(String, String)>` containing tuples of (image_id, color)
+    /// with colors formatted as `#rrggbb`.
+    pub async fn extract_accent_colors(
+        &self,
+        covers: Vec<(String, String)>,
+    ) -> Result<Vec<(String, String)>, AssetError> {
+        let results: Vec<_> = stream::iter(covers)
+            .map(|(image_id, source)| async move {
+                let color = tokio::task::spawn_blocking(move || {
+                    image::open(&source).map(|image| accent_color(&image))
+                })
+                .await
+                .map_err(|e| AssetError::AccentColor(e.to_string()))??;
+
+                Ok::<_, AssetError>((image_id, color))
+            })
+            .buffer_unordered(5)
+            .collect()
+            .await;
+
+        let successful: Vec<_> = results.into_iter().filter_map(Result::ok).collect();
+
+        Ok(successful)
+    }
+
+    /// Creates small thumbnails of a batch of downloaded artworks, for grid
+    /// and list views.
+    ///
+    /// Thumbnails are cached in the `artwork_thumbnails` directory, leaving
+    /// the originals untouched. Artworks that already have a thumbnail are
+    /// skipped; artworks that cannot be decoded get none.
+    ///
+    /// # Arguments
+    ///
+    /// * `artworks` — List of (image_id, local_path) tuples of downloaded artworks.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Vec<(String, String)>` containing tuples of (image_id, thumbnail_path)
+    /// for successfully created thumbnails.
+    pub async fn create_artwork_thumbnails(
+        &self,
+        artworks: Vec<(String, String)>,
+    ) -> Result<Vec<(String, String)>, AssetError> {
+        let results: Vec<_> = stream::iter(artworks)
+            .map(|(image_id, source)| self.create_artwork_thumbnail(image_id, source))
+            .buffer_unordered(5)
+            .collect()
+            .await;
+
+        let successful: Vec<_> = results.into_iter().filter_map(Result::ok).collect();
+
+        Ok(successful)
+    }
+
+    /// Creates the thumbnail of a single artwork, writing to a `.tmp.jpg`
+    /// file before renaming.
+    async fn create_artwork_thumbnail(
+        &self,
+        image_id: String,
+        source: String,
+    ) -> Result<(String, String), AssetError> {
         let local_path = self
             .assets_dir
-            .join("covers")
+            .join("artwork_thumbnails")
             .join(format!("{}.jpg", image_id));
 
         // Skip if already exists
@@ -126,12 +616,135 @@ impl AssetManager {
             return Ok((image_id, local_path.to_string_lossy().to_string()));
         }
 
+        let tmp_path = local_path.with_extension("tmp.jpg");
+        let destination = tmp_path.clone();
+
+        let result = tokio::task::spawn_blocking(move || {
+            image::open(&source)?
+                .thumbnail(THUMBNAIL_WIDTH, THUMBNAIL_HEIGHT)
+                .to_rgb8()
+                .save_with_format(&destination, ImageFormat::Jpeg)
+        })
+        .await
+        .map_err(|e| AssetError::Thumbnail(e.to_string()))?;
+
+        if let Err(e) = result {
+            let _ = fs::remove_file(&tmp_path).await;
+            return Err(e.into());
+        }
+
+        fs::rename(&tmp_path, &local_path).await?;
+
+        Ok((image_id, local_path.to_string_lossy().to_string()))
+    }
+
+    /// Upscales a single cover, writing to a `.tmp.jpg` file before renaming.
+    async fn upscale_cover(
+        &self,
+        image_id: String,
+        source: String,
+        method: &CoverUpscaling,
+    ) -> Result<(String, String), AssetError> {
+        let local_path = self
+            .assets_dir
+            .join("covers_upscaled")
+            .join(format!("{}.jpg", image_id));
+
+        // Skip if already exists
+        if local_path.exists() {
+            return Ok((image_id, local_path.to_string_lossy().to_string()));
+        }
+
+        let tmp_path = local_path.with_extension("tmp.jpg");
+
+        let result = match method {
+            CoverUpscaling::Lanczos => Self::upscale_lanczos(source, tmp_path.clone()).await,
+            CoverUpscaling::External(command) => {
+                Self::upscale_external(command, &source, &tmp_path).await
+            }
+        };
+
+        if let Err(e) = result {
+            let _ = fs::remove_file(&tmp_path).await;
+            return Err(e);
+        }
+
+        fs::rename(&tmp_path, &local_path).await?;
+
+        Ok((image_id, local_path.to_string_lossy().to_string()))
+    }
+
+    /// Resizes an image by [`UPSCALE_FACTOR`] with a Lanczos3 filter.
+    async fn upscale_lanczos(source: String, destination: PathBuf) -> Result<(), AssetError> {
+        tokio::task::spawn_blocking(move || {
+            let image = image::open(&source)?;
+            image
+                .resize(
+                    image.width() * UPSCALE_FACTOR,
+                    image.height() * UPSCALE_FACTOR,
+                    FilterType::Lanczos3,
+                )
+                .to_rgb8()
+                .save_with_format(&destination, ImageFormat::Jpeg)?;
+            Ok(())
+        })
+        .await
+        .map_err(|e| AssetError::Upscale(e.to_string()))?
+    }
+
+    /// Runs a user-configured upscaling command on `source`.
+    ///
+    /// The placeholders are replaced after splitting the command line, so
+    /// paths with spaces stay a single argument.
+    async fn upscale_external(
+        command: &str,
+        source: &str,
+        destination: &Path,
+    ) -> Result<(), AssetError> {
+        let destination = destination.to_string_lossy();
+        let parts = shlex::split(command).ok_or(AssetError::Upscale(
+            "unbalanced quotes in upscale command".to_string(),
+        ))?;
+        let mut parts = parts.into_iter().map(|part| {
+            part.replace("{input}", source)
+                .replace("{output}", &destination)
+        });
+
+        let program = parts
+            .next()
+            .ok_or(AssetError::Upscale("empty upscale command".to_string()))?;
+
+        let status = Command::new(&program).args(parts).status().await?;
+
+        if !status.success() {
+            return Err(AssetError::Upscale(format!(
+                "{} exited with {}",
+                program, status
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Downloads a single cover image with retry logic.
+    async fn download_cover(&self, image_id: String) -> Result<(String, String), AssetError> {
+        let local_path = self
+            .assets_dir
+            .join("covers")
+            .join(format!("{}.jpg", image_id));
+
+        // Skip if already exists, as JPEG or WebP
+        if let Some(existing) = Self::find_downloaded(&local_path) {
+            return Ok((image_id, existing.to_string_lossy().to_string()));
+        }
+
         let url = format!(
             "https://images.igdb.com/igdb/image/upload/t_cover_small/{}.jpg",
             image_id
         );
 
-        self.download_with_retry(&url, &local_path).await?;
+        let (local_path, _) = self.fetch_igdb_image("covers", &url, local_path).await?;
+        let local_path = self.encode_webp(local_path).await;
 
         Ok((image_id, local_path.to_string_lossy().to_string()))
     }
@@ -143,9 +756,9 @@ impl AssetManager {
             .join("artworks")
             .join(format!("{}.jpg", image_id));
 
-        // Skip if already exists
-        if local_path.exists() {
-            return Ok((image_id, local_path.to_string_lossy().to_string()));
+        // Skip if already exists, as JPEG or WebP
+        if let Some(existing) = Self::find_downloaded(&local_path) {
+            return Ok((image_id, existing.to_string_lossy().to_string()));
         }
 
         let url = format!(
@@ -153,77 +766,628 @@ impl AssetManager {
             image_id
         );
 
-        self.download_with_retry(&url, &local_path).await?;
+        let (local_path, downloaded) = self.fetch_igdb_image("artworks", &url, local_path).await?;
+
+        if !downloaded {
+            let _ = self
+                .restore_previous("artwork_thumbnails", &format!("{}.jpg", image_id))
+                .await;
+        }
+
+        // The thumbnail is made from the JPEG before it is re-encoded, so it
+        // is not compressed twice
+        let _ = self
+            .create_artwork_thumbnail(image_id.clone(), local_path.to_string_lossy().to_string())
+            .await;
+        let local_path = self.encode_webp(local_path).await;
+
+        Ok((image_id, local_path.to_string_lossy().to_string()))
+    }
+
+    /// Downloads a single screenshot with retry logic.
+    async fn download_screenshot(&self, image_id: String) -> Result<(String, String), AssetError> {
+        let local_path = self
+            .assets_dir
+            .join("screenshots")
+            .join(format!("{}.jpg", image_id));
+
+        // Skip if already exists, as JPEG or WebP
+        if let Some(existing) = Self::find_downloaded(&local_path) {
+            return Ok((image_id, existing.to_string_lossy().to_string()));
+        }
+
+        let url = format!(
+            "https://images.igdb.com/igdb/image/upload/t_screenshot_huge/{}.jpg",
+            image_id
+        );
+
+        let (local_path, _) = self
+            .fetch_igdb_image("screenshots", &url, local_path)
+            .await?;
+        let local_path = self.encode_webp(local_path).await;
 
         Ok((image_id, local_path.to_string_lossy().to_string()))
     }
 
-    /// Downloads a file from URL to local path with exponential backoff retry.
+    /// Downloads an IGDB image of a category to `local_path`, unless the
+    /// copy of the previous refresh is still current.
     ///
-    /// Attempts download up to 3 times with delays of 1s, 2s, 4s between attempts.
-    /// Uses atomic write pattern (download to .tmp file, then rename).
-    async fn download_with_retry(&self, url: &str, local_path: &PathBuf) -> Result<(), AssetError> {
-        let tmp_path = local_path.with_extension("tmp");
-        let max_attempts = 3;
-
-        for attempt in 0..max_attempts {
-            match self.try_download(url, &tmp_path).await {
-                Ok(_) => {
-                    // Atomic rename from .tmp to final path
-                    fs::rename(&tmp_path, local_path).await?;
-                    return Ok(());
-                }
-                Err(_) => {
-                    // Clean up tmp file on error
-                    let _ = fs::remove_file(&tmp_path).await;
-
-                    if attempt < max_attempts - 1 {
-                        // Exponential backoff: 1s, 2s, 4s
-                        let delay = Duration::from_secs(2_u64.pow(attempt as u32));
-                        tokio::time::sleep(delay).await;
-                    } else {
-                        return Err(AssetError::DownloadFailed(url.to_string()));
+    /// When the previous copy exists and validators were stored for `url`,
+    /// the request is conditional. On `304 Not Modified`, the previous copy,
+    /// which may be the WebP variant, is moved back in place.
+    ///
+    /// # Returns
+    ///
+    /// Returns the path of the image, and whether it was downloaded.
+    async fn fetch_igdb_image(
+        &self,
+        category: &str,
+        url: &str,
+        local_path: PathBuf,
+    ) -> Result<(PathBuf, bool), AssetError> {
+        let previous = local_path.file_name().and_then(|name| {
+            Self::find_downloaded(&self.assets_dir.join(PREVIOUS_DIR).join(category).join(name))
+        });
+        let validators = match (&previous, &self.validators) {
+            (Some(_), Some(repository)) => repository.get(url).await.ok().flatten(),
+            _ => None,
+        };
+
+        match self
+            .download_with_retry(url, &local_path, validators.as_ref())
+            .await?
+        {
+            Fetched::Downloaded(validators) => {
+                if let Some(repository) = &self.validators {
+                    if validators.etag.is_some() || validators.last_modified.is_some() {
+                        // Best effort: a failed write only costs a full
+                        // download later
+                        let _ = repository.set(url, &validators).await;
                     }
                 }
+                self.downloaded.fetch_add(1, Ordering::Relaxed);
+
+                Ok((local_path, true))
+            }
+            Fetched::NotModified => {
+                // Only sent for conditional requests, made when a previous
+                // copy exists
+                let Some(previous) = previous else {
+                    return Err(AssetError::DownloadFailed(url.to_string(), 1));
+                };
+                let restored = local_path.with_file_name(previous.file_name().unwrap_or_default());
+
+                fs::rename(&previous, &restored).await?;
+
+                Ok((restored, false))
+            }
+        }
+    }
+
+    /// Moves the cached images at `paths` back from the previous refresh,
+    /// for the games kept by the refresh. Paths outside of the
+    /// [`PREVIOUS_DIRS`] are ignored.
+    pub async fn keep_previous(&self, paths: &[String]) -> Result<(), AssetError> {
+        for path in paths {
+            let Some(path) = self.resolve(Path::new(path)) else {
+                continue;
+            };
+            let (Some(category), Some(file_name)) = (
+                path.parent()
+                    .and_then(Path::file_name)
+                    .and_then(|category| category.to_str()),
+                path.file_name().and_then(|name| name.to_str()),
+            ) else {
+                continue;
+            };
+
+            if PREVIOUS_DIRS.contains(&category) {
+                self.restore_previous(category, file_name).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Moves a file of a category back from the previous refresh, if it is
+    /// there.
+    async fn restore_previous(&self, category: &str, file_name: &str) -> Result<(), AssetError> {
+        let previous = self
+            .assets_dir
+            .join(PREVIOUS_DIR)
+            .join(category)
+            .join(file_name);
+
+        if previous.exists() {
+            fs::rename(&previous, self.assets_dir.join(category).join(file_name)).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Moves the images of the refresh before back from the previous
+    /// directory, unless the last refresh downloaded an image of the same
+    /// name. Used when the library is rolled back to before the last
+    /// refresh, whose rows point at these images.
+    pub async fn restore_previous_images(&self) -> Result<(), AssetError> {
+        for category in PREVIOUS_DIRS {
+            let dir = self.assets_dir.join(PREVIOUS_DIR).join(category);
+
+            if !dir.exists() {
+                continue;
+            }
+
+            let mut entries = fs::read_dir(&dir).await?;
+
+            while let Some(entry) = entries.next_entry().await? {
+                let destination = self.assets_dir.join(category).join(entry.file_name());
+
+                if !destination.exists() {
+                    fs::rename(entry.path(), destination).await?;
+                }
             }
         }
 
-        Err(AssetError::DownloadFailed(url.to_string()))
+        Ok(())
+    }
+
+    /// Returns the path of an image downloaded to `local_path` or to its
+    /// WebP variant, if any.
+    fn find_downloaded(local_path: &Path) -> Option<PathBuf> {
+        [local_path.with_extension("webp"), local_path.to_path_buf()]
+            .into_iter()
+            .find(|path| path.exists())
     }
 
-    /// Attempts a single download operation.
-    async fn try_download(&self, url: &str, tmp_path: &PathBuf) -> Result<(), AssetError> {
-        let response = self.client.get(url).send().await?;
+    /// Re-encodes a downloaded JPEG to lossy WebP when a WebP quality is set,
+    /// and returns the path of the image to keep.
+    ///
+    /// The JPEG is kept, and the failure logged, when it cannot be decoded
+    /// or the WebP cannot be written.
+    async fn encode_webp(&self, jpeg_path: PathBuf) -> PathBuf {
+        let quality = self.webp_quality.load(Ordering::Relaxed);
+
+        // Images moved back from the previous refresh may already be WebP
+        if quality == 0
+            || jpeg_path
+                .extension()
+                .is_some_and(|extension| extension == "webp")
+        {
+            return jpeg_path;
+        }
+
+        let webp_path = jpeg_path.with_extension("webp");
+        let tmp_path = jpeg_path.with_extension("tmp.webp");
 
-        if !response.status().is_success() {
-            return Err(AssetError::Request(
-                response.error_for_status().unwrap_err(),
-            ));
+        if let Err(e) = Self::write_webp(&jpeg_path, &tmp_path, quality).await {
+            tracing::warn!("failed to encode {} to WebP: {e}", jpeg_path.display());
+            let _ = fs::remove_file(&tmp_path).await;
+            return jpeg_path;
         }
 
-        let bytes = response.bytes().await?;
+        let _ = fs::remove_file(&jpeg_path).await;
+
+        webp_path
+    }
+
+    /// Decodes the image at `source` and writes it to `tmp_path` as lossy
+    /// WebP at `quality`, then renames it to the `.webp` variant of
+    /// `source`.
+    async fn write_webp(source: &Path, tmp_path: &Path, quality: u8) -> Result<(), AssetError> {
+        let image_path = source.to_path_buf();
 
-        let mut file = fs::File::create(tmp_path).await?;
-        file.write_all(&bytes).await?;
+        let encoded = tokio::task::spawn_blocking(move || {
+            let rgb = image::open(image_path)?.to_rgb8();
+
+            webp::Encoder::from_rgb(&rgb, rgb.width(), rgb.height())
+                .encode_simple(false, f32::from(quality))
+                .map(|webp| webp.to_vec())
+                .map_err(|e| AssetError::WebP(format!("{e:?}")))
+        })
+        .await
+        .map_err(|e| AssetError::WebP(e.to_string()))??;
+
+        fs::write(tmp_path, encoded).await?;
+        fs::rename(tmp_path, source.with_extension("webp")).await?;
+
+        Ok(())
+    }
+
+    /// Downloads an image chosen from SteamGridDB for a Steam game.
+    ///
+    /// The image is stored as `<store_id>_<kind>` with the extension of
+    /// `url`, replacing the previous image of that kind.
+    ///
+    /// # Returns
+    ///
+    /// Returns the local path of the image.
+    pub async fn download_steamgriddb_image(
+        &self,
+        store_id: &str,
+        kind: &str,
+        url: &str,
+    ) -> Result<String, AssetError> {
+        let extension = url
+            .rsplit_once('.')
+            .map(|(_, extension)| extension)
+            .filter(|extension| extension.chars().all(|c| c.is_ascii_alphanumeric()))
+            .unwrap_or("png");
+
+        let local_path = self
+            .assets_dir
+            .join(STEAMGRIDDB_DIR)
+            .join(format!("{}_{}.{}", store_id, kind, extension));
+
+        self.download_with_retry(url, &local_path, None).await?;
+
+        Ok(local_path.to_string_lossy().to_string())
+    }
+
+    /// Copies an image chosen by the user into the assets directory, under
+    /// `image_id` and the extension of `source`.
+    ///
+    /// # Returns
+    ///
+    /// Returns the local path of the copy.
+    pub async fn copy_custom_image(
+        &self,
+        image_id: &str,
+        source: &Path,
+    ) -> Result<String, AssetError> {
+        let extension = source
+            .extension()
+            .map(|extension| extension.to_string_lossy().to_ascii_lowercase())
+            .filter(|extension| CUSTOM_IMAGE_EXTENSIONS.contains(&extension.as_str()))
+            .ok_or_else(|| AssetError::UnsupportedImage(source.to_string_lossy().to_string()))?;
+
+        let local_path = self
+            .assets_dir
+            .join(CUSTOM_DIR)
+            .join(format!("{}.{}", image_id, extension));
+
+        fs::copy(source, &local_path).await?;
+
+        Ok(local_path.to_string_lossy().to_string())
+    }
+
+    /// Downloads a file from URL to local path, retrying with exponential
+    /// backoff according to the [`RetryPolicy`].
+    ///
+    /// Uses atomic write pattern (download to .tmp file, then rename). The
+    /// partial .tmp file of a failed attempt is kept, and the next attempt
+    /// resumes it. With `validators`, requests are conditional, and
+    /// nothing is written when the server answers `304 Not Modified`.
+    async fn download_with_retry(
+        &self,
+        url: &str,
+        local_path: &PathBuf,
+        validators: Option<&AssetValidators>,
+    ) -> Result<Fetched, AssetError> {
+        let tmp_path = local_path.with_extension("tmp");
+
+        // Leftovers of an interrupted run are not resumed
+        let _ = fs::remove_file(&tmp_path).await;
+
+        let result = self
+            .retry_policy
+            .run(
+                || async {
+                    if self.cancelled.load(Ordering::Relaxed) {
+                        return Err(AssetError::Cancelled);
+                    }
+
+                    self.try_download(url, &tmp_path, validators).await
+                },
+                |e| !matches!(e, AssetError::Cancelled),
+            )
+            .await;
+
+        match result {
+            Ok(Fetched::NotModified) => Ok(Fetched::NotModified),
+            Ok(fetched) => {
+                // Atomic rename from .tmp to final path
+                fs::rename(&tmp_path, local_path).await?;
+                Ok(fetched)
+            }
+            Err(AssetError::Cancelled) => Err(AssetError::Cancelled),
+            Err(_) => {
+                // Clean up tmp file once all attempts failed
+                let _ = fs::remove_file(&tmp_path).await;
+
+                Err(AssetError::DownloadFailed(
+                    url.to_string(),
+                    self.retry_policy.max_attempts(),
+                ))
+            }
+        }
+    }
+
+    /// Attempts a single download operation, streaming the body to the .tmp
+    /// file.
+    ///
+    /// If the .tmp file already holds part of the image, only the remaining
+    /// bytes are requested with a `Range` header. Servers ignoring the range
+    /// send the whole image, which replaces the partial file. Otherwise, the
+    /// request is made conditional on `validators`, if any.
+    async fn try_download(
+        &self,
+        url: &str,
+        tmp_path: &PathBuf,
+        validators: Option<&AssetValidators>,
+    ) -> Result<Fetched, AssetError> {
+        let offset = fs::metadata(tmp_path)
+            .await
+            .map_or(0, |metadata| metadata.len());
+
+        let mut request = self.client.get(url);
+
+        if offset > 0 {
+            request = request.header(RANGE, format!("bytes={}-", offset));
+        } else if let Some(validators) = validators {
+            if let Some(etag) = &validators.etag {
+                request = request.header(IF_NONE_MATCH, etag);
+            }
+            if let Some(last_modified) = &validators.last_modified {
+                request = request.header(IF_MODIFIED_SINCE, last_modified);
+            }
+        }
+
+        let response = request.send().await?;
+
+        if response.status() == StatusCode::NOT_MODIFIED {
+            return Ok(Fetched::NotModified);
+        }
+
+        if response.status() == StatusCode::RANGE_NOT_SATISFIABLE {
+            // The partial file does not match the image; start over
+            let _ = fs::remove_file(tmp_path).await;
+        }
+
+        let mut response = response.error_for_status()?;
+        let validators = AssetValidators {
+            etag: header_value(&response, ETAG),
+            last_modified: header_value(&response, LAST_MODIFIED),
+        };
+
+        let mut file = if response.status() == StatusCode::PARTIAL_CONTENT {
+            fs::OpenOptions::new().append(true).open(tmp_path).await?
+        } else {
+            fs::File::create(tmp_path).await?
+        };
+
+        let result = async {
+            while let Some(chunk) = response.chunk().await? {
+                file.write_all(&chunk).await?;
+                self.throttle.consume(chunk.len()).await;
+            }
+
+            Ok(())
+        }
+        .await;
+
+        // Flushed on failure too, so the next attempt resumes after the
+        // bytes actually written
         file.flush().await?;
 
+        result.map(|()| Fetched::Downloaded(validators))
+    }
+
+    /// Returns the number, size, and age of the cached images of each
+    /// category. Temporary files of downloads in progress are left out.
+    pub async fn get_cache_stats(&self) -> Result<CacheStats, AssetError> {
+        let mut categories = Vec::new();
+
+        for dir in IGDB_DIRS.into_iter().chain([STEAMGRIDDB_DIR, CUSTOM_DIR]) {
+            let mut stats = CacheCategoryStats {
+                category: dir.to_string(),
+                file_count: 0,
+                size: 0,
+                oldest: None,
+                newest: None,
+            };
+            let mut entries = fs::read_dir(self.assets_dir.join(dir)).await?;
+
+            while let Some(entry) = entries.next_entry().await? {
+                let name = entry.file_name();
+
+                if name.to_string_lossy().contains(".tmp") {
+                    continue;
+                }
+
+                let metadata = entry.metadata().await?;
+
+                if !metadata.is_file() {
+                    continue;
+                }
+
+                let modified = metadata
+                    .modified()
+                    .ok()
+                    .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+                    .map(|modified| modified.as_secs() as i64);
+
+                stats.file_count += 1;
+                stats.size += metadata.len();
+
+                if let Some(modified) = modified {
+                    stats.oldest =
+                        Some(stats.oldest.map_or(modified, |oldest| oldest.min(modified)));
+                    stats.newest =
+                        Some(stats.newest.map_or(modified, |newest| newest.max(modified)));
+                }
+            }
+
+            categories.push(stats);
+        }
+
+        Ok(CacheStats {
+            total_size: categories.iter().map(|stats| stats.size).sum(),
+            categories,
+        })
+    }
+
+    /// Checks that each cached IGDB image is complete, and removes the
+    /// corrupt ones so they can be downloaded or created again.
+    ///
+    /// JPEG headers are decoded and the end-of-image marker is checked;
+    /// WebP files must be as long as their RIFF header declares. Temporary
+    /// files of downloads in progress are skipped.
+    pub async fn remove_corrupt_files(&self) -> Result<CorruptFiles, AssetError> {
+        let mut checked = 0;
+        let mut paths = Vec::new();
+
+        for dir in IGDB_DIRS {
+            let mut entries = fs::read_dir(self.assets_dir.join(dir)).await?;
+
+            while let Some(entry) = entries.next_entry().await? {
+                if entry.file_name().to_string_lossy().contains(".tmp") {
+                    continue;
+                }
+
+                let path = entry.path();
+                let bytes = fs::read(&path).await?;
+                checked += 1;
+
+                if !is_complete_image(&bytes) {
+                    fs::remove_file(&path).await?;
+                    paths.push(path.to_string_lossy().to_string());
+                }
+            }
+        }
+
+        Ok(CorruptFiles { checked, paths })
+    }
+
+    /// Removes the temporary files of interrupted downloads and upscales.
+    pub async fn remove_partial_files(&self) -> Result<(), AssetError> {
+        for dir in IGDB_DIRS.into_iter().chain([STEAMGRIDDB_DIR]) {
+            let mut entries = fs::read_dir(self.assets_dir.join(dir)).await?;
+
+            while let Some(entry) = entries.next_entry().await? {
+                let name = entry.file_name();
+                let name = name.to_string_lossy();
+
+                if name.ends_with(".tmp")
+                    || name.ends_with(".tmp.jpg")
+                    || name.ends_with(".tmp.webp")
+                {
+                    let _ = fs::remove_file(entry.path()).await;
+                }
+            }
+        }
+
         Ok(())
     }
 
-    /// Clears all locally cached images.
+    /// Clears all locally cached IGDB images.
+    ///
+    /// Removes the IGDB image directories and recreates them empty. Called
+    /// during database refresh to prevent orphaned files. SteamGridDB
+    /// artwork and images added by the user are kept, as the user's choices
+    /// outlive refreshes.
     ///
-    /// Removes the entire assets directory and recreates it empty.
-    /// Called during database refresh to prevent orphaned files.
+    /// Downloaded images are moved to the `previous` directory instead, so
+    /// those requested again can be kept when unchanged; the images of the
+    /// refresh before are removed.
     pub async fn clear_all(&self) -> Result<(), AssetError> {
-        // Remove the entire assets directory
-        if self.assets_dir.exists() {
-            fs::remove_dir_all(&self.assets_dir).await?;
+        let previous_dir = self.assets_dir.join(PREVIOUS_DIR);
+
+        if previous_dir.exists() {
+            fs::remove_dir_all(&previous_dir).await?;
         }
 
-        // Recreate empty directories
-        fs::create_dir_all(self.assets_dir.join("covers")).await?;
-        fs::create_dir_all(self.assets_dir.join("artworks")).await?;
+        fs::create_dir_all(&previous_dir).await?;
+
+        for name in IGDB_DIRS {
+            let dir = self.assets_dir.join(name);
+
+            if dir.exists() {
+                if PREVIOUS_DIRS.contains(&name) {
+                    fs::rename(&dir, previous_dir.join(name)).await?;
+                } else {
+                    fs::remove_dir_all(&dir).await?;
+                }
+            }
+
+            fs::create_dir_all(&dir).await?;
+        }
 
         Ok(())
     }
 }
+
+/// Returns the value of a response header, if present and valid text.
+fn header_value(response: &Response, name: HeaderName) -> Option<String> {
+    response
+        .headers()
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string)
+}
+
+/// Returns `true` if `bytes` hold a whole JPEG or WebP image.
+///
+/// Truncated files, e.g. left by a crash mid-write, miss the end of the
+/// JPEG stream or are shorter than the size in the WebP header.
+fn is_complete_image(bytes: &[u8]) -> bool {
+    match image::guess_format(bytes) {
+        Ok(ImageFormat::Jpeg) => {
+            bytes.ends_with(&[0xFF, 0xD9])
+                && image::ImageReader::with_format(Cursor::new(bytes), ImageFormat::Jpeg)
+                    .into_dimensions()
+                    .is_ok()
+        }
+        Ok(ImageFormat::WebP) => bytes.get(4..8).is_some_and(|size| {
+            let size = u32::from_le_bytes([size[0], size[1], size[2], size[3]]);
+            size as usize + 8 == bytes.len()
+        }),
+        _ => false,
+    }
+}
+
+/// Returns the dominant color of an image as `#rrggbb`.
+///
+/// Pixels of a shrunk copy are grouped by color, with 3 bits per channel;
+/// the average of the largest group wins. Near-black and near-white pixels
+/// are left out, as they are mostly borders and text, unless the image has
+/// nothing else.
+fn accent_color(image: &DynamicImage) -> String {
+    let sample = image
+        .thumbnail(ACCENT_SAMPLE_SIZE, ACCENT_SAMPLE_SIZE)
+        .to_rgb8();
+
+    let mut buckets: HashMap<(u8, u8, u8), (u32, [u32; 3])> = HashMap::new();
+
+    for pixel in sample.pixels() {
+        let [r, g, b] = pixel.0;
+        let max = r.max(g).max(b);
+        let min = r.min(g).min(b);
+        let neutral = max < 32 || min > 224;
+        let key = if neutral {
+            (u8::MAX, 0, 0)
+        } else {
+            (r >> 5, g >> 5, b >> 5)
+        };
+
+        let (count, sum) = buckets.entry(key).or_default();
+        *count += 1;
+        sum[0] += r as u32;
+        sum[1] += g as u32;
+        sum[2] += b as u32;
+    }
+
+    let neutral = buckets.remove(&(u8::MAX, 0, 0));
+    let (count, sum) = buckets
+        .into_values()
+        .max_by_key(|(count, _)| *count)
+        .or(neutral)
+        .unwrap_or_default();
+    let count = count.max(1);
+
+    format!(
+        "#{:02x}{:02x}{:02x}",
+        sum[0] / count,
+        sum[1] / count,
+        sum[2] / count
+    )
+}
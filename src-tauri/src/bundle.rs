@@ -0,0 +1,148 @@
+//! Portable bundles of custom game definitions.
+//!
+//! A bundle is a JSON file listing custom games with their executable, Wine
+//! configuration, controller profile, and IGDB image IDs. Paths under a
+//! base directory (e.g. an emulation folder) are stored relative to it,
+//! behind a `$BASE/` marker, and resolved against the importing machine's
+//! base directory, so a curated setup can be shared between machines. Images
+//! are not embedded; they are downloaded again from their IGDB IDs.
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::wine::WineConfig;
+
+/// Version written in exported bundles. Bundles of other versions are
+/// rejected on import.
+const BUNDLE_VERSION: u32 = 1;
+
+/// Prefix of the paths stored relative to the base directory.
+const BASE_DIR_PREFIX: &str = "$BASE/";
+
+/// Errors that can occur while exporting or importing a bundle.
+#[derive(Debug, thiserror::Error)]
+pub enum BundleError {
+    /// The bundle file could not be read or written.
+    #[error("unable to access bundle: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// The bundle file is not valid JSON or is missing fields.
+    #[error("invalid bundle: {0}")]
+    InvalidData(#[from] serde_json::Error),
+
+    /// The bundle was written by an incompatible version of Rocade.
+    #[error("unsupported bundle version {0}")]
+    UnsupportedVersion(u32),
+}
+
+/// A custom game as stored in a bundle.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CustomGameDefinition {
+    pub name: String,
+    /// Path to the executable.
+    pub executable: String,
+    /// Wine configuration, `None` when the game runs natively.
+    pub wine: Option<WineConfig>,
+    /// Controller-remapping profile loaded while the game runs.
+    pub controller_profile: Option<String>,
+    /// IGDB image ID of the cover.
+    pub cover: Option<String>,
+    /// IGDB image IDs of the artworks.
+    #[serde(default)]
+    pub artworks: Vec<String>,
+}
+
+impl CustomGameDefinition {
+    /// Applies `rewrite` to every path of the definition.
+    fn map_paths(mut self, rewrite: impl Fn(&str) -> String) -> Self {
+        self.executable = rewrite(&self.executable);
+
+        if let Some(wine) = &mut self.wine {
+            wine.runtime = rewrite(&wine.runtime);
+            wine.prefix = rewrite(&wine.prefix);
+        }
+
+        self.controller_profile = self.controller_profile.as_deref().map(&rewrite);
+
+        self
+    }
+}
+
+/// Top-level structure of a bundle file.
+#[derive(Serialize, Deserialize)]
+struct CustomGameBundle {
+    version: u32,
+    games: Vec<CustomGameDefinition>,
+}
+
+/// Writes `games` to a bundle at `path`, with paths under `base_dir` made
+/// relative to it.
+pub async fn export(
+    path: &Path,
+    base_dir: &Path,
+    games: Vec<CustomGameDefinition>,
+) -> Result<(), BundleError> {
+    let bundle = CustomGameBundle {
+        version: BUNDLE_VERSION,
+        games: games
+            .into_iter()
+            .map(|game| game.map_paths(|path| relativize(path, base_dir)))
+            .collect(),
+    };
+
+    tokio::fs::write(path, serde_json::to_string_pretty(&bundle)?).await?;
+
+    Ok(())
+}
+
+/// Reads the games of the bundle at `path`, with relative paths resolved
+/// against `base_dir`.
+pub async fn import(
+    path: &Path,
+    base_dir: &Path,
+) -> Result<Vec<CustomGameDefinition>, BundleError> {
+    let bundle: CustomGameBundle = serde_json::from_str(&tokio::fs::read_to_string(path).await?)?;
+
+    if bundle.version != BUNDLE_VERSION {
+        return Err(BundleError::UnsupportedVersion(bundle.version));
+    }
+
+    Ok(bundle
+        .games
+        .into_iter()
+        .map(|game| game.map_paths(|path| resolve(path, base_dir)))
+        .collect())
+}
+
+/// Returns `path` relative to `base_dir` with `/` separators and the
+/// [`BASE_DIR_PREFIX`], or unchanged if it is outside of it.
+fn relativize(path: &str, base_dir: &Path) -> String {
+    match Path::new(path).strip_prefix(base_dir) {
+        Ok(relative) => {
+            let components: Vec<_> = relative
+                .components()
+                .map(|component| component.as_os_str().to_string_lossy())
+                .collect();
+
+            format!("{}{}", BASE_DIR_PREFIX, components.join("/"))
+        }
+        Err(_) => path.to_string(),
+    }
+}
+
+/// Returns `path` resolved against `base_dir` if it starts with the
+/// [`BASE_DIR_PREFIX`], or unchanged otherwise.
+fn resolve(path: &str, base_dir: &Path) -> String {
+    let Some(relative) = path.strip_prefix(BASE_DIR_PREFIX) else {
+        return path.to_string();
+    };
+
+    relative
+        .split('/')
+        .fold(PathBuf::from(base_dir), |resolved, component| {
+            resolved.join(component)
+        })
+        .to_string_lossy()
+        .into_owned()
+}
@@ -0,0 +1,61 @@
+//! In-memory cache of single-game records.
+//!
+//! Filled by `prefetch_game` when the user hovers a grid tile so the detail
+//! page does not wait on the aggregated game query once the tile is clicked.
+
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use crate::db::game::Game;
+
+/// How long a cached game stays valid. Hover-to-click delays are short, and
+/// a short lifetime keeps playtime and launch dates from going stale.
+const ENTRY_TTL: Duration = Duration::from_secs(30);
+
+/// Short-lived cache of games keyed by database ID.
+#[derive(Default)]
+pub struct GameCache {
+    entries: Mutex<HashMap<i64, (Instant, Game)>>,
+}
+
+impl GameCache {
+    /// Creates an empty `GameCache`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the cached game, or `None` if it is missing or expired.
+    pub fn get(&self, game_id: i64) -> Option<Game> {
+        let mut entries = self.entries.lock().unwrap();
+
+        match entries.get(&game_id) {
+            Some((cached_at, game)) if cached_at.elapsed() < ENTRY_TTL => Some(game.clone()),
+            Some(_) => {
+                entries.remove(&game_id);
+                None
+            }
+            None => None,
+        }
+    }
+
+    /// Caches `game`, replacing any previous entry.
+    pub fn insert(&self, game: Game) {
+        self.entries
+            .lock()
+            .unwrap()
+            .insert(game.id, (Instant::now(), game));
+    }
+
+    /// Removes the cached entry for a game.
+    pub fn invalidate(&self, game_id: i64) {
+        self.entries.lock().unwrap().remove(&game_id);
+    }
+
+    /// Removes all cached entries.
+    pub fn clear(&self) {
+        self.entries.lock().unwrap().clear();
+    }
+}
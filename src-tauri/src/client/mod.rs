@@ -1 +1,2 @@
 pub mod steam;
+pub mod watcher;
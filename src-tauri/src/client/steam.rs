@@ -4,8 +4,12 @@
 //! and check the installation state of games by reading ACF manifest files
 //! and opening `steam://` protocol URLs via the OS.
 
-use std::{fs, path::PathBuf};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
 
+use serde::Serialize;
 use tauri::AppHandle;
 use tauri_plugin_opener::OpenerExt;
 
@@ -21,6 +25,68 @@ pub enum SteamClientError {
     ClientConfigError(String),
 }
 
+/// `StateFlags` bit set when an update or install is pending.
+const STATE_UPDATE_REQUIRED: i64 = 2;
+/// `StateFlags` bit set when the game is fully installed.
+const STATE_FULLY_INSTALLED: i64 = 4;
+/// `StateFlags` bit set while an update or install is running.
+const STATE_UPDATE_RUNNING: i64 = 256;
+/// `StateFlags` bit set while an update or install is paused.
+const STATE_UPDATE_PAUSED: i64 = 512;
+/// `StateFlags` bit set while content is being downloaded.
+const STATE_DOWNLOADING: i64 = 1_048_576;
+
+/// Install state of a game, derived from its ACF manifest `StateFlags`.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum InstallState {
+    /// No manifest exists for the game.
+    NotInstalled,
+    /// The install or update is waiting in the Steam download queue.
+    Queued,
+    /// Content is being downloaded or installed.
+    Downloading,
+    /// The download was paused.
+    Paused,
+    /// The game is fully installed and up to date.
+    Installed,
+}
+
+/// Install state and download progress of a game.
+#[derive(Serialize, Debug, Clone)]
+pub struct InstallProgress {
+    pub state: InstallState,
+    pub bytes_downloaded: Option<i64>,
+    pub bytes_to_download: Option<i64>,
+    /// Download progress in `[0.0, 100.0]`.
+    pub percentage: f64,
+    /// Steam's download restrictions, only set while the download is
+    /// queued, to explain why it is not progressing.
+    pub schedule: Option<DownloadSchedule>,
+}
+
+/// Download restrictions configured in the Steam client settings.
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct DownloadSchedule {
+    /// Hour (0–23, local time) from which automatic updates may run, when
+    /// restricted to a time window.
+    pub window_start_hour: Option<u32>,
+    /// Hour (0–23, local time) at which automatic updates stop.
+    pub window_end_hour: Option<u32>,
+    /// Download bandwidth limit in kilobits per second, when set.
+    pub throttle_kbps: Option<u64>,
+}
+
+/// Download-related fields read from an ACF manifest.
+#[derive(Default)]
+struct AppManifest {
+    state_flags: Option<i64>,
+    bytes_to_download: Option<i64>,
+    bytes_downloaded: Option<i64>,
+    /// Name of the app's directory inside the library.
+    install_dir: Option<String>,
+}
+
 /// Interface to the locally installed Steam client.
 ///
 /// Operates on the Steam library directory to check installation state and
@@ -29,26 +95,83 @@ pub enum SteamClientError {
 pub struct SteamClient {
     /// Path to the Steam library `steamapps` directory.
     path: PathBuf,
+    /// `steamapps` directories of every Steam library, including `path`.
+    libraries: Vec<PathBuf>,
+    /// Path to Steam's `registry.vdf`, which records the running app.
+    registry_path: PathBuf,
+    /// Path to Steam's `config.vdf`, which holds the download settings.
+    config_path: PathBuf,
 }
 
 impl SteamClient {
     /// Creates a new `SteamClient` pointing at the given Steam library path.
     ///
     /// `path` should be the `steamapps` directory inside the Steam library
-    /// (e.g. `~/.steam/steam/steamapps`).
-    pub fn new(path: PathBuf) -> Self {
-        SteamClient { path }
+    /// (e.g. `~/.steam/steam/steamapps`) and `registry_path` Steam's
+    /// `registry.vdf` (e.g. `~/.steam/registry.vdf`). Additional libraries
+    /// listed in `libraryfolders.vdf` are picked up as well.
+    pub fn new(path: PathBuf, registry_path: PathBuf) -> Self {
+        let libraries = Self::read_library_folders(&path);
+        let config_path = path
+            .parent()
+            .unwrap_or(&path)
+            .join("config")
+            .join("config.vdf");
+
+        SteamClient {
+            path,
+            libraries,
+            registry_path,
+            config_path,
+        }
+    }
+
+    /// Returns the `steamapps` directories of every known Steam library.
+    pub fn library_paths(&self) -> &[PathBuf] {
+        &self.libraries
+    }
+
+    /// Reads the library folders listed in `libraryfolders.vdf`.
+    ///
+    /// Always includes `path` first, even if the file is missing.
+    fn read_library_folders(path: &Path) -> Vec<PathBuf> {
+        let mut libraries = vec![path.to_path_buf()];
+
+        let content = match fs::read_to_string(path.join("libraryfolders.vdf")) {
+            Ok(contents) => contents,
+            Err(_) => return libraries,
+        };
+
+        for line in content.lines() {
+            let mut parts = line.split_whitespace();
+            if let (Some("\"path\""), Some(value)) = (parts.next(), parts.next()) {
+                let library = PathBuf::from(value.trim_matches('"')).join("steamapps");
+
+                if !libraries.contains(&library) {
+                    libraries.push(library);
+                }
+            }
+        }
+
+        libraries
     }
 
     /// Returns the expected path to the ACF manifest file for a given game.
     ///
     /// Steam stores per-game metadata in `appmanifest_<id>.acf` files inside
-    /// the library's `steamapps` directory.
+    /// the library's `steamapps` directory. The first library holding the
+    /// manifest wins; if none does, the path in the main library is returned.
     fn get_game_manifest_file_path(&self, steam_game_id: &str) -> Result<PathBuf, String> {
-        let mut steam_dir = self.path.clone();
-        steam_dir.push(format!("appmanifest_{}", steam_game_id));
-        steam_dir.set_extension("acf");
-        Ok(steam_dir)
+        let file_name = format!("appmanifest_{}.acf", steam_game_id);
+
+        let manifest = self
+            .libraries
+            .iter()
+            .map(|library| library.join(&file_name))
+            .find(|manifest| manifest.exists())
+            .unwrap_or_else(|| self.path.join(&file_name));
+
+        Ok(manifest)
     }
 
     /// Triggers installation of a Steam game via the `steam://install` protocol.
@@ -68,6 +191,41 @@ impl SteamClient {
         Ok(true)
     }
 
+    /// Launches a Steam game via the `steam://rungameid` protocol.
+    ///
+    /// Opens the URL in the OS default handler, which hands control to the
+    /// running Steam client. Returns `true` if the URL was opened successfully.
+    pub fn launch_game(
+        app_handle: AppHandle,
+        steam_game_id: &str,
+    ) -> Result<bool, SteamClientError> {
+        app_handle
+            .opener()
+            .open_url(format!("steam://rungameid/{}", steam_game_id), None::<&str>)
+            .map_err(|_| SteamClientError::OperationError("unable to launch game".to_string()))?;
+
+        Ok(true)
+    }
+
+    /// Returns `true` if Steam reports the game as currently running.
+    ///
+    /// Reads the `RunningAppID` value Steam keeps up to date in its
+    /// `registry.vdf` file.
+    pub fn is_steam_game_running(&self, game_id: &str) -> bool {
+        let content = match fs::read_to_string(&self.registry_path) {
+            Ok(contents) => contents,
+            Err(_) => return false,
+        };
+
+        content.lines().any(|line| {
+            let mut parts = line.split_whitespace();
+            matches!(
+                (parts.next(), parts.next()),
+                (Some("\"RunningAppID\""), Some(value)) if value.trim_matches('"') == game_id
+            )
+        })
+    }
+
     /// Triggers uninstallation of a Steam game via the `steam://uninstall` protocol.
     ///
     /// Opens the URL in the OS default handler, which hands control to the
@@ -93,47 +251,152 @@ impl SteamClient {
     /// installed only when both values are present and equal, meaning no pending
     /// download remains.
     pub fn is_steam_game_installed(&self, game_id: &str) -> bool {
-        let manifest_file = match self.get_game_manifest_file_path(game_id) {
-            Ok(file) => file,
-            Err(_) => return false,
+        let manifest = match self.read_manifest(game_id) {
+            Some(manifest) => manifest,
+            None => return false,
+        };
+
+        matches!((manifest.bytes_to_download, manifest.bytes_downloaded), (Some(to_dl), Some(downloaded)) if to_dl == downloaded)
+    }
+
+    /// Returns the directory of an installed soundtrack.
+    ///
+    /// Steam installs soundtracks in the `music` directory of the library
+    /// holding their manifest. Returns `None` if the soundtrack is not
+    /// installed.
+    pub fn get_soundtrack_path(&self, store_id: &str) -> Option<PathBuf> {
+        let manifest_file = self.get_game_manifest_file_path(store_id).ok()?;
+        let install_dir = self.read_manifest(store_id)?.install_dir?;
+
+        let path = manifest_file.parent()?.join("music").join(install_dir);
+
+        path.try_exists().unwrap_or(false).then_some(path)
+    }
+
+    /// Returns the install state and download progress of a game.
+    ///
+    /// Reads `StateFlags`, `BytesToDownload`, and `BytesDownloaded` from the
+    /// game's ACF manifest. A missing manifest means the game is not
+    /// installed. `percentage` is `100.0` when there is nothing to download.
+    pub fn get_install_progress(&self, game_id: &str) -> InstallProgress {
+        let manifest = match self.read_manifest(game_id) {
+            Some(manifest) => manifest,
+            None => {
+                return InstallProgress {
+                    state: InstallState::NotInstalled,
+                    bytes_downloaded: None,
+                    bytes_to_download: None,
+                    percentage: 0.0,
+                    schedule: None,
+                }
+            }
         };
 
-        let exist = match manifest_file.try_exists() {
-            Ok(exist) => exist,
-            Err(_) => false,
+        let flags = manifest.state_flags.unwrap_or_default();
+
+        let state = if flags & (STATE_UPDATE_RUNNING | STATE_DOWNLOADING) != 0 {
+            InstallState::Downloading
+        } else if flags & STATE_UPDATE_PAUSED != 0 {
+            InstallState::Paused
+        } else if flags & STATE_UPDATE_REQUIRED != 0 {
+            InstallState::Queued
+        } else if flags & STATE_FULLY_INSTALLED != 0 {
+            InstallState::Installed
+        } else {
+            InstallState::Queued
         };
 
-        if !exist {
-            return false;
+        let percentage = match (manifest.bytes_downloaded, manifest.bytes_to_download) {
+            (Some(downloaded), Some(to_dl)) if to_dl > 0 => {
+                downloaded as f64 / to_dl as f64 * 100.0
+            }
+            _ if state == InstallState::Installed => 100.0,
+            _ => 0.0,
+        };
+
+        InstallProgress {
+            state,
+            bytes_downloaded: manifest.bytes_downloaded,
+            bytes_to_download: manifest.bytes_to_download,
+            percentage,
+            schedule: (state == InstallState::Queued).then(|| self.get_download_schedule()),
         }
+    }
 
-        let content = match fs::read_to_string(manifest_file) {
+    /// Returns the download restrictions set in the Steam client settings.
+    ///
+    /// Reads the auto-update window and bandwidth limit from Steam's
+    /// `config.vdf`. The window only applies to automatic updates; installs
+    /// started by the user are not delayed. Missing or disabled settings are
+    /// `None`.
+    fn get_download_schedule(&self) -> DownloadSchedule {
+        let mut schedule = DownloadSchedule::default();
+
+        let content = match fs::read_to_string(&self.config_path) {
             Ok(contents) => contents,
-            Err(_) => return false,
+            Err(_) => return schedule,
         };
 
-        let mut bytes_to_download: Option<i64> = None;
-        let mut bytes_downloaded: Option<i64> = None;
+        let mut window_enabled = false;
 
         for line in content.lines() {
-            let mut parts = line.trim().split_whitespace();
+            let mut parts = line.split_whitespace();
             if let (Some(property), Some(value)) = (parts.next(), parts.next()) {
+                let value = value.trim_matches('"');
                 match property {
-                    "\"BytesToDownload\"" => {
-                        bytes_to_download = value.trim_matches('"').parse().ok();
-                    }
-                    "\"BytesDownloaded\"" => {
-                        bytes_downloaded = value.trim_matches('"').parse().ok();
+                    "\"AutoUpdateWindowEnabled\"" => window_enabled = value == "1",
+                    "\"AutoUpdateWindowStart\"" => schedule.window_start_hour = value.parse().ok(),
+                    "\"AutoUpdateWindowEnd\"" => schedule.window_end_hour = value.parse().ok(),
+                    "\"DownloadThrottleKbps\"" => {
+                        schedule.throttle_kbps = value.parse().ok().filter(|&kbps| kbps > 0)
                     }
                     _ => continue,
                 }
+            }
+        }
+
+        if !window_enabled {
+            schedule.window_start_hour = None;
+            schedule.window_end_hour = None;
+        }
+
+        schedule
+    }
+
+    /// Reads the download-related fields of a game's ACF manifest.
+    ///
+    /// Returns `None` if the manifest does not exist or cannot be read.
+    fn read_manifest(&self, game_id: &str) -> Option<AppManifest> {
+        let manifest_file = self.get_game_manifest_file_path(game_id).ok()?;
 
-                if bytes_to_download.is_some() && bytes_downloaded.is_some() {
-                    break;
+        if !manifest_file.try_exists().unwrap_or(false) {
+            return None;
+        }
+
+        let content = fs::read_to_string(manifest_file).ok()?;
+
+        let mut manifest = AppManifest::default();
+
+        for line in content.lines() {
+            // Directory names may contain spaces, so the value is the rest of
+            // the line
+            if let Some(install_dir) = line.trim().strip_prefix("\"installdir\"") {
+                manifest.install_dir = Some(install_dir.trim().trim_matches('"').to_string());
+                continue;
+            }
+
+            let mut parts = line.split_whitespace();
+            if let (Some(property), Some(value)) = (parts.next(), parts.next()) {
+                let value = value.trim_matches('"').parse().ok();
+                match property {
+                    "\"StateFlags\"" => manifest.state_flags = value,
+                    "\"BytesToDownload\"" => manifest.bytes_to_download = value,
+                    "\"BytesDownloaded\"" => manifest.bytes_downloaded = value,
+                    _ => continue,
                 }
             }
         }
 
-        matches!((bytes_to_download, bytes_downloaded), (Some(to_dl), Some(downloaded)) if to_dl == downloaded)
+        Some(manifest)
     }
 }
@@ -0,0 +1,96 @@
+//! Watches Steam library folders for install state changes.
+//!
+//! Steam writes an `appmanifest_<id>.acf` file per installed game and updates
+//! it while downloading. Watching these files lets the frontend update
+//! install buttons without polling `get_game`, and lets games installed from
+//! the Steam client directly be imported without waiting for a refresh.
+
+use std::collections::HashSet;
+
+use notify::{Event, RecommendedWatcher, RecursiveMode, Watcher};
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::commands::spawn_game_import;
+
+use super::steam::{InstallProgress, SteamClient};
+
+/// Name of the event emitted when a game's ACF manifest appears, changes, or
+/// disappears.
+pub const INSTALL_CHANGED_EVENT: &str = "steam://install-changed";
+
+/// Payload of the [`INSTALL_CHANGED_EVENT`].
+#[derive(Serialize, Clone)]
+pub struct InstallChanged {
+    /// Steam App ID of the game.
+    pub store_id: String,
+    /// Install state and progress read after the change.
+    pub progress: InstallProgress,
+}
+
+/// Keeps the Steam library watcher alive for as long as it is managed.
+pub struct SteamLibraryWatcher {
+    _watcher: RecommendedWatcher,
+}
+
+impl SteamLibraryWatcher {
+    /// Starts watching the `steamapps` directory of every library known to
+    /// the managed [`SteamClient`].
+    ///
+    /// Libraries that cannot be watched (e.g. an unmounted drive) are
+    /// skipped. The first time a manifest is seen, its game is imported in
+    /// the background if it is not in the library yet.
+    pub fn start(app: AppHandle) -> Result<Self, notify::Error> {
+        let handle = app.clone();
+        let mut seen_store_ids = HashSet::new();
+
+        let mut watcher = notify::recommended_watcher(move |res: notify::Result<Event>| {
+            let Ok(event) = res else {
+                return;
+            };
+
+            emit_install_changes(&handle, &event);
+
+            if event.kind.is_create() || event.kind.is_modify() {
+                for store_id in manifest_store_ids(&event) {
+                    if seen_store_ids.insert(store_id.clone()) {
+                        spawn_game_import(handle.clone(), store_id);
+                    }
+                }
+            }
+        })?;
+
+        for library in app.state::<SteamClient>().library_paths() {
+            let _ = watcher.watch(library, RecursiveMode::NonRecursive);
+        }
+
+        Ok(SteamLibraryWatcher { _watcher: watcher })
+    }
+}
+
+/// Emits an [`INSTALL_CHANGED_EVENT`] for every ACF manifest touched by
+/// `event`.
+fn emit_install_changes(app: &AppHandle, event: &Event) {
+    if event.kind.is_access() {
+        return;
+    }
+
+    let steam_client = app.state::<SteamClient>();
+
+    for store_id in manifest_store_ids(event) {
+        let progress = steam_client.get_install_progress(&store_id);
+
+        let _ = app.emit(INSTALL_CHANGED_EVENT, InstallChanged { store_id, progress });
+    }
+}
+
+/// Returns the Steam App IDs of the ACF manifests touched by `event`.
+fn manifest_store_ids(event: &Event) -> impl Iterator<Item = String> + '_ {
+    event.paths.iter().filter_map(|path| {
+        path.file_name()?
+            .to_str()?
+            .strip_prefix("appmanifest_")?
+            .strip_suffix(".acf")
+            .map(String::from)
+    })
+}
@@ -0,0 +1,131 @@
+//! Tauri commands for Steam achievements.
+//!
+//! Achievements are synced from Steam by combining each game's achievement
+//! schema with the player's unlock state, and read back per game by the
+//! detail page.
+
+use std::collections::HashMap;
+
+use serde::Serialize;
+use tauri::State;
+
+use crate::{
+    db::{
+        achievement::{Achievement, AchievementRepository},
+        game::GameRepository,
+    },
+    service::steam::{SteamApi, SteamError},
+};
+
+use super::game::RocadeError;
+
+/// Outcome of an achievement sync.
+#[derive(Serialize)]
+pub struct AchievementSyncReport {
+    /// Number of games whose achievements were synced.
+    pub synced: usize,
+    /// Number of games whose achievements could not be fetched or stored.
+    pub failed: usize,
+}
+
+/// Syncs achievements from Steam.
+///
+/// Syncs a single game when `game_id` is set, otherwise every game with
+/// recorded playtime; unplayed games have nothing unlocked. A game that fails
+/// to sync is logged and counted as failed without stopping the others.
+#[tauri::command]
+pub async fn sync_achievements(
+    steam_client: State<'_, Box<dyn SteamApi>>,
+    game_repository: State<'_, GameRepository>,
+    achievement_repository: State<'_, AchievementRepository>,
+    game_id: Option<i64>,
+) -> Result<AchievementSyncReport, RocadeError> {
+    let games: Vec<(i64, String)> = match game_id {
+        Some(game_id) => vec![(game_id, game_repository.get_game_store_id(game_id).await?)],
+        None => game_repository
+            .get_games()
+            .await?
+            .into_iter()
+            .filter(|game| game.playtime_forever.unwrap_or_default() > 0)
+            .filter_map(|game| game.store_id.map(|store_id| (game.id, store_id)))
+            .collect(),
+    };
+
+    let mut report = AchievementSyncReport {
+        synced: 0,
+        failed: 0,
+    };
+
+    for (game_id, store_id) in &games {
+        match sync_game_achievements(&**steam_client, &achievement_repository, *game_id, store_id)
+            .await
+        {
+            Ok(()) => report.synced += 1,
+            Err(e) => {
+                tracing::warn!("failed to sync achievements of {store_id}: {e}");
+                report.failed += 1;
+            }
+        }
+    }
+
+    Ok(report)
+}
+
+/// Returns the achievements of a game with their unlock state, unlocked ones
+/// first.
+#[tauri::command]
+pub async fn get_game_achievements(
+    achievement_repository: State<'_, AchievementRepository>,
+    game_id: i64,
+) -> Result<Vec<Achievement>, RocadeError> {
+    Ok(achievement_repository.get_achievements(game_id).await?)
+}
+
+/// Fetches a game's achievement schema and unlock state from Steam and
+/// replaces its stored achievements.
+pub(super) async fn sync_game_achievements(
+    steam_client: &dyn SteamApi,
+    achievement_repository: &AchievementRepository,
+    game_id: i64,
+    store_id: &str,
+) -> Result<(), RocadeError> {
+    let appid: u64 = store_id
+        .parse()
+        .map_err(|_| SteamError::InvalidResponse(format!("invalid app id: {}", store_id)))?;
+
+    let schema = steam_client.get_game_achievements(appid).await?;
+
+    let mut unlocks: HashMap<String, i64> = HashMap::new();
+
+    if !schema.is_empty() {
+        for achievement in steam_client.get_player_achievements(appid).await? {
+            if achievement.achieved == 1 {
+                unlocks.insert(achievement.apiname, achievement.unlocktime);
+            }
+        }
+    }
+
+    let achievements = schema
+        .into_iter()
+        .map(|achievement| {
+            let unlocked_at = unlocks.get(&achievement.name).copied();
+
+            Achievement {
+                achieved: unlocked_at.is_some(),
+                unlocked_at: unlocked_at.filter(|&t| t > 0),
+                api_name: achievement.name,
+                display_name: achievement.display_name,
+                description: achievement.description,
+                icon: achievement.icon,
+                icon_gray: achievement.icongray,
+                hidden: achievement.hidden == 1,
+            }
+        })
+        .collect();
+
+    achievement_repository
+        .replace_achievements(game_id, achievements)
+        .await?;
+
+    Ok(())
+}
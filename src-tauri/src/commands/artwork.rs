@@ -0,0 +1,213 @@
+//! Tauri commands choosing where the artwork of a game comes from.
+//!
+//! IGDB covers are often low quality or missing for smaller titles. For
+//! Steam games, the user can switch to the artwork of SteamGridDB instead:
+//! a vertical grid, a hero banner, and a logo, downloaded once and kept
+//! across library refreshes. Any game can also get a cover or artworks from
+//! the user's own image files.
+
+use std::{
+    path::Path,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use serde::Deserialize;
+use tauri::State;
+use tokio::fs;
+
+use crate::{
+    assets::AssetManager,
+    cache::GameCache,
+    config::RocadeConfigError,
+    db::game::{GameRepository, SteamGridDbArtwork},
+    secrets::{Secret, SecretStore},
+    service::steamgriddb::{SteamGridDbClient, SteamGridDbImageKind},
+};
+
+use super::game::{store_accent_colors, RocadeError};
+
+/// Where the artwork of a game comes from.
+#[derive(Deserialize, Debug, Clone, Copy)]
+pub enum ArtworkSource {
+    /// The IGDB cover and artworks (the default).
+    #[serde(rename = "igdb")]
+    Igdb,
+    /// The top-rated SteamGridDB grid, hero banner, and logo.
+    #[serde(rename = "steamgriddb")]
+    SteamGridDb,
+}
+
+/// Stores the SteamGridDB API key. An empty key counts as none.
+#[tauri::command]
+pub async fn set_steamgriddb_api_key(
+    secret_store: State<'_, SecretStore>,
+    api_key: String,
+) -> Result<(), RocadeError> {
+    secret_store
+        .set(Secret::SteamGridDbApiKey, api_key.trim())
+        .map_err(RocadeConfigError::from)?;
+
+    Ok(())
+}
+
+/// Sets where the artwork of a Steam game comes from.
+///
+/// Switching to SteamGridDB downloads its current artwork right away and
+/// requires an API key, see [`set_steamgriddb_api_key`]. Calling it again
+/// fetches the artwork anew.
+#[tauri::command]
+pub async fn set_artwork_source(
+    game_repository: State<'_, GameRepository>,
+    asset_manager: State<'_, AssetManager>,
+    secret_store: State<'_, SecretStore>,
+    game_cache: State<'_, GameCache>,
+    game_id: i64,
+    source: ArtworkSource,
+) -> Result<(), RocadeError> {
+    let Some(store_id) = game_repository.get_game_by_id(game_id).await?.store_id else {
+        return Err(RocadeConfigError::ConfigError(
+            "only Steam games can use SteamGridDB artwork".to_string(),
+        )
+        .into());
+    };
+
+    match source {
+        ArtworkSource::Igdb => {
+            game_repository
+                .delete_steamgriddb_artwork(&store_id)
+                .await?
+        }
+        ArtworkSource::SteamGridDb => {
+            let api_key = secret_store
+                .get(Secret::SteamGridDbApiKey)
+                .map_err(RocadeConfigError::from)?
+                .filter(|api_key| !api_key.is_empty())
+                .ok_or_else(|| {
+                    RocadeConfigError::ConfigError("no SteamGridDB API key set".to_string())
+                })?;
+            let client = SteamGridDbClient::new(api_key);
+
+            let artwork = SteamGridDbArtwork {
+                grid_path: download(
+                    &client,
+                    &asset_manager,
+                    &store_id,
+                    SteamGridDbImageKind::Grid,
+                )
+                .await?,
+                hero_path: download(
+                    &client,
+                    &asset_manager,
+                    &store_id,
+                    SteamGridDbImageKind::Hero,
+                )
+                .await?,
+                logo_path: download(
+                    &client,
+                    &asset_manager,
+                    &store_id,
+                    SteamGridDbImageKind::Logo,
+                )
+                .await?,
+            };
+
+            game_repository
+                .set_steamgriddb_artwork(&store_id, &artwork)
+                .await?;
+        }
+    }
+
+    game_cache.invalidate(game_id);
+
+    Ok(())
+}
+
+/// Sets an image file chosen by the user as the cover of a game.
+///
+/// The file is copied, so the original can be moved or deleted afterwards.
+/// The cover is kept across library refreshes and re-matches.
+#[tauri::command]
+pub async fn set_custom_cover(
+    game_repository: State<'_, GameRepository>,
+    asset_manager: State<'_, AssetManager>,
+    game_cache: State<'_, GameCache>,
+    game_id: i64,
+    file_path: String,
+) -> Result<(), RocadeError> {
+    let image_id = custom_image_id(game_id);
+    let local_path = asset_manager
+        .copy_custom_image(&image_id, Path::new(&file_path))
+        .await?;
+
+    if let Some(replaced) = game_repository
+        .set_custom_cover(game_id, &image_id, &local_path)
+        .await?
+    {
+        let _ = fs::remove_file(replaced).await;
+    }
+
+    store_accent_colors(
+        &game_repository,
+        &asset_manager,
+        vec![(image_id, local_path)],
+    )
+    .await?;
+
+    game_cache.invalidate(game_id);
+
+    Ok(())
+}
+
+/// Adds an image file chosen by the user to the artworks of a game.
+///
+/// Like [`set_custom_cover`], the file is copied and kept across library
+/// refreshes and re-matches.
+#[tauri::command]
+pub async fn add_custom_artwork(
+    game_repository: State<'_, GameRepository>,
+    asset_manager: State<'_, AssetManager>,
+    game_cache: State<'_, GameCache>,
+    game_id: i64,
+    file_path: String,
+) -> Result<(), RocadeError> {
+    let image_id = custom_image_id(game_id);
+    let local_path = asset_manager
+        .copy_custom_image(&image_id, Path::new(&file_path))
+        .await?;
+
+    game_repository
+        .add_custom_artwork(game_id, &image_id, &local_path)
+        .await?;
+    game_cache.invalidate(game_id);
+
+    Ok(())
+}
+
+/// Returns a new image ID for an image added to a game, unique across
+/// games and over time.
+fn custom_image_id(game_id: i64) -> String {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |now| now.as_millis());
+
+    format!("custom_{}_{}", game_id, now)
+}
+
+/// Downloads the top-rated SteamGridDB image of a kind for a Steam game.
+/// Returns its local path, or `None` if SteamGridDB has none.
+async fn download(
+    client: &SteamGridDbClient,
+    asset_manager: &AssetManager,
+    store_id: &str,
+    kind: SteamGridDbImageKind,
+) -> Result<Option<String>, RocadeError> {
+    let Some(url) = client.get_image_url(store_id, kind).await? else {
+        return Ok(None);
+    };
+
+    Ok(Some(
+        asset_manager
+            .download_steamgriddb_image(store_id, kind.key(), &url)
+            .await?,
+    ))
+}
@@ -0,0 +1,217 @@
+//! Tauri command benchmarking the library refresh pipeline.
+//!
+//! The pipeline runs against recorded fixture data instead of Steam and
+//! IGDB, and inserts into a throwaway in-memory database, so timings are
+//! comparable across releases and the user's library is left untouched.
+//! Each phase is timed and reported to the logs.
+
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+use futures::stream::{self, StreamExt, TryStreamExt};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{
+    assets::AssetError,
+    config::RocadeConfigError,
+    db::{game::GameRepository, DatabaseState},
+    igdb::IgdbGame,
+    service::steam::SteamGame,
+};
+
+use super::{
+    game::{insert_games_with_images, RocadeError},
+    stats::to_player_stats,
+};
+
+/// Recorded Steam and IGDB responses the benchmark is run against.
+const FIXTURE: &str = include_str!("../../fixtures/refresh.json");
+
+/// Size of the placeholder written for each image, close to an IGDB cover.
+const FIXTURE_IMAGE_SIZE: usize = 24 * 1024;
+
+/// Raw fixture records, expanded to the requested sample size before being
+/// deserialized.
+#[derive(Deserialize)]
+struct Fixture {
+    steam_games: Vec<Value>,
+    igdb_games: Vec<Value>,
+}
+
+/// Time spent in each phase of a benchmarked refresh, in milliseconds.
+#[derive(Serialize, Debug)]
+pub struct RefreshBenchmark {
+    /// Number of games refreshed.
+    pub sample_size: usize,
+    /// Building the Steam and IGDB records and the player stats.
+    pub enrichment_ms: u128,
+    /// Writing the images to disk.
+    pub downloads_ms: u128,
+    /// Inserting the games, their image paths and their playtime.
+    pub inserts_ms: u128,
+}
+
+/// Runs the refresh pipeline on `sample_size` games built from the recorded
+/// fixtures and returns the time spent in each phase.
+///
+/// No request is sent: images are replaced by fixed-size placeholders, so
+/// the downloads phase measures disk writes only.
+#[tauri::command]
+pub async fn benchmark_refresh(sample_size: usize) -> Result<RefreshBenchmark, RocadeError> {
+    let fixture: Fixture = serde_json::from_str(FIXTURE).map_err(invalid_fixture)?;
+
+    if fixture.steam_games.is_empty() || fixture.igdb_games.is_empty() {
+        return Err(
+            RocadeConfigError::ConfigError("benchmark fixture is empty".to_string()).into(),
+        );
+    }
+
+    // 1. Enrichment
+    let start = Instant::now();
+    let steam_games: Vec<SteamGame> = expand(&fixture.steam_games, sample_size, |game, appid| {
+        game["appid"] = appid.into();
+    })?;
+    let igdb_games: Vec<IgdbGame> = expand(&fixture.igdb_games, sample_size, |game, appid| {
+        game["store_id"] = appid.to_string().into();
+        suffix_image_id(&mut game["cover"], appid);
+        if let Some(artworks) = game["artworks"].as_array_mut() {
+            artworks
+                .iter_mut()
+                .for_each(|artwork| suffix_image_id(artwork, appid));
+        }
+        if let Some(screenshots) = game["screenshots"].as_array_mut() {
+            screenshots
+                .iter_mut()
+                .for_each(|screenshot| suffix_image_id(screenshot, appid));
+        }
+    })?;
+    let player_stats = to_player_stats(&steam_games);
+    let enrichment = start.elapsed();
+
+    // 2. Downloads
+    let start = Instant::now();
+    let image_dir = std::env::temp_dir().join("rocade-benchmark");
+    tokio::fs::create_dir_all(&image_dir)
+        .await
+        .map_err(AssetError::from)?;
+
+    let mut cover_ids = Vec::new();
+    let mut artwork_ids = Vec::new();
+    let mut screenshot_ids = Vec::new();
+
+    for game in &igdb_games {
+        if let Some(cover) = &game.cover {
+            cover_ids.push(cover.image_id.clone());
+        }
+        if let Some(artworks) = &game.artworks {
+            for artwork in artworks {
+                artwork_ids.push(artwork.image_id.clone());
+            }
+        }
+        if let Some(screenshots) = &game.screenshots {
+            for screenshot in screenshots {
+                screenshot_ids.push(screenshot.image_id.clone());
+            }
+        }
+    }
+
+    let placeholder = vec![0u8; FIXTURE_IMAGE_SIZE];
+    let write_images = |image_ids: Vec<String>| {
+        let image_dir = &image_dir;
+        let placeholder = &placeholder;
+
+        stream::iter(image_ids)
+            .map(move |image_id| async move {
+                let path = image_dir.join(format!("{image_id}.jpg"));
+                tokio::fs::write(&path, placeholder).await?;
+                Ok::<_, std::io::Error>((image_id, path.to_string_lossy().into_owned()))
+            })
+            .buffer_unordered(5)
+            .try_collect::<HashMap<String, String>>()
+    };
+
+    let cover_map = write_images(cover_ids).await.map_err(AssetError::from)?;
+    let artwork_map = write_images(artwork_ids).await.map_err(AssetError::from)?;
+    let screenshot_map = write_images(screenshot_ids)
+        .await
+        .map_err(AssetError::from)?;
+    let downloads = start.elapsed();
+
+    // 3. Inserts
+    let db_state = DatabaseState::in_memory().await?;
+    let game_repository = GameRepository::new(db_state.pool.clone());
+
+    let start = Instant::now();
+    let mut tx = game_repository.begin().await?;
+    insert_games_with_images(
+        &game_repository,
+        &mut tx,
+        igdb_games,
+        cover_map,
+        artwork_map,
+        screenshot_map,
+    )
+    .await?;
+    game_repository
+        .update_player_stats(&mut tx, player_stats)
+        .await?;
+    tx.commit().await?;
+    let inserts = start.elapsed();
+
+    db_state.pool.close().await;
+    let _ = tokio::fs::remove_dir_all(&image_dir).await;
+
+    log_phase("enrichment", enrichment, sample_size);
+    log_phase("downloads", downloads, sample_size);
+    log_phase("inserts", inserts, sample_size);
+
+    Ok(RefreshBenchmark {
+        sample_size,
+        enrichment_ms: enrichment.as_millis(),
+        downloads_ms: downloads.as_millis(),
+        inserts_ms: inserts.as_millis(),
+    })
+}
+
+/// Builds `sample_size` records by cycling through the fixture records.
+///
+/// Each copy is given a distinct Steam App ID by `customize` so that copies
+/// are stored as distinct games.
+fn expand<T: serde::de::DeserializeOwned>(
+    records: &[Value],
+    sample_size: usize,
+    customize: impl Fn(&mut Value, u64),
+) -> Result<Vec<T>, RocadeError> {
+    records
+        .iter()
+        .cycle()
+        .take(sample_size)
+        .enumerate()
+        .map(|(i, record)| {
+            let mut record = record.clone();
+            customize(&mut record, i as u64 + 1);
+            serde_json::from_value(record).map_err(invalid_fixture)
+        })
+        .collect()
+}
+
+/// Appends a Steam App ID to an image's ID so copies get distinct files.
+fn suffix_image_id(image: &mut Value, appid: u64) {
+    if let Some(image_id) = image["image_id"].as_str() {
+        image["image_id"] = format!("{image_id}-{appid}").into();
+    }
+}
+
+fn invalid_fixture(err: serde_json::Error) -> RocadeError {
+    RocadeConfigError::ConfigError(format!("invalid benchmark fixture: {err}")).into()
+}
+
+fn log_phase(phase: &str, elapsed: Duration, sample_size: usize) {
+    tracing::info!(
+        "refresh benchmark: {phase} took {} ms for {sample_size} games",
+        elapsed.as_millis()
+    );
+}
@@ -0,0 +1,63 @@
+//! Tauri commands for organizing the library into collections.
+
+use tauri::State;
+
+use crate::db::collection::{Collection, CollectionRepository};
+
+use super::game::RocadeError;
+
+/// Returns all collections with their games.
+#[tauri::command]
+pub async fn get_collections(
+    collection_repository: State<'_, CollectionRepository>,
+) -> Result<Vec<Collection>, RocadeError> {
+    Ok(collection_repository.get_collections().await?)
+}
+
+/// Creates an empty collection. Returns its database ID.
+#[tauri::command]
+pub async fn create_collection(
+    collection_repository: State<'_, CollectionRepository>,
+    name: String,
+) -> Result<i64, RocadeError> {
+    Ok(collection_repository.insert_collection(&name).await?)
+}
+
+/// Renames a collection.
+#[tauri::command]
+pub async fn rename_collection(
+    collection_repository: State<'_, CollectionRepository>,
+    collection_id: i64,
+    name: String,
+) -> Result<(), RocadeError> {
+    collection_repository
+        .rename_collection(collection_id, &name)
+        .await?;
+    Ok(())
+}
+
+/// Adds a library entry to a collection.
+#[tauri::command]
+pub async fn add_game_to_collection(
+    collection_repository: State<'_, CollectionRepository>,
+    collection_id: i64,
+    game_id: i64,
+) -> Result<(), RocadeError> {
+    collection_repository
+        .add_game(collection_id, game_id)
+        .await?;
+    Ok(())
+}
+
+/// Removes a library entry from a collection.
+#[tauri::command]
+pub async fn remove_game_from_collection(
+    collection_repository: State<'_, CollectionRepository>,
+    collection_id: i64,
+    game_id: i64,
+) -> Result<(), RocadeError> {
+    collection_repository
+        .remove_game(collection_id, game_id)
+        .await?;
+    Ok(())
+}
@@ -0,0 +1,150 @@
+//! Tauri commands for price and deal tracking.
+//!
+//! The prices of wishlist games and of DLC for recently played games are
+//! refreshed at most every few hours. Steam's storefront is rate-limited, so
+//! name and DLC lookups stop at the first failed request and resume on the
+//! next refresh. When Steam cannot be reached, the stored prices are used.
+
+use std::time::Duration;
+
+use tauri::State;
+
+use crate::{
+    db::{
+        deals::{Deal, DealKind, DealRepository},
+        game::GameRepository,
+        settings::{Setting, SettingsRepository},
+    },
+    service::{
+        deals::DealsClient,
+        steam::SteamApi,
+        steam_store::{SteamStoreApi, DEFAULT_LANGUAGE},
+    },
+};
+
+use super::{game::RocadeError, news::is_stale};
+
+/// How long stored prices are served before Steam is queried again.
+const DEALS_TTL: Duration = Duration::from_secs(6 * 60 * 60);
+
+/// How long a game's DLC list is trusted before it is looked up again.
+const DLC_LOOKUP_TTL: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// Number of recently played games whose DLC is tracked.
+const DLC_GAMES_LIMIT: u32 = 20;
+
+/// Returns the wishlist games and DLC of owned games currently on sale,
+/// biggest discount first, with their historical low.
+#[tauri::command]
+pub async fn get_deals(
+    steam_client: State<'_, Box<dyn SteamApi>>,
+    store_client: State<'_, Box<dyn SteamStoreApi>>,
+    deals_client: State<'_, DealsClient>,
+    game_repository: State<'_, GameRepository>,
+    deal_repository: State<'_, DealRepository>,
+    settings_repository: State<'_, SettingsRepository>,
+) -> Result<Vec<Deal>, RocadeError> {
+    if is_stale(deal_repository.get_updated_at().await?, DEALS_TTL) {
+        let language = settings_repository
+            .get(Setting::StoreLanguage)
+            .await?
+            .unwrap_or_else(|| DEFAULT_LANGUAGE.to_string());
+
+        // Offline: fall back to the stored prices
+        let _ = sync_deals(
+            &**steam_client,
+            &**store_client,
+            &deals_client,
+            &game_repository,
+            &deal_repository,
+            &language,
+        )
+        .await;
+    }
+
+    Ok(deal_repository.get_deals().await?)
+}
+
+/// Updates the tracked apps from the wishlist and the DLC of recently played
+/// games, then fetches their current prices.
+async fn sync_deals(
+    steam_client: &dyn SteamApi,
+    store_client: &dyn SteamStoreApi,
+    deals_client: &DealsClient,
+    game_repository: &GameRepository,
+    deal_repository: &DealRepository,
+    language: &str,
+) -> Result<(), RocadeError> {
+    let wishlist = steam_client
+        .get_wishlist()
+        .await?
+        .into_iter()
+        .map(|appid| appid.to_string())
+        .collect();
+
+    deal_repository.retain_wishlist(&wishlist).await?;
+
+    let owned = game_repository.get_store_ids().await?;
+    let tracked = deal_repository.get_tracked_store_ids().await?;
+
+    let mut candidates: Vec<(String, DealKind, Option<String>)> = wishlist
+        .into_iter()
+        .filter(|store_id| !tracked.contains(store_id))
+        .map(|store_id| (store_id, DealKind::Wishlist, None))
+        .collect();
+
+    let looked_up = deal_repository
+        .get_dlc_lookups(DLC_LOOKUP_TTL.as_secs() as i64)
+        .await?;
+
+    for game in game_repository.get_recently_played(DLC_GAMES_LIMIT).await? {
+        let Some(store_id) = game.store_id.filter(|id| !looked_up.contains(id)) else {
+            continue;
+        };
+        let Ok(appid) = store_id.parse() else {
+            continue;
+        };
+
+        let Ok(details) = store_client.get_app_details(appid, language).await else {
+            break;
+        };
+
+        for dlc in details.and_then(|details| details.dlc).unwrap_or_default() {
+            let dlc = dlc.to_string();
+
+            if !owned.contains(&dlc) && !tracked.contains(&dlc) {
+                candidates.push((dlc, DealKind::Dlc, Some(store_id.clone())));
+            }
+        }
+
+        deal_repository.record_dlc_lookup(&store_id).await?;
+    }
+
+    for (store_id, kind, parent_store_id) in candidates {
+        let Ok(appid) = store_id.parse() else {
+            continue;
+        };
+
+        let details = match store_client.get_app_details(appid, language).await {
+            Ok(Some(details)) => details,
+            Ok(None) => continue,
+            Err(_) => break,
+        };
+
+        deal_repository
+            .track(&store_id, &details.name, kind, parent_store_id.as_deref())
+            .await?;
+    }
+
+    let appids: Vec<u64> = deal_repository
+        .get_tracked_store_ids()
+        .await?
+        .iter()
+        .filter_map(|store_id| store_id.parse().ok())
+        .collect();
+
+    let prices = deals_client.get_prices(&appids).await?;
+    deal_repository.update_prices(prices).await?;
+
+    Ok(())
+}
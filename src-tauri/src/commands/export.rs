@@ -0,0 +1,163 @@
+//! Tauri commands exporting and importing the library.
+
+use std::{collections::HashMap, path::Path};
+
+use serde::Serialize;
+use tauri::State;
+
+use crate::{
+    cache::GameCache,
+    db::{collection::CollectionRepository, game::GameRepository},
+    export::{self, ExportFormat, LibraryEntry},
+};
+
+use super::game::RocadeError;
+
+/// Outcome of [`import_library`].
+#[derive(Serialize)]
+pub struct LibraryImport {
+    /// Number of exported games found in the library and merged.
+    pub merged: usize,
+    /// Number of exported games not in the library, left out.
+    pub skipped: usize,
+}
+
+/// Exports all games with their genres, developers, collections, notes,
+/// statuses, ratings, and playtime to a JSON or CSV file at `path`.
+///
+/// Collections are exported as the tags of their games.
+#[tauri::command]
+pub async fn export_library(
+    game_repository: State<'_, GameRepository>,
+    collection_repository: State<'_, CollectionRepository>,
+    format: ExportFormat,
+    path: String,
+) -> Result<(), RocadeError> {
+    let mut notes = game_repository.get_all_notes().await?;
+
+    let mut tags: HashMap<i64, Vec<String>> = HashMap::new();
+    for collection in collection_repository.get_collections().await? {
+        for game_id in collection.game_ids {
+            tags.entry(game_id)
+                .or_default()
+                .push(collection.name.clone());
+        }
+    }
+
+    let entries: Vec<LibraryEntry> = game_repository
+        .get_games()
+        .await?
+        .into_iter()
+        .map(|game| LibraryEntry {
+            store_id: game.store_id,
+            genres: game.genres.unwrap_or_default(),
+            developers: game.developers.unwrap_or_default(),
+            tags: tags.remove(&game.id).unwrap_or_default(),
+            notes: notes.remove(&game.id),
+            status: game.status,
+            user_rating: game.user_rating,
+            is_favorite: game.is_favorite,
+            hidden: game.hidden,
+            playtime_minutes: game.playtime_forever,
+            last_played_at: game.last_played_at,
+            name: game.name,
+        })
+        .collect();
+
+    export::export(Path::new(&path), format, &entries).await?;
+
+    Ok(())
+}
+
+/// Merges the curation of a JSON export at `path` into the library.
+///
+/// Exported games are matched to library games by Steam store ID, or by
+/// name for custom games, so importing twice changes nothing. The notes,
+/// status, and rating of a matched game are replaced by the exported ones
+/// when set; it is marked favorite or hidden when it was exported as such,
+/// and added to the collections it was tagged with, which are created if
+/// missing. Exported games not in the library are skipped.
+#[tauri::command]
+pub async fn import_library(
+    game_repository: State<'_, GameRepository>,
+    collection_repository: State<'_, CollectionRepository>,
+    game_cache: State<'_, GameCache>,
+    path: String,
+) -> Result<LibraryImport, RocadeError> {
+    let entries = export::import(Path::new(&path)).await?;
+
+    let mut by_store_id = HashMap::new();
+    let mut by_name = HashMap::new();
+    for game in game_repository.get_games().await? {
+        match game.store_id {
+            Some(store_id) => by_store_id.insert(store_id, game.id),
+            None => by_name.insert(game.name.to_lowercase(), game.id),
+        };
+    }
+
+    let mut collection_ids: HashMap<String, i64> = collection_repository
+        .get_collections()
+        .await?
+        .into_iter()
+        .map(|collection| (collection.name, collection.id))
+        .collect();
+
+    let mut merged = 0;
+
+    for entry in &entries {
+        let game_id = match &entry.store_id {
+            Some(store_id) => by_store_id.get(store_id),
+            None => by_name.get(&entry.name.to_lowercase()),
+        };
+        let Some(&game_id) = game_id else {
+            continue;
+        };
+
+        if entry.notes.is_some() {
+            game_repository
+                .set_notes(game_id, entry.notes.as_deref())
+                .await?;
+        }
+        if entry.status.is_some() {
+            game_repository.set_status(game_id, entry.status).await?;
+        }
+        if let Some(rating) = entry
+            .user_rating
+            .and_then(|rating| u8::try_from(rating).ok())
+            .filter(|rating| *rating <= 10)
+        {
+            game_repository
+                .set_user_rating(game_id, Some(rating))
+                .await?;
+        }
+        if entry.is_favorite {
+            game_repository.set_favorite(game_id, true).await?;
+        }
+        if entry.hidden {
+            game_repository.set_hidden(game_id, true).await?;
+        }
+
+        for tag in &entry.tags {
+            let collection_id = match collection_ids.get(tag) {
+                Some(&collection_id) => collection_id,
+                None => {
+                    let collection_id = collection_repository.insert_collection(tag).await?;
+                    collection_ids.insert(tag.clone(), collection_id);
+                    collection_id
+                }
+            };
+            collection_repository
+                .add_game(collection_id, game_id)
+                .await?;
+        }
+
+        merged += 1;
+    }
+
+    game_cache.clear();
+
+    Ok(LibraryImport {
+        merged,
+        skipped: entries.len() - merged,
+    })
+}
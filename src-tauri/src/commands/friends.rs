@@ -0,0 +1,105 @@
+//! Tauri commands for comparing the library with Steam friends.
+//!
+//! Friends' libraries are fetched on demand from the Steam Web API and are
+//! not stored. Friends with private game details are listed without games.
+
+use futures::stream::{self, StreamExt};
+use serde::Serialize;
+use tauri::State;
+
+use crate::{
+    db::game::{GameRepository, GameSummary},
+    service::steam::{SteamApi, SteamError},
+};
+
+use super::game::RocadeError;
+
+/// Maximum number of friend libraries fetched concurrently.
+const CONCURRENT_LIBRARY_REQUESTS: usize = 5;
+
+/// A Steam friend and the games they own.
+#[derive(Serialize)]
+pub struct FriendLibrary {
+    /// SteamID64 of the friend.
+    pub steam_id: String,
+    /// Display name, `None` if Steam did not return a profile.
+    pub name: Option<String>,
+    /// URL of the friend's avatar.
+    pub avatar: Option<String>,
+    /// Steam App IDs of the owned games, `None` if the friend's game
+    /// details are private.
+    pub store_ids: Option<Vec<String>>,
+}
+
+/// Returns the configured profile's Steam friends with their owned games.
+///
+/// Requires the profile's friend list to be public.
+#[tauri::command]
+pub async fn get_friend_libraries(
+    steam_client: State<'_, Box<dyn SteamApi>>,
+) -> Result<Vec<FriendLibrary>, RocadeError> {
+    let steam_ids: Vec<String> = steam_client
+        .get_friends()
+        .await?
+        .into_iter()
+        .map(|friend| friend.steamid)
+        .collect();
+
+    let summaries = steam_client.get_player_summaries(&steam_ids).await?;
+
+    let steam_client = &*steam_client;
+    let libraries: Vec<Result<FriendLibrary, SteamError>> = stream::iter(steam_ids)
+        .map(|steam_id| {
+            let summary = summaries.iter().find(|s| s.steamid == steam_id);
+            let name = summary.map(|s| s.personaname.clone());
+            let avatar = summary.and_then(|s| s.avatarmedium.clone());
+
+            async move {
+                let store_ids = steam_client
+                    .get_owned_games(&steam_id)
+                    .await?
+                    .map(|games| games.iter().map(|game| game.appid.to_string()).collect());
+
+                Ok(FriendLibrary {
+                    steam_id,
+                    name,
+                    avatar,
+                    store_ids,
+                })
+            }
+        })
+        .buffer_unordered(CONCURRENT_LIBRARY_REQUESTS)
+        .collect()
+        .await;
+
+    let mut libraries = libraries.into_iter().collect::<Result<Vec<_>, _>>()?;
+    libraries.sort_by_key(|library| library.name.clone().unwrap_or_default().to_lowercase());
+
+    Ok(libraries)
+}
+
+/// Returns the games of the local library that a friend also owns, ordered
+/// by name.
+///
+/// # Errors
+///
+/// Returns [`SteamError::ProfileUnavailable`] if the friend's game details
+/// are private.
+#[tauri::command]
+pub async fn get_common_games(
+    steam_client: State<'_, Box<dyn SteamApi>>,
+    game_repository: State<'_, GameRepository>,
+    friend_steam_id: String,
+) -> Result<Vec<GameSummary>, RocadeError> {
+    let store_ids: Vec<String> = steam_client
+        .get_owned_games(&friend_steam_id)
+        .await?
+        .ok_or(SteamError::ProfileUnavailable(friend_steam_id))?
+        .iter()
+        .map(|game| game.appid.to_string())
+        .collect();
+
+    Ok(game_repository
+        .get_summaries_by_store_ids(&store_ids)
+        .await?)
+}
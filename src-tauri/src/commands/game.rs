@@ -5,26 +5,79 @@
 //! retrieving a single game with its install status, and triggering
 //! Steam install/uninstall actions.
 
-use std::collections::{HashMap, HashSet};
+use std::{
+    cmp::{Ordering, Reverse},
+    collections::{HashMap, HashSet},
+    path::Path,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
 
 use crate::{
-    assets::{AssetError, AssetManager},
-    client::steam::{SteamClient, SteamClientError},
+    archive::ArchiveError,
+    assets::{AssetError, AssetManager, CoverUpscaling},
+    bundle::BundleError,
+    cache::GameCache,
+    client::steam::{InstallProgress, SteamClient, SteamClientError},
+    config::RocadeConfigError,
+    controller::ControllerError,
     db::{
-        game::{Game, GameRepository},
-        DatabaseState,
+        achievement::AchievementRepository,
+        collection::CollectionRepository,
+        company::CompanyRepository,
+        custom::CustomGameRepository,
+        game::{
+            Facet, Game, GameCursor, GameFilters, GameImage, GameRepository, GameSort, GameStatus,
+            GameSummary, GamesWindow, InstallSource, MetadataOverride, UserFlag,
+        },
+        genre::GenreRepository,
+        history::{HistoryEvent, HistoryRepository},
+        hook::HookRepository,
+        launch::LaunchSettingsRepository,
+        playtime::PlaytimeRepository,
+        refresh_report::{ChangedGame, RefreshReport, RefreshReportRepository, ReportedGame},
+        rule::ExclusionRuleRepository,
+        session::SessionRepository,
+        settings::{Setting, SettingsRepository},
+        subscription::SubscriptionRepository,
+        translation::TranslationRepository,
+        BackupError, DatabaseState,
+    },
+    export::ExportError,
+    hooks::{run_hooks, HookEvent},
+    igdb::{IgdbApi, IgdbError, IgdbGame},
+    logging::LogError,
+    retry::is_transient,
+    rules::RuleError,
+    secrets::SecretStore,
+    service::{
+        steam::{SteamApi, SteamError},
+        steam_store::{AppDetails, SteamStoreApi, DEFAULT_LANGUAGE},
+        steamgriddb::SteamGridDbError,
     },
-    igdb::{IgdbApiClient, IgdbError, IgdbGame},
-    service::steam::{SteamApiClient, SteamError},
+    shutdown::RefreshTracker,
+    twitch::TwitchError,
+    wine::WineError,
 };
 use serde::{Deserialize, Serialize};
-use tauri::{async_runtime::Mutex, AppHandle, State};
+use sqlx::{Sqlite, Transaction};
+use tauri::{async_runtime::Mutex, AppHandle, Manager, Runtime, State};
+use tauri_plugin_opener::OpenerExt;
 use thiserror::Error;
 
+use super::{
+    hooks::trigger_hooks, news::is_stale, parental::max_age_rating, protondb::spawn_protondb_sync,
+    rules::apply_exclusion_rules, stats::to_player_stats,
+};
+
+/// How long a translated summary is trusted before the store page is
+/// fetched again.
+const TRANSLATION_TTL: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+
 /// Top-level error type returned by all Tauri commands in this module.
 ///
-/// Serialized as a plain string message so the frontend receives a
-/// human-readable error rather than a structured object.
+/// Serialized as an [`ErrorPayload`], so the frontend can tell causes apart
+/// by their [`kind`](Self::kind) while still showing a human-readable
+/// message.
 #[derive(Debug, Error)]
 pub enum RocadeError {
     /// A database operation failed.
@@ -42,6 +95,142 @@ pub enum RocadeError {
     /// An asset management operation failed.
     #[error("asset error: {0}")]
     Asset(#[from] AssetError),
+    /// Saving or applying the application configuration failed.
+    #[error("config error: {0}")]
+    Config(#[from] RocadeConfigError),
+    /// An exclusion rule is invalid.
+    #[error("rule error: {0}")]
+    Rule(#[from] RuleError),
+    /// A Wine or Proton operation failed.
+    #[error("wine error: {0}")]
+    Wine(#[from] WineError),
+    /// A controller-remapping profile could not be loaded.
+    #[error("controller profile error: {0}")]
+    Controller(#[from] ControllerError),
+    /// A custom game could not be started.
+    #[error("unable to launch game: {0}")]
+    Launch(#[from] std::io::Error),
+    /// A custom game bundle could not be exported or imported.
+    #[error("bundle error: {0}")]
+    Bundle(#[from] BundleError),
+    /// A SteamGridDB API operation failed.
+    #[error("steamgriddb error: {0}")]
+    SteamGridDb(#[from] SteamGridDbError),
+    /// The library could not be exported.
+    #[error("export error: {0}")]
+    Export(#[from] ExportError),
+    /// A full backup archive could not be created or restored.
+    #[error("archive error: {0}")]
+    Archive(#[from] ArchiveError),
+    /// A database backup could not be made or restored.
+    #[error("backup error: {0}")]
+    Backup(#[from] BackupError),
+    /// The logs could not be read.
+    #[error("log error: {0}")]
+    Log(#[from] LogError),
+    /// Two games owned on Steam cannot be merged, as the next refresh would
+    /// insert the removed one again.
+    #[error("unable to merge two games owned on Steam")]
+    MergeOwnedGames,
+    /// The refresh was stopped before replacing the library, as the app is
+    /// exiting.
+    #[error("refresh cancelled")]
+    RefreshCancelled,
+}
+
+impl RocadeError {
+    /// Returns a stable code identifying the cause of the error, so the
+    /// frontend can react to it without parsing the message.
+    ///
+    /// HTTP requests that failed share the `network` code, whatever the
+    /// service; [`Self::is_retryable`] tells whether it may answer later.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            RocadeError::Database(_) => "database",
+            RocadeError::Steam(SteamError::Request(_)) => "network",
+            RocadeError::Steam(SteamError::Unauthorized) => "steam_unauthorized",
+            RocadeError::Steam(SteamError::ProfileUnavailable(_)) => "steam_profile_unavailable",
+            RocadeError::Steam(_) => "steam_invalid_response",
+            RocadeError::SteamLocalClient(_) => "steam_client",
+            RocadeError::Igdb(
+                IgdbError::Request(_) | IgdbError::Twitch(TwitchError::Request(_)),
+            ) => "network",
+            RocadeError::Igdb(IgdbError::Twitch(TwitchError::Unauthorized(_))) => {
+                "igdb_unauthorized"
+            }
+            RocadeError::Igdb(IgdbError::RateLimited) => "igdb_rate_limited",
+            RocadeError::Igdb(IgdbError::NoData(_)) => "igdb_not_found",
+            RocadeError::Igdb(IgdbError::Client(_)) => "igdb_client",
+            RocadeError::Igdb(_) => "igdb_invalid_response",
+            RocadeError::Asset(AssetError::Request(_)) => "network",
+            RocadeError::Asset(AssetError::DownloadFailed(..)) => "download_failed",
+            RocadeError::Asset(AssetError::Cancelled) => "cancelled",
+            RocadeError::Asset(AssetError::UnsupportedImage(_)) => "unsupported_image",
+            RocadeError::Asset(_) => "asset",
+            RocadeError::Config(_) => "config",
+            RocadeError::Rule(_) => "invalid_rule",
+            RocadeError::Wine(_) => "wine",
+            RocadeError::Controller(_) => "controller",
+            RocadeError::Launch(_) => "launch",
+            RocadeError::Bundle(_) => "bundle",
+            RocadeError::SteamGridDb(SteamGridDbError::Request(_)) => "network",
+            RocadeError::SteamGridDb(SteamGridDbError::Unauthorized) => "steamgriddb_unauthorized",
+            RocadeError::SteamGridDb(_) => "steamgriddb_invalid_response",
+            RocadeError::Export(_) => "export",
+            RocadeError::Archive(_) => "archive",
+            RocadeError::Backup(BackupError::NotFound(_) | BackupError::NoBackup) => {
+                "backup_not_found"
+            }
+            RocadeError::Backup(BackupError::NotRefreshBackup) => "backup_not_from_refresh",
+            RocadeError::Backup(_) => "backup",
+            RocadeError::Log(_) => "log",
+            RocadeError::MergeOwnedGames => "merge_owned_games",
+            RocadeError::RefreshCancelled => "cancelled",
+        }
+    }
+
+    /// Returns `true` if running the command again later may succeed: a
+    /// service could not be reached, timed out, or was overloaded, or the
+    /// database was busy.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            RocadeError::Database(e) => is_database_busy(e),
+            RocadeError::Steam(SteamError::Request(e))
+            | RocadeError::Igdb(
+                IgdbError::Request(e) | IgdbError::Twitch(TwitchError::Request(e)),
+            )
+            | RocadeError::Asset(AssetError::Request(e))
+            | RocadeError::SteamGridDb(SteamGridDbError::Request(e)) => is_transient(e),
+            RocadeError::Igdb(IgdbError::RateLimited)
+            | RocadeError::Asset(AssetError::DownloadFailed(..)) => true,
+            _ => false,
+        }
+    }
+}
+
+/// Returns `true` if a database operation failed because the database was
+/// locked by another connection, or no connection was free in time.
+fn is_database_busy(error: &sqlx::Error) -> bool {
+    match error {
+        sqlx::Error::PoolTimedOut => true,
+        // SQLITE_BUSY and SQLITE_LOCKED, with their extended codes
+        sqlx::Error::Database(e) => e
+            .code()
+            .and_then(|code| code.parse::<i32>().ok())
+            .is_some_and(|code| matches!(code & 0xff, 5 | 6)),
+        _ => false,
+    }
+}
+
+/// A [`RocadeError`] as received by the frontend.
+#[derive(Serialize)]
+struct ErrorPayload {
+    /// Stable code of the cause, see [`RocadeError::kind`].
+    kind: &'static str,
+    /// Human-readable description of the error.
+    message: String,
+    /// Whether running the command again later may succeed.
+    retryable: bool,
 }
 
 impl Serialize for RocadeError {
@@ -49,47 +238,534 @@ impl Serialize for RocadeError {
     where
         S: serde::Serializer,
     {
-        serializer.serialize_str(&self.to_string())
+        ErrorPayload {
+            kind: self.kind(),
+            message: self.to_string(),
+            retryable: self.is_retryable(),
+        }
+        .serialize(serializer)
     }
 }
 
 /// Optional filter parameters accepted by [`get_games`].
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Debug, Default)]
 pub struct GameQuery {
     /// When set, only games whose name matches this string are returned.
     name: Option<String>,
+    /// Sort order of the returned games. Defaults to [`GameSort::Name`].
+    sort_by: Option<GameSort>,
+    /// When `true`, only favorite games are returned.
+    #[serde(default)]
+    favorites_only: bool,
+    /// When `true`, games hidden by the user are returned too.
+    #[serde(default)]
+    include_hidden: bool,
+    /// When `true`, only the games no longer owned on Steam are returned;
+    /// otherwise they are left out.
+    #[serde(default)]
+    no_longer_owned: bool,
+    /// When set, only games with this backlog status are returned.
+    status: Option<GameStatus>,
+    /// When set, only games with this IGDB game mode (e.g. "Split screen")
+    /// are returned. Case-insensitive.
+    game_mode: Option<String>,
+    /// When set, only games with this IGDB player perspective (e.g. "First
+    /// person") are returned. Case-insensitive.
+    player_perspective: Option<String>,
+    /// Parental PIN. When it matches, games above the mature-content
+    /// filter's age limit are returned too.
+    pin: Option<String>,
+}
+
+/// Returns `true` if `filter` is unset or is one of `values`, ignoring case.
+fn matches_tag(values: &Option<Vec<String>>, filter: &Option<String>) -> bool {
+    filter.as_ref().is_none_or(|filter| {
+        values
+            .iter()
+            .flatten()
+            .any(|value| value.eq_ignore_ascii_case(filter))
+    })
+}
+
+/// Number of games from which search pre-filters apply. Smaller libraries
+/// always score every title.
+const PREFILTER_MIN_LIBRARY_SIZE: usize = 2000;
+
+/// Cheap checks skipping titles before trigram scoring in fuzzy search.
+///
+/// They can drop weak fuzzy matches, so they only apply to libraries of at
+/// least [`PREFILTER_MIN_LIBRARY_SIZE`] games. Substring matches are never
+/// filtered out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SearchPrefilter {
+    /// The title must start with the same trigram as the query, i.e. the
+    /// same first character.
+    FirstTrigram,
+    /// The title length must be between half and three times the query
+    /// length.
+    LengthWindow,
+}
+
+impl SearchPrefilter {
+    /// Parses the [`Setting::SearchPrefilters`] value. Unknown names are
+    /// ignored; an unset value enables every pre-filter.
+    fn from_setting(value: Option<&str>) -> Vec<Self> {
+        let Some(value) = value else {
+            return vec![SearchPrefilter::FirstTrigram, SearchPrefilter::LengthWindow];
+        };
+
+        value
+            .split(',')
+            .filter_map(|name| match name.trim() {
+                "first_trigram" => Some(SearchPrefilter::FirstTrigram),
+                "length_window" => Some(SearchPrefilter::LengthWindow),
+                _ => None,
+            })
+            .collect()
+    }
 }
 
-/// Returns all games in the local database, optionally filtered by name.
+/// Fuzzy name matcher used by [`get_games`].
 ///
-/// Filtering applies a case-insensitive substring check first; if that
-/// fails, a trigram similarity score above `0.4` is used as a fallback
-/// for fuzzy matching.
+/// Computes the query's trigrams once instead of once per title.
+struct NameMatcher {
+    query: String,
+    query_len: usize,
+    trigrams: HashSet<String>,
+    prefilters: Vec<SearchPrefilter>,
+}
+
+impl NameMatcher {
+    fn new(query: &str, prefilters: Vec<SearchPrefilter>) -> Self {
+        let query = query.to_ascii_lowercase();
+
+        NameMatcher {
+            query_len: query.chars().count(),
+            trigrams: trigrams(&query),
+            query,
+            prefilters,
+        }
+    }
+
+    /// Returns `true` if the title contains the query or is similar enough.
+    fn matches(&self, name: &str) -> bool {
+        let name = name.to_ascii_lowercase();
+
+        if name.contains(&self.query) {
+            return true;
+        }
+
+        let passes_prefilters = self.prefilters.iter().all(|prefilter| match prefilter {
+            SearchPrefilter::FirstTrigram => name.chars().next() == self.query.chars().next(),
+            SearchPrefilter::LengthWindow => {
+                let name_len = name.chars().count();
+                name_len * 2 >= self.query_len && name_len <= self.query_len * 3
+            }
+        });
+
+        passes_prefilters && similarity(&self.trigrams, &name) > 0.4
+    }
+}
+
+/// Returns all games in the local database, optionally filtered by name or
+/// to favorites, and sorted. Games hidden by an exclusion rule are left out,
+/// as are games hidden by the user unless `include_hidden` is set. Games no
+/// longer owned on Steam are only returned, alone, when `no_longer_owned` is
+/// set. Games can also be filtered by backlog status, game mode, and player
+/// perspective.
+/// Games rated above the age limit of the mature-content filter are left out
+/// unless the query carries the parental PIN. Pinned games come first, in pin
+/// order, whatever the sort.
+///
+/// Filtering applies to the name and the IGDB alternative names (e.g. "GTA
+/// V"). A case-insensitive substring check comes first; if that fails, a
+/// trigram similarity score above `0.4` is used as a fallback for fuzzy
+/// matching. On large libraries, the pre-filters configured in
+/// [`Setting::SearchPrefilters`] skip unlikely titles before scoring. Games
+/// without playtime or rating sort last when sorting by either.
 #[tauri::command]
 pub async fn get_games(
     game_repository: State<'_, GameRepository>,
+    settings_repository: State<'_, SettingsRepository>,
+    secret_store: State<'_, SecretStore>,
     query: Option<GameQuery>,
 ) -> Result<Vec<Game>, RocadeError> {
     let mut games = game_repository.get_games().await?;
 
-    if let Some(name) = query.and_then(|q| q.name) {
-        games = games
-            .into_iter()
-            .filter(|game| {
-                let game_name_lower = game.name.to_ascii_lowercase();
+    let query = query.unwrap_or_default();
+    let max_age = max_age_rating(&settings_repository, &secret_store, query.pin.as_deref()).await?;
 
-                if game_name_lower.contains(&name.to_ascii_lowercase()) {
-                    return true;
-                }
+    games.retain(|game| {
+        !game.excluded
+            && (game.is_favorite || !query.favorites_only)
+            && (!game.hidden || query.include_hidden)
+            && game.removed_at.is_some() == query.no_longer_owned
+            && query
+                .status
+                .is_none_or(|status| game.status == Some(status))
+            && matches_tag(&game.game_modes, &query.game_mode)
+            && matches_tag(&game.player_perspectives, &query.player_perspective)
+            && max_age.is_none_or(|max_age| game.minimum_age.is_none_or(|age| age <= max_age))
+    });
 
-                similarity(&name.to_ascii_lowercase(), &game.name.to_ascii_lowercase()) > 0.4
-            })
-            .collect();
+    match query.sort_by.unwrap_or(GameSort::Name) {
+        // Already ordered by name by the repository
+        GameSort::Name => {}
+        GameSort::Playtime => games.sort_by_key(|game| Reverse(game.playtime_forever)),
+        GameSort::RecentPlaytime => games.sort_by_key(|game| Reverse(game.playtime_2weeks)),
+        GameSort::UserRating => games.sort_by_key(|game| Reverse(game.user_rating)),
+        GameSort::Rating => games.sort_by(|a, b| {
+            b.aggregated_rating
+                .partial_cmp(&a.aggregated_rating)
+                .unwrap_or(Ordering::Equal)
+        }),
+    }
+
+    // Stable: unpinned games keep the requested order
+    games.sort_by_key(|game| (game.pin_position.is_none(), game.pin_position));
+
+    if let Some(name) = query.name {
+        let prefilters = if games.len() >= PREFILTER_MIN_LIBRARY_SIZE {
+            SearchPrefilter::from_setting(
+                settings_repository
+                    .get(Setting::SearchPrefilters)
+                    .await?
+                    .as_deref(),
+            )
+        } else {
+            Vec::new()
+        };
+
+        let matcher = NameMatcher::new(&name, prefilters);
+
+        games.retain(|game| {
+            matcher.matches(&game.name)
+                || game
+                    .alternative_names
+                    .iter()
+                    .flatten()
+                    .any(|alternative_name| matcher.matches(alternative_name))
+        });
     }
 
     Ok(games)
 }
 
+/// Returns a window of slim game summaries for virtual scrolling.
+///
+/// Uses keyset pagination: pass the `next` cursor of the previous window as
+/// `start` to continue, or `None` for the first window. Unlike
+/// [`get_games`], the name filter is a plain case-insensitive substring
+/// match so it can run in SQL.
+#[tauri::command]
+pub async fn get_games_window(
+    game_repository: State<'_, GameRepository>,
+    start: Option<GameCursor>,
+    count: u32,
+    sort: Option<GameSort>,
+    filters: Option<GameFilters>,
+) -> Result<GamesWindow, RocadeError> {
+    Ok(game_repository
+        .get_games_window(
+            start,
+            count,
+            sort.unwrap_or(GameSort::Name),
+            filters.unwrap_or_default(),
+        )
+        .await?)
+}
+
+/// Returns the genres of the library with their number of games, for the
+/// genre filter.
+///
+/// Excluded, hidden, and no longer owned games are not counted, here and in
+/// [`get_all_developers`] and [`get_all_release_years`].
+#[tauri::command]
+pub async fn get_all_genres(
+    game_repository: State<'_, GameRepository>,
+) -> Result<Vec<Facet<String>>, RocadeError> {
+    Ok(game_repository.get_genre_facets().await?)
+}
+
+/// Returns the developers of the library with their number of games, for
+/// the developer filter.
+#[tauri::command]
+pub async fn get_all_developers(
+    game_repository: State<'_, GameRepository>,
+) -> Result<Vec<Facet<String>>, RocadeError> {
+    Ok(game_repository.get_developer_facets().await?)
+}
+
+/// Returns the release years of the library with their number of games,
+/// most recent first, for the release year filter.
+#[tauri::command]
+pub async fn get_all_release_years(
+    game_repository: State<'_, GameRepository>,
+) -> Result<Vec<Facet<i64>>, RocadeError> {
+    Ok(game_repository.get_release_year_facets().await?)
+}
+
+/// Picks a random game matching `filters`, for when the user cannot decide
+/// what to play. Returns `None` when no game matches.
+///
+/// Installed games are those found in the local Steam library, or custom
+/// games whose executable exists.
+#[tauri::command]
+pub async fn pick_random_game(
+    game_repository: State<'_, GameRepository>,
+    custom_game_repository: State<'_, CustomGameRepository>,
+    steam_client: State<'_, SteamClient>,
+    filters: Option<GameFilters>,
+) -> Result<Option<GameSummary>, RocadeError> {
+    let filters = filters.unwrap_or_default();
+    let games = game_repository.get_shuffled_games(&filters).await?;
+
+    if !filters.installed {
+        return Ok(games.into_iter().next());
+    }
+
+    for game in games {
+        let is_installed = match &game.store_id {
+            Some(store_id) => steam_client.is_steam_game_installed(store_id),
+            None => custom_game_repository
+                .get_executable(game.id)
+                .await?
+                .is_some_and(|executable| Path::new(&executable).exists()),
+        };
+
+        if is_installed {
+            return Ok(Some(game));
+        }
+    }
+
+    Ok(None)
+}
+
+/// Pins a game to the favorites shelf, or unpins it if it already is.
+/// Returns whether the game is now a favorite.
+#[tauri::command]
+pub async fn toggle_favorite(
+    game_repository: State<'_, GameRepository>,
+    game_cache: State<'_, GameCache>,
+    game_id: i64,
+) -> Result<bool, RocadeError> {
+    let is_favorite = game_repository.toggle_favorite(game_id).await?;
+    game_cache.invalidate(game_id);
+
+    Ok(is_favorite)
+}
+
+/// Hides a game from the library, or shows it again.
+///
+/// Hidden games are left out of [`get_games`] unless requested, and out of
+/// windows and shelves.
+#[tauri::command]
+pub async fn set_game_hidden(
+    game_repository: State<'_, GameRepository>,
+    game_cache: State<'_, GameCache>,
+    game_id: i64,
+    hidden: bool,
+) -> Result<(), RocadeError> {
+    game_repository.set_hidden(game_id, hidden).await?;
+    game_cache.invalidate(game_id);
+
+    Ok(())
+}
+
+/// Returns the personal notes of a game, or `None` if it has none.
+#[tauri::command]
+pub async fn get_game_notes(
+    game_repository: State<'_, GameRepository>,
+    game_id: i64,
+) -> Result<Option<String>, RocadeError> {
+    Ok(game_repository.get_notes(game_id).await?)
+}
+
+/// Stores personal notes (save locations, mods, reminders, ...) with a game.
+/// Empty notes are cleared. Notes are kept across library refreshes.
+#[tauri::command]
+pub async fn set_game_notes(
+    game_repository: State<'_, GameRepository>,
+    game_id: i64,
+    notes: String,
+) -> Result<(), RocadeError> {
+    let notes = Some(notes.as_str()).filter(|notes| !notes.trim().is_empty());
+    game_repository.set_notes(game_id, notes).await?;
+
+    Ok(())
+}
+
+/// Sets the backlog status of a game, or clears it with `None`, and records
+/// the change in the history. Statuses are kept across library refreshes.
+#[tauri::command]
+pub async fn set_game_status(
+    game_repository: State<'_, GameRepository>,
+    history_repository: State<'_, HistoryRepository>,
+    game_cache: State<'_, GameCache>,
+    game_id: i64,
+    status: Option<GameStatus>,
+) -> Result<(), RocadeError> {
+    game_repository.set_status(game_id, status).await?;
+    history_repository
+        .record(
+            HistoryEvent::StatusChanged,
+            game_id,
+            status.as_ref().map(GameStatus::key),
+        )
+        .await?;
+    game_cache.invalidate(game_id);
+
+    Ok(())
+}
+
+/// Corrects the name, summary, release date, or cover of a game.
+///
+/// `metadata` holds every corrected field; fields left to `None` are not
+/// overridden. Corrections of Steam games are kept across refreshes and
+/// re-matches instead of being reverted to the IGDB or store page data. A
+/// new cover, given as an IGDB image ID, is downloaded right away.
+#[tauri::command]
+pub async fn edit_game_metadata(
+    game_repository: State<'_, GameRepository>,
+    asset_manager: State<'_, AssetManager>,
+    game_cache: State<'_, GameCache>,
+    game_id: i64,
+    metadata: MetadataOverride,
+) -> Result<(), RocadeError> {
+    let new_cover = game_repository
+        .set_metadata_override(game_id, &metadata)
+        .await?;
+    game_cache.invalidate(game_id);
+
+    if let Some(cover_id) = new_cover {
+        let cover_paths = asset_manager.download_batch_covers(vec![cover_id]).await?;
+
+        for (cover_id, local_path) in &cover_paths {
+            game_repository
+                .update_cover_path(game_id, cover_id, local_path)
+                .await?;
+        }
+
+        store_accent_colors(&game_repository, &asset_manager, cover_paths).await?;
+    }
+
+    Ok(())
+}
+
+/// Returns the Steam games IGDB has no match for, for the user to fix their
+/// match with `rematch_game`.
+///
+/// These games are filled in from their Steam store page, or only have a
+/// name when the store page could not be fetched either.
+#[tauri::command]
+pub async fn get_unmatched_games(
+    game_repository: State<'_, GameRepository>,
+) -> Result<Vec<GameSummary>, RocadeError> {
+    Ok(game_repository.get_unmatched_games().await?)
+}
+
+/// Returns the games of the library in a franchise or series, oldest first,
+/// for the "Other games in this series" row of the detail page.
+///
+/// `franchise_id` is the ID of one of the `franchises` of a [`Game`]. The
+/// game being viewed is included; the frontend filters it out.
+#[tauri::command]
+pub async fn get_franchise_games(
+    game_repository: State<'_, GameRepository>,
+    franchise_id: i64,
+) -> Result<Vec<GameSummary>, RocadeError> {
+    Ok(game_repository.get_franchise_games(franchise_id).await?)
+}
+
+/// Highest accepted personal rating.
+const MAX_USER_RATING: u8 = 10;
+
+/// Sets the user's own rating of a game, from 0 to 10, or clears it with
+/// `None`. Ratings are kept across library refreshes.
+#[tauri::command]
+pub async fn rate_game(
+    game_repository: State<'_, GameRepository>,
+    game_cache: State<'_, GameCache>,
+    game_id: i64,
+    rating: Option<u8>,
+) -> Result<(), RocadeError> {
+    if rating.is_some_and(|rating| rating > MAX_USER_RATING) {
+        return Err(RocadeConfigError::ConfigError(format!(
+            "rating must be between 0 and {}",
+            MAX_USER_RATING
+        ))
+        .into());
+    }
+
+    game_repository.set_user_rating(game_id, rating).await?;
+    game_cache.invalidate(game_id);
+
+    Ok(())
+}
+
+/// Pins a game to the top of the library, after the already pinned games.
+#[tauri::command]
+pub async fn pin_game(
+    game_repository: State<'_, GameRepository>,
+    game_cache: State<'_, GameCache>,
+    game_id: i64,
+) -> Result<(), RocadeError> {
+    game_repository.pin_game(game_id).await?;
+    game_cache.invalidate(game_id);
+
+    Ok(())
+}
+
+/// Sets the order of the pinned games. Pinned games missing from `game_ids`
+/// are unpinned.
+#[tauri::command]
+pub async fn reorder_pins(
+    game_repository: State<'_, GameRepository>,
+    game_cache: State<'_, GameCache>,
+    game_ids: Vec<i64>,
+) -> Result<(), RocadeError> {
+    game_repository.reorder_pins(&game_ids).await?;
+    game_cache.clear();
+
+    Ok(())
+}
+
+/// Default number of games returned by [`get_recently_played`].
+const RECENTLY_PLAYED_LIMIT: u32 = 10;
+
+/// Returns the most recently played games for the "Continue playing" shelf.
+///
+/// Based on the last-played dates stored by the player stats sync and on
+/// launches from Rocade. Returns at most `limit` games, 10 by default.
+#[tauri::command]
+pub async fn get_recently_played(
+    game_repository: State<'_, GameRepository>,
+    limit: Option<u32>,
+) -> Result<Vec<GameSummary>, RocadeError> {
+    Ok(game_repository
+        .get_recently_played(limit.unwrap_or(RECENTLY_PLAYED_LIMIT))
+        .await?)
+}
+
+/// Default number of games returned by [`get_recently_added`].
+const RECENTLY_ADDED_LIMIT: u32 = 10;
+
+/// Returns the games most recently added to the library, for the "New in
+/// your library" shelf.
+///
+/// Games are dated by the refresh that first found them; those already in
+/// the library when dates started being tracked are left out. Returns at
+/// most `limit` games, 10 by default.
+#[tauri::command]
+pub async fn get_recently_added(
+    game_repository: State<'_, GameRepository>,
+    limit: Option<u32>,
+) -> Result<Vec<GameSummary>, RocadeError> {
+    Ok(game_repository
+        .get_recently_added(limit.unwrap_or(RECENTLY_ADDED_LIMIT))
+        .await?)
+}
+
 /// Computes the set of trigrams for a string.
 ///
 /// The input is padded with two leading spaces and one trailing space before
@@ -107,12 +783,12 @@ pub fn trigrams(s: &str) -> HashSet<String> {
     hashset
 }
 
-/// Returns a trigram-based similarity score between two strings in `[0.0, 1.0]`.
+/// Returns a trigram-based similarity score between two strings in `[0.0, 1.0]`,
+/// given the precomputed trigrams of `a`.
 ///
 /// Computed as `|trigrams(a) ∩ trigrams(b)| / |trigrams(a)|`. A score of
 /// `1.0` means `a`'s trigrams are a subset of `b`'s; `0.0` means no overlap.
-pub fn similarity(a: &str, b: &str) -> f64 {
-    let tri_a = trigrams(a);
+pub fn similarity(tri_a: &HashSet<String>, b: &str) -> f64 {
     let tri_b = trigrams(b);
 
     tri_a.intersection(&tri_b).count() as f64 / tri_a.len() as f64
@@ -121,109 +797,743 @@ pub fn similarity(a: &str, b: &str) -> f64 {
 /// Refreshes the local game library from Steam and IGDB.
 ///
 /// Fetches the user's owned games from Steam, enriches each entry with
-/// metadata from IGDB (cover art, genres, companies, etc.), wipes the
-/// existing database records, downloads all game images locally, and inserts
-/// the updated set with local image paths.
+/// metadata from IGDB (cover art, genres, companies, etc.), downloads all
+/// game images locally, then wipes the existing database records and inserts
+/// the updated set with local image paths and Steam playtime, in a single
+/// transaction: a refresh that fails or is interrupted leaves the library as
+/// it was; a refresh still running when the app exits is cancelled before
+/// the library is replaced. Games IGDB has no match for are filled in from their Steam store
+/// page, or kept with their name only when it cannot be fetched. Exclusion
+/// rules are then applied, covers are upscaled when enabled in the
+/// settings, and the `game_added` and `refresh_finished` hooks are run. On
+/// Linux, ProtonDB tiers are then synced in the background.
+///
+/// Games re-matched by hand with `rematch_game` keep their chosen match.
+/// Steam store IDs held by custom games, e.g. after `merge_games`, are left
+/// to them.
+/// Games no longer owned on Steam are kept, marked with `removed_at`, with
+/// their notes, playtime, and images. The database is backed up before it
+/// is wiped, so the refresh can be rolled back with [`undo_last_refresh`].
 #[tauri::command]
-pub async fn refresh_games(
-    steam_client: State<'_, SteamApiClient>,
-    igdb_client: State<'_, Mutex<IgdbApiClient>>,
-    asset_manager: State<'_, AssetManager>,
-    db_state: State<'_, DatabaseState>,
-    game_repository: State<'_, GameRepository>,
-) -> Result<(), RocadeError> {
+#[tracing::instrument(skip_all, err)]
+pub async fn refresh_games<R: Runtime>(app: AppHandle<R>) -> Result<RefreshReport, RocadeError> {
+    let steam_client = app.state::<Box<dyn SteamApi>>();
+    let igdb_client = app.state::<Mutex<Box<dyn IgdbApi>>>();
+    let asset_manager = app.state::<AssetManager>();
+    let db_state = app.state::<DatabaseState>();
+    let game_repository = app.state::<GameRepository>();
+    let game_cache = app.state::<GameCache>();
+    let settings_repository = app.state::<SettingsRepository>();
+    let hook_repository = app.state::<HookRepository>();
+    let store_client = app.state::<Box<dyn SteamStoreApi>>();
+    let playtime_repository = app.state::<PlaytimeRepository>();
+    let refresh_report_repository = app.state::<RefreshReportRepository>();
+    let history_repository = app.state::<HistoryRepository>();
+    let refresh_tracker = app.state::<RefreshTracker>();
+    let _running = refresh_tracker.start().await;
+
     // 1. Fetch games from Steam
     let games_res = steam_client.get_games().await?;
 
-    // 2. Fetch IGDB metadata
+    // 2. Fetch IGDB metadata, using the matches chosen by the user over the
+    // automatic ones. Store IDs held by custom games, e.g. after a merge, are
+    // left to them.
+    let owned_store_ids: HashSet<String> = games_res
+        .iter()
+        .map(|game| game.appid.to_string())
+        .collect();
+    let custom_store_ids = game_repository.get_custom_store_ids().await?;
+    let refreshed_appids: Vec<u64> = games_res
+        .iter()
+        .map(|game| game.appid)
+        .filter(|appid| !custom_store_ids.contains(&appid.to_string()))
+        .collect();
+    let manual_matches: Vec<(String, u64)> = game_repository
+        .get_manual_igdb_matches()
+        .await?
+        .into_iter()
+        .filter(|(store_id, _)| {
+            owned_store_ids.contains(store_id) && !custom_store_ids.contains(store_id)
+        })
+        .collect();
+    let manual_store_ids: HashSet<&String> = manual_matches
+        .iter()
+        .map(|(store_id, _)| store_id)
+        .collect();
+
+    let genre_repository = app.state::<GenreRepository>();
     let mut locked_client = igdb_client.lock().await;
-    let igdb_games = locked_client
-        .get_games(games_res.iter().map(|game| game.appid).collect())
+    load_dictionaries(
+        &mut **locked_client,
+        &genre_repository,
+        &app.state::<CompanyRepository>(),
+    )
+    .await?;
+    let mut igdb_games = locked_client
+        .get_games(
+            refreshed_appids
+                .iter()
+                .copied()
+                .filter(|appid| !manual_store_ids.contains(&appid.to_string()))
+                .collect(),
+        )
+        .await?;
+    igdb_games.extend(locked_client.get_games_by_igdb_ids(manual_matches).await?);
+    store_refetched_genres(&mut **locked_client, &genre_repository).await?;
+    game_repository
+        .apply_metadata_overrides(&mut igdb_games)
+        .await?;
+
+    let player_stats = to_player_stats(&games_res);
+    playtime_repository.record_snapshots(&player_stats).await?;
+
+    // 3. Fetch the store pages of the games IGDB has no match for, before
+    // the database is touched
+    let matched_store_ids: HashSet<String> = igdb_games
+        .iter()
+        .filter_map(|game| game.store_id.clone())
+        .collect();
+    let unmatched_appids: Vec<u64> = refreshed_appids
+        .into_iter()
+        .filter(|appid| !matched_store_ids.contains(&appid.to_string()))
+        .collect();
+
+    let language = settings_repository
+        .get(Setting::StoreLanguage)
+        .await?
+        .unwrap_or_else(|| DEFAULT_LANGUAGE.to_string());
+
+    let store_games = fetch_store_games(&**store_client, unmatched_appids.clone(), &language).await;
+    let store_appids: HashSet<u64> = store_games.iter().map(|(appid, _)| *appid).collect();
+
+    // 4. Remember which games were known, their metadata for the report, the
+    // flags, pins, notes, ratings, statuses, images, collections,
+    // subscriptions, and launch settings set by the user, and the play
+    // sessions, last launches, and achievements
+    let known_store_ids = game_repository.get_store_ids().await?;
+    let previous_games = game_repository.get_games().await?;
+    let favorite_store_ids = game_repository
+        .get_flagged_store_ids(UserFlag::Favorite)
+        .await?;
+    let hidden_store_ids = game_repository
+        .get_flagged_store_ids(UserFlag::Hidden)
+        .await?;
+    let pinned_store_ids = game_repository.get_pinned_store_ids().await?;
+    let user_data = game_repository.get_user_data_by_store_id().await?;
+    let added_at = game_repository.get_added_at_by_store_id().await?;
+    let custom_images = game_repository.get_custom_images().await?;
+
+    // Owned games are re-inserted with new IDs, so the rows referencing them
+    // are kept by Steam store ID
+    let collection_repository = app.state::<CollectionRepository>();
+    let collection_memberships = collection_repository.get_store_memberships().await?;
+    let session_repository = app.state::<SessionRepository>();
+    let sessions = session_repository.get_sessions_by_store_id().await?;
+    let launch_settings_repository = app.state::<LaunchSettingsRepository>();
+    let launch_settings = launch_settings_repository
+        .get_launch_settings_by_store_id()
+        .await?;
+    let subscription_repository = app.state::<SubscriptionRepository>();
+    let subscription_games = subscription_repository.get_store_games().await?;
+    let achievement_repository = app.state::<AchievementRepository>();
+    let achievements = achievement_repository
+        .get_achievements_by_store_id()
         .await?;
 
-    // 3. Clear database and assets
-    prepare_db(&db_state, &asset_manager).await?;
+    // 5. Back up the database, then clear the assets and download the covers
+    // of all games in parallel. Only the backup of the last refresh can be
+    // restored by `undo_last_refresh`. Artworks and screenshots are
+    // downloaded by `ensure_game_artworks` when a detail page is opened.
+    if refresh_tracker.is_cancelled() {
+        return Err(RocadeError::RefreshCancelled);
+    }
+
+    if let Some(backup) = db_state.backup().await? {
+        settings_repository
+            .set(Setting::LastRefreshBackup, &backup.name)
+            .await?;
+    }
+    asset_manager.clear_all().await?;
 
-    // 4. Collect image IDs from all games
-    let mut cover_ids = Vec::new();
-    let mut artwork_ids = Vec::new();
+    let cover_ids: Vec<String> = igdb_games
+        .iter()
+        .filter_map(|game| game.cover.as_ref())
+        .map(|cover| cover.image_id.clone())
+        .collect();
+
+    asset_manager.take_download_count();
+    let cover_paths = asset_manager.download_batch_covers(cover_ids).await?;
+    let cover_map: HashMap<String, String> = cover_paths.iter().cloned().collect();
 
-    for game in &igdb_games {
-        if let Some(cover) = &game.cover {
-            cover_ids.push(cover.image_id.clone());
+    // 6. Replace the library in a single transaction, so a failed or
+    // interrupted refresh leaves it as it was: mark the games no longer
+    // owned, clear the others, insert the updated set, then restore what
+    // the user set on them. Owned DLC are attached to their game instead.
+    let replaced: Result<(), RocadeError> = async {
+        if refresh_tracker.is_cancelled() {
+            return Err(RocadeError::RefreshCancelled);
         }
-        if let Some(artworks) = &game.artworks {
-            for artwork in artworks {
-                artwork_ids.push(artwork.image_id.clone());
-            }
+
+        let mut tx = game_repository.begin().await?;
+
+        game_repository
+            .mark_removed_games(&mut tx, &owned_store_ids)
+            .await?;
+        db_state.clean(&mut tx).await?;
+
+        for (store_id, parent_store_id, name) in take_dlcs(&mut igdb_games) {
+            game_repository
+                .insert_dlc(&mut tx, &store_id, &parent_store_id, &name)
+                .await?;
         }
+
+        insert_games_with_images(
+            &game_repository,
+            &mut tx,
+            igdb_games,
+            cover_map,
+            HashMap::new(),
+            HashMap::new(),
+        )
+        .await?;
+        insert_store_games(&game_repository, &mut tx, store_games).await?;
+
+        for game in games_res.iter().filter(|game| {
+            unmatched_appids.contains(&game.appid) && !store_appids.contains(&game.appid)
+        }) {
+            game_repository
+                .insert_unmatched_game(&mut tx, &game.appid.to_string(), &game.name)
+                .await?;
+        }
+
+        game_repository
+            .update_player_stats(&mut tx, player_stats)
+            .await?;
+        game_repository
+            .set_flagged_store_ids(&mut tx, UserFlag::Favorite, &favorite_store_ids)
+            .await?;
+        game_repository
+            .set_flagged_store_ids(&mut tx, UserFlag::Hidden, &hidden_store_ids)
+            .await?;
+        game_repository
+            .set_pinned_store_ids(&mut tx, &pinned_store_ids)
+            .await?;
+        game_repository
+            .set_user_data_by_store_id(&mut tx, user_data)
+            .await?;
+        game_repository
+            .set_added_at_by_store_id(&mut tx, added_at)
+            .await?;
+        game_repository
+            .set_custom_images(&mut tx, custom_images)
+            .await?;
+        collection_repository
+            .set_store_memberships(&mut tx, &collection_memberships)
+            .await?;
+        session_repository
+            .set_sessions_by_store_id(&mut tx, &sessions)
+            .await?;
+        launch_settings_repository
+            .set_launch_settings_by_store_id(&mut tx, &launch_settings)
+            .await?;
+        subscription_repository
+            .set_store_games(&mut tx, &subscription_games)
+            .await?;
+        achievement_repository
+            .set_achievements_by_store_id(&mut tx, achievements)
+            .await?;
+
+        tx.commit().await?;
+
+        Ok(())
     }
+    .await;
 
-    // 5. Download images in parallel
-    let cover_paths = asset_manager.download_batch_covers(cover_ids).await?;
-    let artwork_paths = asset_manager.download_batch_artworks(artwork_ids).await?;
+    // The rows rolled back point at the images of the refresh before
+    if let Err(e) = replaced {
+        if let Err(e) = asset_manager.restore_previous_images().await {
+            tracing::warn!("failed to restore the previous images: {e}");
+        }
+        return Err(e);
+    }
+
+    // 7. Keep the images of the games no longer owned, and apply the
+    // exclusion rules to the new rows
+    game_cache.clear();
+    asset_manager
+        .keep_previous(&game_repository.get_removed_image_paths().await?)
+        .await?;
+    apply_exclusion_rules(&game_repository, &app.state::<ExclusionRuleRepository>()).await?;
+
+    // 8. Extract accent colors, and upscale covers if enabled
+    store_accent_colors(&game_repository, &asset_manager, cover_paths.clone()).await?;
+    upscale_covers(
+        &game_repository,
+        &asset_manager,
+        &settings_repository,
+        cover_paths,
+    )
+    .await?;
+
+    // 9. Notify hooks
+    let games = game_repository.get_games().await?;
+    let added_hooks = hook_repository.get_commands(HookEvent::GameAdded).await?;
+
+    if !added_hooks.is_empty() {
+        for game in games.iter().filter(|game| {
+            game.store_id
+                .as_ref()
+                .is_some_and(|store_id| !known_store_ids.contains(store_id))
+        }) {
+            run_hooks(added_hooks.clone(), HookEvent::GameAdded, game);
+        }
+    }
+
+    trigger_hooks(
+        &hook_repository,
+        HookEvent::RefreshFinished,
+        &serde_json::json!({ "game_count": games.len() }),
+    )
+    .await?;
+
+    // 10. Report what changed
+    let report = build_refresh_report(&previous_games, &games, asset_manager.take_download_count());
+    refresh_report_repository.set_last(&report).await?;
+    history_repository
+        .record_games(HistoryEvent::GameAdded, &report.added)
+        .await?;
+    history_repository
+        .record_games(HistoryEvent::GameRemoved, &report.removed)
+        .await?;
+    tracing::info!(
+        added = report.added.len(),
+        removed = report.removed.len(),
+        changed = report.changed.len(),
+        images_downloaded = report.images_downloaded,
+        "library refreshed"
+    );
+
+    spawn_protondb_sync(app.clone());
+
+    Ok(report)
+}
+
+/// Returns the report of the last refresh, or `None` if the library was
+/// never refreshed.
+#[tauri::command]
+pub async fn get_last_refresh_report(
+    refresh_report_repository: State<'_, RefreshReportRepository>,
+) -> Result<Option<RefreshReport>, RocadeError> {
+    Ok(refresh_report_repository.get_last().await?)
+}
+
+/// Compares the Steam games of the library before and after a refresh.
+///
+/// Games are matched by Steam store ID; custom games are left out, a
+/// refresh not touching them. Games no longer owned count as removed.
+fn build_refresh_report(
+    previous: &[Game],
+    current: &[Game],
+    images_downloaded: usize,
+) -> RefreshReport {
+    let previous = games_by_store_id(previous);
+    let current = games_by_store_id(current);
+    let reported = |(store_id, game): (&&str, &&Game)| ReportedGame {
+        store_id: store_id.to_string(),
+        name: game.name.clone(),
+    };
+
+    let mut report = RefreshReport {
+        finished_at: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_secs() as i64)
+            .unwrap_or_default(),
+        added: current
+            .iter()
+            .filter(|(store_id, _)| !previous.contains_key(*store_id))
+            .map(reported)
+            .collect(),
+        removed: previous
+            .iter()
+            .filter(|(store_id, _)| !current.contains_key(*store_id))
+            .map(reported)
+            .collect(),
+        changed: current
+            .iter()
+            .filter_map(|(store_id, game)| {
+                let fields = changed_fields(previous.get(store_id)?, game);
 
-    // 6. Build maps: image_id -> local_path
-    let cover_map: HashMap<String, String> = cover_paths.into_iter().collect();
-    let artwork_map: HashMap<String, String> = artwork_paths.into_iter().collect();
+                (!fields.is_empty()).then(|| ChangedGame {
+                    store_id: store_id.to_string(),
+                    name: game.name.clone(),
+                    fields,
+                })
+            })
+            .collect(),
+        images_downloaded,
+    };
+
+    report.added.sort_by(|a, b| a.name.cmp(&b.name));
+    report.removed.sort_by(|a, b| a.name.cmp(&b.name));
+    report.changed.sort_by(|a, b| a.name.cmp(&b.name));
+
+    report
+}
+
+/// Indexes the owned Steam games of `games` by store ID.
+fn games_by_store_id(games: &[Game]) -> HashMap<&str, &Game> {
+    games
+        .iter()
+        .filter(|game| game.removed_at.is_none())
+        .filter_map(|game| Some((game.store_id.as_deref()?, game)))
+        .collect()
+}
+
+/// Returns the names of the metadata fields that differ between two
+/// versions of a game. Lists are compared regardless of order.
+fn changed_fields(before: &Game, after: &Game) -> Vec<String> {
+    let sorted = |list: &Option<Vec<String>>| {
+        let mut list = list.clone().unwrap_or_default();
+        list.sort();
+        list
+    };
+    let cover_id = |game: &Game| game.cover.as_ref().map(|cover| cover.id.clone());
+
+    [
+        ("name", before.name != after.name),
+        ("summary", before.summary != after.summary),
+        ("storyline", before.storyline != after.storyline),
+        ("release_date", before.release_date != after.release_date),
+        ("game_type", before.game_type != after.game_type),
+        ("genres", sorted(&before.genres) != sorted(&after.genres)),
+        (
+            "developers",
+            sorted(&before.developers) != sorted(&after.developers),
+        ),
+        (
+            "publishers",
+            sorted(&before.publishers) != sorted(&after.publishers),
+        ),
+        (
+            "categories",
+            sorted(&before.categories) != sorted(&after.categories),
+        ),
+        (
+            "aggregated_rating",
+            before.aggregated_rating != after.aggregated_rating,
+        ),
+        (
+            "metacritic_score",
+            before.metacritic_score != after.metacritic_score,
+        ),
+        ("cover", cover_id(before) != cover_id(after)),
+    ]
+    .into_iter()
+    .filter(|(_, changed)| *changed)
+    .map(|(field, _)| field.to_string())
+    .collect()
+}
+
+/// Returns groups of games that look like the same game, e.g. a game
+/// owned on Steam and added again as a custom game, or two games matched to
+/// the same IGDB entry.
+#[tauri::command]
+pub async fn find_duplicate_games(
+    game_repository: State<'_, GameRepository>,
+) -> Result<Vec<Vec<GameSummary>>, RocadeError> {
+    Ok(game_repository.find_duplicates().await?)
+}
+
+/// Merges the game `remove_id` into `keep_id`: its store ID, executable,
+/// images, tags, collections, sessions, and user data move to the kept
+/// game, then it is deleted. Does nothing if both IDs are the same.
+///
+/// # Errors
+///
+/// Returns [`RocadeError::MergeOwnedGames`] if both games are owned on
+/// Steam, as the next refresh would insert the removed one again.
+#[tauri::command]
+pub async fn merge_games(
+    game_repository: State<'_, GameRepository>,
+    game_cache: State<'_, GameCache>,
+    keep_id: i64,
+    remove_id: i64,
+) -> Result<(), RocadeError> {
+    if keep_id == remove_id {
+        return Ok(());
+    }
 
-    // 7. Insert games and update image paths
-    insert_games_with_images(&game_repository, igdb_games, cover_map, artwork_map).await?;
+    if game_repository.is_owned_on_steam(keep_id).await?
+        && game_repository.is_owned_on_steam(remove_id).await?
+    {
+        return Err(RocadeError::MergeOwnedGames);
+    }
+
+    game_repository.merge_games(keep_id, remove_id).await?;
+    game_cache.clear();
 
     Ok(())
 }
 
-/// Clears all existing game records from the database and cached assets.
-async fn prepare_db(
-    db_state: &DatabaseState,
+/// Rolls the library back to its state before the last refresh, then
+/// restarts the app to reopen the database.
+///
+/// [`refresh_games`] backs up the whole database before replacing the
+/// library, and that backup is restored. Everything changed since the
+/// refresh is lost with it: notes, ratings, collections, play sessions,
+/// settings, and any other change made after the refresh. Images the
+/// refresh moved away are moved back; upscaled covers are not kept by
+/// refreshes and are created again by the next one.
+///
+/// # Errors
+///
+/// Returns [`BackupError::NotRefreshBackup`] if the most recent backup was
+/// not taken by the last refresh, e.g. when it was already restored or a
+/// backup was made since.
+#[tauri::command]
+pub async fn undo_last_refresh(
+    app: AppHandle,
+    db_state: State<'_, DatabaseState>,
+    settings_repository: State<'_, SettingsRepository>,
+    asset_manager: State<'_, AssetManager>,
+) -> Result<(), RocadeError> {
+    let backup = db_state
+        .list_backups()
+        .await?
+        .into_iter()
+        .next()
+        .ok_or(BackupError::NoBackup)?;
+
+    // The restored database records the backup of the refresh before, so
+    // the same backup cannot be restored twice
+    if settings_repository.get(Setting::LastRefreshBackup).await? != Some(backup.name.clone()) {
+        return Err(BackupError::NotRefreshBackup.into());
+    }
+
+    db_state.restore_backup(&backup.name).await?;
+    asset_manager.restore_previous_images().await?;
+
+    app.restart()
+}
+
+/// Loads the genre and company dictionaries into the IGDB client.
+///
+/// The dictionaries are read from the local `genres` and `companies` tables;
+/// on first use the genres are prefetched from IGDB and stored so later
+/// refreshes skip the request. Entries missing from them are fetched by the
+/// client, see [`store_refetched_genres`].
+pub(super) async fn load_dictionaries(
+    igdb_client: &mut dyn IgdbApi,
+    genre_repository: &GenreRepository,
+    company_repository: &CompanyRepository,
+) -> Result<(), RocadeError> {
+    let mut genres = genre_repository.get_genres().await?;
+
+    if genres.is_empty() {
+        genres = igdb_client.get_genres().await?;
+        genre_repository.insert_genres(&genres).await?;
+    }
+
+    igdb_client.set_genres(genres);
+    igdb_client.set_companies(company_repository.get_companies().await?);
+
+    Ok(())
+}
+
+/// Stores the genre dictionary if the IGDB client fetched it again for a
+/// genre missing from it.
+///
+/// Companies need no such step: they are stored with the games they made.
+pub(super) async fn store_refetched_genres(
+    igdb_client: &mut dyn IgdbApi,
+    genre_repository: &GenreRepository,
+) -> Result<(), RocadeError> {
+    if let Some(genres) = igdb_client.take_refetched_genres() {
+        genre_repository.insert_genres(&genres).await?;
+    }
+
+    Ok(())
+}
+
+/// Upscales the given downloaded covers with the method set in the
+/// settings, if any, and stores the upscaled paths.
+///
+/// Returns the `(image_id, upscaled_path)` pairs stored.
+pub(super) async fn upscale_covers(
+    game_repository: &GameRepository,
+    asset_manager: &AssetManager,
+    settings_repository: &SettingsRepository,
+    cover_paths: Vec<(String, String)>,
+) -> Result<Vec<(String, String)>, RocadeError> {
+    let upscaling = CoverUpscaling::from_settings(
+        settings_repository
+            .get(Setting::CoverUpscaling)
+            .await?
+            .as_deref(),
+        settings_repository
+            .get(Setting::CoverUpscaleCommand)
+            .await?,
+    );
+
+    let Some(method) = upscaling else {
+        return Ok(Vec::new());
+    };
+
+    let upscaled = asset_manager.upscale_covers(cover_paths, &method).await?;
+
+    for (image_id, upscaled_path) in &upscaled {
+        game_repository
+            .update_cover_upscaled_path(image_id, upscaled_path)
+            .await?;
+    }
+
+    Ok(upscaled)
+}
+
+/// Extracts the accent color of the given downloaded covers and stores it
+/// on their games.
+pub(super) async fn store_accent_colors(
+    game_repository: &GameRepository,
     asset_manager: &AssetManager,
+    cover_paths: Vec<(String, String)>,
 ) -> Result<(), RocadeError> {
-    db_state.clean().await?;
-    asset_manager.clear_all().await?;
+    for (image_id, color) in asset_manager.extract_accent_colors(cover_paths).await? {
+        game_repository
+            .update_accent_color(&image_id, &color)
+            .await?;
+    }
+
     Ok(())
 }
 
-/// Inserts a batch of IGDB games into the database and updates their image paths.
-async fn insert_games_with_images(
+/// Creates thumbnails of the given downloaded artworks and stores their
+/// paths.
+///
+/// Returns the `(image_id, thumbnail_path)` pairs stored.
+pub(super) async fn create_artwork_thumbnails(
+    game_repository: &GameRepository,
+    asset_manager: &AssetManager,
+    artwork_paths: Vec<(String, String)>,
+) -> Result<Vec<(String, String)>, RocadeError> {
+    let thumbnails = asset_manager
+        .create_artwork_thumbnails(artwork_paths)
+        .await?;
+
+    for (image_id, thumbnail_path) in &thumbnails {
+        game_repository
+            .update_artwork_thumbnail_path(image_id, thumbnail_path)
+            .await?;
+    }
+
+    Ok(thumbnails)
+}
+
+/// Removes the DLC and expansions of other owned games from `games`.
+///
+/// A game is a DLC when an owned game lists it among its `dlcs` or
+/// `expansions`, or is its `parent_game`. Returns the Steam store ID, the
+/// parent's Steam store ID, and the name of each DLC removed.
+fn take_dlcs(games: &mut Vec<IgdbGame>) -> Vec<(String, String, String)> {
+    let owned: HashMap<u64, String> = games
+        .iter()
+        .filter_map(|game| Some((game.id, game.store_id.clone()?)))
+        .collect();
+
+    let mut parents: HashMap<u64, String> = HashMap::new();
+    for game in games.iter() {
+        if let Some(store_id) = &game.store_id {
+            for dlc_id in &game.dlcs {
+                parents.insert(*dlc_id, store_id.clone());
+            }
+        }
+    }
+
+    let mut dlcs = Vec::new();
+
+    games.retain(|game| {
+        let parent_store_id = parents
+            .get(&game.id)
+            .or_else(|| game.parent_game.and_then(|parent_id| owned.get(&parent_id)));
+
+        match (&game.store_id, parent_store_id) {
+            (Some(store_id), Some(parent_store_id)) if store_id != parent_store_id => {
+                dlcs.push((store_id.clone(), parent_store_id.clone(), game.name.clone()));
+                false
+            }
+            _ => true,
+        }
+    });
+
+    dlcs
+}
+
+/// Inserts a batch of IGDB games into the database with the local paths of
+/// their downloaded images.
+pub(super) async fn insert_games_with_images(
     game_repository: &GameRepository,
+    tx: &mut Transaction<'_, Sqlite>,
     games: Vec<IgdbGame>,
     cover_map: HashMap<String, String>,
     artwork_map: HashMap<String, String>,
+    screenshot_map: HashMap<String, String>,
 ) -> Result<(), sqlx::Error> {
-    for game in games {
-        // Get cover and artwork info before move
-        let cover_id = game.cover.as_ref().map(|c| c.image_id.clone());
-        let artwork_ids: Vec<String> = game
-            .artworks
-            .as_ref()
-            .map(|artworks| artworks.iter().map(|a| a.image_id.clone()).collect())
-            .unwrap_or_default();
-
-        // Insert game
-        let game_id = game_repository.insert_complete_game(game).await?;
-
-        // Update cover path if downloaded
-        if let Some(cover_id) = cover_id {
-            if let Some(local_path) = cover_map.get(&cover_id) {
-                game_repository
-                    .update_cover_path(game_id, &cover_id, local_path)
-                    .await?;
-            }
+    game_repository
+        .insert_games_bulk(tx, games, &cover_map, &artwork_map, &screenshot_map)
+        .await?;
+
+    Ok(())
+}
+
+/// Fetches the Steam store pages of games, with their Steam App ID.
+///
+/// Apps without a store page are skipped. The storefront rate-limits
+/// requests, so the remaining games are skipped once a request fails; they
+/// are retried on the next refresh.
+pub(super) async fn fetch_store_games(
+    store_client: &dyn SteamStoreApi,
+    appids: Vec<u64>,
+    language: &str,
+) -> Vec<(u64, AppDetails)> {
+    let mut store_games = Vec::new();
+
+    for appid in appids {
+        match store_client.get_app_details(appid, language).await {
+            Ok(Some(details)) => store_games.push((appid, details)),
+            Ok(None) => continue,
+            Err(_) => break,
         }
+    }
 
-        // Update artwork paths
-        let downloaded_artworks: Vec<(String, String)> = artwork_ids
-            .into_iter()
-            .filter_map(|id| {
-                artwork_map.get(&id).map(|path| (id.clone(), path.clone()))
-            })
-            .collect();
+    store_games
+}
+
+/// Inserts games from their Steam store page, fetched with
+/// [`fetch_store_games`].
+///
+/// Soundtracks and DLC are attached to their game instead of being inserted.
+pub(super) async fn insert_store_games(
+    game_repository: &GameRepository,
+    tx: &mut Transaction<'_, Sqlite>,
+    store_games: Vec<(u64, AppDetails)>,
+) -> Result<(), sqlx::Error> {
+    for (appid, details) in store_games {
+        if let Some(parent_store_id) = details.soundtrack_of() {
+            game_repository
+                .insert_soundtrack(tx, &appid.to_string(), parent_store_id, &details.name)
+                .await?;
+            continue;
+        }
 
-        if !downloaded_artworks.is_empty() {
+        if let Some(parent_store_id) = details.dlc_of() {
             game_repository
-                .update_artwork_paths(game_id, downloaded_artworks)
+                .insert_dlc(tx, &appid.to_string(), parent_store_id, &details.name)
                 .await?;
+            continue;
         }
+
+        game_repository
+            .insert_store_game(tx, &appid.to_string(), details)
+            .await?;
     }
 
     Ok(())
@@ -231,28 +1541,249 @@ async fn insert_games_with_images(
 
 /// Returns a single game by its database ID, with its current install status.
 ///
-/// Looks up the game's Steam store ID and checks the local Steam library to
-/// determine whether the game is fully installed, then sets `is_installed`
-/// on the returned record.
+/// Served from the [`GameCache`] when the game was recently prefetched,
+/// otherwise queried from the database. The game's Steam store ID is then
+/// checked against the local Steam library to determine whether the game is
+/// fully installed; custom games are installed when their executable exists.
+/// `is_installed` and `installed_via` are set on the returned record.
+///
+/// When `lang` is a Steam language other than English, the summary is
+/// replaced by its translation, falling back to English when the store page
+/// has none. Without `lang`, the language configured in
+/// [`Setting::StoreLanguage`] is used. The storyline is always in English.
 #[tauri::command]
 pub async fn get_game(
-    game_repository: State<'_, GameRepository>,
-    steam_client: State<'_, SteamClient>,
+    app: AppHandle,
     game_id: i64,
+    lang: Option<String>,
 ) -> Result<Game, RocadeError> {
-    let mut game = game_repository.get_game_by_id(game_id).await?;
+    let game_repository = app.state::<GameRepository>();
+
+    let mut game = match app.state::<GameCache>().get(game_id) {
+        Some(game) => game,
+        None => game_repository.get_game_by_id(game_id).await?,
+    };
+
+    let lang = match lang {
+        Some(lang) => Some(lang),
+        None => {
+            app.state::<SettingsRepository>()
+                .get(Setting::StoreLanguage)
+                .await?
+        }
+    };
 
-    let mut is_installed = false;
+    if let (Some(lang), Some(store_id)) = (lang, &game.store_id) {
+        if lang != DEFAULT_LANGUAGE {
+            let summary = get_translated_summary(
+                &app.state::<TranslationRepository>(),
+                &**app.state::<Box<dyn SteamStoreApi>>(),
+                store_id,
+                &lang,
+            )
+            .await?;
 
-    if let Some(store_id) = game.store_id.clone() {
-        is_installed = steam_client.is_steam_game_installed(&store_id);
+            if summary.is_some() {
+                game.summary = summary;
+            }
+        }
     }
 
-    game.is_installed = Some(is_installed);
+    let installed_via = match &game.store_id {
+        Some(store_id) => app
+            .state::<SteamClient>()
+            .is_steam_game_installed(store_id)
+            .then_some(InstallSource::Steam),
+        None => app
+            .state::<CustomGameRepository>()
+            .get_executable(game_id)
+            .await?
+            .filter(|executable| Path::new(executable).exists())
+            .map(|_| InstallSource::Custom),
+    };
+
+    game.is_installed = Some(installed_via.is_some());
+    game.installed_via = installed_via;
 
     Ok(game)
 }
 
+/// Returns the summary of a game in `lang`, fetching it from the Steam store
+/// page first if needed.
+///
+/// Returns `None` when the store page has no summary, or when it cannot be
+/// reached and no translation is stored.
+async fn get_translated_summary(
+    translation_repository: &TranslationRepository,
+    store_client: &dyn SteamStoreApi,
+    store_id: &str,
+    lang: &str,
+) -> Result<Option<String>, RocadeError> {
+    let stored = translation_repository.get_summary(store_id, lang).await?;
+
+    if !is_stale(stored.as_ref().map(|t| t.fetched_at), TRANSLATION_TTL) {
+        return Ok(stored.and_then(|t| t.summary));
+    }
+
+    let details = match store_id.parse() {
+        Ok(appid) => store_client.get_app_details(appid, lang).await,
+        Err(_) => return Ok(None),
+    };
+
+    // Offline: fall back to whatever is stored
+    let Ok(details) = details else {
+        return Ok(stored.and_then(|t| t.summary));
+    };
+
+    let summary = details.and_then(|details| details.short_description);
+    translation_repository
+        .set_summary(store_id, lang, summary.as_deref())
+        .await?;
+
+    Ok(summary)
+}
+
+/// Warms the [`GameCache`] for a game about to be opened.
+///
+/// Meant to be called when the user hovers a grid tile. The game is queried
+/// and cached, and its cover, artworks and screenshots are checked on disk; missing images
+/// are downloaded in the background and the cache entry is dropped once their
+/// paths are updated.
+#[tauri::command]
+pub async fn prefetch_game(
+    app: AppHandle,
+    game_repository: State<'_, GameRepository>,
+    game_cache: State<'_, GameCache>,
+    game_id: i64,
+) -> Result<(), RocadeError> {
+    let game = game_repository.get_game_by_id(game_id).await?;
+
+    let missing_cover = missing_images(game.cover.iter());
+    let missing_artworks = missing_images(game.artworks.iter().flatten());
+    let missing_screenshots = missing_images(game.screenshots.iter().flatten());
+
+    game_cache.insert(game);
+
+    if !missing_cover.is_empty() || !missing_artworks.is_empty() || !missing_screenshots.is_empty()
+    {
+        tauri::async_runtime::spawn(async move {
+            let _ = download_missing_images(
+                &app,
+                game_id,
+                missing_cover,
+                missing_artworks,
+                missing_screenshots,
+            )
+            .await;
+        });
+    }
+
+    Ok(())
+}
+
+/// Downloads the artworks and screenshots of a game that are not on disk yet.
+///
+/// Refreshes only download covers, so the full-size images of a game are
+/// fetched the first time its detail page is opened. Returns once their
+/// paths are stored; later calls only check the files on disk.
+#[tauri::command]
+pub async fn ensure_game_artworks(
+    app: AppHandle,
+    game_repository: State<'_, GameRepository>,
+    game_id: i64,
+) -> Result<(), RocadeError> {
+    let game = game_repository.get_game_by_id(game_id).await?;
+
+    let missing_artworks = missing_images(game.artworks.iter().flatten());
+    let missing_screenshots = missing_images(game.screenshots.iter().flatten());
+
+    if missing_artworks.is_empty() && missing_screenshots.is_empty() {
+        return Ok(());
+    }
+
+    download_missing_images(
+        &app,
+        game_id,
+        Vec::new(),
+        missing_artworks,
+        missing_screenshots,
+    )
+    .await
+}
+
+/// Returns the IDs of the images whose file is not on disk.
+fn missing_images<'a>(images: impl Iterator<Item = &'a GameImage>) -> Vec<String> {
+    images
+        .filter(|image| !is_image_cached(image.local_path.as_deref()))
+        .map(|image| image.id.clone())
+        .collect()
+}
+
+/// Returns `true` if an image has a local path that exists on disk.
+fn is_image_cached(local_path: Option<&str>) -> bool {
+    local_path.is_some_and(|path| Path::new(path).exists())
+}
+
+/// Downloads the given images of a game, stores their local paths, and
+/// invalidates the game's cache entry.
+async fn download_missing_images(
+    app: &AppHandle,
+    game_id: i64,
+    cover_ids: Vec<String>,
+    artwork_ids: Vec<String>,
+    screenshot_ids: Vec<String>,
+) -> Result<(), RocadeError> {
+    let asset_manager = app.state::<AssetManager>();
+    let game_repository = app.state::<GameRepository>();
+
+    let cover_paths = asset_manager.download_batch_covers(cover_ids).await?;
+
+    for (cover_id, local_path) in &cover_paths {
+        game_repository
+            .update_cover_path(game_id, cover_id, local_path)
+            .await?;
+    }
+
+    store_accent_colors(&game_repository, &asset_manager, cover_paths).await?;
+
+    let artwork_paths = asset_manager.download_batch_artworks(artwork_ids).await?;
+
+    if !artwork_paths.is_empty() {
+        game_repository
+            .update_artwork_paths(game_id, artwork_paths.clone())
+            .await?;
+        create_artwork_thumbnails(&game_repository, &asset_manager, artwork_paths).await?;
+    }
+
+    let screenshot_paths = asset_manager
+        .download_batch_screenshots(screenshot_ids)
+        .await?;
+
+    if !screenshot_paths.is_empty() {
+        game_repository
+            .update_screenshot_paths(game_id, screenshot_paths)
+            .await?;
+    }
+
+    app.state::<GameCache>().invalidate(game_id);
+
+    Ok(())
+}
+
+/// Returns the install state and download progress of a game.
+///
+/// Meant to be polled after [`install_game`] to display a progress bar.
+#[tauri::command]
+pub async fn get_install_progress(
+    game_repository: State<'_, GameRepository>,
+    steam_client: State<'_, SteamClient>,
+    game_id: i64,
+) -> Result<InstallProgress, RocadeError> {
+    let store_id = game_repository.get_game_store_id(game_id).await?;
+
+    Ok(steam_client.get_install_progress(&store_id))
+}
+
 /// Triggers installation of a game via the Steam client.
 ///
 /// Resolves the game's Steam store ID from the database and opens the
@@ -271,6 +1802,42 @@ pub async fn install_game(
     Ok(true)
 }
 
+/// Opens the soundtrack of a game.
+///
+/// Opens the soundtrack's directory when it is installed, or asks Steam to
+/// install it otherwise.
+///
+/// # Errors
+///
+/// Returns [`sqlx::Error::RowNotFound`] if the game's soundtrack is not
+/// owned.
+#[tauri::command]
+pub async fn play_soundtrack(
+    game_repository: State<'_, GameRepository>,
+    steam_client: State<'_, SteamClient>,
+    app: AppHandle,
+    game_id: i64,
+) -> Result<(), RocadeError> {
+    let store_id = game_repository
+        .get_soundtrack_store_id(game_id)
+        .await?
+        .ok_or(sqlx::Error::RowNotFound)?;
+
+    match steam_client.get_soundtrack_path(&store_id) {
+        Some(path) => app
+            .opener()
+            .open_path(path.to_string_lossy(), None::<&str>)
+            .map_err(|_| {
+                SteamClientError::OperationError("unable to open soundtrack".to_string())
+            })?,
+        None => {
+            SteamClient::install_game(app, &store_id)?;
+        }
+    }
+
+    Ok(())
+}
+
 /// Triggers uninstallation of a game via the Steam client.
 ///
 /// Resolves the game's Steam store ID from the database and opens the
@@ -287,3 +1854,299 @@ pub async fn uninstall_game(
     SteamClient::uninstall_game(app, store_id)?;
     Ok(true)
 }
+
+#[cfg(test)]
+mod tests {
+    use tauri::{
+        test::{mock_app, MockRuntime},
+        App,
+    };
+    use tempfile::TempDir;
+
+    use super::*;
+    use crate::{
+        assets::DEFAULT_DOWNLOAD_CONCURRENCY,
+        db::{
+            achievement::Achievement, launch::LaunchSettings, protondb::ProtonDbRepository,
+            subscription::NewSubscription,
+        },
+        igdb::mock::MockIgdbApi,
+        retry::RetryPolicy,
+        service::{
+            protondb::{mock::MockProtonDbApi, ProtonDbApi},
+            steam::mock::MockSteamApi,
+            steam_store::mock::MockSteamStoreApi,
+        },
+    };
+
+    /// Owned games of the mocked Steam library.
+    const LIBRARY: [(u64, &str); 7] = [
+        (10, "Alpha"),
+        (20, "Beta"),
+        (30, "Gamma"),
+        (40, "Delta"),
+        (50, "Epsilon"),
+        (60, "Zeta"),
+        (70, "Eta"),
+    ];
+
+    const SORTS: [GameSort; 5] = [
+        GameSort::Name,
+        GameSort::Playtime,
+        GameSort::RecentPlaytime,
+        GameSort::UserRating,
+        GameSort::Rating,
+    ];
+
+    /// Returns a mock app managing the state used by [`refresh_games`], over
+    /// mocked Steam and IGDB APIs, an in-memory database, and assets stored
+    /// in `app_dir`.
+    async fn refresh_app(steam: &MockSteamApi, app_dir: &TempDir) -> App<MockRuntime> {
+        let app = mock_app();
+        let db_state = DatabaseState::in_memory().await.unwrap();
+        let pool = &db_state.pool;
+
+        let asset_manager = AssetManager::new(
+            app_dir.path().to_path_buf(),
+            DEFAULT_DOWNLOAD_CONCURRENCY,
+            RetryPolicy::default(),
+        )
+        .await
+        .unwrap();
+
+        app.manage::<Box<dyn SteamApi>>(Box::new(steam.clone()));
+        app.manage::<Mutex<Box<dyn IgdbApi>>>(Mutex::new(Box::new(MockIgdbApi::default())));
+        app.manage::<AssetManager>(asset_manager);
+        app.manage::<GameRepository>(GameRepository::new(pool.clone()));
+        app.manage::<GameCache>(GameCache::new());
+        app.manage::<RefreshTracker>(RefreshTracker::new());
+        app.manage::<GenreRepository>(GenreRepository::new(pool.clone()));
+        app.manage::<CompanyRepository>(CompanyRepository::new(pool.clone()));
+        app.manage::<SettingsRepository>(SettingsRepository::new(pool.clone()));
+        app.manage::<HookRepository>(HookRepository::new(pool.clone()));
+        app.manage::<Box<dyn SteamStoreApi>>(Box::new(MockSteamStoreApi));
+        app.manage::<PlaytimeRepository>(PlaytimeRepository::new(pool.clone()));
+        app.manage::<RefreshReportRepository>(RefreshReportRepository::new(pool.clone()));
+        app.manage::<HistoryRepository>(HistoryRepository::new(pool.clone()));
+        app.manage::<ExclusionRuleRepository>(ExclusionRuleRepository::new(pool.clone()));
+        app.manage::<CollectionRepository>(CollectionRepository::new(pool.clone()));
+        app.manage::<SessionRepository>(SessionRepository::new(pool.clone()));
+        app.manage::<LaunchSettingsRepository>(LaunchSettingsRepository::new(pool.clone()));
+        app.manage::<SubscriptionRepository>(SubscriptionRepository::new(pool.clone()));
+        app.manage::<AchievementRepository>(AchievementRepository::new(pool.clone()));
+        app.manage::<ProtonDbRepository>(ProtonDbRepository::new(pool.clone()));
+        app.manage::<Box<dyn ProtonDbApi>>(Box::new(MockProtonDbApi));
+        app.manage::<DatabaseState>(db_state);
+
+        app
+    }
+
+    /// Returns the database ID of the game with the given Steam store ID.
+    async fn game_id(app: &App<MockRuntime>, store_id: &str) -> i64 {
+        app.state::<GameRepository>()
+            .get_summaries_by_store_ids(&[store_id.to_string()])
+            .await
+            .unwrap()[0]
+            .id
+    }
+
+    #[test]
+    fn refresh_keeps_user_data_of_owned_games() {
+        tauri::async_runtime::block_on(async {
+            let app_dir = TempDir::new().unwrap();
+            let steam = MockSteamApi::default();
+            steam.set_library(&LIBRARY);
+            let app = refresh_app(&steam, &app_dir).await;
+
+            refresh_games(app.handle().clone()).await.unwrap();
+
+            let game_repository = app.state::<GameRepository>();
+            let collection_repository = app.state::<CollectionRepository>();
+            let session_repository = app.state::<SessionRepository>();
+            let launch_settings_repository = app.state::<LaunchSettingsRepository>();
+            let subscription_repository = app.state::<SubscriptionRepository>();
+            let achievement_repository = app.state::<AchievementRepository>();
+
+            let id = game_id(&app, "10").await;
+            game_repository.set_user_rating(id, Some(8)).await.unwrap();
+            game_repository
+                .set_status(id, Some(GameStatus::Playing))
+                .await
+                .unwrap();
+            game_repository.update_last_launched(id).await.unwrap();
+            let collection_id = collection_repository
+                .insert_collection("Favorites")
+                .await
+                .unwrap();
+            collection_repository
+                .add_game(collection_id, id)
+                .await
+                .unwrap();
+            let session_id = session_repository.start_session(id).await.unwrap();
+            session_repository.end_session(session_id).await.unwrap();
+            launch_settings_repository
+                .set_launch_settings(
+                    id,
+                    &LaunchSettings {
+                        controller_profile: Some("profile.amgp".to_string()),
+                    },
+                )
+                .await
+                .unwrap();
+            let subscription_id = subscription_repository
+                .insert_subscription(&NewSubscription {
+                    name: "Pass".to_string(),
+                    monthly_cost: 10.0,
+                    started_at: 0,
+                    ended_at: None,
+                })
+                .await
+                .unwrap();
+            subscription_repository
+                .add_game(subscription_id, id)
+                .await
+                .unwrap();
+            achievement_repository
+                .replace_achievements(
+                    id,
+                    vec![Achievement {
+                        api_name: "WIN".to_string(),
+                        display_name: "Winner".to_string(),
+                        description: None,
+                        icon: None,
+                        icon_gray: None,
+                        hidden: false,
+                        achieved: true,
+                        unlocked_at: Some(1),
+                    }],
+                )
+                .await
+                .unwrap();
+
+            refresh_games(app.handle().clone()).await.unwrap();
+
+            let id = game_id(&app, "10").await;
+            let game = game_repository.get_game_by_id(id).await.unwrap();
+            assert_eq!(game.user_rating, Some(8));
+            assert_eq!(game.status, Some(GameStatus::Playing));
+            assert!(game.last_launched_at.is_some());
+
+            let collections = collection_repository.get_collections().await.unwrap();
+            assert_eq!(collections[0].game_ids, vec![id]);
+
+            let sessions = session_repository.get_sessions(id).await.unwrap();
+            assert_eq!(sessions.len(), 1);
+            assert_eq!(sessions[0].id, session_id);
+
+            let settings = launch_settings_repository
+                .get_launch_settings(id)
+                .await
+                .unwrap();
+            assert_eq!(settings.controller_profile.as_deref(), Some("profile.amgp"));
+
+            let subscriptions = subscription_repository.get_subscriptions().await.unwrap();
+            assert_eq!(subscriptions[0].game_ids, vec![id]);
+
+            let achievements = achievement_repository.get_achievements(id).await.unwrap();
+            assert_eq!(achievements.len(), 1);
+            assert_eq!(achievements[0].api_name, "WIN");
+        });
+    }
+
+    #[test]
+    fn refresh_leaves_games_no_longer_owned_out_of_windows() {
+        tauri::async_runtime::block_on(async {
+            let app_dir = TempDir::new().unwrap();
+            let steam = MockSteamApi::default();
+            steam.set_library(&LIBRARY);
+            let app = refresh_app(&steam, &app_dir).await;
+
+            refresh_games(app.handle().clone()).await.unwrap();
+            steam.set_library(&LIBRARY[1..]);
+            let report = refresh_games(app.handle().clone()).await.unwrap();
+
+            assert_eq!(report.removed.len(), 1);
+            assert_eq!(report.removed[0].store_id, "10");
+
+            let removed_id = game_id(&app, "10").await;
+            let game_repository = app.state::<GameRepository>();
+
+            for sort in SORTS {
+                let mut ids = Vec::new();
+                let mut start = None;
+
+                loop {
+                    let window = game_repository
+                        .get_games_window(start, 2, sort, GameFilters::default())
+                        .await
+                        .unwrap();
+                    assert_eq!(window.total, LIBRARY.len() as i64 - 1, "{sort:?}");
+                    ids.extend(window.games.iter().map(|game| game.id));
+
+                    match window.next {
+                        Some(next) => start = Some(next),
+                        None => break,
+                    }
+                }
+
+                let mut unique_ids = ids.clone();
+                unique_ids.sort();
+                unique_ids.dedup();
+
+                assert_eq!(ids.len(), LIBRARY.len() - 1, "{sort:?}");
+                assert_eq!(unique_ids.len(), ids.len(), "{sort:?}");
+                assert!(!ids.contains(&removed_id), "{sort:?}");
+            }
+        });
+    }
+
+    #[test]
+    fn refresh_records_added_and_removed_games_in_history() {
+        tauri::async_runtime::block_on(async {
+            let app_dir = TempDir::new().unwrap();
+            let steam = MockSteamApi::default();
+            steam.set_library(&LIBRARY[1..]);
+            let app = refresh_app(&steam, &app_dir).await;
+
+            refresh_games(app.handle().clone()).await.unwrap();
+            steam.set_library(&LIBRARY[..LIBRARY.len() - 1]);
+            refresh_games(app.handle().clone()).await.unwrap();
+
+            let history = app
+                .state::<HistoryRepository>()
+                .get_history(100, None)
+                .await
+                .unwrap();
+            let events: Vec<(HistoryEvent, &str)> = history
+                .iter()
+                .take(2)
+                .map(|entry| (entry.event, entry.store_id.as_deref().unwrap()))
+                .collect();
+
+            assert_eq!(history.len(), LIBRARY.len() + 1);
+            assert!(events.contains(&(HistoryEvent::GameAdded, "10")));
+            assert!(events.contains(&(HistoryEvent::GameRemoved, "70")));
+        });
+    }
+
+    #[test]
+    fn cancelled_refresh_leaves_library_unchanged() {
+        tauri::async_runtime::block_on(async {
+            let app_dir = TempDir::new().unwrap();
+            let steam = MockSteamApi::default();
+            steam.set_library(&LIBRARY);
+            let app = refresh_app(&steam, &app_dir).await;
+
+            refresh_games(app.handle().clone()).await.unwrap();
+            steam.set_library(&LIBRARY[1..]);
+            assert!(app.state::<RefreshTracker>().cancel().await);
+            let result = refresh_games(app.handle().clone()).await;
+
+            assert!(matches!(result, Err(RocadeError::RefreshCancelled)));
+
+            let games = app.state::<GameRepository>().get_games().await.unwrap();
+            assert_eq!(games.len(), LIBRARY.len());
+            assert!(games.iter().all(|game| game.removed_at.is_none()));
+        });
+    }
+}
@@ -0,0 +1,18 @@
+//! Tauri command reading the library history.
+
+use tauri::State;
+
+use crate::db::history::{HistoryEntry, HistoryRepository};
+
+use super::game::RocadeError;
+
+/// Returns up to `limit` history entries, most recent first. Pass the ID of
+/// the oldest entry loaded as `before` to load the next page.
+#[tauri::command]
+pub async fn get_history(
+    history_repository: State<'_, HistoryRepository>,
+    limit: u32,
+    before: Option<i64>,
+) -> Result<Vec<HistoryEntry>, RocadeError> {
+    Ok(history_repository.get_history(limit, before).await?)
+}
@@ -0,0 +1,85 @@
+//! Tauri command looking up HowLongToBeat completion times.
+//!
+//! Lookups are cached for a month, including misses. When HowLongToBeat
+//! cannot be reached, the cached times are returned.
+
+use std::time::Duration;
+
+use tauri::State;
+
+use crate::{
+    cache::GameCache,
+    db::{
+        game::{CompletionTimes, GameRepository},
+        hltb::CompletionTimeRepository,
+    },
+    service::hltb::{HltbClient, HltbGame},
+};
+
+use super::{
+    game::{similarity, trigrams, RocadeError},
+    news::is_stale,
+};
+
+/// How long a lookup is trusted before HowLongToBeat is searched again.
+const COMPLETION_TIMES_TTL: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+
+/// Minimum trigram similarity between a game name and a search result for
+/// the result to be used.
+const MATCH_THRESHOLD: f64 = 0.6;
+
+/// Returns the average completion times of a game, looking them up on
+/// HowLongToBeat first if needed.
+///
+/// Returns `None` if no search result matches the game's name closely
+/// enough.
+#[tauri::command]
+pub async fn get_completion_times(
+    hltb_client: State<'_, HltbClient>,
+    game_repository: State<'_, GameRepository>,
+    completion_time_repository: State<'_, CompletionTimeRepository>,
+    game_cache: State<'_, GameCache>,
+    game_id: i64,
+) -> Result<Option<CompletionTimes>, RocadeError> {
+    let game = game_repository.get_game_by_id(game_id).await?;
+
+    if !is_stale(
+        completion_time_repository
+            .get_fetched_at(&game.name)
+            .await?,
+        COMPLETION_TIMES_TTL,
+    ) {
+        return Ok(game.completion_times);
+    }
+
+    // Offline: fall back to whatever is cached
+    let Ok(results) = hltb_client.search(&game.name).await else {
+        return Ok(game.completion_times);
+    };
+
+    completion_time_repository
+        .set_completion_times(&game.name, best_match(&game.name, &results))
+        .await?;
+    game_cache.invalidate(game_id);
+
+    Ok(game_repository
+        .get_game_by_id(game_id)
+        .await?
+        .completion_times)
+}
+
+/// Returns the search result whose name is the most similar to `name`, if
+/// it is similar enough.
+fn best_match<'a>(name: &str, results: &'a [HltbGame]) -> Option<&'a HltbGame> {
+    let name_trigrams = trigrams(&name.to_ascii_lowercase());
+
+    results
+        .iter()
+        .map(|result| {
+            let score = similarity(&name_trigrams, &result.game_name.to_ascii_lowercase());
+            (result, score)
+        })
+        .filter(|(_, score)| *score >= MATCH_THRESHOLD)
+        .max_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map(|(result, _)| result)
+}
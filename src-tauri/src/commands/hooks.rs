@@ -0,0 +1,54 @@
+//! Tauri commands for managing user scripting hooks.
+
+use serde::Serialize;
+use tauri::State;
+
+use crate::{
+    db::hook::{Hook, HookRepository},
+    hooks::{run_hooks, HookEvent},
+};
+
+use super::game::RocadeError;
+
+/// Returns all registered hooks.
+#[tauri::command]
+pub async fn get_hooks(
+    hook_repository: State<'_, HookRepository>,
+) -> Result<Vec<Hook>, RocadeError> {
+    Ok(hook_repository.get_hooks().await?)
+}
+
+/// Registers a command to run when `event` fires. Returns the hook's ID.
+#[tauri::command]
+pub async fn add_hook(
+    hook_repository: State<'_, HookRepository>,
+    event: HookEvent,
+    command: String,
+) -> Result<i64, RocadeError> {
+    Ok(hook_repository.insert_hook(event, &command).await?)
+}
+
+/// Removes a registered hook.
+#[tauri::command]
+pub async fn delete_hook(
+    hook_repository: State<'_, HookRepository>,
+    hook_id: i64,
+) -> Result<(), RocadeError> {
+    hook_repository.delete_hook(hook_id).await?;
+    Ok(())
+}
+
+/// Runs all hooks registered for `event` in the background.
+pub(super) async fn trigger_hooks<T: Serialize>(
+    hook_repository: &HookRepository,
+    event: HookEvent,
+    data: &T,
+) -> Result<(), RocadeError> {
+    let commands = hook_repository.get_commands(event).await?;
+
+    if !commands.is_empty() {
+        run_hooks(commands, event, data);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,186 @@
+//! Background import of games installed outside of Rocade.
+//!
+//! A game bought and installed from the Steam client directly is unknown to
+//! the library until the next refresh. When the Steam library watcher sees
+//! its manifest, the game is enriched and inserted on its own instead, and
+//! the frontend is notified.
+
+use std::collections::HashMap;
+
+use tauri::{async_runtime::Mutex, AppHandle, Emitter, Manager};
+
+use crate::{
+    assets::AssetManager,
+    db::{
+        company::CompanyRepository,
+        game::{GameRepository, GameSummary},
+        genre::GenreRepository,
+        hook::HookRepository,
+        rule::ExclusionRuleRepository,
+        settings::{Setting, SettingsRepository},
+    },
+    hooks::HookEvent,
+    igdb::{IgdbApi, IgdbError},
+    service::steam_store::{SteamStoreApi, DEFAULT_LANGUAGE},
+};
+
+use super::{
+    game::{
+        fetch_store_games, insert_games_with_images, insert_store_games, load_dictionaries,
+        store_accent_colors, store_refetched_genres, upscale_covers, RocadeError,
+    },
+    hooks::trigger_hooks,
+    rules::apply_exclusion_rules,
+};
+
+/// Name of the event emitted with the [`GameSummary`] of a game imported
+/// outside of a refresh.
+pub const GAME_IMPORTED_EVENT: &str = "library://game-imported";
+
+/// Imports the game with the given Steam App ID in the background, unless it
+/// is already in the library.
+///
+/// Does nothing until the IGDB client is configured: the first refresh
+/// imports the whole library anyway. Emits [`GAME_IMPORTED_EVENT`] once the
+/// game is inserted. Failures are ignored; the game is then picked up by the
+/// next refresh.
+pub(crate) fn spawn_game_import(app: AppHandle, store_id: String) {
+    if app.try_state::<Mutex<Box<dyn IgdbApi>>>().is_none() {
+        return;
+    }
+
+    tauri::async_runtime::spawn(async move {
+        if let Ok(Some(summary)) = import_game(&app, &store_id).await {
+            let _ = app.emit(GAME_IMPORTED_EVENT, summary);
+        }
+    });
+}
+
+/// Enriches and inserts a single game, from IGDB or from its Steam store page
+/// when IGDB has no match.
+///
+/// Returns `None` when the game was already known, has no store page, or
+/// turned out to be a soundtrack or DLC, which is attached to its game
+/// instead.
+async fn import_game(app: &AppHandle, store_id: &str) -> Result<Option<GameSummary>, RocadeError> {
+    let game_repository = app.state::<GameRepository>();
+
+    let Ok(appid) = store_id.parse::<u64>() else {
+        return Ok(None);
+    };
+
+    if game_repository.is_known_store_id(store_id).await? {
+        return Ok(None);
+    }
+
+    let igdb_client = app.state::<Mutex<Box<dyn IgdbApi>>>();
+
+    let igdb_game = {
+        let mut igdb_client = igdb_client.lock().await;
+        let genre_repository = app.state::<GenreRepository>();
+        load_dictionaries(
+            &mut **igdb_client,
+            &genre_repository,
+            &app.state::<CompanyRepository>(),
+        )
+        .await?;
+
+        let game = igdb_client.get_game(appid).await;
+        store_refetched_genres(&mut **igdb_client, &genre_repository).await?;
+
+        match game {
+            Ok(mut game) => {
+                game_repository
+                    .apply_metadata_overrides(std::slice::from_mut(&mut game))
+                    .await?;
+                Some(game)
+            }
+            Err(IgdbError::NoData(_)) => None,
+            Err(e) => return Err(e.into()),
+        }
+    };
+
+    let settings_repository = app.state::<SettingsRepository>();
+
+    if let Some(game) = &igdb_game {
+        if let Some(parent_id) = game.parent_game {
+            if let Some(parent_store_id) =
+                game_repository.get_store_id_by_igdb_id(parent_id).await?
+            {
+                let mut tx = game_repository.begin().await?;
+                game_repository
+                    .insert_dlc(&mut tx, store_id, &parent_store_id, &game.name)
+                    .await?;
+                tx.commit().await?;
+                return Ok(None);
+            }
+        }
+    }
+
+    match igdb_game {
+        Some(game) => {
+            let asset_manager = app.state::<AssetManager>();
+
+            // Artworks and screenshots are downloaded by `ensure_game_artworks`
+            let cover_ids = game.cover.iter().map(|cover| cover.image_id.clone());
+
+            let cover_paths = asset_manager
+                .download_batch_covers(cover_ids.collect())
+                .await?;
+
+            let cover_map: HashMap<String, String> = cover_paths.iter().cloned().collect();
+
+            let mut tx = game_repository.begin().await?;
+            insert_games_with_images(
+                &game_repository,
+                &mut tx,
+                vec![game],
+                cover_map,
+                HashMap::new(),
+                HashMap::new(),
+            )
+            .await?;
+            tx.commit().await?;
+            store_accent_colors(&game_repository, &asset_manager, cover_paths.clone()).await?;
+            upscale_covers(
+                &game_repository,
+                &asset_manager,
+                &settings_repository,
+                cover_paths,
+            )
+            .await?;
+        }
+        None => {
+            let language = settings_repository
+                .get(Setting::StoreLanguage)
+                .await?
+                .unwrap_or_else(|| DEFAULT_LANGUAGE.to_string());
+
+            let store_games = fetch_store_games(
+                &**app.state::<Box<dyn SteamStoreApi>>(),
+                vec![appid],
+                &language,
+            )
+            .await;
+
+            let mut tx = game_repository.begin().await?;
+            insert_store_games(&game_repository, &mut tx, store_games).await?;
+            tx.commit().await?;
+        }
+    }
+
+    apply_exclusion_rules(&game_repository, &app.state::<ExclusionRuleRepository>()).await?;
+
+    let Some(summary) = game_repository
+        .get_summaries_by_store_ids(&[store_id.to_string()])
+        .await?
+        .pop()
+    else {
+        return Ok(None);
+    };
+
+    let game = game_repository.get_game_by_id(summary.id).await?;
+    trigger_hooks(&app.state::<HookRepository>(), HookEvent::GameAdded, &game).await?;
+
+    Ok(Some(summary))
+}
@@ -0,0 +1,22 @@
+//! Tauri command reading the app logs, for diagnostics.
+
+use tauri::State;
+
+use crate::logging::LogFiles;
+
+use super::game::RocadeError;
+
+/// Default number of lines returned by [`get_recent_logs`].
+const RECENT_LOG_LINES: usize = 200;
+
+/// Returns the last `lines` logged lines, oldest first, 200 by default, so
+/// users can attach them to bug reports.
+#[tauri::command]
+pub async fn get_recent_logs(
+    log_files: State<'_, LogFiles>,
+    lines: Option<usize>,
+) -> Result<Vec<String>, RocadeError> {
+    Ok(log_files
+        .recent_lines(lines.unwrap_or(RECENT_LOG_LINES))
+        .await?)
+}
@@ -1,7 +1,133 @@
+mod achievements;
+mod artwork;
+mod benchmark;
+mod collections;
+mod deals;
+mod export;
+mod friends;
 mod game;
+mod history;
+mod hltb;
+mod hooks;
+mod import;
+mod logs;
+mod news;
+mod parental;
+mod profile;
+mod protondb;
+mod rematch;
+mod rules;
+mod session;
+mod settings;
+mod setup;
+mod similar;
+mod stats;
+mod subscription;
+mod whats_new;
+mod wine;
 
+pub use achievements::get_game_achievements;
+pub use achievements::sync_achievements;
+pub use artwork::add_custom_artwork;
+pub use artwork::set_artwork_source;
+pub use artwork::set_custom_cover;
+pub use artwork::set_steamgriddb_api_key;
+pub use benchmark::benchmark_refresh;
+pub use collections::add_game_to_collection;
+pub use collections::create_collection;
+pub use collections::get_collections;
+pub use collections::remove_game_from_collection;
+pub use collections::rename_collection;
+pub use deals::get_deals;
+pub use export::export_library;
+pub use export::import_library;
+pub use friends::get_common_games;
+pub use friends::get_friend_libraries;
+pub use game::edit_game_metadata;
+pub use game::ensure_game_artworks;
+pub use game::find_duplicate_games;
+pub use game::get_all_developers;
+pub use game::get_all_genres;
+pub use game::get_all_release_years;
+pub use game::get_franchise_games;
 pub use game::get_game;
+pub use game::get_game_notes;
 pub use game::get_games;
+pub use game::get_games_window;
+pub use game::get_install_progress;
+pub use game::get_last_refresh_report;
+pub use game::get_recently_added;
+pub use game::get_recently_played;
+pub use game::get_unmatched_games;
 pub use game::install_game;
+pub use game::merge_games;
+pub use game::pick_random_game;
+pub use game::pin_game;
+pub use game::play_soundtrack;
+pub use game::prefetch_game;
+pub use game::rate_game;
 pub use game::refresh_games;
+pub use game::reorder_pins;
+pub use game::set_game_hidden;
+pub use game::set_game_notes;
+pub use game::set_game_status;
+pub use game::toggle_favorite;
+pub use game::undo_last_refresh;
 pub use game::uninstall_game;
+pub use history::get_history;
+pub use hltb::get_completion_times;
+pub use hooks::add_hook;
+pub use hooks::delete_hook;
+pub use hooks::get_hooks;
+pub use logs::get_recent_logs;
+pub use news::get_game_news;
+pub use parental::get_mature_content_filter;
+pub use parental::set_mature_content_filter;
+pub use profile::get_profile_summary;
+pub use rematch::rematch_all;
+pub use rematch::rematch_game;
+pub use rematch::search_igdb;
+pub use rules::add_exclusion_rule;
+pub use rules::delete_exclusion_rule;
+pub use rules::get_exclusion_rules;
+pub use rules::preview_exclusion_rule;
+pub use session::get_launch_settings;
+pub use session::get_play_sessions;
+pub use session::launch_game;
+pub use session::set_launch_settings;
+pub use settings::check_db_integrity;
+pub use settings::create_full_backup;
+pub use settings::get_cache_stats;
+pub use settings::get_setting;
+pub use settings::list_backups;
+pub use settings::restore_backup;
+pub use settings::restore_full_backup;
+pub use settings::run_db_maintenance;
+pub use settings::set_setting;
+pub use settings::verify_assets;
+pub use setup::complete_setup;
+pub use setup::is_setup_complete;
+pub use setup::validate_steam_credentials;
+pub use setup::validate_twitch_credentials;
+pub use similar::get_similar_games;
+pub use stats::get_playtime_history;
+pub use stats::sync_player_stats;
+pub use subscription::add_subscription;
+pub use subscription::add_subscription_game;
+pub use subscription::delete_subscription;
+pub use subscription::end_subscription;
+pub use subscription::get_subscription_report;
+pub use subscription::get_subscriptions;
+pub use subscription::remove_subscription_game;
+pub use whats_new::dismiss_whats_new;
+pub use whats_new::get_whats_new;
+pub use wine::add_custom_game;
+pub use wine::create_wine_prefix;
+pub use wine::export_custom_games;
+pub use wine::get_wine_config;
+pub use wine::import_custom_games;
+pub use wine::open_wine_prefix;
+pub use wine::set_wine_config;
+
+pub(crate) use import::spawn_game_import;
+pub(crate) use stats::schedule_player_stats_sync;
@@ -0,0 +1,64 @@
+//! Tauri commands for per-game Steam news.
+//!
+//! News is cached in the database and refreshed from Steam at most every few
+//! hours. When Steam cannot be reached, the cached news is returned so the
+//! detail page still works offline.
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use tauri::State;
+
+use crate::{
+    db::{
+        game::GameRepository,
+        news::{GameNews, NewsRepository},
+    },
+    service::steam::{SteamApi, SteamError},
+};
+
+use super::game::RocadeError;
+
+/// How long cached news is served before Steam is queried again.
+const NEWS_TTL: Duration = Duration::from_secs(6 * 60 * 60);
+
+/// Number of news items fetched per game.
+const NEWS_COUNT: u32 = 20;
+
+/// Returns the recent news of a game, most recent first.
+#[tauri::command]
+pub async fn get_game_news(
+    steam_client: State<'_, Box<dyn SteamApi>>,
+    game_repository: State<'_, GameRepository>,
+    news_repository: State<'_, NewsRepository>,
+    game_id: i64,
+) -> Result<Vec<GameNews>, RocadeError> {
+    let store_id = game_repository.get_game_store_id(game_id).await?;
+
+    if is_stale(news_repository.get_fetched_at(&store_id).await?, NEWS_TTL) {
+        let appid: u64 = store_id
+            .parse()
+            .map_err(|_| SteamError::InvalidResponse(format!("invalid app id: {}", store_id)))?;
+
+        // Offline: fall back to whatever is cached
+        if let Ok(news) = steam_client.get_game_news(appid, NEWS_COUNT).await {
+            news_repository.replace_news(&store_id, news).await?;
+        }
+    }
+
+    Ok(news_repository.get_news(&store_id).await?)
+}
+
+/// Returns `true` if data fetched at `fetched_at` is older than `ttl` and
+/// should be refreshed.
+pub(super) fn is_stale(fetched_at: Option<i64>, ttl: Duration) -> bool {
+    let Some(fetched_at) = fetched_at else {
+        return true;
+    };
+
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or_default();
+
+    now - fetched_at >= ttl.as_secs() as i64
+}
@@ -0,0 +1,124 @@
+//! Tauri commands for the mature-content filter.
+//!
+//! Games whose PEGI or ESRB rating is above a configured age are left out of
+//! `get_games`. The filter is protected by a PIN kept in the OS keychain: the
+//! PIN is required to change the filter, and passing it to `get_games` lists
+//! every game.
+
+use serde::Serialize;
+use tauri::State;
+
+use crate::{
+    config::RocadeConfigError,
+    db::settings::{Setting, SettingsRepository},
+    secrets::{Secret, SecretStore},
+};
+
+use super::game::RocadeError;
+
+/// State of the mature-content filter, as returned by
+/// [`get_mature_content_filter`].
+#[derive(Serialize)]
+pub struct MatureContentFilter {
+    /// Highest minimum player age of the listed games, `None` when the
+    /// filter is off.
+    pub max_age: Option<i64>,
+    /// Whether a PIN was chosen. The first call to
+    /// [`set_mature_content_filter`] sets it.
+    pub has_pin: bool,
+}
+
+/// Returns the configured age limit and whether a PIN is set.
+#[tauri::command]
+pub async fn get_mature_content_filter(
+    settings_repository: State<'_, SettingsRepository>,
+    secret_store: State<'_, SecretStore>,
+) -> Result<MatureContentFilter, RocadeError> {
+    Ok(MatureContentFilter {
+        max_age: get_max_age(&settings_repository).await?,
+        has_pin: get_pin(&secret_store)?.is_some(),
+    })
+}
+
+/// Sets the highest minimum player age of the games listed by `get_games`,
+/// or turns the filter off with `None`.
+///
+/// `pin` must match the stored PIN; when none is set yet, it becomes the
+/// PIN. Passing `new_pin` replaces the PIN.
+#[tauri::command]
+pub async fn set_mature_content_filter(
+    settings_repository: State<'_, SettingsRepository>,
+    secret_store: State<'_, SecretStore>,
+    max_age: Option<i64>,
+    pin: String,
+    new_pin: Option<String>,
+) -> Result<(), RocadeError> {
+    match get_pin(&secret_store)? {
+        Some(stored) if stored != pin => {
+            return Err(RocadeConfigError::ConfigError("invalid PIN".to_string()).into())
+        }
+        Some(_) => {}
+        None => set_pin(&secret_store, &pin)?,
+    }
+
+    if let Some(new_pin) = new_pin {
+        set_pin(&secret_store, &new_pin)?;
+    }
+
+    match max_age {
+        Some(max_age) => {
+            settings_repository
+                .set(Setting::MaxAgeRating, &max_age.to_string())
+                .await?
+        }
+        None => settings_repository.delete(Setting::MaxAgeRating).await?,
+    }
+
+    Ok(())
+}
+
+/// Returns the age limit `get_games` applies: the configured one, or `None`
+/// when the filter is off or `pin` unlocks it.
+pub(super) async fn max_age_rating(
+    settings_repository: &SettingsRepository,
+    secret_store: &SecretStore,
+    pin: Option<&str>,
+) -> Result<Option<i64>, RocadeError> {
+    let Some(max_age) = get_max_age(settings_repository).await? else {
+        return Ok(None);
+    };
+
+    if pin.is_some() && get_pin(secret_store)?.as_deref() == pin {
+        return Ok(None);
+    }
+
+    Ok(Some(max_age))
+}
+
+/// Returns the configured age limit, ignoring an invalid stored value.
+async fn get_max_age(settings_repository: &SettingsRepository) -> Result<Option<i64>, RocadeError> {
+    Ok(settings_repository
+        .get(Setting::MaxAgeRating)
+        .await?
+        .and_then(|value| value.parse().ok()))
+}
+
+/// Returns the stored PIN, or `None` if none was chosen.
+fn get_pin(secret_store: &SecretStore) -> Result<Option<String>, RocadeError> {
+    Ok(secret_store
+        .get(Secret::ParentalPin)
+        .map_err(RocadeConfigError::from)?)
+}
+
+/// Stores `pin` as the PIN, rejecting an empty one.
+fn set_pin(secret_store: &SecretStore, pin: &str) -> Result<(), RocadeError> {
+    if pin.is_empty() {
+        return Err(RocadeConfigError::ConfigError("the PIN cannot be empty".to_string()).into());
+    }
+
+    secret_store
+        .set(Secret::ParentalPin, pin)
+        .map_err(RocadeConfigError::from)?;
+
+    Ok(())
+}
@@ -0,0 +1,16 @@
+//! Tauri commands for the configured Steam profile.
+
+use tauri::State;
+
+use crate::service::steam::{ProfileSummary, SteamApi};
+
+use super::game::RocadeError;
+
+/// Returns the avatar, persona name, and online state of the configured
+/// Steam user.
+#[tauri::command]
+pub async fn get_profile_summary(
+    steam_client: State<'_, Box<dyn SteamApi>>,
+) -> Result<ProfileSummary, RocadeError> {
+    Ok(steam_client.get_profile_summary().await?)
+}
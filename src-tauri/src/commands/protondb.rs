@@ -0,0 +1,59 @@
+//! Background sync of ProtonDB compatibility tiers.
+//!
+//! Tiers only matter on Linux, so they are only synced there. They change
+//! slowly and are refreshed weekly, after each library refresh.
+
+use std::time::Duration;
+
+use futures::stream::{self, StreamExt};
+use tauri::{AppHandle, Manager, Runtime};
+
+use crate::{cache::GameCache, db::protondb::ProtonDbRepository, service::protondb::ProtonDbApi};
+
+/// How long a cached tier is trusted before ProtonDB is queried again.
+const TIER_TTL: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// Maximum number of tiers fetched concurrently.
+const CONCURRENT_TIER_REQUESTS: usize = 5;
+
+/// Fetches the stale ProtonDB tiers of the library in the background.
+///
+/// Does nothing on other platforms than Linux. Games whose tier cannot be
+/// fetched keep their cached tier and are retried on the next sync.
+pub(super) fn spawn_protondb_sync<R: Runtime>(app: AppHandle<R>) {
+    if !cfg!(target_os = "linux") {
+        return;
+    }
+
+    tauri::async_runtime::spawn(async move {
+        let protondb_repository = app.state::<ProtonDbRepository>();
+        let protondb_client = app.state::<Box<dyn ProtonDbApi>>();
+
+        let Ok(store_ids) = protondb_repository
+            .get_stale_store_ids(TIER_TTL.as_secs() as i64)
+            .await
+        else {
+            return;
+        };
+
+        let protondb_client = &**protondb_client;
+        let tiers: Vec<_> = stream::iter(store_ids)
+            .map(|store_id| async move {
+                let tier = protondb_client.get_tier(&store_id).await;
+                (store_id, tier)
+            })
+            .buffer_unordered(CONCURRENT_TIER_REQUESTS)
+            .collect()
+            .await;
+
+        for (store_id, tier) in tiers {
+            if let Ok(tier) = tier {
+                let _ = protondb_repository
+                    .set_tier(&store_id, tier.as_deref())
+                    .await;
+            }
+        }
+
+        app.state::<GameCache>().clear();
+    });
+}
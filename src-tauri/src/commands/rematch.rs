@@ -0,0 +1,255 @@
+//! Tauri commands re-running IGDB matching over the library, or fixing the
+//! match of a single game by hand.
+//!
+//! Re-matching the library runs as a background job: the command returns
+//! immediately and the outcome is reported through events. Matched games are
+//! updated in place, so their sessions, achievements, and settings are kept,
+//! and only images that changed are downloaded.
+
+use std::collections::HashMap;
+
+use serde::Serialize;
+use tauri::{async_runtime::Mutex, AppHandle, Emitter, Manager, State};
+
+use crate::{
+    assets::AssetManager,
+    cache::GameCache,
+    config::RocadeConfigError,
+    db::{
+        company::CompanyRepository,
+        game::{GameRepository, IgdbMatch, NewImages},
+        genre::GenreRepository,
+        rule::ExclusionRuleRepository,
+    },
+    igdb::{IgdbApi, IgdbError, IgdbSearchResult},
+};
+
+use super::{
+    game::{
+        create_artwork_thumbnails, load_dictionaries, store_accent_colors, store_refetched_genres,
+        RocadeError,
+    },
+    rules::apply_exclusion_rules,
+};
+
+/// Name of the event emitted with a [`RematchReport`] once a re-match ends.
+pub const REMATCH_FINISHED_EVENT: &str = "igdb://rematch-finished";
+
+/// Name of the event emitted with the error message when a re-match fails.
+pub const REMATCH_FAILED_EVENT: &str = "igdb://rematch-failed";
+
+/// Outcome of a re-match.
+#[derive(Serialize, Clone)]
+pub struct RematchReport {
+    /// Number of games whose match was checked.
+    pub checked: usize,
+    /// Number of games that got a new or different IGDB match.
+    pub improved: usize,
+}
+
+/// Re-runs IGDB matching across the library in the background.
+///
+/// When `unmatched_only` is set, only games without an IGDB match (filled in
+/// from the Steam store) are checked. Emits [`REMATCH_FINISHED_EVENT`] or
+/// [`REMATCH_FAILED_EVENT`] when done.
+#[tauri::command]
+pub async fn rematch_all(app: AppHandle, unmatched_only: bool) -> Result<(), RocadeError> {
+    if app.try_state::<Mutex<Box<dyn IgdbApi>>>().is_none() {
+        return Err(
+            RocadeConfigError::ConfigError("IGDB client is not configured".to_string()).into(),
+        );
+    }
+
+    tauri::async_runtime::spawn(async move {
+        let _ = match rematch(&app, unmatched_only).await {
+            Ok(report) => app.emit(REMATCH_FINISHED_EVENT, report),
+            Err(e) => app.emit(REMATCH_FAILED_EVENT, e.to_string()),
+        };
+    });
+
+    Ok(())
+}
+
+/// Fetches the IGDB matches of the selected games and applies those that
+/// differ from the stored one.
+async fn rematch(app: &AppHandle, unmatched_only: bool) -> Result<RematchReport, RocadeError> {
+    let game_repository = app.state::<GameRepository>();
+    let asset_manager = app.state::<AssetManager>();
+    let igdb_client = app.state::<Mutex<Box<dyn IgdbApi>>>();
+
+    let matches = game_repository.get_igdb_matches(unmatched_only).await?;
+    let checked = matches.len();
+
+    let mut igdb_games = {
+        let mut igdb_client = igdb_client.lock().await;
+        let genre_repository = app.state::<GenreRepository>();
+        load_dictionaries(
+            &mut **igdb_client,
+            &genre_repository,
+            &app.state::<CompanyRepository>(),
+        )
+        .await?;
+
+        let games = igdb_client
+            .get_games(
+                matches
+                    .iter()
+                    .filter_map(|current| current.store_id.parse().ok())
+                    .collect(),
+            )
+            .await?;
+        store_refetched_genres(&mut **igdb_client, &genre_repository).await?;
+
+        games
+    };
+    game_repository
+        .apply_metadata_overrides(&mut igdb_games)
+        .await?;
+
+    let matches: HashMap<String, IgdbMatch> = matches
+        .into_iter()
+        .map(|current| (current.store_id.clone(), current))
+        .collect();
+
+    let mut improved = 0;
+
+    for game in igdb_games {
+        let Some(current) = game.store_id.as_ref().and_then(|id| matches.get(id)) else {
+            continue;
+        };
+
+        if current.igdb_id == Some(game.id as i64) {
+            continue;
+        }
+
+        let new_images = game_repository
+            .replace_igdb_metadata(current.game_id, &game)
+            .await?;
+        download_new_images(
+            &game_repository,
+            &asset_manager,
+            current.game_id,
+            new_images,
+        )
+        .await?;
+
+        improved += 1;
+    }
+
+    if improved > 0 {
+        apply_exclusion_rules(&game_repository, &app.state::<ExclusionRuleRepository>()).await?;
+        app.state::<GameCache>().clear();
+    }
+
+    Ok(RematchReport { checked, improved })
+}
+
+/// Searches IGDB games by name, with their covers, for the user to pick the
+/// right match of a game.
+#[tauri::command]
+pub async fn search_igdb(
+    igdb_client: State<'_, Mutex<Box<dyn IgdbApi>>>,
+    name: String,
+) -> Result<Vec<IgdbSearchResult>, RocadeError> {
+    Ok(igdb_client.lock().await.search_games(&name).await?)
+}
+
+/// Replaces the IGDB match of a Steam game with the entry chosen by the user.
+///
+/// The game's metadata is re-fetched and updated in place, and only the
+/// images that changed are downloaded. The chosen match is remembered: later
+/// refreshes and library re-matches keep it.
+#[tauri::command]
+pub async fn rematch_game(app: AppHandle, game_id: i64, igdb_id: u64) -> Result<(), RocadeError> {
+    let game_repository = app.state::<GameRepository>();
+    let store_id = game_repository.get_game_store_id(game_id).await?;
+
+    let mut igdb_games = {
+        let igdb_client = app.state::<Mutex<Box<dyn IgdbApi>>>();
+        let mut igdb_client = igdb_client.lock().await;
+        let genre_repository = app.state::<GenreRepository>();
+        load_dictionaries(
+            &mut **igdb_client,
+            &genre_repository,
+            &app.state::<CompanyRepository>(),
+        )
+        .await?;
+
+        let games = igdb_client
+            .get_games_by_igdb_ids(vec![(store_id.clone(), igdb_id)])
+            .await?;
+        store_refetched_genres(&mut **igdb_client, &genre_repository).await?;
+
+        games
+    };
+    game_repository
+        .apply_metadata_overrides(&mut igdb_games)
+        .await?;
+
+    let game = igdb_games
+        .pop()
+        .ok_or_else(|| IgdbError::NoData(format!("no IGDB game with ID {}", igdb_id)))?;
+
+    let new_images = game_repository
+        .replace_igdb_metadata(game_id, &game)
+        .await?;
+    game_repository
+        .set_manual_igdb_match(&store_id, igdb_id)
+        .await?;
+    app.state::<GameCache>().invalidate(game_id);
+
+    download_new_images(
+        &game_repository,
+        &app.state::<AssetManager>(),
+        game_id,
+        new_images,
+    )
+    .await?;
+    apply_exclusion_rules(&game_repository, &app.state::<ExclusionRuleRepository>()).await?;
+
+    Ok(())
+}
+
+/// Downloads the images a re-matched game does not have yet and stores
+/// their local paths.
+async fn download_new_images(
+    game_repository: &GameRepository,
+    asset_manager: &AssetManager,
+    game_id: i64,
+    new_images: NewImages,
+) -> Result<(), RocadeError> {
+    if let Some(cover_id) = new_images.cover {
+        let cover_paths = asset_manager.download_batch_covers(vec![cover_id]).await?;
+
+        for (image_id, local_path) in &cover_paths {
+            game_repository
+                .update_cover_path(game_id, image_id, local_path)
+                .await?;
+        }
+
+        store_accent_colors(game_repository, asset_manager, cover_paths).await?;
+    }
+
+    if !new_images.artworks.is_empty() {
+        let paths = asset_manager
+            .download_batch_artworks(new_images.artworks)
+            .await?;
+
+        game_repository
+            .update_artwork_paths(game_id, paths.clone())
+            .await?;
+        create_artwork_thumbnails(game_repository, asset_manager, paths).await?;
+    }
+
+    if !new_images.screenshots.is_empty() {
+        let paths = asset_manager
+            .download_batch_screenshots(new_images.screenshots)
+            .await?;
+
+        game_repository
+            .update_screenshot_paths(game_id, paths)
+            .await?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,98 @@
+//! Tauri commands for managing exclusion rules.
+//!
+//! Exclusion rules hide matching games from the library. They are applied
+//! after each refresh and re-applied whenever a rule is added or removed.
+
+use tauri::State;
+
+use crate::{
+    db::{
+        game::{Game, GameRepository},
+        rule::{ExclusionRuleRepository, StoredExclusionRule},
+    },
+    rules::{ExclusionRule, RuleMatcher},
+};
+
+use super::game::RocadeError;
+
+/// Returns all exclusion rules.
+#[tauri::command]
+pub async fn get_exclusion_rules(
+    rule_repository: State<'_, ExclusionRuleRepository>,
+) -> Result<Vec<StoredExclusionRule>, RocadeError> {
+    Ok(rule_repository.get_rules().await?)
+}
+
+/// Adds an exclusion rule and immediately applies it to the library.
+///
+/// Returns the new rule's database ID.
+#[tauri::command]
+pub async fn add_exclusion_rule(
+    rule_repository: State<'_, ExclusionRuleRepository>,
+    game_repository: State<'_, GameRepository>,
+    rule: ExclusionRule,
+) -> Result<i64, RocadeError> {
+    rule.compile()?;
+
+    let rule_id = rule_repository.insert_rule(&rule).await?;
+
+    apply_exclusion_rules(&game_repository, &rule_repository).await?;
+
+    Ok(rule_id)
+}
+
+/// Deletes an exclusion rule, un-hiding games no other rule matches.
+#[tauri::command]
+pub async fn delete_exclusion_rule(
+    rule_repository: State<'_, ExclusionRuleRepository>,
+    game_repository: State<'_, GameRepository>,
+    rule_id: i64,
+) -> Result<(), RocadeError> {
+    rule_repository.delete_rule(rule_id).await?;
+
+    apply_exclusion_rules(&game_repository, &rule_repository).await?;
+
+    Ok(())
+}
+
+/// Returns the games a rule would hide, without saving or applying it.
+#[tauri::command]
+pub async fn preview_exclusion_rule(
+    game_repository: State<'_, GameRepository>,
+    rule: ExclusionRule,
+) -> Result<Vec<Game>, RocadeError> {
+    let matcher = rule.compile()?;
+
+    let games = game_repository.get_games().await?;
+
+    Ok(games
+        .into_iter()
+        .filter(|game| matcher.matches(game))
+        .collect())
+}
+
+/// Re-evaluates all exclusion rules and updates the `excluded` flag of every
+/// game accordingly.
+pub(super) async fn apply_exclusion_rules(
+    game_repository: &GameRepository,
+    rule_repository: &ExclusionRuleRepository,
+) -> Result<(), RocadeError> {
+    let matchers = rule_repository
+        .get_rules()
+        .await?
+        .iter()
+        .map(|stored| stored.rule.compile())
+        .collect::<Result<Vec<RuleMatcher>, _>>()?;
+
+    let excluded: Vec<i64> = game_repository
+        .get_games()
+        .await?
+        .iter()
+        .filter(|game| matchers.iter().any(|matcher| matcher.matches(game)))
+        .map(|game| game.id)
+        .collect();
+
+    game_repository.set_excluded_games(&excluded).await?;
+
+    Ok(())
+}
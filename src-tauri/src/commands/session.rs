@@ -0,0 +1,309 @@
+//! Tauri commands for launching games and tracking play sessions.
+//!
+//! Launching a game opens a session, then a background task polls Steam's
+//! running-app state until the game exits and records the session duration.
+//! Custom games are started directly, or through Wine when configured, and
+//! their process is awaited instead. A game's controller-remapping profile is
+//! loaded for the duration of the session, and the game is shown as Discord
+//! Rich Presence when enabled.
+
+use std::{path::Path, time::Duration};
+
+use tauri::{AppHandle, Manager, State};
+use tokio::process::{Child, Command};
+
+use crate::{
+    cache::GameCache,
+    client::steam::SteamClient,
+    controller::{self, DEFAULT_LOAD_COMMAND, DEFAULT_UNLOAD_COMMAND},
+    db::{
+        custom::CustomGameRepository,
+        game::GameRepository,
+        history::HistoryRepository,
+        hook::HookRepository,
+        launch::{LaunchSettings, LaunchSettingsRepository},
+        session::{PlaySession, SessionRepository},
+        settings::{Setting, SettingsRepository},
+    },
+    discord::DiscordPresence,
+    hooks::HookEvent,
+};
+
+use super::{game::RocadeError, hooks::trigger_hooks};
+
+/// Interval between two checks of the game's running state.
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// How long to wait for Steam to start the game before giving up on the
+/// session. Covers pending updates and launcher screens.
+const START_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// A launched game whose exit is being watched.
+enum LaunchedGame {
+    /// A Steam game, identified by its Steam App ID.
+    Steam(String),
+    /// A custom game process.
+    Process(Child),
+}
+
+/// Launches a game, records the launch time, and starts tracking its play
+/// session.
+///
+/// Steam games are launched through Steam; custom games run their executable,
+/// through Wine if the game has a Wine configuration. The game's controller
+/// profile, if any, is loaded first and unloaded once the session ends.
+/// Returns the ID of the opened session. If a Steam game does not start
+/// within five minutes, the session is discarded.
+#[tauri::command]
+pub async fn launch_game(app: AppHandle, game_id: i64) -> Result<i64, RocadeError> {
+    let game_repository = app.state::<GameRepository>();
+    let launch_settings = app
+        .state::<LaunchSettingsRepository>()
+        .get_launch_settings(game_id)
+        .await?;
+
+    if let Some(profile) = &launch_settings.controller_profile {
+        let load_command = app
+            .state::<SettingsRepository>()
+            .get(Setting::ControllerProfileLoadCommand)
+            .await?
+            .unwrap_or_else(|| DEFAULT_LOAD_COMMAND.to_string());
+
+        controller::load_profile(&load_command, profile)?;
+    }
+
+    let unload_profile = launch_settings.controller_profile.is_some();
+
+    let launched = match start_game(
+        &app,
+        &game_repository,
+        &app.state::<CustomGameRepository>(),
+        game_id,
+    )
+    .await
+    {
+        Ok(launched) => launched,
+        Err(e) => {
+            if unload_profile {
+                unload_controller_profile(&app).await;
+            }
+            return Err(e);
+        }
+    };
+
+    let store_id = match &launched {
+        LaunchedGame::Steam(store_id) => Some(store_id.clone()),
+        LaunchedGame::Process(_) => None,
+    };
+
+    game_repository.update_last_launched(game_id).await?;
+    app.state::<GameCache>().invalidate(game_id);
+
+    let session_id = app
+        .state::<SessionRepository>()
+        .start_session(game_id)
+        .await?;
+
+    let show_presence = show_discord_presence(&app, &game_repository, game_id).await;
+
+    trigger_hooks(
+        &app.state::<HookRepository>(),
+        HookEvent::GameLaunched,
+        &serde_json::json!({
+            "game_id": game_id,
+            "store_id": store_id,
+            "session_id": session_id,
+        }),
+    )
+    .await?;
+
+    tauri::async_runtime::spawn(async move {
+        match launched {
+            LaunchedGame::Steam(store_id) => watch_session(&app, session_id, store_id).await,
+            LaunchedGame::Process(child) => watch_process(&app, session_id, child).await,
+        }
+
+        if unload_profile {
+            unload_controller_profile(&app).await;
+        }
+
+        if show_presence {
+            app.state::<DiscordPresence>().clear();
+        }
+    });
+
+    Ok(session_id)
+}
+
+/// Shows the game as Discord Rich Presence if enabled in the settings.
+///
+/// Returns `true` if the activity was set. Failures, e.g. Discord not
+/// running, are ignored.
+async fn show_discord_presence(
+    app: &AppHandle,
+    game_repository: &GameRepository,
+    game_id: i64,
+) -> bool {
+    let settings_repository = app.state::<SettingsRepository>();
+
+    if !settings_repository
+        .is_enabled(Setting::DiscordRichPresence)
+        .await
+        .unwrap_or(false)
+    {
+        return false;
+    }
+
+    let Ok(Some(application_id)) = settings_repository.get(Setting::DiscordApplicationId).await
+    else {
+        return false;
+    };
+
+    let Ok(game) = game_repository.get_game_by_id(game_id).await else {
+        return false;
+    };
+
+    app.state::<DiscordPresence>()
+        .set_playing(
+            &application_id,
+            &game.name,
+            game.cover.as_ref().map(|cover| cover.id.as_str()),
+        )
+        .is_ok()
+}
+
+/// Starts a game through Steam, or directly for custom games.
+async fn start_game(
+    app: &AppHandle,
+    game_repository: &GameRepository,
+    custom_game_repository: &CustomGameRepository,
+    game_id: i64,
+) -> Result<LaunchedGame, RocadeError> {
+    match custom_game_repository.get_executable(game_id).await? {
+        Some(executable) => {
+            let child = match custom_game_repository.get_wine_config(game_id).await? {
+                Some(config) => config.launch(&executable)?,
+                None => spawn_executable(&executable)?,
+            };
+
+            Ok(LaunchedGame::Process(child))
+        }
+        None => {
+            let store_id = game_repository.get_game_store_id(game_id).await?;
+            SteamClient::launch_game(app.clone(), &store_id)?;
+
+            Ok(LaunchedGame::Steam(store_id))
+        }
+    }
+}
+
+/// Unloads the current controller profile. Failures are ignored.
+async fn unload_controller_profile(app: &AppHandle) {
+    let unload_command = app
+        .state::<SettingsRepository>()
+        .get(Setting::ControllerProfileUnloadCommand)
+        .await
+        .ok()
+        .flatten()
+        .unwrap_or_else(|| DEFAULT_UNLOAD_COMMAND.to_string());
+
+    let _ = controller::unload_profile(&unload_command).await;
+}
+
+/// Returns the launch settings of a game.
+#[tauri::command]
+pub async fn get_launch_settings(
+    launch_settings_repository: State<'_, LaunchSettingsRepository>,
+    game_id: i64,
+) -> Result<LaunchSettings, RocadeError> {
+    Ok(launch_settings_repository
+        .get_launch_settings(game_id)
+        .await?)
+}
+
+/// Stores the launch settings of a game. They apply from the next launch.
+#[tauri::command]
+pub async fn set_launch_settings(
+    launch_settings_repository: State<'_, LaunchSettingsRepository>,
+    game_id: i64,
+    settings: LaunchSettings,
+) -> Result<(), RocadeError> {
+    launch_settings_repository
+        .set_launch_settings(game_id, &settings)
+        .await?;
+
+    Ok(())
+}
+
+/// Starts a native executable from its own directory.
+fn spawn_executable(executable: &str) -> Result<Child, std::io::Error> {
+    let mut command = Command::new(executable);
+
+    if let Some(dir) = Path::new(executable).parent() {
+        command.current_dir(dir);
+    }
+
+    command.spawn()
+}
+
+/// Waits for a custom game process to exit and closes the session.
+async fn watch_process(app: &AppHandle, session_id: i64, mut child: Child) {
+    let _ = child.wait().await;
+
+    close_session(app, session_id).await;
+}
+
+/// Closes a session and records it in the history.
+async fn close_session(app: &AppHandle, session_id: i64) {
+    if let Err(e) = app
+        .state::<SessionRepository>()
+        .end_session(session_id)
+        .await
+    {
+        tracing::warn!("failed to end session {session_id}: {e}");
+        return;
+    }
+
+    if let Err(e) = app
+        .state::<HistoryRepository>()
+        .record_session(session_id)
+        .await
+    {
+        tracing::warn!("failed to record session {session_id} in the history: {e}");
+    }
+}
+
+/// Returns the play sessions recorded for a game, most recent first.
+#[tauri::command]
+pub async fn get_play_sessions(
+    session_repository: State<'_, SessionRepository>,
+    game_id: i64,
+) -> Result<Vec<PlaySession>, RocadeError> {
+    Ok(session_repository.get_sessions(game_id).await?)
+}
+
+/// Waits for the game to start and then exit, and closes the session.
+///
+/// The session is deleted if the game never starts.
+async fn watch_session(app: &AppHandle, session_id: i64, store_id: String) {
+    let steam_client = app.state::<SteamClient>();
+    let session_repository = app.state::<SessionRepository>();
+
+    let mut waited = Duration::ZERO;
+
+    while !steam_client.is_steam_game_running(&store_id) {
+        if waited >= START_TIMEOUT {
+            let _ = session_repository.delete_session(session_id).await;
+            return;
+        }
+
+        tokio::time::sleep(POLL_INTERVAL).await;
+        waited += POLL_INTERVAL;
+    }
+
+    while steam_client.is_steam_game_running(&store_id) {
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+
+    close_session(app, session_id).await;
+}
@@ -0,0 +1,354 @@
+//! Tauri commands for reading and changing application settings.
+
+use std::{collections::HashSet, path::Path};
+
+use serde::Serialize;
+use tauri::{AppHandle, State};
+
+use crate::{
+    archive,
+    assets::{AssetError, AssetManager, CacheStats},
+    cache::GameCache,
+    config::RocadeConfigError,
+    db::{
+        game::GameRepository,
+        settings::{Setting, SettingsRepository},
+        DatabaseBackup, DatabaseState, MaintenanceReport,
+    },
+    retry,
+};
+
+use super::game::{create_artwork_thumbnails, upscale_covers, RocadeError};
+
+/// Outcome of [`verify_assets`].
+#[derive(Serialize)]
+pub struct AssetVerification {
+    /// Number of cached images checked.
+    pub checked: usize,
+    /// Number of corrupt images found.
+    pub corrupt: usize,
+    /// Number of corrupt images downloaded or created again.
+    pub repaired: usize,
+}
+
+/// Returns the stored value of a setting, or `None` if it is unset.
+#[tauri::command]
+pub async fn get_setting(
+    settings_repository: State<'_, SettingsRepository>,
+    setting: Setting,
+) -> Result<Option<String>, RocadeError> {
+    ensure_user_editable(setting)?;
+
+    Ok(settings_repository.get(setting).await?)
+}
+
+/// Stores a setting value. Passing `None` resets the setting.
+///
+/// Credentials cannot be changed here; they are managed by the setup wizard.
+/// Neither can the mature-content filter, see `set_mature_content_filter`.
+/// Low-graphics mode, the WebP quality, and the download rate limit apply
+/// immediately. Out-of-range retry attempts and delays are rejected.
+#[tauri::command]
+pub async fn set_setting(
+    settings_repository: State<'_, SettingsRepository>,
+    game_repository: State<'_, GameRepository>,
+    game_cache: State<'_, GameCache>,
+    asset_manager: State<'_, AssetManager>,
+    setting: Setting,
+    value: Option<String>,
+) -> Result<(), RocadeError> {
+    ensure_user_editable(setting)?;
+
+    match value {
+        Some(value) => {
+            retry::validate_setting(setting, &value).map_err(RocadeConfigError::ConfigError)?;
+            settings_repository.set(setting, &value).await?
+        }
+        None => settings_repository.delete(setting).await?,
+    }
+
+    if let Setting::LowGraphicsMode = setting {
+        let text_only = settings_repository.is_enabled(setting).await?;
+        game_repository.set_text_only(text_only);
+        asset_manager.set_text_only(text_only);
+        game_cache.clear();
+    }
+
+    if let Setting::WebpQuality = setting {
+        asset_manager.set_webp_quality(
+            settings_repository
+                .get(setting)
+                .await?
+                .and_then(|quality| quality.parse().ok()),
+        );
+    }
+
+    if let Setting::DownloadRateLimit = setting {
+        asset_manager.set_download_rate_limit(
+            settings_repository
+                .get(setting)
+                .await?
+                .and_then(|limit| limit.trim().parse().ok()),
+        );
+    }
+
+    Ok(())
+}
+
+/// Returns the disk usage of the cached images, by category.
+#[tauri::command]
+pub async fn get_cache_stats(
+    asset_manager: State<'_, AssetManager>,
+) -> Result<CacheStats, RocadeError> {
+    Ok(asset_manager.get_cache_stats().await?)
+}
+
+/// Runs `PRAGMA integrity_check` on the database and returns the problems
+/// it reports, empty if the database is sound.
+#[tauri::command]
+pub async fn check_db_integrity(
+    db_state: State<'_, DatabaseState>,
+) -> Result<Vec<String>, RocadeError> {
+    Ok(db_state.check_integrity().await?)
+}
+
+/// Checkpoints the write-ahead log, checks the database integrity, and, if
+/// it is sound, compacts the database and refreshes its statistics.
+#[tauri::command]
+pub async fn run_db_maintenance(
+    db_state: State<'_, DatabaseState>,
+) -> Result<MaintenanceReport, RocadeError> {
+    Ok(db_state.run_maintenance().await?)
+}
+
+/// Returns the database backups made before each refresh, most recent
+/// first.
+#[tauri::command]
+pub async fn list_backups(
+    db_state: State<'_, DatabaseState>,
+) -> Result<Vec<DatabaseBackup>, RocadeError> {
+    Ok(db_state.list_backups().await?)
+}
+
+/// Replaces the database with one of the backups returned by
+/// [`list_backups`], then restarts the app to reopen it.
+#[tauri::command]
+pub async fn restore_backup(
+    app: AppHandle,
+    db_state: State<'_, DatabaseState>,
+    name: String,
+) -> Result<(), RocadeError> {
+    db_state.restore_backup(&name).await?;
+
+    app.restart()
+}
+
+/// Packs the database and the cached images into a zip archive at `path`,
+/// to move the library to another machine.
+#[tauri::command]
+pub async fn create_full_backup(
+    db_state: State<'_, DatabaseState>,
+    asset_manager: State<'_, AssetManager>,
+    path: String,
+) -> Result<(), RocadeError> {
+    archive::create(Path::new(&path), &db_state, asset_manager.assets_dir()).await?;
+
+    Ok(())
+}
+
+/// Restores an archive made by [`create_full_backup`], replacing the
+/// database and the cached images, then restarts the app to reopen the
+/// database.
+#[tauri::command]
+pub async fn restore_full_backup(
+    app: AppHandle,
+    db_state: State<'_, DatabaseState>,
+    asset_manager: State<'_, AssetManager>,
+    path: String,
+) -> Result<(), RocadeError> {
+    archive::restore(Path::new(&path), &db_state, asset_manager.assets_dir()).await?;
+
+    app.restart()
+}
+
+/// Checks that the cached images are not truncated or otherwise corrupt,
+/// and repairs the corrupt ones.
+///
+/// Corrupt covers, artworks, and screenshots are downloaded again; corrupt
+/// upscaled covers and artwork thumbnails are created again from their
+/// original. Images that cannot be repaired, e.g. while offline, are left
+/// missing and downloaded again on the next refresh. An image counts as
+/// repaired once downloaded or created again, whatever path it is saved at.
+#[tauri::command]
+pub async fn verify_assets(
+    asset_manager: State<'_, AssetManager>,
+    game_repository: State<'_, GameRepository>,
+    settings_repository: State<'_, SettingsRepository>,
+    game_cache: State<'_, GameCache>,
+) -> Result<AssetVerification, RocadeError> {
+    let corrupt_files = asset_manager.remove_corrupt_files().await?;
+    let corrupt: HashSet<&str> = corrupt_files.paths.iter().map(String::as_str).collect();
+    let is_corrupt =
+        |path: &Option<String>| path.as_deref().is_some_and(|path| corrupt.contains(path));
+
+    let mut covers = Vec::new();
+    let mut artworks = Vec::new();
+    let mut screenshots = Vec::new();
+    // Image IDs of the corrupt upscaled covers and artwork thumbnails, and
+    // of the corrupt originals of each kind
+    let mut corrupt_upscaled = Vec::new();
+    let mut corrupt_thumbnails = Vec::new();
+    let mut corrupt_covers = Vec::new();
+    let mut corrupt_artworks = Vec::new();
+
+    if !corrupt.is_empty() {
+        for game in game_repository.get_games().await? {
+            if let Some(cover) = game.cover {
+                if is_corrupt(&cover.local_path) {
+                    corrupt_covers.push(cover.id.clone());
+                }
+                if is_corrupt(&cover.upscaled_path) {
+                    corrupt_upscaled.push(cover.id.clone());
+                }
+                if is_corrupt(&cover.local_path) || is_corrupt(&cover.upscaled_path) {
+                    covers.push((game.id, cover.id));
+                }
+            }
+
+            // Artworks are downloaded again to create their thumbnail
+            let game_thumbnails: HashSet<String> = game
+                .artwork_thumbnails
+                .into_iter()
+                .flatten()
+                .filter(|thumbnail| is_corrupt(&thumbnail.local_path))
+                .map(|thumbnail| thumbnail.id)
+                .collect();
+
+            for artwork in game.artworks.into_iter().flatten() {
+                if is_corrupt(&artwork.local_path) {
+                    corrupt_artworks.push(artwork.id.clone());
+                }
+                if is_corrupt(&artwork.local_path) || game_thumbnails.contains(&artwork.id) {
+                    artworks.push((game.id, artwork.id));
+                }
+            }
+            corrupt_thumbnails.extend(game_thumbnails);
+            screenshots.extend(
+                game.screenshots
+                    .into_iter()
+                    .flatten()
+                    .filter(|screenshot| is_corrupt(&screenshot.local_path))
+                    .map(|screenshot| (game.id, screenshot.id)),
+            );
+        }
+    }
+
+    let cover_paths = asset_manager
+        .download_batch_covers(covers.iter().map(|(_, id)| id.clone()).collect())
+        .await?;
+
+    for (game_id, cover_id) in &covers {
+        if let Some((_, path)) = cover_paths.iter().find(|(id, _)| id == cover_id) {
+            game_repository
+                .update_cover_path(*game_id, cover_id, path)
+                .await?;
+        }
+    }
+
+    let upscaled_paths = upscale_covers(
+        &game_repository,
+        &asset_manager,
+        &settings_repository,
+        cover_paths.clone(),
+    )
+    .await?;
+
+    let artwork_paths = asset_manager
+        .download_batch_artworks(artworks.iter().map(|(_, id)| id.clone()).collect())
+        .await?;
+
+    for (game_id, artwork_id) in &artworks {
+        if let Some(path) = artwork_paths.iter().find(|(id, _)| id == artwork_id) {
+            game_repository
+                .update_artwork_paths(*game_id, vec![path.clone()])
+                .await?;
+        }
+    }
+
+    let thumbnail_paths =
+        create_artwork_thumbnails(&game_repository, &asset_manager, artwork_paths.clone()).await?;
+
+    let screenshot_paths = asset_manager
+        .download_batch_screenshots(screenshots.iter().map(|(_, id)| id.clone()).collect())
+        .await?;
+
+    for (game_id, screenshot_id) in &screenshots {
+        if let Some(path) = screenshot_paths.iter().find(|(id, _)| id == screenshot_id) {
+            game_repository
+                .update_screenshot_paths(*game_id, vec![path.clone()])
+                .await?;
+        }
+    }
+
+    game_cache.clear();
+
+    // Count the corrupt files whose image was downloaded or created again,
+    // wherever it was saved
+    let screenshot_ids: Vec<String> = screenshots.into_iter().map(|(_, id)| id).collect();
+    let repaired: usize = [
+        (corrupt_covers, &cover_paths),
+        (corrupt_upscaled, &upscaled_paths),
+        (corrupt_artworks, &artwork_paths),
+        (corrupt_thumbnails, &thumbnail_paths),
+        (screenshot_ids, &screenshot_paths),
+    ]
+    .iter()
+    .map(|(corrupt_ids, paths)| {
+        corrupt_ids
+            .iter()
+            .filter(|image_id| paths.iter().any(|(id, _)| id == *image_id))
+            .count()
+    })
+    .sum();
+
+    // Remove the corrupt files whose image was not saved again at the same
+    // path, e.g. a JPEG repaired as WebP
+    let saved: HashSet<&str> = [
+        &cover_paths,
+        &upscaled_paths,
+        &artwork_paths,
+        &thumbnail_paths,
+        &screenshot_paths,
+    ]
+    .into_iter()
+    .flatten()
+    .map(|(_, path)| path.as_str())
+    .collect();
+
+    for path in corrupt.iter().filter(|path| !saved.contains(*path)) {
+        if Path::new(path).exists() {
+            tokio::fs::remove_file(path)
+                .await
+                .map_err(AssetError::from)?;
+        }
+    }
+
+    Ok(AssetVerification {
+        checked: corrupt_files.checked,
+        corrupt: corrupt.len(),
+        repaired,
+    })
+}
+
+/// Rejects settings that are not meant to be edited from the settings screen.
+fn ensure_user_editable(setting: Setting) -> Result<(), RocadeError> {
+    if !setting.is_user_editable() {
+        return Err(RocadeConfigError::ConfigError(format!(
+            "setting {:?} cannot be changed from the settings screen",
+            setting
+        ))
+        .into());
+    }
+
+    Ok(())
+}
@@ -0,0 +1,208 @@
+//! Tauri commands for the first-run setup wizard.
+//!
+//! Lets the frontend validate Steam and Twitch credentials before saving
+//! them, and constructs the API clients only once both have been verified.
+
+use serde::Serialize;
+use tauri::{AppHandle, Manager, State};
+
+use crate::{
+    config::{RocadeConfig, RocadeConfigError},
+    db::settings::{Setting, SettingsRepository},
+    manage_api_clients,
+    secrets::{Secret, SecretStore},
+    service::steam::{SteamApi, SteamApiClient, SteamError},
+    twitch::{TwitchApiClient, TwitchError},
+};
+
+use super::game::RocadeError;
+
+/// Why a set of credentials failed validation.
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum ValidationFailure {
+    /// The Steam Web API key was rejected.
+    InvalidKey,
+    /// The Steam profile does not exist or its game details are private.
+    ProfileUnavailable,
+    /// The Twitch client ID or secret was rejected.
+    InvalidCredentials,
+    /// The API could not be reached.
+    Network,
+    /// The API answered with an unexpected response.
+    InvalidResponse,
+}
+
+/// Result of validating one set of credentials.
+#[derive(Serialize, Debug)]
+pub struct CredentialsValidation {
+    pub valid: bool,
+    /// Failure category, `None` when the credentials are valid.
+    pub reason: Option<ValidationFailure>,
+    /// Human-readable error detail, `None` when the credentials are valid.
+    pub message: Option<String>,
+}
+
+impl CredentialsValidation {
+    fn valid() -> Self {
+        CredentialsValidation {
+            valid: true,
+            reason: None,
+            message: None,
+        }
+    }
+
+    fn invalid(reason: ValidationFailure, message: String) -> Self {
+        CredentialsValidation {
+            valid: false,
+            reason: Some(reason),
+            message: Some(message),
+        }
+    }
+}
+
+impl From<SteamError> for CredentialsValidation {
+    fn from(error: SteamError) -> Self {
+        let reason = match error {
+            SteamError::Unauthorized => ValidationFailure::InvalidKey,
+            SteamError::ProfileUnavailable(_) => ValidationFailure::ProfileUnavailable,
+            SteamError::Request(_) => ValidationFailure::Network,
+            SteamError::InvalidResponse(_) | SteamError::InvalidData(_) => {
+                ValidationFailure::InvalidResponse
+            }
+        };
+
+        CredentialsValidation::invalid(reason, error.to_string())
+    }
+}
+
+impl From<TwitchError> for CredentialsValidation {
+    fn from(error: TwitchError) -> Self {
+        let reason = match error {
+            TwitchError::Unauthorized(_) => ValidationFailure::InvalidCredentials,
+            TwitchError::Request(_) => ValidationFailure::Network,
+            TwitchError::InvalidData(_) => ValidationFailure::InvalidResponse,
+        };
+
+        CredentialsValidation::invalid(reason, error.to_string())
+    }
+}
+
+/// Outcome of [`complete_setup`].
+#[derive(Serialize, Debug)]
+pub struct SetupReport {
+    pub steam: CredentialsValidation,
+    pub twitch: CredentialsValidation,
+    /// Whether the configuration was saved and the API clients constructed.
+    pub completed: bool,
+}
+
+/// Checks a Steam Web API key and profile ID against the Steam Web API.
+#[tauri::command]
+pub async fn validate_steam_credentials(
+    api_key: String,
+    profile_id: String,
+) -> Result<CredentialsValidation, RocadeError> {
+    Ok(check_steam(api_key, profile_id).await)
+}
+
+/// Checks a Twitch client ID and secret by requesting an access token.
+#[tauri::command]
+pub async fn validate_twitch_credentials(
+    client_id: String,
+    client_secret: String,
+) -> Result<CredentialsValidation, RocadeError> {
+    Ok(check_twitch(client_id, client_secret).await)
+}
+
+/// Validates all credentials, then saves them and constructs the API clients.
+///
+/// Nothing is persisted unless both the Steam and Twitch credentials are
+/// valid. Secrets are written to the OS keychain and the remaining values to
+/// the settings table.
+#[tauri::command]
+pub async fn complete_setup(
+    app: AppHandle,
+    secret_store: State<'_, SecretStore>,
+    settings_repository: State<'_, SettingsRepository>,
+    steam_api_key: String,
+    steam_profile_id: String,
+    twitch_client_id: String,
+    twitch_client_secret: String,
+) -> Result<SetupReport, RocadeError> {
+    let steam = check_steam(steam_api_key.clone(), steam_profile_id.clone()).await;
+    let twitch = check_twitch(twitch_client_id.clone(), twitch_client_secret.clone()).await;
+
+    if !steam.valid || !twitch.valid {
+        return Ok(SetupReport {
+            steam,
+            twitch,
+            completed: false,
+        });
+    }
+
+    if is_configured(&app) {
+        return Err(RocadeConfigError::ConfigError("setup already completed".to_string()).into());
+    }
+
+    secret_store
+        .set(Secret::SteamApiKey, &steam_api_key)
+        .map_err(RocadeConfigError::from)?;
+    secret_store
+        .set(Secret::TwitchClientSecret, &twitch_client_secret)
+        .map_err(RocadeConfigError::from)?;
+    settings_repository
+        .set(Setting::SteamProfileId, &steam_profile_id)
+        .await?;
+    settings_repository
+        .set(Setting::TwitchClientId, &twitch_client_id)
+        .await?;
+
+    manage_api_clients(
+        &app,
+        RocadeConfig {
+            steam_api_key,
+            steam_profile_id,
+            twitch_client_id,
+            twitch_client_secret,
+        },
+    )?;
+
+    Ok(SetupReport {
+        steam,
+        twitch,
+        completed: true,
+    })
+}
+
+/// Returns `true` once the API clients are configured, `false` while the
+/// setup wizard still needs to run.
+#[tauri::command]
+pub async fn is_setup_complete(app: AppHandle) -> Result<bool, RocadeError> {
+    Ok(is_configured(&app))
+}
+
+/// Returns `true` if the API clients have been registered as managed state.
+fn is_configured(app: &AppHandle) -> bool {
+    app.try_state::<Box<dyn SteamApi>>().is_some()
+}
+
+async fn check_steam(api_key: String, profile_id: String) -> CredentialsValidation {
+    match SteamApiClient::new(api_key, profile_id)
+        .check_credentials()
+        .await
+    {
+        Ok(()) => CredentialsValidation::valid(),
+        Err(e) => e.into(),
+    }
+}
+
+async fn check_twitch(client_id: String, client_secret: String) -> CredentialsValidation {
+    match TwitchApiClient::new(client_id, client_secret)
+        .refresh_access_token()
+        .await
+    {
+        Ok(_) => CredentialsValidation::valid(),
+        Err(e) => e.into(),
+    }
+}
@@ -0,0 +1,51 @@
+//! Tauri commands for the "You might also enjoy" row of the detail page.
+//!
+//! IGDB lists similar games for most entries. Their IDs are stored during
+//! enrichment, so owned similar games are found locally; only the few
+//! suggestions outside the library are fetched from IGDB.
+
+use serde::Serialize;
+use tauri::{async_runtime::Mutex, State};
+
+use crate::{
+    db::game::{GameRepository, GameSummary},
+    igdb::{IgdbApi, IgdbSearchResult},
+};
+
+use super::game::RocadeError;
+
+/// Number of similar games outside the library returned.
+const SUGGESTION_COUNT: usize = 5;
+
+/// Games similar to a game, as returned by [`get_similar_games`].
+#[derive(Serialize)]
+pub struct SimilarGames {
+    /// Similar games already in the library.
+    pub owned: Vec<GameSummary>,
+    /// A few similar games the user does not own.
+    pub suggested: Vec<IgdbSearchResult>,
+}
+
+/// Returns the games IGDB lists as similar to a game, split between those in
+/// the library and up to 5 the user does not own, in IGDB order.
+#[tauri::command]
+pub async fn get_similar_games(
+    igdb_client: State<'_, Mutex<Box<dyn IgdbApi>>>,
+    game_repository: State<'_, GameRepository>,
+    game_id: i64,
+) -> Result<SimilarGames, RocadeError> {
+    let owned = game_repository.get_owned_similar_games(game_id).await?;
+
+    let mut unowned_ids = game_repository
+        .get_unowned_similar_igdb_ids(game_id)
+        .await?;
+    unowned_ids.truncate(SUGGESTION_COUNT);
+
+    let suggested = igdb_client
+        .lock()
+        .await
+        .get_game_previews(&unowned_ids)
+        .await?;
+
+    Ok(SimilarGames { owned, suggested })
+}
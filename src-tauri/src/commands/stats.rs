@@ -0,0 +1,172 @@
+//! Tauri commands for syncing Steam player statistics.
+//!
+//! Playtime, last-played dates, and achievements change daily while metadata
+//! rarely does, so they are synced by a lightweight job that skips IGDB and
+//! the asset cache. The job runs on demand and on a schedule set in the settings.
+//! Each run also snapshots the total playtime of every game for playtime
+//! history charts.
+
+use std::time::Duration;
+
+use tauri::{AppHandle, Manager, State};
+
+use crate::{
+    cache::GameCache,
+    db::{
+        achievement::AchievementRepository,
+        game::{GameRepository, PlayerStats},
+        playtime::{PlaytimeRepository, PlaytimeSnapshot},
+        settings::{Setting, SettingsRepository},
+    },
+    service::steam::{SteamApi, SteamGame},
+};
+
+use super::{achievements::sync_game_achievements, game::RocadeError};
+
+/// Default interval between two scheduled syncs, in minutes.
+const DEFAULT_SYNC_INTERVAL: u64 = 60;
+
+/// Interval between two checks of the sync setting while scheduling is
+/// disabled.
+const DISABLED_POLL_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Updates playtime, last-played dates, and achievements from Steam without
+/// touching game metadata or assets.
+///
+/// Achievements are only synced for games played in the last two weeks.
+///
+/// Returns the number of Steam games whose statistics were received.
+#[tauri::command]
+pub async fn sync_player_stats(
+    steam_client: State<'_, Box<dyn SteamApi>>,
+    game_repository: State<'_, GameRepository>,
+    game_cache: State<'_, GameCache>,
+    achievement_repository: State<'_, AchievementRepository>,
+    playtime_repository: State<'_, PlaytimeRepository>,
+) -> Result<usize, RocadeError> {
+    sync(
+        &**steam_client,
+        &game_repository,
+        &game_cache,
+        &achievement_repository,
+        &playtime_repository,
+    )
+    .await
+}
+
+/// Returns the recorded total playtime of a game over time, oldest first.
+///
+/// Games without a Steam App ID have no history.
+#[tauri::command]
+pub async fn get_playtime_history(
+    game_repository: State<'_, GameRepository>,
+    playtime_repository: State<'_, PlaytimeRepository>,
+    game_id: i64,
+) -> Result<Vec<PlaytimeSnapshot>, RocadeError> {
+    let Some(store_id) = game_repository.get_game_by_id(game_id).await?.store_id else {
+        return Ok(Vec::new());
+    };
+
+    Ok(playtime_repository.get_snapshots(&store_id).await?)
+}
+
+/// Starts the background task running the player stats sync on the interval
+/// set by [`Setting::PlayerStatsSyncInterval`].
+///
+/// The interval is re-read before every run so changes apply without a
+/// restart. Runs are skipped until the Steam API client is configured.
+pub(crate) fn schedule_player_stats_sync(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let interval = sync_interval(&app.state::<SettingsRepository>()).await;
+
+            let Some(interval) = interval else {
+                tokio::time::sleep(DISABLED_POLL_INTERVAL).await;
+                continue;
+            };
+
+            tokio::time::sleep(interval).await;
+
+            if let Some(steam_client) = app.try_state::<Box<dyn SteamApi>>() {
+                let _ = sync(
+                    &**steam_client,
+                    &app.state::<GameRepository>(),
+                    &app.state::<GameCache>(),
+                    &app.state::<AchievementRepository>(),
+                    &app.state::<PlaytimeRepository>(),
+                )
+                .await;
+            }
+        }
+    });
+}
+
+/// Returns the configured sync interval, or `None` if scheduling is disabled.
+async fn sync_interval(settings_repository: &SettingsRepository) -> Option<Duration> {
+    let minutes = settings_repository
+        .get(Setting::PlayerStatsSyncInterval)
+        .await
+        .ok()
+        .flatten()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(DEFAULT_SYNC_INTERVAL);
+
+    (minutes > 0).then(|| Duration::from_secs(minutes * 60))
+}
+
+/// Fetches the owned games from Steam, stores their player statistics and
+/// a playtime snapshot, then syncs the achievements of recently played games.
+///
+/// A game whose achievements cannot be synced is logged and skipped, so the
+/// other games are still synced.
+async fn sync(
+    steam_client: &dyn SteamApi,
+    game_repository: &GameRepository,
+    game_cache: &GameCache,
+    achievement_repository: &AchievementRepository,
+    playtime_repository: &PlaytimeRepository,
+) -> Result<usize, RocadeError> {
+    let games = steam_client.get_games().await?;
+    let stats = to_player_stats(&games);
+    let count = stats.len();
+
+    playtime_repository.record_snapshots(&stats).await?;
+
+    let mut tx = game_repository.begin().await?;
+    game_repository.update_player_stats(&mut tx, stats).await?;
+    tx.commit().await?;
+    game_cache.clear();
+
+    for game in game_repository.get_games().await? {
+        if game.playtime_2weeks.unwrap_or_default() == 0 {
+            continue;
+        }
+
+        if let Some(store_id) = game.store_id {
+            if let Err(e) =
+                sync_game_achievements(steam_client, achievement_repository, game.id, &store_id)
+                    .await
+            {
+                tracing::warn!("failed to sync achievements of {store_id}: {e}");
+            }
+        }
+    }
+
+    Ok(count)
+}
+
+/// Extracts the player statistics of Steam games.
+///
+/// Steam reports a last play time of `0` for games that were never played;
+/// it is stored as `None`. Playtimes are stored as reported, `0` included.
+pub(super) fn to_player_stats(games: &[SteamGame]) -> Vec<PlayerStats> {
+    games
+        .iter()
+        .map(|game| PlayerStats {
+            store_id: game.appid.to_string(),
+            playtime_forever: game.playtime_forever.map(|p| p as i64),
+            playtime_2weeks: game.playtime_2weeks.map(|p| p as i64),
+            last_played_at: game.rtime_last_played.filter(|&t| t > 0),
+        })
+        .collect()
+}
@@ -0,0 +1,120 @@
+//! Tauri commands for subscription services and their value report.
+
+use serde::Serialize;
+use tauri::State;
+
+use crate::db::subscription::{NewSubscription, Subscription, SubscriptionRepository};
+
+use super::game::RocadeError;
+
+/// Value of a subscription over one month, as shown in the report.
+#[derive(Serialize, Debug)]
+pub struct SubscriptionValue {
+    pub subscription_id: i64,
+    pub subscription_name: String,
+    /// Month in `YYYY-MM` format.
+    pub month: String,
+    pub monthly_cost: f64,
+    /// Hours played on the subscription's games during the month.
+    pub hours_played: f64,
+    /// Cost divided by hours played, `None` when nothing was played.
+    pub cost_per_hour: Option<f64>,
+}
+
+/// Returns all recorded subscriptions with their games.
+#[tauri::command]
+pub async fn get_subscriptions(
+    subscription_repository: State<'_, SubscriptionRepository>,
+) -> Result<Vec<Subscription>, RocadeError> {
+    Ok(subscription_repository.get_subscriptions().await?)
+}
+
+/// Records a subscription service. Returns its database ID.
+#[tauri::command]
+pub async fn add_subscription(
+    subscription_repository: State<'_, SubscriptionRepository>,
+    subscription: NewSubscription,
+) -> Result<i64, RocadeError> {
+    Ok(subscription_repository
+        .insert_subscription(&subscription)
+        .await?)
+}
+
+/// Sets or clears the cancellation date of a subscription.
+#[tauri::command]
+pub async fn end_subscription(
+    subscription_repository: State<'_, SubscriptionRepository>,
+    subscription_id: i64,
+    ended_at: Option<i64>,
+) -> Result<(), RocadeError> {
+    subscription_repository
+        .set_ended_at(subscription_id, ended_at)
+        .await?;
+    Ok(())
+}
+
+/// Deletes a subscription.
+#[tauri::command]
+pub async fn delete_subscription(
+    subscription_repository: State<'_, SubscriptionRepository>,
+    subscription_id: i64,
+) -> Result<(), RocadeError> {
+    subscription_repository
+        .delete_subscription(subscription_id)
+        .await?;
+    Ok(())
+}
+
+/// Marks a library entry as coming from a subscription.
+#[tauri::command]
+pub async fn add_subscription_game(
+    subscription_repository: State<'_, SubscriptionRepository>,
+    subscription_id: i64,
+    game_id: i64,
+) -> Result<(), RocadeError> {
+    subscription_repository
+        .add_game(subscription_id, game_id)
+        .await?;
+    Ok(())
+}
+
+/// Removes a library entry from a subscription.
+#[tauri::command]
+pub async fn remove_subscription_game(
+    subscription_repository: State<'_, SubscriptionRepository>,
+    subscription_id: i64,
+    game_id: i64,
+) -> Result<(), RocadeError> {
+    subscription_repository
+        .remove_game(subscription_id, game_id)
+        .await?;
+    Ok(())
+}
+
+/// Returns the estimated value of each subscription per month, most recent
+/// month first.
+///
+/// Hours come from play sessions tracked by Rocade, as Steam does not
+/// report playtime per month.
+#[tauri::command]
+pub async fn get_subscription_report(
+    subscription_repository: State<'_, SubscriptionRepository>,
+) -> Result<Vec<SubscriptionValue>, RocadeError> {
+    let months = subscription_repository.get_monthly_playtime().await?;
+
+    Ok(months
+        .into_iter()
+        .map(|month| {
+            let hours_played = month.seconds_played as f64 / 3600.0;
+
+            SubscriptionValue {
+                subscription_id: month.subscription_id,
+                subscription_name: month.subscription_name,
+                month: month.month,
+                monthly_cost: month.monthly_cost,
+                hours_played,
+                cost_per_hour: (hours_played > 0.0).then(|| month.monthly_cost / hours_played),
+            }
+        })
+        .collect())
+}
@@ -0,0 +1,25 @@
+//! Tauri commands for the post-update "what's new" screen.
+
+use tauri::State;
+
+use crate::db::whats_new::{WhatsNewNote, WhatsNewRepository};
+
+use super::game::RocadeError;
+
+/// Returns the changes introduced by updates that the user has not
+/// dismissed yet.
+#[tauri::command]
+pub async fn get_whats_new(
+    whats_new_repository: State<'_, WhatsNewRepository>,
+) -> Result<Vec<WhatsNewNote>, RocadeError> {
+    Ok(whats_new_repository.get_unseen().await?)
+}
+
+/// Dismisses all pending "what's new" notes.
+#[tauri::command]
+pub async fn dismiss_whats_new(
+    whats_new_repository: State<'_, WhatsNewRepository>,
+) -> Result<(), RocadeError> {
+    whats_new_repository.mark_all_seen().await?;
+    Ok(())
+}
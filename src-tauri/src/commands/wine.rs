@@ -0,0 +1,161 @@
+//! Tauri commands for custom games and their Wine configuration.
+
+use std::path::Path;
+
+use tauri::{AppHandle, State};
+use tauri_plugin_opener::OpenerExt;
+
+use crate::{
+    bundle,
+    config::RocadeConfigError,
+    db::custom::CustomGameRepository,
+    wine::{WineConfig, WineError},
+};
+
+use super::game::RocadeError;
+
+/// Adds a custom (non-Steam) game to the library. Returns its database ID.
+///
+/// Custom games are kept across library refreshes.
+#[tauri::command]
+pub async fn add_custom_game(
+    custom_game_repository: State<'_, CustomGameRepository>,
+    name: String,
+    executable: String,
+) -> Result<i64, RocadeError> {
+    Ok(custom_game_repository
+        .insert_custom_game(&name, &executable)
+        .await?)
+}
+
+/// Exports all custom games to a bundle file at `path`.
+///
+/// Paths under `base_dir` are stored relative to it so the bundle can be
+/// imported on a machine where that directory lives elsewhere.
+#[tauri::command]
+pub async fn export_custom_games(
+    custom_game_repository: State<'_, CustomGameRepository>,
+    path: String,
+    base_dir: String,
+) -> Result<(), RocadeError> {
+    let definitions = custom_game_repository.get_definitions().await?;
+
+    bundle::export(Path::new(&path), Path::new(&base_dir), definitions).await?;
+
+    Ok(())
+}
+
+/// Adds the custom games of the bundle file at `path` to the library, with
+/// relative paths resolved against `base_dir`. Returns their database IDs.
+#[tauri::command]
+pub async fn import_custom_games(
+    custom_game_repository: State<'_, CustomGameRepository>,
+    path: String,
+    base_dir: String,
+) -> Result<Vec<i64>, RocadeError> {
+    let definitions = bundle::import(Path::new(&path), Path::new(&base_dir)).await?;
+
+    let mut game_ids = Vec::with_capacity(definitions.len());
+    for definition in &definitions {
+        game_ids.push(custom_game_repository.insert_definition(definition).await?);
+    }
+
+    Ok(game_ids)
+}
+
+/// Returns the Wine configuration of a game, or `None` if it runs natively.
+#[tauri::command]
+pub async fn get_wine_config(
+    custom_game_repository: State<'_, CustomGameRepository>,
+    game_id: i64,
+) -> Result<Option<WineConfig>, RocadeError> {
+    Ok(custom_game_repository.get_wine_config(game_id).await?)
+}
+
+/// Sets the Wine runtime and prefix used to launch a custom game. Passing
+/// `None` makes the game run natively again.
+#[tauri::command]
+pub async fn set_wine_config(
+    custom_game_repository: State<'_, CustomGameRepository>,
+    game_id: i64,
+    config: Option<WineConfig>,
+) -> Result<(), RocadeError> {
+    ensure_custom_game(&custom_game_repository, game_id).await?;
+
+    match config {
+        Some(config) => {
+            custom_game_repository
+                .set_wine_config(game_id, &config)
+                .await?
+        }
+        None => custom_game_repository.delete_wine_config(game_id).await?,
+    }
+
+    Ok(())
+}
+
+/// Creates and initializes the Wine prefix of a game.
+///
+/// Waits for `wineboot` to finish, which can take a minute on first run.
+#[tauri::command]
+pub async fn create_wine_prefix(
+    custom_game_repository: State<'_, CustomGameRepository>,
+    game_id: i64,
+) -> Result<(), RocadeError> {
+    wine_config(&custom_game_repository, game_id)
+        .await?
+        .create_prefix()
+        .await?;
+
+    Ok(())
+}
+
+/// Opens the Wine prefix folder of a game in the file manager.
+#[tauri::command]
+pub async fn open_wine_prefix(
+    app: AppHandle,
+    custom_game_repository: State<'_, CustomGameRepository>,
+    game_id: i64,
+) -> Result<(), RocadeError> {
+    let config = wine_config(&custom_game_repository, game_id).await?;
+
+    app.opener()
+        .open_path(&config.prefix, None::<&str>)
+        .map_err(|e| WineError::OpenPrefix(e.to_string()))?;
+
+    Ok(())
+}
+
+/// Returns the Wine configuration of a game, failing if it has none.
+async fn wine_config(
+    custom_game_repository: &CustomGameRepository,
+    game_id: i64,
+) -> Result<WineConfig, RocadeError> {
+    custom_game_repository
+        .get_wine_config(game_id)
+        .await?
+        .ok_or_else(|| {
+            RocadeConfigError::ConfigError(format!("game {} has no wine configuration", game_id))
+                .into()
+        })
+}
+
+/// Rejects Steam games, which are always launched through Steam.
+async fn ensure_custom_game(
+    custom_game_repository: &CustomGameRepository,
+    game_id: i64,
+) -> Result<(), RocadeError> {
+    if custom_game_repository
+        .get_executable(game_id)
+        .await?
+        .is_none()
+    {
+        return Err(RocadeConfigError::ConfigError(format!(
+            "game {} is not a custom game",
+            game_id
+        ))
+        .into());
+    }
+
+    Ok(())
+}
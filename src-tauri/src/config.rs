@@ -1,3 +1,8 @@
+use crate::{
+    db::settings::{Setting, SettingsRepository},
+    secrets::{Secret, SecretError, SecretStore},
+};
+
 pub struct RocadeConfig {
     pub steam_api_key: String,
     pub steam_profile_id: String,
@@ -5,6 +10,34 @@ pub struct RocadeConfig {
     pub twitch_client_secret: String,
 }
 
+impl RocadeConfig {
+    /// Loads the configuration from the settings table and the OS keychain.
+    ///
+    /// Values still present in the environment (legacy `.env` setup) are
+    /// migrated into their persistent storage on first load.
+    pub async fn load(
+        secret_store: &SecretStore,
+        settings: &SettingsRepository,
+    ) -> Result<Self, RocadeConfigError> {
+        Ok(RocadeConfig {
+            steam_api_key: secret_store.get_or_migrate(Secret::SteamApiKey)?,
+            steam_profile_id: settings
+                .get_or_migrate(Setting::SteamProfileId)
+                .await?
+                .ok_or(RocadeConfigError::EnvError(
+                    "steam profile id not set".to_string(),
+                ))?,
+            twitch_client_id: settings
+                .get_or_migrate(Setting::TwitchClientId)
+                .await?
+                .ok_or(RocadeConfigError::EnvError(
+                    "twitch client id not set".to_string(),
+                ))?,
+            twitch_client_secret: secret_store.get_or_migrate(Secret::TwitchClientSecret)?,
+        })
+    }
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum RocadeConfigError {
     #[error("environment config error: {0}")]
@@ -18,4 +51,7 @@ pub enum RocadeConfigError {
 
     #[error("config error: {0}")]
     ConfigError(String),
+
+    #[error("secret error: {0}")]
+    SecretError(#[from] SecretError),
 }
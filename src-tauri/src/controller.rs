@@ -0,0 +1,51 @@
+//! Controller-remapping profiles loaded around game sessions.
+//!
+//! A game can be associated with a profile of an external remapping tool.
+//! The profile is loaded before the game starts and unloaded once it exits,
+//! using commands configurable in the settings. They default to AntiMicroX.
+
+use tokio::process::Command;
+
+/// Default command loading a profile. `{profile}` is replaced by the
+/// profile path.
+pub const DEFAULT_LOAD_COMMAND: &str = "antimicrox --hidden --profile {profile}";
+
+/// Default command unloading the current profile.
+pub const DEFAULT_UNLOAD_COMMAND: &str = "antimicrox --unload";
+
+/// Errors that can occur while running a remapping tool.
+#[derive(Debug, thiserror::Error)]
+pub enum ControllerError {
+    /// The configured command is empty.
+    #[error("empty controller profile command")]
+    EmptyCommand,
+
+    /// The remapping tool could not be started.
+    #[error("unable to run controller profile command: {0}")]
+    Process(#[from] std::io::Error),
+}
+
+/// Loads `profile` with the remapping tool.
+///
+/// The tool is not awaited: AntiMicroX keeps running in the background when
+/// it was not already started.
+pub fn load_profile(load_command: &str, profile: &str) -> Result<(), ControllerError> {
+    let mut parts = load_command.split_whitespace();
+    let program = parts.next().ok_or(ControllerError::EmptyCommand)?;
+
+    Command::new(program)
+        .args(parts.map(|arg| arg.replace("{profile}", profile)))
+        .spawn()?;
+
+    Ok(())
+}
+
+/// Unloads the current profile and waits for the tool to return.
+pub async fn unload_profile(unload_command: &str) -> Result<(), ControllerError> {
+    let mut parts = unload_command.split_whitespace();
+    let program = parts.next().ok_or(ControllerError::EmptyCommand)?;
+
+    Command::new(program).args(parts).status().await?;
+
+    Ok(())
+}
@@ -1,11 +1,84 @@
-use std::{fs, path::PathBuf};
+use std::{
+    cmp::Reverse,
+    fs,
+    path::{Path, PathBuf},
+    str::FromStr,
+    time::{SystemTime, UNIX_EPOCH},
+};
 
-use sqlx::{sqlite::SqliteConnectOptions, Pool, Sqlite, SqlitePool};
+use serde::Serialize;
+use sqlx::{
+    sqlite::{SqliteConnectOptions, SqlitePoolOptions},
+    Pool, Sqlite, SqlitePool, Transaction,
+};
 
 use crate::config::RocadeConfigError;
 
+/// Directory holding the database backups, inside the app directory.
+const BACKUP_DIR: &str = "backups";
+
+/// Number of backups kept; older ones are removed when a new one is made.
+const BACKUPS_KEPT: usize = 5;
+
+/// Backup copied over the database on the next start, see
+/// [`DatabaseState::restore_backup`].
+const PENDING_RESTORE: &str = "rocade.db.restore";
+
 pub struct DatabaseState {
     pub pool: Pool<Sqlite>,
+    /// App directory holding the database file, `None` for an in-memory
+    /// database.
+    app_dir: Option<PathBuf>,
+}
+
+/// A copy of the database made by [`DatabaseState::backup`].
+#[derive(Serialize)]
+pub struct DatabaseBackup {
+    /// File name, passed to [`DatabaseState::restore_backup`].
+    pub name: String,
+    /// Unix timestamp of the backup, in seconds.
+    pub created_at: i64,
+    /// Size of the backup file, in bytes.
+    pub size: u64,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum BackupError {
+    #[error("database error: {0}")]
+    Database(#[from] sqlx::Error),
+
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("backup not found: {0}")]
+    NotFound(String),
+
+    #[error("no backup to restore")]
+    NoBackup,
+
+    /// The most recent backup was not taken by the last refresh, or was
+    /// already restored.
+    #[error("the latest backup was not taken by the last refresh")]
+    NotRefreshBackup,
+}
+
+/// Outcome of [`DatabaseState::run_maintenance`].
+#[derive(Serialize)]
+pub struct MaintenanceReport {
+    /// Problems found by the integrity check, empty if the database is sound.
+    pub integrity_errors: Vec<String>,
+    /// Write-ahead log frames copied into the database file.
+    pub checkpointed_frames: i64,
+    /// `true` if readers kept the write-ahead log from being fully
+    /// checkpointed.
+    pub checkpoint_busy: bool,
+    /// `true` if the database was rebuilt and its statistics refreshed.
+    /// Skipped when the integrity check fails.
+    pub optimized: bool,
+    /// Size of the database file before maintenance, in bytes.
+    pub size_before: i64,
+    /// Size of the database file after maintenance, in bytes.
+    pub size_after: i64,
 }
 
 impl DatabaseState {
@@ -14,6 +87,17 @@ impl DatabaseState {
 
         let db_path = app_dir.join("rocade.db");
 
+        let pending_restore = app_dir.join(PENDING_RESTORE);
+        if pending_restore.exists() {
+            // The write-ahead log belongs to the replaced database
+            for suffix in ["-wal", "-shm"] {
+                let _ = fs::remove_file(app_dir.join(format!("rocade.db{suffix}")));
+            }
+            fs::rename(&pending_restore, &db_path).map_err(|e| {
+                RocadeConfigError::ConfigError(format!("unable to restore database backup: {e}"))
+            })?;
+        }
+
         let connection = SqliteConnectOptions::new()
             .filename(&db_path)
             .create_if_missing(true)
@@ -22,30 +106,286 @@ impl DatabaseState {
 
         let pool = SqlitePool::connect_with(connection).await?;
 
+        let fresh_install = !sqlx::query_scalar::<_, bool>(
+            "select exists(select 1 from sqlite_master where type = 'table' and name = '_sqlx_migrations')",
+        )
+        .fetch_one(&pool)
+        .await?;
+
         sqlx::migrate!().run(&pool).await?;
 
-        Ok(Self { pool })
+        // "What's new" notes only make sense after an update
+        if fresh_install {
+            whats_new::WhatsNewRepository::new(pool.clone())
+                .mark_all_seen()
+                .await?;
+        }
+
+        Ok(Self {
+            pool,
+            app_dir: Some(app_dir),
+        })
+    }
+
+    /// Opens a migrated, throwaway in-memory database, used to benchmark
+    /// without touching the user's library.
+    pub async fn in_memory() -> Result<DatabaseState, RocadeConfigError> {
+        let connection =
+            SqliteConnectOptions::from_str("sqlite::memory:")?.pragma("foreign_keys", "ON");
+
+        // Each connection to `:memory:` gets its own database
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .idle_timeout(None)
+            .max_lifetime(None)
+            .connect_with(connection)
+            .await?;
+
+        sqlx::migrate!().run(&pool).await?;
+
+        Ok(Self {
+            pool,
+            app_dir: None,
+        })
+    }
+
+    /// Checkpoints the write-ahead log into the database file, then closes
+    /// the pool. Queries issued afterwards fail.
+    pub async fn close(&self) -> Result<(), sqlx::Error> {
+        sqlx::query("pragma wal_checkpoint(truncate)")
+            .execute(&self.pool)
+            .await?;
+        self.pool.close().await;
+
+        Ok(())
+    }
+
+    /// Runs `PRAGMA integrity_check` and returns the problems it reports,
+    /// empty if the database is sound.
+    pub async fn check_integrity(&self) -> Result<Vec<String>, sqlx::Error> {
+        let mut messages: Vec<String> = sqlx::query_scalar("pragma integrity_check")
+            .fetch_all(&self.pool)
+            .await?;
+        messages.retain(|message| message != "ok");
+
+        Ok(messages)
+    }
+
+    /// Checkpoints the write-ahead log, checks the database integrity, then
+    /// rebuilds it with `VACUUM` and refreshes the query planner statistics
+    /// with `ANALYZE`.
+    ///
+    /// A corrupt database is only checkpointed: rebuilding it could lose the
+    /// rows the user still has a chance to recover.
+    pub async fn run_maintenance(&self) -> Result<MaintenanceReport, sqlx::Error> {
+        let size_before = self.size().await?;
+
+        let (busy, _, checkpointed_frames): (i64, i64, i64) =
+            sqlx::query_as("pragma wal_checkpoint(truncate)")
+                .fetch_one(&self.pool)
+                .await?;
+
+        let integrity_errors = self.check_integrity().await?;
+        let optimized = integrity_errors.is_empty();
+
+        if optimized {
+            sqlx::query("vacuum").execute(&self.pool).await?;
+            sqlx::query("analyze").execute(&self.pool).await?;
+        }
+
+        Ok(MaintenanceReport {
+            integrity_errors,
+            checkpointed_frames,
+            checkpoint_busy: busy != 0,
+            optimized,
+            size_before,
+            size_after: self.size().await?,
+        })
+    }
+
+    /// Returns the size of the database file, in bytes.
+    async fn size(&self) -> Result<i64, sqlx::Error> {
+        sqlx::query_scalar(
+            "select page_count * page_size from pragma_page_count(), pragma_page_size()",
+        )
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    /// Copies the database to a timestamped file of the backup directory,
+    /// then removes all but the [`BACKUPS_KEPT`] most recent backups.
+    ///
+    /// Returns `None` for an in-memory database, which is not backed up.
+    pub async fn backup(&self) -> Result<Option<DatabaseBackup>, BackupError> {
+        let Some(backup_dir) = self.backup_dir() else {
+            return Ok(None);
+        };
+        tokio::fs::create_dir_all(&backup_dir).await?;
+
+        let created_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_secs() as i64)
+            .unwrap_or_default();
+        let path = backup_dir.join(format!("rocade-{created_at}.db"));
+
+        self.copy_to(&path).await?;
+
+        let mut backups = self.list_backups().await?;
+        for backup in backups.drain(BACKUPS_KEPT.min(backups.len())..) {
+            tokio::fs::remove_file(backup_dir.join(&backup.name)).await?;
+        }
+
+        Ok(backups
+            .into_iter()
+            .find(|backup| backup.created_at == created_at))
+    }
+
+    /// Returns the database backups, most recent first.
+    pub async fn list_backups(&self) -> Result<Vec<DatabaseBackup>, BackupError> {
+        let Some(backup_dir) = self.backup_dir() else {
+            return Ok(Vec::new());
+        };
+        if !backup_dir.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut backups = Vec::new();
+        let mut entries = tokio::fs::read_dir(&backup_dir).await?;
+
+        while let Some(entry) = entries.next_entry().await? {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            let Some(created_at) = name
+                .strip_prefix("rocade-")
+                .and_then(|name| name.strip_suffix(".db"))
+                .and_then(|timestamp| timestamp.parse().ok())
+            else {
+                continue;
+            };
+
+            backups.push(DatabaseBackup {
+                name,
+                created_at,
+                size: entry.metadata().await?.len(),
+            });
+        }
+
+        backups.sort_by_key(|backup| Reverse(backup.created_at));
+
+        Ok(backups)
     }
 
-    /// Empty all database
-    pub async fn clean(&self) -> Result<(), sqlx::Error> {
+    /// Schedules a backup to replace the database. The backup is copied over
+    /// the database on the next start, once no connection is open.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`BackupError::NotFound`] if `name` is not one of the
+    /// backups returned by [`Self::list_backups`].
+    pub async fn restore_backup(&self, name: &str) -> Result<(), BackupError> {
+        let (Some(pending_restore), Some(backup_dir)) =
+            (self.pending_restore_path(), self.backup_dir())
+        else {
+            return Err(BackupError::NotFound(name.to_string()));
+        };
+
+        // Only listed names are accepted, so `name` cannot escape the
+        // backup directory
+        if !self
+            .list_backups()
+            .await?
+            .iter()
+            .any(|backup| backup.name == name)
+        {
+            return Err(BackupError::NotFound(name.to_string()));
+        }
+
+        tokio::fs::copy(backup_dir.join(name), pending_restore).await?;
+
+        Ok(())
+    }
+
+    /// Writes a consistent copy of the database to `path`, replacing any
+    /// file there. The database stays usable while it is copied.
+    pub async fn copy_to(&self, path: &Path) -> Result<(), sqlx::Error> {
+        // `vacuum into` refuses to overwrite a file
+        let _ = tokio::fs::remove_file(path).await;
+        sqlx::query("vacuum into ?")
+            .bind(path.to_string_lossy().into_owned())
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Returns the path a database file is moved to in order to replace the
+    /// database on the next start, `None` for an in-memory database.
+    pub fn pending_restore_path(&self) -> Option<PathBuf> {
+        self.app_dir
+            .as_ref()
+            .map(|app_dir| app_dir.join(PENDING_RESTORE))
+    }
+
+    /// Returns the directory of the database backups, `None` for an
+    /// in-memory database.
+    fn backup_dir(&self) -> Option<PathBuf> {
+        self.app_dir
+            .as_ref()
+            .map(|app_dir| app_dir.join(BACKUP_DIR))
+    }
+
+    /// Empty all database, except custom games and games no longer owned, in
+    /// the refresh's transaction
+    pub async fn clean(&self, tx: &mut Transaction<'_, Sqlite>) -> Result<(), sqlx::Error> {
         sqlx::query!(
             "
             delete
-            from games;
+            from games
+            where id not in (select game_id from custom_games) and removed_at is null;
+
+            -- Upscaled covers are not kept by `AssetManager::clear_all`
+            update covers
+            set upscaled_path = null
+            where game_id in (select id from games where removed_at is not null);
 
             delete
-            from genres;
+            from companies
+            where not exists (select 1 from developed_by where developed_by.studio_id = companies.id)
+                and not exists (select 1 from published_by where published_by.company_id = companies.id);
 
             delete
-            from companies;
+            from franchises
+            where not exists (
+                select 1 from game_franchises where game_franchises.franchise_id = franchises.id
+            );
             "
         )
-        .execute(&self.pool)
+        .execute(&mut **tx)
         .await?;
 
         Ok(())
     }
 }
 
+pub mod achievement;
+pub mod asset_validator;
+pub mod collection;
+pub mod company;
+pub mod custom;
+pub mod deals;
 pub mod game;
+pub mod genre;
+pub mod history;
+pub mod hltb;
+pub mod hook;
+pub mod igdb_cache;
+pub mod launch;
+pub mod news;
+pub mod playtime;
+pub mod protondb;
+pub mod refresh_report;
+pub mod rule;
+pub mod session;
+pub mod settings;
+pub mod subscription;
+pub mod translation;
+pub mod whats_new;
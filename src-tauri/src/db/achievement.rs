@@ -0,0 +1,143 @@
+//! Database access layer for Steam achievements.
+//!
+//! Each row combines an achievement's schema (name, description, icons) with
+//! the player's unlock state. Rows are replaced per game on every sync, and
+//! kept by Steam store ID across library refreshes.
+
+use serde::Serialize;
+use sqlx::{sqlite::SqliteRow, FromRow, Pool, Row, Sqlite, Transaction};
+
+/// An achievement of a game, with the player's unlock state.
+#[derive(Serialize, FromRow, Debug)]
+pub struct Achievement {
+    /// Steam API name, unique per game.
+    pub api_name: String,
+    pub display_name: String,
+    pub description: Option<String>,
+    /// URL of the icon shown once unlocked.
+    pub icon: Option<String>,
+    /// URL of the icon shown while locked.
+    pub icon_gray: Option<String>,
+    /// Whether the achievement is hidden until unlocked.
+    pub hidden: bool,
+    /// Whether the player unlocked the achievement.
+    pub achieved: bool,
+    /// Unix timestamp of the unlock, `None` while locked.
+    pub unlocked_at: Option<i64>,
+}
+
+/// Data-access object for achievements.
+pub struct AchievementRepository {
+    pool: Pool<Sqlite>,
+}
+
+impl AchievementRepository {
+    /// Creates a new `AchievementRepository` backed by the given connection
+    /// pool.
+    pub fn new(pool: Pool<Sqlite>) -> Self {
+        Self { pool }
+    }
+
+    /// Replaces all achievements of a game in a single transaction.
+    pub async fn replace_achievements(
+        &self,
+        game_id: i64,
+        achievements: Vec<Achievement>,
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("DELETE FROM achievements WHERE game_id = ?")
+            .bind(game_id)
+            .execute(&mut *tx)
+            .await?;
+
+        for achievement in achievements {
+            sqlx::query(
+                "INSERT INTO achievements
+                 (game_id, api_name, display_name, description, icon, icon_gray, hidden, achieved, unlocked_at)
+                 VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            )
+            .bind(game_id)
+            .bind(achievement.api_name)
+            .bind(achievement.display_name)
+            .bind(achievement.description)
+            .bind(achievement.icon)
+            .bind(achievement.icon_gray)
+            .bind(achievement.hidden)
+            .bind(achievement.achieved)
+            .bind(achievement.unlocked_at)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    /// Returns all achievements of a game, unlocked ones first, most recent
+    /// unlock first.
+    pub async fn get_achievements(&self, game_id: i64) -> Result<Vec<Achievement>, sqlx::Error> {
+        let achievements = sqlx::query_as::<_, Achievement>(
+            "select api_name, display_name, description, icon, icon_gray, hidden, achieved, unlocked_at
+             from achievements
+             where game_id = ?
+             order by achieved desc, unlocked_at desc, display_name",
+        )
+        .bind(game_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(achievements)
+    }
+
+    /// Returns the achievements of the owned Steam games with their Steam
+    /// store ID. Used with [`Self::set_achievements_by_store_id`] to keep
+    /// them across a refresh, which re-inserts these games.
+    pub async fn get_achievements_by_store_id(
+        &self,
+    ) -> Result<Vec<(String, Achievement)>, sqlx::Error> {
+        let achievements = sqlx::query(
+            "select games_store.store_id, api_name, display_name, description, icon, icon_gray,
+                 hidden, achieved, unlocked_at
+             from achievements
+             join games_store on games_store.game_id = achievements.game_id
+             join games on games.id = achievements.game_id
+             where games.removed_at is null",
+        )
+        .try_map(|row: SqliteRow| Ok((row.try_get("store_id")?, Achievement::from_row(&row)?)))
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(achievements)
+    }
+
+    /// Stores achievements for the games with the given Steam store IDs, in
+    /// the refresh's transaction. Unknown store IDs are ignored.
+    pub async fn set_achievements_by_store_id(
+        &self,
+        tx: &mut Transaction<'_, Sqlite>,
+        achievements: Vec<(String, Achievement)>,
+    ) -> Result<(), sqlx::Error> {
+        for (store_id, achievement) in achievements {
+            sqlx::query(
+                "INSERT OR IGNORE INTO achievements
+                 (game_id, api_name, display_name, description, icon, icon_gray, hidden, achieved, unlocked_at)
+                 SELECT game_id, ?, ?, ?, ?, ?, ?, ?, ? FROM games_store WHERE store_id = ?",
+            )
+            .bind(achievement.api_name)
+            .bind(achievement.display_name)
+            .bind(achievement.description)
+            .bind(achievement.icon)
+            .bind(achievement.icon_gray)
+            .bind(achievement.hidden)
+            .bind(achievement.achieved)
+            .bind(achievement.unlocked_at)
+            .bind(store_id)
+            .execute(&mut **tx)
+            .await?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,55 @@
+//! Database access layer for the HTTP validators of downloaded images.
+//!
+//! The `ETag` and `Last-Modified` headers of each downloaded image are kept
+//! by URL, so a refresh requesting the same image again sends a conditional
+//! request and keeps its copy when the server answers `304 Not Modified`.
+
+use sqlx::{FromRow, Pool, Sqlite};
+
+/// HTTP validators of a downloaded image.
+#[derive(FromRow, Debug, Clone, Default)]
+pub struct AssetValidators {
+    /// `ETag` header of the response.
+    pub etag: Option<String>,
+    /// `Last-Modified` header of the response.
+    pub last_modified: Option<String>,
+}
+
+/// Data-access object for the validators of downloaded images.
+#[derive(Debug)]
+pub struct AssetValidatorRepository {
+    pool: Pool<Sqlite>,
+}
+
+impl AssetValidatorRepository {
+    /// Creates a new `AssetValidatorRepository` backed by the given connection pool.
+    pub fn new(pool: Pool<Sqlite>) -> Self {
+        Self { pool }
+    }
+
+    /// Returns the validators stored for `url`, or `None` if the image was
+    /// never downloaded or the server sent none.
+    pub async fn get(&self, url: &str) -> Result<Option<AssetValidators>, sqlx::Error> {
+        sqlx::query_as::<_, AssetValidators>(
+            "select etag, last_modified from asset_validators where url = ?",
+        )
+        .bind(url)
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    /// Stores the validators of `url`, replacing any previous ones.
+    pub async fn set(&self, url: &str, validators: &AssetValidators) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "insert or replace into asset_validators (url, etag, last_modified)
+             values (?, ?, ?)",
+        )
+        .bind(url)
+        .bind(&validators.etag)
+        .bind(&validators.last_modified)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+}
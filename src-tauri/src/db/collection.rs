@@ -0,0 +1,143 @@
+//! Database access layer for collections.
+//!
+//! Collections are user-named groups of library entries ("Couch co-op",
+//! "Finish in 2025", …). A game can belong to any number of collections.
+
+use serde::Serialize;
+use sqlx::{sqlite::SqliteRow, Pool, Row, Sqlite, Transaction};
+
+/// A collection and the games it contains.
+#[derive(Serialize, Debug)]
+pub struct Collection {
+    pub id: i64,
+    pub name: String,
+    /// Database IDs of the games in the collection.
+    pub game_ids: Vec<i64>,
+}
+
+/// Data-access object for collections.
+pub struct CollectionRepository {
+    pool: Pool<Sqlite>,
+}
+
+impl CollectionRepository {
+    /// Creates a new `CollectionRepository` backed by the given connection
+    /// pool.
+    pub fn new(pool: Pool<Sqlite>) -> Self {
+        Self { pool }
+    }
+
+    /// Returns all collections with their games, ordered by name.
+    pub async fn get_collections(&self) -> Result<Vec<Collection>, sqlx::Error> {
+        let collections = sqlx::query(
+            "select
+                collections.id as id, name,
+                json_group_array(collection_games.game_id) as game_ids
+             from collections
+             left join collection_games on collection_games.collection_id = collections.id
+             group by collections.id
+             order by name",
+        )
+        .map(|row: SqliteRow| Collection {
+            id: row.get("id"),
+            name: row.get("name"),
+            game_ids: serde_json::from_str::<Vec<Option<i64>>>(row.get("game_ids"))
+                .map(|ids| ids.into_iter().flatten().collect())
+                .unwrap_or_default(),
+        })
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(collections)
+    }
+
+    /// Stores a new, empty collection and returns its database ID.
+    ///
+    /// Fails if a collection with the same name already exists.
+    pub async fn insert_collection(&self, name: &str) -> Result<i64, sqlx::Error> {
+        let id =
+            sqlx::query_scalar::<_, i64>("INSERT INTO collections (name) VALUES (?) RETURNING id")
+                .bind(name)
+                .fetch_one(&self.pool)
+                .await?;
+
+        Ok(id)
+    }
+
+    /// Renames a collection.
+    pub async fn rename_collection(
+        &self,
+        collection_id: i64,
+        name: &str,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE collections SET name = ? WHERE id = ?")
+            .bind(name)
+            .bind(collection_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Adds a game to a collection. Does nothing if it already is in it.
+    pub async fn add_game(&self, collection_id: i64, game_id: i64) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "INSERT OR IGNORE INTO collection_games (collection_id, game_id) VALUES (?, ?)",
+        )
+        .bind(collection_id)
+        .bind(game_id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Removes a game from a collection.
+    pub async fn remove_game(&self, collection_id: i64, game_id: i64) -> Result<(), sqlx::Error> {
+        sqlx::query("DELETE FROM collection_games WHERE collection_id = ? AND game_id = ?")
+            .bind(collection_id)
+            .bind(game_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Returns the collections of the owned Steam games, as
+    /// `(collection ID, Steam store ID)` pairs. Used with
+    /// [`Self::set_store_memberships`] to keep them across a refresh, which
+    /// re-inserts these games.
+    pub async fn get_store_memberships(&self) -> Result<Vec<(i64, String)>, sqlx::Error> {
+        let memberships = sqlx::query_as(
+            "select collection_games.collection_id, games_store.store_id from collection_games
+             join games_store on games_store.game_id = collection_games.game_id
+             join games on games.id = collection_games.game_id
+             where games.removed_at is null",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(memberships)
+    }
+
+    /// Adds the games with the given Steam store IDs to their collections,
+    /// in the refresh's transaction. Unknown store IDs are ignored.
+    pub async fn set_store_memberships(
+        &self,
+        tx: &mut Transaction<'_, Sqlite>,
+        memberships: &[(i64, String)],
+    ) -> Result<(), sqlx::Error> {
+        for (collection_id, store_id) in memberships {
+            sqlx::query(
+                "INSERT OR IGNORE INTO collection_games (collection_id, game_id)
+                 SELECT ?, game_id FROM games_store WHERE store_id = ?",
+            )
+            .bind(collection_id)
+            .bind(store_id)
+            .execute(&mut **tx)
+            .await?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,33 @@
+//! Database access layer for the company dictionary.
+//!
+//! Companies are stored with their IGDB ID when the games they made are
+//! inserted, so game enrichment can resolve company IDs without fetching
+//! every company again.
+
+use sqlx::{Pool, Sqlite};
+
+use crate::igdb::IgdbCompany;
+
+/// Data-access object for the local company lookup table.
+pub struct CompanyRepository {
+    pool: Pool<Sqlite>,
+}
+
+impl CompanyRepository {
+    /// Creates a new `CompanyRepository` backed by the given connection pool.
+    pub fn new(pool: Pool<Sqlite>) -> Self {
+        Self { pool }
+    }
+
+    /// Returns every stored company.
+    pub async fn get_companies(&self) -> Result<Vec<IgdbCompany>, sqlx::Error> {
+        let companies = sqlx::query_as::<_, (i64, String)>("select igdb_id, name from companies")
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(companies
+            .into_iter()
+            .map(|(id, name)| IgdbCompany { id, name })
+            .collect())
+    }
+}
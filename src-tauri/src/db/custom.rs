@@ -0,0 +1,198 @@
+//! Database access layer for custom (non-Steam) games.
+//!
+//! Custom games are regular `games` rows without a `games_store` entry, plus
+//! the executable to run and an optional Wine configuration. They are kept
+//! across library refreshes.
+
+use sqlx::{sqlite::SqliteRow, Pool, Row, Sqlite};
+
+use crate::{bundle::CustomGameDefinition, wine::WineConfig};
+
+/// Data-access object for custom games and their Wine configuration.
+pub struct CustomGameRepository {
+    pool: Pool<Sqlite>,
+}
+
+impl CustomGameRepository {
+    /// Creates a new `CustomGameRepository` backed by the given connection
+    /// pool.
+    pub fn new(pool: Pool<Sqlite>) -> Self {
+        Self { pool }
+    }
+
+    /// Inserts a custom game and returns its database ID.
+    pub async fn insert_custom_game(
+        &self,
+        name: &str,
+        executable: &str,
+    ) -> Result<i64, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let game_id =
+            sqlx::query_scalar::<_, i64>("INSERT INTO games (name) VALUES (?) RETURNING id")
+                .bind(name)
+                .fetch_one(&mut *tx)
+                .await?;
+
+        sqlx::query("INSERT INTO custom_games (game_id, executable) VALUES (?, ?)")
+            .bind(game_id)
+            .bind(executable)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+
+        Ok(game_id)
+    }
+
+    /// Returns the definitions of all custom games, ordered by name.
+    pub async fn get_definitions(&self) -> Result<Vec<CustomGameDefinition>, sqlx::Error> {
+        let definitions = sqlx::query(
+            "select
+                games.name as name, custom_games.executable as executable,
+                wine_configs.runtime as runtime, wine_configs.prefix as prefix,
+                launch_settings.controller_profile as controller_profile,
+                (select cover_id from covers where covers.game_id = games.id) as cover,
+                (select json_group_array(artwork_id) from artworks
+                 where artworks.game_id = games.id) as artworks
+             from custom_games
+             join games on games.id = custom_games.game_id
+             left join wine_configs on wine_configs.game_id = games.id
+             left join launch_settings on launch_settings.game_id = games.id
+             order by games.name",
+        )
+        .map(|row: SqliteRow| CustomGameDefinition {
+            name: row.get("name"),
+            executable: row.get("executable"),
+            wine: row
+                .get::<Option<String>, _>("runtime")
+                .map(|runtime| WineConfig {
+                    runtime,
+                    prefix: row.get("prefix"),
+                }),
+            controller_profile: row.get("controller_profile"),
+            cover: row.get("cover"),
+            artworks: serde_json::from_str(row.get("artworks")).unwrap_or_default(),
+        })
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(definitions)
+    }
+
+    /// Inserts a custom game with its Wine configuration, launch settings,
+    /// and images, in a single transaction. Returns its database ID.
+    ///
+    /// Images are inserted without a local path; they are downloaded when
+    /// the game is prefetched.
+    pub async fn insert_definition(
+        &self,
+        definition: &CustomGameDefinition,
+    ) -> Result<i64, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let game_id =
+            sqlx::query_scalar::<_, i64>("INSERT INTO games (name) VALUES (?) RETURNING id")
+                .bind(&definition.name)
+                .fetch_one(&mut *tx)
+                .await?;
+
+        sqlx::query("INSERT INTO custom_games (game_id, executable) VALUES (?, ?)")
+            .bind(game_id)
+            .bind(&definition.executable)
+            .execute(&mut *tx)
+            .await?;
+
+        if let Some(wine) = &definition.wine {
+            sqlx::query("INSERT INTO wine_configs (game_id, runtime, prefix) VALUES (?, ?, ?)")
+                .bind(game_id)
+                .bind(&wine.runtime)
+                .bind(&wine.prefix)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        if let Some(controller_profile) = &definition.controller_profile {
+            sqlx::query("INSERT INTO launch_settings (game_id, controller_profile) VALUES (?, ?)")
+                .bind(game_id)
+                .bind(controller_profile)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        if let Some(cover) = &definition.cover {
+            sqlx::query("INSERT INTO covers (game_id, cover_id) VALUES (?, ?)")
+                .bind(game_id)
+                .bind(cover)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        for artwork in &definition.artworks {
+            sqlx::query("INSERT INTO artworks (game_id, artwork_id) VALUES (?, ?)")
+                .bind(game_id)
+                .bind(artwork)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(game_id)
+    }
+
+    /// Returns the executable of a custom game, or `None` for Steam games.
+    pub async fn get_executable(&self, game_id: i64) -> Result<Option<String>, sqlx::Error> {
+        let executable =
+            sqlx::query_scalar("select executable from custom_games where game_id = ?")
+                .bind(game_id)
+                .fetch_optional(&self.pool)
+                .await?;
+
+        Ok(executable)
+    }
+
+    /// Returns the Wine configuration of a game, or `None` if it runs
+    /// natively.
+    pub async fn get_wine_config(&self, game_id: i64) -> Result<Option<WineConfig>, sqlx::Error> {
+        let config = sqlx::query("select runtime, prefix from wine_configs where game_id = ?")
+            .bind(game_id)
+            .map(|row: sqlx::sqlite::SqliteRow| WineConfig {
+                runtime: row.get("runtime"),
+                prefix: row.get("prefix"),
+            })
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(config)
+    }
+
+    /// Stores the Wine configuration of a game, replacing any previous one.
+    pub async fn set_wine_config(
+        &self,
+        game_id: i64,
+        config: &WineConfig,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "INSERT INTO wine_configs (game_id, runtime, prefix) VALUES (?, ?, ?)
+             ON CONFLICT(game_id) DO UPDATE SET runtime = excluded.runtime, prefix = excluded.prefix",
+        )
+        .bind(game_id)
+        .bind(&config.runtime)
+        .bind(&config.prefix)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Removes the Wine configuration of a game so it runs natively.
+    pub async fn delete_wine_config(&self, game_id: i64) -> Result<(), sqlx::Error> {
+        sqlx::query("DELETE FROM wine_configs WHERE game_id = ?")
+            .bind(game_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,198 @@
+//! Database access layer for tracked prices.
+//!
+//! Wishlist games and DLC of owned games are tracked by Steam App ID, so the
+//! recorded historical lows survive library refreshes.
+
+use std::collections::{HashMap, HashSet};
+
+use serde::Serialize;
+use sqlx::{FromRow, Pool, QueryBuilder, Sqlite};
+
+use crate::service::deals::PriceOverview;
+
+/// Why an app's price is tracked.
+#[derive(Debug, Clone, Copy)]
+pub enum DealKind {
+    /// The app is on the user's wishlist.
+    Wishlist,
+    /// The app is a DLC of an owned game.
+    Dlc,
+}
+
+impl DealKind {
+    /// Returns the value stored in the `kind` column.
+    fn key(&self) -> &'static str {
+        match self {
+            DealKind::Wishlist => "wishlist",
+            DealKind::Dlc => "dlc",
+        }
+    }
+}
+
+/// A tracked app and its last known price.
+#[derive(Serialize, FromRow, Debug)]
+pub struct Deal {
+    /// Steam App ID.
+    pub store_id: String,
+    pub name: String,
+    /// `wishlist` or `dlc`.
+    pub kind: String,
+    /// Steam App ID of the owned game a DLC belongs to.
+    pub parent_store_id: Option<String>,
+    /// ISO 4217 currency code.
+    pub currency: Option<String>,
+    /// Price before discount, in cents.
+    pub initial_price: Option<i64>,
+    /// Current price, in cents.
+    pub final_price: Option<i64>,
+    pub discount_percent: i64,
+    /// Lowest price seen since the app is tracked, in cents.
+    pub historical_low: Option<i64>,
+}
+
+/// Data-access object for tracked prices.
+pub struct DealRepository {
+    pool: Pool<Sqlite>,
+}
+
+impl DealRepository {
+    /// Creates a new `DealRepository` backed by the given connection pool.
+    pub fn new(pool: Pool<Sqlite>) -> Self {
+        Self { pool }
+    }
+
+    /// Returns the tracked apps currently on sale, biggest discount first.
+    pub async fn get_deals(&self) -> Result<Vec<Deal>, sqlx::Error> {
+        let deals = sqlx::query_as::<_, Deal>(
+            "select store_id, name, kind, parent_store_id, currency, initial_price, final_price,
+                discount_percent, historical_low
+             from tracked_prices
+             where discount_percent > 0
+             order by discount_percent desc, name",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(deals)
+    }
+
+    /// Returns the Unix timestamp of the last price update, or `None` if
+    /// prices were never fetched.
+    pub async fn get_updated_at(&self) -> Result<Option<i64>, sqlx::Error> {
+        let updated_at = sqlx::query_scalar("select max(updated_at) from tracked_prices")
+            .fetch_one(&self.pool)
+            .await?;
+
+        Ok(updated_at)
+    }
+
+    /// Returns the Steam App IDs of all tracked apps.
+    pub async fn get_tracked_store_ids(&self) -> Result<HashSet<String>, sqlx::Error> {
+        let store_ids: Vec<String> = sqlx::query_scalar("select store_id from tracked_prices")
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(store_ids.into_iter().collect())
+    }
+
+    /// Starts tracking the price of an app. Already tracked apps are left
+    /// unchanged.
+    pub async fn track(
+        &self,
+        store_id: &str,
+        name: &str,
+        kind: DealKind,
+        parent_store_id: Option<&str>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "insert or ignore into tracked_prices (store_id, name, kind, parent_store_id)
+             values (?, ?, ?, ?)",
+        )
+        .bind(store_id)
+        .bind(name)
+        .bind(kind.key())
+        .bind(parent_store_id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Stops tracking the wishlist apps that are not in `wishlist` anymore.
+    pub async fn retain_wishlist(&self, wishlist: &HashSet<String>) -> Result<(), sqlx::Error> {
+        let mut query: QueryBuilder<Sqlite> =
+            QueryBuilder::new("delete from tracked_prices where kind = ");
+        query.push_bind(DealKind::Wishlist.key());
+
+        if !wishlist.is_empty() {
+            query.push(" and store_id not in (");
+            let mut separated = query.separated(", ");
+            for store_id in wishlist {
+                separated.push_bind(store_id);
+            }
+            separated.push_unseparated(")");
+        }
+
+        query.build().execute(&self.pool).await?;
+
+        Ok(())
+    }
+
+    /// Stores the current prices of tracked apps and lowers their historical
+    /// low when needed, in a single transaction.
+    pub async fn update_prices(
+        &self,
+        prices: HashMap<u64, PriceOverview>,
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        for (appid, price) in prices {
+            sqlx::query(
+                "update tracked_prices
+                 set currency = ?, initial_price = ?, final_price = ?, discount_percent = ?,
+                     historical_low = min(coalesce(historical_low, ?), ?),
+                     updated_at = strftime('%s', 'now')
+                 where store_id = ?",
+            )
+            .bind(&price.currency)
+            .bind(price.initial)
+            .bind(price.final_price)
+            .bind(price.discount_percent)
+            .bind(price.final_price)
+            .bind(price.final_price)
+            .bind(appid.to_string())
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    /// Returns the Steam App IDs of the games whose DLC list was looked up
+    /// less than `max_age` seconds ago.
+    pub async fn get_dlc_lookups(&self, max_age: i64) -> Result<HashSet<String>, sqlx::Error> {
+        let store_ids: Vec<String> = sqlx::query_scalar(
+            "select store_id from dlc_lookups where checked_at > strftime('%s', 'now') - ?",
+        )
+        .bind(max_age)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(store_ids.into_iter().collect())
+    }
+
+    /// Records that a game's DLC list was just looked up.
+    pub async fn record_dlc_lookup(&self, store_id: &str) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "insert or replace into dlc_lookups (store_id, checked_at)
+             values (?, strftime('%s', 'now'))",
+        )
+        .bind(store_id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+}
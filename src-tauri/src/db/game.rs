@@ -4,10 +4,24 @@
 //! and [`GameRepository`], which handles all SQL queries and inserts against
 //! the SQLite database.
 
+use std::{
+    collections::{HashMap, HashSet},
+    sync::atomic::{AtomicBool, Ordering},
+};
+
 use serde::{Deserialize, Serialize};
-use sqlx::{sqlite::SqliteRow, Pool, Row, Sqlite};
+use sqlx::{
+    query_builder::Separated, sqlite::SqliteRow, Pool, QueryBuilder, Row, Sqlite, Transaction,
+};
+
+use crate::{
+    igdb::{game_type_name, IgdbCompany, IgdbGame, IgdbImage},
+    service::steam_store::AppDetails,
+};
 
-use crate::igdb::IgdbGame;
+/// Rows inserted per statement by [`GameRepository::insert_games_bulk`],
+/// keeping the bound parameters under SQLite's limit.
+const BULK_INSERT_ROWS: usize = 1000;
 
 /// Represents a game image with both IGDB ID and optional local file path.
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -16,6 +30,108 @@ pub struct GameImage {
     pub id: String,
     /// Local filesystem path to cached image, if downloaded.
     pub local_path: Option<String>,
+    /// Local filesystem path to the upscaled variant. Only set for covers
+    /// when upscaling is enabled.
+    pub upscaled_path: Option<String>,
+}
+
+/// A franchise or series a game belongs to.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct GameFranchise {
+    /// Database ID of the franchise, for [`GameRepository::get_franchise_games`].
+    pub id: i64,
+    pub name: String,
+}
+
+/// An owned DLC or expansion, listed under its game.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct GameDlc {
+    /// Steam App ID of the DLC.
+    pub store_id: String,
+    pub name: String,
+}
+
+/// Artwork the user chose from SteamGridDB in place of the IGDB images.
+///
+/// Kept by Steam store ID, so the choice survives library refreshes. Each
+/// path is `None` when SteamGridDB had no image of that kind.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct SteamGridDbArtwork {
+    /// Local path of the vertical grid, shown in place of the cover.
+    pub grid_path: Option<String>,
+    /// Local path of the hero banner, shown in place of the artworks.
+    pub hero_path: Option<String>,
+    /// Local path of the logo, with a transparent background.
+    pub logo_path: Option<String>,
+}
+
+/// A PEGI or ESRB age rating of a game.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct AgeRating {
+    /// Rating board: `PEGI` or `ESRB`.
+    pub organization: String,
+    /// Rating as named by IGDB, e.g. `Sixteen` or `M`.
+    pub rating: String,
+}
+
+/// A video of a game, hosted on YouTube.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct GameVideo {
+    /// YouTube video ID.
+    pub id: String,
+    /// Title of the video, e.g. "Trailer".
+    pub name: Option<String>,
+}
+
+/// Where the installed copy of a game comes from.
+#[derive(Serialize, Clone, Copy, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum InstallSource {
+    /// Installed by the Steam client.
+    Steam,
+    /// A custom game whose executable is present on disk.
+    Custom,
+}
+
+/// Where a game stands in the user's backlog.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum GameStatus {
+    /// Not started yet, but meant to be played.
+    Backlog,
+    /// Currently being played.
+    Playing,
+    /// Finished.
+    Completed,
+    /// Dropped for good.
+    Abandoned,
+    /// Paused, to be resumed later.
+    OnHold,
+}
+
+impl GameStatus {
+    /// Returns the value stored in the `status` column.
+    pub(crate) fn key(&self) -> &'static str {
+        match self {
+            GameStatus::Backlog => "backlog",
+            GameStatus::Playing => "playing",
+            GameStatus::Completed => "completed",
+            GameStatus::Abandoned => "abandoned",
+            GameStatus::OnHold => "on_hold",
+        }
+    }
+
+    /// Parses a value of the `status` column. Unknown values are `None`.
+    fn from_key(key: &str) -> Option<Self> {
+        match key {
+            "backlog" => Some(GameStatus::Backlog),
+            "playing" => Some(GameStatus::Playing),
+            "completed" => Some(GameStatus::Completed),
+            "abandoned" => Some(GameStatus::Abandoned),
+            "on_hold" => Some(GameStatus::OnHold),
+            _ => None,
+        }
+    }
 }
 
 /// A fully resolved game record, ready to be serialized and sent to the
@@ -25,81 +141,506 @@ pub struct GameImage {
 /// collected from their respective join tables. `is_installed` is not stored
 /// in the database — it is set at query time by checking the local Steam
 /// library.
-#[derive(Serialize)]
+#[derive(Serialize, Clone)]
 pub struct Game {
     /// Internal database ID.
     pub id: i64,
     pub name: String,
     pub summary: Option<String>,
+    /// Storyline from IGDB, in English.
+    pub storyline: Option<String>,
     /// Steam App ID, sourced from the `games_store` table.
     pub store_id: Option<String>,
-    /// Game cover image with ID and optional local path.
+    /// Game cover image with ID and optional local path. Omitted in
+    /// low-graphics mode.
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub cover: Option<GameImage>,
     /// Whether the game is fully installed in the local Steam library.
     /// Always `None` when retrieved from the database — callers must set it.
     pub is_installed: Option<bool>,
-    /// List of artwork images with IDs and optional local paths.
+    /// Where the installed copy comes from. Like `is_installed`, always
+    /// `None` when retrieved from the database.
+    pub installed_via: Option<InstallSource>,
+    /// List of artwork images with IDs and optional local paths. Omitted in
+    /// low-graphics mode.
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub artworks: Option<Vec<GameImage>>,
+    /// Dominant color of the cover as `#rrggbb`, to tint the detail page.
+    pub accent_color: Option<String>,
+    /// Small versions of the artworks, for grid and list views. Same IDs as
+    /// `artworks`; `local_path` is `None` for artworks without a thumbnail.
+    /// Omitted in low-graphics mode.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub artwork_thumbnails: Option<Vec<GameImage>>,
+    /// List of screenshots with IDs and optional local paths. Omitted in
+    /// low-graphics mode.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub screenshots: Option<Vec<GameImage>>,
+    /// Trailers and other videos from IGDB, to embed from YouTube.
+    pub videos: Option<Vec<GameVideo>>,
     /// Unix timestamp of the game's first release.
     pub release_date: Option<i64>,
+    /// Total Steam playtime in minutes.
+    pub playtime_forever: Option<i64>,
+    /// Steam playtime in minutes over the last two weeks.
+    pub playtime_2weeks: Option<i64>,
+    /// Unix timestamp of the last launch from Rocade.
+    pub last_launched_at: Option<i64>,
+    /// Unix timestamp of the last time the game was played, as reported by
+    /// Steam.
+    pub last_played_at: Option<i64>,
     pub genres: Option<Vec<String>>,
     pub developers: Option<Vec<String>>,
+    pub publishers: Option<Vec<String>>,
+    /// IGDB game modes (e.g. "Single player", "Co-operative").
+    pub game_modes: Option<Vec<String>>,
+    /// IGDB player perspectives (e.g. "First person").
+    pub player_perspectives: Option<Vec<String>>,
+    /// PEGI and ESRB age ratings.
+    pub age_ratings: Option<Vec<AgeRating>>,
+    /// Highest minimum player age among the age ratings, in years.
+    pub minimum_age: Option<i64>,
+    /// IGDB franchises and series the game belongs to.
+    pub franchises: Option<Vec<GameFranchise>>,
+    /// Other names of the game from IGDB (e.g. "GTA V"), matched by searches.
+    pub alternative_names: Option<Vec<String>>,
+    /// IGDB game type name (e.g. `main_game`, `bundle`).
+    pub game_type: Option<String>,
+    /// Whether the game is hidden by an exclusion rule.
+    pub excluded: bool,
+    /// Whether the user pinned the game to the favorites shelf.
+    pub is_favorite: bool,
+    /// Whether the user hid the game from the library.
+    pub hidden: bool,
+    /// Unix timestamp of the refresh that no longer found the game among the
+    /// owned Steam games, `None` while it is owned. Such games are kept with
+    /// their notes and playtime.
+    pub removed_at: Option<i64>,
+    /// Unix timestamp of the refresh that first found the game, `None` for
+    /// games added before it was tracked.
+    pub added_at: Option<i64>,
+    /// Position among the games pinned to the top of the library, `None`
+    /// when not pinned.
+    pub pin_position: Option<i64>,
+    /// The user's own rating, from 0 to 10.
+    pub user_rating: Option<i64>,
+    /// Backlog status set by the user.
+    pub status: Option<GameStatus>,
+    /// Average critic score from IGDB, from 0 to 100.
+    pub aggregated_rating: Option<f64>,
+    /// Number of critic scores behind `aggregated_rating`.
+    pub rating_count: Option<i64>,
+    /// Metacritic score from the Steam store page. Store page fields are only
+    /// set for games without an IGDB match.
+    pub metacritic_score: Option<i64>,
+    /// Steam store categories (e.g. co-op, controller support).
+    pub categories: Option<Vec<String>>,
+    /// URL of the Steam store header image. Omitted in low-graphics mode.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub header_image: Option<String>,
+    /// HowLongToBeat completion times, once looked up and matched.
+    pub completion_times: Option<CompletionTimes>,
+    /// Steam App ID of the owned soundtrack of the game.
+    pub soundtrack_store_id: Option<String>,
+    /// Owned DLC and expansions of the game.
+    pub dlc: Option<Vec<GameDlc>>,
+    /// ProtonDB compatibility tier (`platinum`, `gold`, ...). Only synced on
+    /// Linux.
+    pub protondb_tier: Option<String>,
+    /// Artwork from SteamGridDB, when the user chose it as the artwork
+    /// source of the game. Omitted in low-graphics mode.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub steamgriddb_artwork: Option<SteamGridDbArtwork>,
+}
+
+/// Average completion times of a game from HowLongToBeat, in hours.
+#[derive(Serialize, Clone, Debug)]
+pub struct CompletionTimes {
+    /// Main story.
+    pub main: Option<f64>,
+    /// Main story and extras.
+    pub main_extra: Option<f64>,
+    pub completionist: Option<f64>,
+}
+
+/// Raw row of [`GameRepository::fetch_game_rows`], before its JSON columns
+/// are decoded into a [`Game`].
+struct GameRow {
+    id: i64,
+    name: String,
+    store_id: Option<String>,
+    summary: Option<String>,
+    storyline: Option<String>,
+    release_date: Option<i64>,
+    playtime_forever: Option<i64>,
+    playtime_2weeks: Option<i64>,
+    last_launched_at: Option<i64>,
+    last_played_at: Option<i64>,
+    game_type: Option<i64>,
+    excluded: bool,
+    is_favorite: bool,
+    hidden: bool,
+    removed_at: Option<i64>,
+    added_at: Option<i64>,
+    pin_position: Option<i64>,
+    user_rating: Option<i64>,
+    status: Option<String>,
+    aggregated_rating: Option<f64>,
+    rating_count: Option<i64>,
+    metacritic_score: Option<i64>,
+    header_image: Option<String>,
+    accent_color: Option<String>,
+    hltb_id: Option<i64>,
+    main_hours: Option<f64>,
+    main_extra_hours: Option<f64>,
+    completionist_hours: Option<f64>,
+    genres: Option<String>,
+    categories: Option<String>,
+    soundtrack_store_id: Option<String>,
+    dlc: Option<String>,
+    protondb_tier: Option<String>,
+    steamgriddb_artwork: Option<String>,
+    studios: Option<String>,
+    artworks: Option<String>,
+    artwork_thumbnails: Option<String>,
+    screenshots: Option<String>,
+    videos: Option<String>,
+    publishers: Option<String>,
+    game_modes: Option<String>,
+    player_perspectives: Option<String>,
+    alternative_names: Option<String>,
+    age_ratings: Option<String>,
+    minimum_age: Option<i64>,
+    franchises: Option<String>,
+    covers: Option<String>,
+}
+
+/// A slim game record holding only what a grid tile renders.
+#[derive(Serialize, Clone)]
+pub struct GameSummary {
+    pub id: i64,
+    pub name: String,
+    /// Steam App ID, sourced from the `games_store` table.
+    pub store_id: Option<String>,
+    /// Omitted in low-graphics mode.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cover: Option<GameImage>,
+    /// Local path of the SteamGridDB grid shown in place of the cover, if
+    /// the user chose one. Omitted in low-graphics mode.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub grid_path: Option<String>,
+    /// Total Steam playtime in minutes.
+    pub playtime_forever: Option<i64>,
+    /// Steam playtime in minutes over the last two weeks.
+    pub playtime_2weeks: Option<i64>,
+    /// Unix timestamp of the last time the game was played, from Steam or
+    /// a launch from Rocade, whichever is more recent.
+    pub last_played_at: Option<i64>,
+    pub is_favorite: bool,
+}
+
+/// Sort orders accepted by game queries.
+#[derive(Deserialize, Debug, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum GameSort {
+    /// Alphabetical by name (the default).
+    Name,
+    /// Most total playtime first.
+    Playtime,
+    /// Most playtime over the last two weeks first.
+    RecentPlaytime,
+    /// Highest personal rating first.
+    UserRating,
+    /// Highest IGDB critic rating first.
+    Rating,
+}
+
+impl GameSort {
+    /// Returns the SQL expression of the sort's numeric key, or `None` when
+    /// sorting by name only. Missing playtime or rating counts as `-1` so it
+    /// sorts last. Critic ratings are scaled by 100 to keep an integer key.
+    fn key_column(&self) -> Option<&'static str> {
+        match self {
+            GameSort::Name => None,
+            GameSort::Playtime => Some("coalesce(games.playtime_forever, -1)"),
+            GameSort::RecentPlaytime => Some("coalesce(games.playtime_2weeks, -1)"),
+            GameSort::UserRating => Some("coalesce(games.user_rating, -1)"),
+            GameSort::Rating => {
+                Some("coalesce(cast(games.aggregated_rating * 100 as integer), -1)")
+            }
+        }
+    }
+}
+
+/// Filters accepted by [`GameRepository::get_games_window`].
+#[derive(Deserialize, Debug, Default)]
+pub struct GameFilters {
+    /// Case-insensitive substring of the game name or of one of its
+    /// alternative names.
+    pub name: Option<String>,
+    /// Genre name the game must belong to.
+    pub genre: Option<String>,
+    /// When set, only the games no longer owned on Steam are returned;
+    /// otherwise they are left out.
+    #[serde(default)]
+    pub no_longer_owned: bool,
+    /// When set, only the games never played, on Steam or from Rocade, are
+    /// returned.
+    #[serde(default)]
+    pub unplayed: bool,
+    /// Longest HowLongToBeat main story time, in hours. Games without a
+    /// completion time are left out when set.
+    pub max_hours: Option<f64>,
+    /// When set, only installed games are returned. Install status is not
+    /// stored, so this filter is applied by [`pick_random_game`] only.
+    ///
+    /// [`pick_random_game`]: crate::commands::pick_random_game
+    #[serde(default)]
+    pub installed: bool,
+}
+
+/// Position of the last game of a window, from which the next window starts.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct GameCursor {
+    pub id: i64,
+    pub name: String,
+    /// Numeric sort key of the game; `-1` when sorting by name.
+    pub key: i64,
+}
+
+/// A window of games returned by [`GameRepository::get_games_window`].
+#[derive(Serialize)]
+pub struct GamesWindow {
+    pub games: Vec<GameSummary>,
+    /// Cursor of the next window, `None` once the end is reached.
+    pub next: Option<GameCursor>,
+    /// Number of games matching the filters, for sizing the scroll area.
+    pub total: i64,
+}
+
+/// A distinct value of a game field with the number of games having it,
+/// offered as a filter option.
+#[derive(Serialize, Debug)]
+pub struct Facet<T> {
+    pub value: T,
+    pub game_count: i64,
+}
+
+/// A flag set by the user on a game, kept across library refreshes.
+#[derive(Debug, Clone, Copy)]
+pub enum UserFlag {
+    /// Pinned to the favorites shelf.
+    Favorite,
+    /// Hidden from the library.
+    Hidden,
+}
+
+impl UserFlag {
+    /// Returns the `games` column holding the flag.
+    fn column(&self) -> &'static str {
+        match self {
+            UserFlag::Favorite => "is_favorite",
+            UserFlag::Hidden => "hidden",
+        }
+    }
+}
+
+/// Free-form data the user attached to a game, kept across library
+/// refreshes.
+pub struct UserData {
+    pub notes: Option<String>,
+    /// Rating from 0 to 10.
+    pub user_rating: Option<i64>,
+    /// Raw value of the `status` column.
+    status: Option<String>,
+    /// Unix timestamp of the last launch from Rocade.
+    last_launched_at: Option<i64>,
+}
+
+/// Kind of image the user can add to a game from their own files.
+#[derive(Debug, Clone, Copy)]
+pub enum CustomImageKind {
+    /// Replaces the cover.
+    Cover,
+    /// Added to the artworks.
+    Artwork,
+}
+
+impl CustomImageKind {
+    /// Returns the table holding images of this kind.
+    fn table(&self) -> &'static str {
+        match self {
+            CustomImageKind::Cover => "covers",
+            CustomImageKind::Artwork => "artworks",
+        }
+    }
+
+    /// Returns the image ID column of [`Self::table`].
+    fn id_column(&self) -> &'static str {
+        match self {
+            CustomImageKind::Cover => "cover_id",
+            CustomImageKind::Artwork => "artwork_id",
+        }
+    }
+}
+
+/// An image added by the user to a Steam game, kept across library
+/// refreshes.
+pub struct CustomImage {
+    store_id: String,
+    kind: CustomImageKind,
+    image_id: String,
+    local_path: Option<String>,
+}
+
+/// Metadata corrected by the user, applied over the IGDB and store page
+/// data. `None` fields are not overridden.
+///
+/// Kept by Steam store ID, so corrections survive library refreshes and
+/// re-matches.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct MetadataOverride {
+    pub name: Option<String>,
+    pub summary: Option<String>,
+    /// Unix timestamp of the release date.
+    pub release_date: Option<i64>,
+    /// IGDB image ID of the cover.
+    pub cover: Option<String>,
+}
+
+impl MetadataOverride {
+    /// Returns `true` if no field is overridden.
+    fn is_empty(&self) -> bool {
+        self.name.is_none()
+            && self.summary.is_none()
+            && self.release_date.is_none()
+            && self.cover.is_none()
+    }
+
+    /// Replaces the overridden fields of `game`.
+    fn apply_to(&self, game: &mut IgdbGame) {
+        if let Some(name) = &self.name {
+            game.name = name.clone();
+        }
+        if let Some(summary) = &self.summary {
+            game.summary = Some(summary.clone());
+        }
+        if let Some(release_date) = self.release_date {
+            game.release_date = Some(release_date);
+        }
+        if let Some(cover) = &self.cover {
+            game.cover = Some(IgdbImage {
+                image_id: cover.clone(),
+            });
+        }
+    }
+}
+
+/// Player statistics reported by Steam for a single game.
+///
+/// Updated on every refresh and by the lighter player stats sync, which
+/// leaves metadata and assets untouched.
+pub struct PlayerStats {
+    /// Steam App ID of the game.
+    pub store_id: String,
+    /// Total playtime in minutes.
+    pub playtime_forever: Option<i64>,
+    /// Playtime in minutes over the last two weeks.
+    pub playtime_2weeks: Option<i64>,
+    /// Unix timestamp of the last time the game was played.
+    pub last_played_at: Option<i64>,
+}
+
+/// The current IGDB match of a Steam game.
+pub struct IgdbMatch {
+    pub game_id: i64,
+    /// Steam App ID.
+    pub store_id: String,
+    /// Matched IGDB game ID, `None` for games filled in from the Steam store.
+    pub igdb_id: Option<i64>,
+}
+
+/// Images of a re-matched game that are not downloaded yet.
+pub struct NewImages {
+    /// IGDB image ID of the new cover, if it changed.
+    pub cover: Option<String>,
+    /// IGDB image IDs of the artworks the game did not have before.
+    pub artworks: Vec<String>,
+    /// IGDB image IDs of the screenshots the game did not have before.
+    pub screenshots: Vec<String>,
 }
 
 /// Data-access object for game-related database operations.
 pub struct GameRepository {
     pool: Pool<Sqlite>,
+    /// When set, image fields are stripped from returned games
+    /// (low-graphics mode).
+    text_only: AtomicBool,
 }
 
 impl GameRepository {
-    /// Base SELECT that joins all related tables (genres, companies, artworks,
-    /// covers, store IDs) into a single row per game using `json_group_array`.
-    const BASE_QUERY: &'static str = "
+    /// SELECT list of [`GameSummary`] rows, with one cover per game.
+    const SUMMARY_COLUMNS: &'static str = "
 select
     games.id as id,
     games.name as name,
     games_store.store_id as store_id,
-    summary, release_date,
-    json_group_array(distinct genres.name) as genres,
-    json_group_array(distinct companies.name) as studios,
-    json_group_array(distinct json_object(
-        'id', artworks.artwork_id,
-        'local_path', artworks.local_path
-    )) as artworks,
-    json_group_array(distinct json_object(
-        'id', covers.cover_id,
-        'local_path', covers.local_path
-    )) as covers
+    games.playtime_forever as playtime_forever,
+    games.playtime_2weeks as playtime_2weeks,
+    nullif(max(coalesce(games.last_played_at, 0), coalesce(games.last_launched_at, 0)), 0)
+        as last_played_at,
+    games.is_favorite as is_favorite,
+    covers.cover_id as cover_id,
+    covers.local_path as cover_local_path,
+    covers.upscaled_path as cover_upscaled_path,
+    (select steamgriddb_artworks.grid_path from steamgriddb_artworks
+        where steamgriddb_artworks.store_id = games_store.store_id) as grid_path";
+
+    /// FROM clause of [`GameSummary`] queries. Excluded and hidden games are
+    /// left out.
+    const SUMMARY_FROM: &'static str = "
 from games
-left join developed_by on games.id = developed_by.game_id
-left join companies on developed_by.studio_id = companies.id
-left join belongs_to on games.id = belongs_to.game_id
-left join genres on belongs_to.genre_id = genres.id
-left join artworks on artworks.game_id = games.id
-left join covers on covers.game_id = games.id
 left join games_store on games_store.game_id = games.id
-";
-
-    /// GROUP BY / ORDER BY clause appended to every query built by
-    /// [`Self::build_query_string`]. Results are ordered alphabetically by
-    /// game name.
-    const GROUP_ORDER: &'static str = "
-group by games.id, games.name, games_store.store_id, games.summary, games.release_date
-order by games.name
-";
+left join covers on covers.game_id = games.id
+where games.excluded = 0 and games.hidden = 0";
 
     /// Creates a new `GameRepository` backed by the given connection pool.
     pub fn new(pool: Pool<Sqlite>) -> Self {
-        Self { pool }
+        Self {
+            pool,
+            text_only: AtomicBool::new(false),
+        }
+    }
+
+    /// Starts a transaction, for writes made together by a refresh or an
+    /// import to be rolled back as a whole when one of them fails.
+    pub async fn begin(&self) -> Result<Transaction<'static, Sqlite>, sqlx::Error> {
+        self.pool.begin().await
+    }
+
+    /// Enables or disables low-graphics mode, in which returned games carry
+    /// no image fields.
+    pub fn set_text_only(&self, text_only: bool) {
+        self.text_only.store(text_only, Ordering::Relaxed);
+    }
+
+    /// Returns `true` if low-graphics mode is enabled.
+    fn is_text_only(&self) -> bool {
+        self.text_only.load(Ordering::Relaxed)
     }
 
     /// Returns all games in the database ordered alphabetically by name.
     pub async fn get_games(&self) -> Result<Vec<Game>, sqlx::Error> {
-        let query = Self::build_query_string(None);
-        let games = sqlx::query(&query)
+        let mut games: Vec<Game> = self
+            .fetch_game_rows(None)
+            .await?
+            .into_iter()
             .map(Self::map_game_row)
-            .fetch_all(&self.pool)
-            .await?;
+            .collect();
+
+        if self.is_text_only() {
+            games.iter_mut().for_each(Self::strip_images);
+        }
 
         Ok(games)
     }
@@ -111,196 +652,2295 @@ order by games.name
     /// Returns [`sqlx::Error::RowNotFound`] if no game with the given ID
     /// exists.
     pub async fn get_game_by_id(&self, game_id: i64) -> Result<Game, sqlx::Error> {
-        let query = Self::build_query_string(Some(game_id));
-        let game = sqlx::query(&query)
-            .bind(game_id)
-            .map(Self::map_game_row)
-            .fetch_one(&self.pool)
-            .await?;
+        let row = self
+            .fetch_game_rows(Some(game_id))
+            .await?
+            .pop()
+            .ok_or(sqlx::Error::RowNotFound)?;
+        let mut game = Self::map_game_row(row);
+
+        if self.is_text_only() {
+            Self::strip_images(&mut game);
+        }
 
         Ok(game)
     }
 
-    /// Builds the full SQL query string, optionally appending a `WHERE`
-    /// clause to filter by a specific game ID.
-    fn build_query_string(game_id: Option<i64>) -> String {
-        let where_clause = if game_id.is_some() {
-            " where games.id =  ?"
-        } else {
-            ""
-        };
+    /// Fetches games joined with all related tables (genres, companies,
+    /// artworks, covers, store IDs), one row per game with the related
+    /// records collected by `json_group_array`. Rows are ordered
+    /// alphabetically by name and limited to `game_id` when given.
+    ///
+    /// The query is checked against the schema at compile time.
+    async fn fetch_game_rows(&self, game_id: Option<i64>) -> Result<Vec<GameRow>, sqlx::Error> {
+        sqlx::query_as!(
+            GameRow,
+            r#"
+select
+    games.id as "id!",
+    games.name as "name!",
+    games_store.store_id as "store_id?",
+    games.summary as "summary?",
+    games.storyline as "storyline?",
+    games.release_date as "release_date?",
+    games.playtime_forever as "playtime_forever?",
+    games.playtime_2weeks as "playtime_2weeks?",
+    games.last_launched_at as "last_launched_at?",
+    games.last_played_at as "last_played_at?",
+    games.game_type as "game_type?",
+    games.excluded as "excluded!: bool",
+    games.is_favorite as "is_favorite!: bool",
+    games.hidden as "hidden!: bool",
+    games.removed_at as "removed_at?",
+    games.added_at as "added_at?",
+    games.pin_position as "pin_position?",
+    games.user_rating as "user_rating?",
+    games.status as "status?",
+    games.aggregated_rating as "aggregated_rating?: f64",
+    games.rating_count as "rating_count?",
+    games.metacritic_score as "metacritic_score?",
+    games.header_image as "header_image?",
+    games.accent_color as "accent_color?",
+    completion_times.hltb_id as "hltb_id?",
+    completion_times.main_hours as "main_hours?: f64",
+    completion_times.main_extra_hours as "main_extra_hours?: f64",
+    completion_times.completionist_hours as "completionist_hours?: f64",
+    json_group_array(distinct genres.name) as "genres?: String",
+    json_group_array(distinct store_categories.name) as "categories?: String",
+    max(soundtracks.store_id) as "soundtrack_store_id?: String",
+    (select json_group_array(json_object('store_id', dlcs.store_id, 'name', dlcs.name))
+        from dlcs where dlcs.parent_store_id = games_store.store_id) as "dlc?: String",
+    protondb_tiers.tier as "protondb_tier?",
+    (select json_object(
+        'grid_path', steamgriddb_artworks.grid_path,
+        'hero_path', steamgriddb_artworks.hero_path,
+        'logo_path', steamgriddb_artworks.logo_path
+    ) from steamgriddb_artworks
+        where steamgriddb_artworks.store_id = games_store.store_id)
+        as "steamgriddb_artwork?: String",
+    json_group_array(distinct companies.name) as "studios?: String",
+    json_group_array(distinct json_object(
+        'id', artworks.artwork_id,
+        'local_path', artworks.local_path
+    )) as "artworks?: String",
+    json_group_array(distinct json_object(
+        'id', artworks.artwork_id,
+        'local_path', artworks.thumbnail_path
+    )) as "artwork_thumbnails?: String",
+    (select json_group_array(json_object(
+        'id', screenshots.screenshot_id,
+        'local_path', screenshots.local_path
+    )) from screenshots where screenshots.game_id = games.id) as "screenshots?: String",
+    (select json_group_array(json_object(
+        'id', videos.video_id,
+        'name', videos.name
+    )) from videos where videos.game_id = games.id) as "videos?: String",
+    (select json_group_array(publishers.name)
+        from published_by
+        join companies as publishers on publishers.id = published_by.company_id
+        where published_by.game_id = games.id) as "publishers?: String",
+    (select json_group_array(game_modes.name)
+        from game_modes where game_modes.game_id = games.id) as "game_modes?: String",
+    (select json_group_array(player_perspectives.name)
+        from player_perspectives
+        where player_perspectives.game_id = games.id) as "player_perspectives?: String",
+    (select json_group_array(alternative_names.name)
+        from alternative_names
+        where alternative_names.game_id = games.id) as "alternative_names?: String",
+    (select json_group_array(json_object(
+        'organization', age_ratings.organization,
+        'rating', age_ratings.rating
+    )) from age_ratings where age_ratings.game_id = games.id) as "age_ratings?: String",
+    (select max(age_ratings.minimum_age)
+        from age_ratings where age_ratings.game_id = games.id) as "minimum_age?: i64",
+    (select json_group_array(json_object('id', franchises.id, 'name', franchises.name))
+        from game_franchises
+        join franchises on franchises.id = game_franchises.franchise_id
+        where game_franchises.game_id = games.id) as "franchises?: String",
+    json_group_array(distinct json_object(
+        'id', covers.cover_id,
+        'local_path', covers.local_path,
+        'upscaled_path', covers.upscaled_path
+    )) as "covers?: String"
+from games
+left join developed_by on games.id = developed_by.game_id
+left join companies on developed_by.studio_id = companies.id
+left join belongs_to on games.id = belongs_to.game_id
+left join genres on belongs_to.genre_id = genres.id
+left join artworks on artworks.game_id = games.id
+left join covers on covers.game_id = games.id
+left join games_store on games_store.game_id = games.id
+left join store_categories on store_categories.game_id = games.id
+left join completion_times on completion_times.name = games.name
+left join soundtracks on soundtracks.parent_store_id = games_store.store_id
+left join protondb_tiers on protondb_tiers.store_id = games_store.store_id
+where ?1 is null or games.id = ?1
+group by games.id, games.name, games_store.store_id, games.summary, games.storyline,
+    games.release_date,
+    games.playtime_forever, games.playtime_2weeks, games.last_launched_at, games.last_played_at,
+    games.game_type, games.excluded, games.is_favorite, games.hidden, games.removed_at,
+    games.added_at, games.pin_position,
+    games.user_rating, games.status,
+    games.aggregated_rating, games.rating_count, games.metacritic_score, games.header_image,
+    games.accent_color,
+    completion_times.hltb_id, completion_times.main_hours, completion_times.main_extra_hours,
+    completion_times.completionist_hours, protondb_tiers.tier
+order by games.name
+"#,
+            game_id
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
 
-        format!("{}{}{}", Self::BASE_QUERY, where_clause, Self::GROUP_ORDER)
+    /// Removes the image fields of a game.
+    fn strip_images(game: &mut Game) {
+        game.cover = None;
+        game.artworks = None;
+        game.artwork_thumbnails = None;
+        game.screenshots = None;
+        game.header_image = None;
+        game.steamgriddb_artwork = None;
     }
 
-    /// Maps a raw SQLite row returned by [`BASE_QUERY`](Self::BASE_QUERY) into
-    /// a [`Game`].
+    /// Returns up to `count` game summaries following `start` in the given
+    /// sort order, skipping excluded games.
     ///
-    /// The `genres`, `studios`, `artworks`, and `covers` columns are stored as
-    /// JSON arrays and decoded via [`Self::parse_json_array`]. The cover is
-    /// taken as the last element of the covers array. `is_installed` is always
-    /// initialized to `None` and must be set by the caller.
-    fn map_game_row(row: SqliteRow) -> Game {
-        let genres_json: Option<String> = row.get("genres");
-        let studios_json: Option<String> = row.get("studios");
-        let artworks_json: Option<String> = row.get("artworks");
-        let covers_json: Option<String> = row.get("covers");
+    /// Uses keyset pagination on `(sort key, name, id)` so each window costs
+    /// the same regardless of its position in the library.
+    pub async fn get_games_window(
+        &self,
+        start: Option<GameCursor>,
+        count: u32,
+        sort: GameSort,
+        filters: GameFilters,
+    ) -> Result<GamesWindow, sqlx::Error> {
+        let key_column = sort.key_column();
 
-        Game {
-            id: row.get("id"),
-            release_date: row.get("release_date"),
-            name: row.get("name"),
-            developers: Self::parse_json_array(studios_json),
-            genres: Self::parse_json_array(genres_json),
-            is_installed: None,
-            summary: row.get("summary"),
-            artworks: Self::parse_json_image_array(artworks_json),
-            cover: Self::parse_json_image_array(covers_json).and_then(|mut v| v.pop()),
-            store_id: row.get("store_id"),
+        let mut query = QueryBuilder::<Sqlite>::new(format!(
+            "{},\n    {} as sort_key{}",
+            Self::SUMMARY_COLUMNS,
+            key_column.unwrap_or("-1"),
+            Self::SUMMARY_FROM
+        ));
+
+        Self::push_window_filters(&mut query, &filters);
+
+        if let Some(start) = &start {
+            match key_column {
+                // Keys sort descending, so they are negated to compare rows
+                // in a single direction.
+                Some(key_column) => query
+                    .push(format!(
+                        " and (-({}), games.name, games.id) > (",
+                        key_column
+                    ))
+                    .push_bind(-start.key)
+                    .push(", "),
+                None => query.push(" and (games.name, games.id) > ("),
+            };
+
+            query
+                .push_bind(start.name.clone())
+                .push(", ")
+                .push_bind(start.id)
+                .push(")");
         }
-    }
 
-    /// Deserializes a JSON array string produced by `json_group_array` into a
-    /// `Vec<String>`, filtering out any SQL `NULL` entries that appear as
-    /// `null` in the JSON.
-    ///
-    /// Returns `None` if the input is `None` or the JSON cannot be parsed.
-    fn parse_json_array(json: Option<String>) -> Option<Vec<String>> {
-        json.and_then(|s| {
-            serde_json::from_str::<Vec<Option<String>>>(&s)
-                .ok()
-                .map(|v| v.into_iter().flatten().collect())
-        })
-    }
+        match key_column {
+            Some(key_column) => query.push(format!(
+                " order by {} desc, games.name, games.id limit ",
+                key_column
+            )),
+            None => query.push(" order by games.name, games.id limit "),
+        }
+        .push_bind(count);
 
-    /// Deserializes a JSON array of image objects into `Vec<GameImage>`,
-    /// filtering out any entries with NULL `id` fields.
-    ///
-    /// Returns `None` if the input is `None` or the JSON cannot be parsed.
-    fn parse_json_image_array(json: Option<String>) -> Option<Vec<GameImage>> {
-        json.and_then(|s| {
-            serde_json::from_str::<Vec<serde_json::Value>>(&s)
-                .ok()
-                .map(|values| {
-                    values
-                        .into_iter()
-                        .filter_map(|v| {
-                            let id = v.get("id")?.as_str()?;
-                            let local_path = v.get("local_path")
-                                .and_then(|p| p.as_str())
-                                .map(String::from);
+        let rows = query.build().fetch_all(&self.pool).await?;
 
-                            Some(GameImage {
-                                id: id.to_string(),
-                                local_path,
-                            })
-                        })
-                        .collect()
-                })
-        })
+        let next = match rows.last() {
+            Some(row) if rows.len() == count as usize => Some(GameCursor {
+                id: row.get("id"),
+                name: row.get("name"),
+                key: row.get("sort_key"),
+            }),
+            _ => None,
+        };
+
+        let games = rows.iter().map(|row| self.map_summary_row(row)).collect();
+
+        let mut count_query = QueryBuilder::<Sqlite>::new(
+            "select count(*) from games where games.excluded = 0 and games.hidden = 0",
+        );
+        Self::push_window_filters(&mut count_query, &filters);
+
+        let total: i64 = count_query
+            .build_query_scalar()
+            .fetch_one(&self.pool)
+            .await?;
+
+        Ok(GamesWindow { games, next, total })
     }
 
-    /// Returns the Steam store ID for the given game.
-    ///
-    /// # Errors
+    /// Returns the genres of the library with their number of games,
+    /// ordered by name.
     ///
-    /// Returns [`sqlx::Error::RowNotFound`] if the game has no associated
-    /// store entry.
-    pub async fn get_game_store_id(&self, game_id: i64) -> Result<String, sqlx::Error> {
-        let store_id: String =
-            sqlx::query_scalar("select store_id from games_store where game_id = $1")
-                .bind(game_id)
-                .fetch_one(&self.pool)
-                .await?;
+    /// Like the other facets, only counts the owned games shown in the
+    /// library: excluded, hidden, and no longer owned games are left out.
+    pub async fn get_genre_facets(&self) -> Result<Vec<Facet<String>>, sqlx::Error> {
+        self.get_facets(
+            "select genres.name, count(distinct games.id) from genres
+             join belongs_to on belongs_to.genre_id = genres.id
+             join games on games.id = belongs_to.game_id
+             where games.excluded = 0 and games.hidden = 0 and games.removed_at is null
+             group by genres.name
+             order by genres.name",
+        )
+        .await
+    }
 
-        Ok(store_id)
+    /// Returns the developers of the library with their number of games,
+    /// ordered by name.
+    pub async fn get_developer_facets(&self) -> Result<Vec<Facet<String>>, sqlx::Error> {
+        self.get_facets(
+            "select companies.name, count(distinct games.id) from companies
+             join developed_by on developed_by.studio_id = companies.id
+             join games on games.id = developed_by.game_id
+             where games.excluded = 0 and games.hidden = 0 and games.removed_at is null
+             group by companies.name
+             order by companies.name",
+        )
+        .await
     }
 
-    /// Inserts a game and all its related data in a single transaction.
-    ///
-    /// The following records are created:
-    /// - The core game row (`games` table).
-    /// - Its Steam store ID (`games_store`).
-    /// - Its cover image, if present (`covers`).
-    /// - Each artwork image (`artworks`).
-    /// - Each genre, upserted by name to avoid duplicates (`genres`), with a
-    ///   `belongs_to` link.
-    /// - Each developer company, upserted by IGDB ID (`companies`), with a
-    ///   `developed_by` link.
-    ///
-    /// Returns the newly created game's database ID.
-    pub async fn insert_complete_game(&self, game: IgdbGame) -> Result<i64, sqlx::Error> {
-        let mut tx = self.pool.begin().await?;
-        let id = sqlx::query_scalar::<_, i64>(
-            r#"insert into games (name, summary, release_date) values ( ?, ?, ?) returning id"#,
+    /// Returns the release years of the library with their number of games,
+    /// most recent first. Games without a release date are left out.
+    pub async fn get_release_year_facets(&self) -> Result<Vec<Facet<i64>>, sqlx::Error> {
+        self.get_facets(
+            "select cast(strftime('%Y', release_date, 'unixepoch') as integer) as year, count(*)
+             from games
+             where excluded = 0 and hidden = 0 and removed_at is null
+                and release_date is not null
+             group by year
+             order by year desc",
         )
-        .bind(&game.name)
-        .bind(&game.summary)
-        .bind(&game.release_date)
-        .fetch_one(&mut *tx)
-        .await?;
+        .await
+    }
 
-        // Insert store
-        sqlx::query("INSERT INTO games_store (game_id, store_id) VALUES (?, ?)")
-            .bind(id)
-            .bind(&game.store_id)
-            .execute(&mut *tx)
+    /// Runs a facet query selecting a value and a game count.
+    async fn get_facets<T>(&self, query: &str) -> Result<Vec<Facet<T>>, sqlx::Error>
+    where
+        T: for<'r> sqlx::Decode<'r, Sqlite> + sqlx::Type<Sqlite> + Send + Unpin,
+    {
+        let facets = sqlx::query_as::<_, (T, i64)>(query)
+            .fetch_all(&self.pool)
             .await?;
 
-        if let Some(cover_id) = game.cover.as_ref().map(|cover| &cover.image_id) {
-            sqlx::query("INSERT INTO covers (game_id, cover_id) VALUES (?, ?)")
-                .bind(id)
-                .bind(cover_id)
-                .execute(&mut *tx)
-                .await?;
-        }
+        Ok(facets
+            .into_iter()
+            .map(|(value, game_count)| Facet { value, game_count })
+            .collect())
+    }
 
-        // Insert artworks
-        if let Some(artworks) = game.artworks {
-            for artwork in artworks {
-                sqlx::query("INSERT INTO artworks (game_id, artwork_id) VALUES (?, ?)")
-                    .bind(id)
-                    .bind(&artwork.image_id)
-                    .execute(&mut *tx)
-                    .await?;
+    /// Returns the summaries of the games matching `filters` in random
+    /// order, skipping excluded games. [`GameFilters::installed`] is ignored.
+    pub async fn get_shuffled_games(
+        &self,
+        filters: &GameFilters,
+    ) -> Result<Vec<GameSummary>, sqlx::Error> {
+        let mut query =
+            QueryBuilder::<Sqlite>::new(format!("{}{}", Self::SUMMARY_COLUMNS, Self::SUMMARY_FROM));
+
+        Self::push_window_filters(&mut query, filters);
+        query.push(" order by random()");
+
+        let rows = query.build().fetch_all(&self.pool).await?;
+
+        Ok(rows.iter().map(|row| self.map_summary_row(row)).collect())
+    }
+
+    /// Returns up to `limit` owned games, most recently added to the library
+    /// first. Games added before this was tracked are left out.
+    pub async fn get_recently_added(&self, limit: u32) -> Result<Vec<GameSummary>, sqlx::Error> {
+        let query = format!(
+            "{}{}
+    and games.added_at is not null and games.removed_at is null
+order by games.added_at desc, games.name
+limit ?",
+            Self::SUMMARY_COLUMNS,
+            Self::SUMMARY_FROM
+        );
+
+        let rows = sqlx::query(&query)
+            .bind(limit)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(rows.iter().map(|row| self.map_summary_row(row)).collect())
+    }
+
+    /// Returns up to `limit` games that were played, most recent first.
+    ///
+    /// Uses the more recent of Steam's last-played date and the last launch
+    /// from Rocade. Games no longer owned are left out.
+    pub async fn get_recently_played(&self, limit: u32) -> Result<Vec<GameSummary>, sqlx::Error> {
+        let query = format!(
+            "{}{}
+    and (games.last_played_at is not null or games.last_launched_at is not null)
+    and games.removed_at is null
+order by last_played_at desc
+limit ?",
+            Self::SUMMARY_COLUMNS,
+            Self::SUMMARY_FROM
+        );
+
+        let rows = sqlx::query(&query)
+            .bind(limit)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(rows.iter().map(|row| self.map_summary_row(row)).collect())
+    }
+
+    /// Returns the summaries of the games with the given Steam store IDs,
+    /// ordered by name. Unknown store IDs are ignored.
+    pub async fn get_summaries_by_store_ids(
+        &self,
+        store_ids: &[String],
+    ) -> Result<Vec<GameSummary>, sqlx::Error> {
+        if store_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut query = QueryBuilder::<Sqlite>::new(format!(
+            "{}{} and games_store.store_id in (",
+            Self::SUMMARY_COLUMNS,
+            Self::SUMMARY_FROM
+        ));
+
+        let mut separated = query.separated(", ");
+        for store_id in store_ids {
+            separated.push_bind(store_id.clone());
+        }
+
+        query.push(") order by games.name");
+
+        let rows = query.build().fetch_all(&self.pool).await?;
+
+        Ok(rows.iter().map(|row| self.map_summary_row(row)).collect())
+    }
+
+    /// Returns the summaries of the games of a franchise or series, ordered
+    /// by release date.
+    pub async fn get_franchise_games(
+        &self,
+        franchise_id: i64,
+    ) -> Result<Vec<GameSummary>, sqlx::Error> {
+        let rows = sqlx::query(&format!(
+            "{}{} and games.id in (select game_id from game_franchises where franchise_id = ?)
+             order by games.release_date is null, games.release_date, games.name",
+            Self::SUMMARY_COLUMNS,
+            Self::SUMMARY_FROM
+        ))
+        .bind(franchise_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.iter().map(|row| self.map_summary_row(row)).collect())
+    }
+
+    /// Returns the summaries of the games of the library IGDB lists as similar
+    /// to a game, in IGDB order.
+    pub async fn get_owned_similar_games(
+        &self,
+        game_id: i64,
+    ) -> Result<Vec<GameSummary>, sqlx::Error> {
+        let rows = sqlx::query(&format!(
+            "{}{} and games.igdb_id in (select igdb_id from similar_games where game_id = ?)
+             order by (select rowid from similar_games
+                 where game_id = ? and similar_games.igdb_id = games.igdb_id)",
+            Self::SUMMARY_COLUMNS,
+            Self::SUMMARY_FROM
+        ))
+        .bind(game_id)
+        .bind(game_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.iter().map(|row| self.map_summary_row(row)).collect())
+    }
+
+    /// Returns the IGDB IDs of the games similar to a game that are not in
+    /// the library, in IGDB order.
+    pub async fn get_unowned_similar_igdb_ids(
+        &self,
+        game_id: i64,
+    ) -> Result<Vec<u64>, sqlx::Error> {
+        let ids: Vec<i64> = sqlx::query_scalar(
+            "select igdb_id from similar_games
+             where game_id = ?
+                and igdb_id not in (select igdb_id from games where igdb_id is not null)
+             order by rowid",
+        )
+        .bind(game_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(ids.into_iter().map(|id| id as u64).collect())
+    }
+
+    /// Returns the summaries of the Steam games without an IGDB match,
+    /// ordered by name: games filled in from their store page, and games
+    /// only known by name.
+    pub async fn get_unmatched_games(&self) -> Result<Vec<GameSummary>, sqlx::Error> {
+        let rows = sqlx::query(&format!(
+            "{}{} and games.igdb_id is null and games_store.store_id is not null
+             order by games.name",
+            Self::SUMMARY_COLUMNS,
+            Self::SUMMARY_FROM
+        ))
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.iter().map(|row| self.map_summary_row(row)).collect())
+    }
+
+    /// Returns groups of games that look like the same game: they share an
+    /// IGDB match or have the same name, ignoring case, punctuation, and
+    /// spacing. Games are grouped transitively; groups are ordered by the
+    /// name of their first game.
+    pub async fn find_duplicates(&self) -> Result<Vec<Vec<GameSummary>>, sqlx::Error> {
+        let rows = sqlx::query(&format!(
+            "{}, games.igdb_id as igdb_id{} order by games.name",
+            Self::SUMMARY_COLUMNS,
+            Self::SUMMARY_FROM
+        ))
+        .fetch_all(&self.pool)
+        .await?;
+
+        // Union-find over the row indices, joined by shared keys
+        let mut parents: Vec<usize> = (0..rows.len()).collect();
+
+        let mut first_by_key: HashMap<String, usize> = HashMap::new();
+        for (index, row) in rows.iter().enumerate() {
+            let igdb_id: Option<i64> = row.get("igdb_id");
+            let keys = [
+                igdb_id.map(|igdb_id| format!("igdb:{igdb_id}")),
+                Some(format!("name:{}", normalize_name(row.get("name")))),
+            ];
+
+            for key in keys.into_iter().flatten() {
+                let first = *first_by_key.entry(key).or_insert(index);
+                let (a, b) = (root(&mut parents, first), root(&mut parents, index));
+                parents[b] = a;
+            }
+        }
+
+        let mut groups: HashMap<usize, Vec<GameSummary>> = HashMap::new();
+        for (index, row) in rows.iter().enumerate() {
+            groups
+                .entry(root(&mut parents, index))
+                .or_default()
+                .push(self.map_summary_row(row));
+        }
+
+        let mut groups: Vec<Vec<GameSummary>> = groups
+            .into_values()
+            .filter(|group| group.len() > 1)
+            .collect();
+        groups.sort_by(|a, b| a[0].name.cmp(&b[0].name));
+
+        Ok(groups)
+    }
+
+    /// Merges the game `remove_id` into `keep_id`, then deletes it.
+    ///
+    /// The play sessions, achievements, collections, subscriptions, images,
+    /// videos, genres, companies, and other IGDB and store tags of the
+    /// removed game move to the kept one. Its store ID, cover, executable,
+    /// launch settings, Wine configuration, notes, status, and rating move
+    /// only where the kept game has none, and it stays a favorite when
+    /// either game was. Runs in a single transaction.
+    ///
+    /// A kept game given an executable becomes a custom game, and refreshes
+    /// leave its Steam store ID to it. Merging two games owned on Steam is
+    /// refused by the `merge_games` command: the removed game's store ID
+    /// would be dropped, and the next refresh would insert it again.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`sqlx::Error::RowNotFound`] if either game does not exist.
+    pub async fn merge_games(&self, keep_id: i64, remove_id: i64) -> Result<(), sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let found: i64 = sqlx::query_scalar("select count(*) from games where id in (?, ?)")
+            .bind(keep_id)
+            .bind(remove_id)
+            .fetch_one(&mut *tx)
+            .await?;
+        if found != 2 {
+            return Err(sqlx::Error::RowNotFound);
+        }
+
+        // Moved unless the kept game already has one
+        for table in ["games_store", "covers"] {
+            sqlx::query(&format!(
+                "UPDATE {table} SET game_id = ?1 WHERE game_id = ?2
+                 AND NOT EXISTS (SELECT 1 FROM {table} WHERE game_id = ?1)"
+            ))
+            .bind(keep_id)
+            .bind(remove_id)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        // Moved, skipping the rows the kept game already has
+        for table in [
+            "play_sessions",
+            "achievements",
+            "collection_games",
+            "subscription_games",
+            "custom_games",
+            "launch_settings",
+            "wine_configs",
+            "store_categories",
+            "game_franchises",
+            "similar_games",
+            "game_modes",
+            "player_perspectives",
+            "age_ratings",
+            "alternative_names",
+        ] {
+            sqlx::query(&format!(
+                "UPDATE OR IGNORE {table} SET game_id = ? WHERE game_id = ?"
+            ))
+            .bind(keep_id)
+            .bind(remove_id)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        // Moved unless the kept game already has the same value, these
+        // tables having no unique constraint
+        for (table, column) in [
+            ("artworks", "artwork_id"),
+            ("screenshots", "screenshot_id"),
+            ("videos", "video_id"),
+            ("belongs_to", "genre_id"),
+            ("developed_by", "studio_id"),
+            ("published_by", "company_id"),
+        ] {
+            sqlx::query(&format!(
+                "UPDATE {table} SET game_id = ?1 WHERE game_id = ?2
+                 AND {column} NOT IN (SELECT {column} FROM {table} WHERE game_id = ?1)"
+            ))
+            .bind(keep_id)
+            .bind(remove_id)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        sqlx::query(
+            "UPDATE games SET
+                notes = coalesce(games.notes, removed.notes),
+                status = coalesce(games.status, removed.status),
+                user_rating = coalesce(games.user_rating, removed.user_rating),
+                is_favorite = games.is_favorite or removed.is_favorite,
+                last_played_at = nullif(max(
+                    coalesce(games.last_played_at, 0),
+                    coalesce(removed.last_played_at, 0)
+                ), 0),
+                last_launched_at = nullif(max(
+                    coalesce(games.last_launched_at, 0),
+                    coalesce(removed.last_launched_at, 0)
+                ), 0)
+             FROM (SELECT * FROM games WHERE id = ?) AS removed
+             WHERE games.id = ?",
+        )
+        .bind(remove_id)
+        .bind(keep_id)
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query("DELETE FROM games WHERE id = ?")
+            .bind(remove_id)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    /// Maps a row selected with [`SUMMARY_COLUMNS`](Self::SUMMARY_COLUMNS)
+    /// into a [`GameSummary`]. The cover and grid are dropped in
+    /// low-graphics mode.
+    fn map_summary_row(&self, row: &SqliteRow) -> GameSummary {
+        GameSummary {
+            id: row.get("id"),
+            name: row.get("name"),
+            store_id: row.get("store_id"),
+            playtime_forever: row.get("playtime_forever"),
+            playtime_2weeks: row.get("playtime_2weeks"),
+            last_played_at: row.get("last_played_at"),
+            is_favorite: row.get("is_favorite"),
+            cover: row
+                .get::<Option<String>, _>("cover_id")
+                .filter(|_| !self.is_text_only())
+                .map(|id| GameImage {
+                    id,
+                    local_path: row.get("cover_local_path"),
+                    upscaled_path: row.get("cover_upscaled_path"),
+                }),
+            grid_path: row
+                .get::<Option<String>, _>("grid_path")
+                .filter(|_| !self.is_text_only()),
+        }
+    }
+
+    /// Appends the `AND` conditions of `filters` to a games query.
+    fn push_window_filters(query: &mut QueryBuilder<'_, Sqlite>, filters: &GameFilters) {
+        query.push(if filters.no_longer_owned {
+            " and games.removed_at is not null"
+        } else {
+            " and games.removed_at is null"
+        });
+
+        if let Some(name) = &filters.name {
+            query
+                .push(" and (games.name like '%' || ")
+                .push_bind(name.clone())
+                .push(
+                    " || '%' or exists (select 1 from alternative_names
+    where alternative_names.game_id = games.id and alternative_names.name like '%' || ",
+                )
+                .push_bind(name.clone())
+                .push(" || '%'))");
+        }
+
+        if let Some(genre) = &filters.genre {
+            query
+                .push(
+                    " and exists (select 1 from belongs_to
+    join genres on belongs_to.genre_id = genres.id
+    where belongs_to.game_id = games.id and genres.name = ",
+                )
+                .push_bind(genre.clone())
+                .push(")");
+        }
+
+        if filters.unplayed {
+            query.push(
+                " and coalesce(games.playtime_forever, 0) = 0
+    and games.last_played_at is null and games.last_launched_at is null",
+            );
+        }
+
+        if let Some(max_hours) = filters.max_hours {
+            query
+                .push(
+                    " and exists (select 1 from completion_times
+    where completion_times.name = games.name and completion_times.main_hours <= ",
+                )
+                .push_bind(max_hours)
+                .push(")");
+        }
+    }
+
+    /// Maps a [`GameRow`] returned by [`Self::fetch_game_rows`] into a
+    /// [`Game`].
+    ///
+    /// The `genres`, `studios`, `publishers`, `game_modes`,
+    /// `player_perspectives`, `alternative_names`, `franchises`, `artworks`, `screenshots`,
+    /// `videos`, and `covers` columns are stored as
+    /// JSON arrays and decoded via [`Self::parse_json_array`]. The cover is
+    /// taken as the last element of the covers array. `is_installed` is always
+    /// initialized to `None` and must be set by the caller.
+    fn map_game_row(row: GameRow) -> Game {
+        Game {
+            id: row.id,
+            release_date: row.release_date,
+            playtime_forever: row.playtime_forever,
+            playtime_2weeks: row.playtime_2weeks,
+            last_launched_at: row.last_launched_at,
+            last_played_at: row.last_played_at,
+            name: row.name,
+            developers: Self::parse_json_array(row.studios),
+            publishers: Self::parse_json_array(row.publishers),
+            game_modes: Self::parse_json_array(row.game_modes),
+            player_perspectives: Self::parse_json_array(row.player_perspectives),
+            alternative_names: Self::parse_json_array(row.alternative_names),
+            age_ratings: row
+                .age_ratings
+                .and_then(|json| serde_json::from_str(&json).ok()),
+            minimum_age: row.minimum_age,
+            franchises: row
+                .franchises
+                .and_then(|json| serde_json::from_str(&json).ok()),
+            genres: Self::parse_json_array(row.genres),
+            is_installed: None,
+            installed_via: None,
+            summary: row.summary,
+            storyline: row.storyline,
+            artworks: Self::parse_json_image_array(row.artworks),
+            artwork_thumbnails: Self::parse_json_image_array(row.artwork_thumbnails),
+            screenshots: Self::parse_json_image_array(row.screenshots),
+            videos: row.videos.and_then(|json| serde_json::from_str(&json).ok()),
+            cover: Self::parse_json_image_array(row.covers).and_then(|mut v| v.pop()),
+            store_id: row.store_id,
+            game_type: row.game_type.and_then(game_type_name).map(String::from),
+            excluded: row.excluded,
+            is_favorite: row.is_favorite,
+            hidden: row.hidden,
+            removed_at: row.removed_at,
+            added_at: row.added_at,
+            pin_position: row.pin_position,
+            user_rating: row.user_rating,
+            status: row.status.as_deref().and_then(GameStatus::from_key),
+            aggregated_rating: row.aggregated_rating,
+            rating_count: row.rating_count,
+            metacritic_score: row.metacritic_score,
+            categories: Self::parse_json_array(row.categories),
+            header_image: row.header_image,
+            accent_color: row.accent_color,
+            completion_times: row.hltb_id.map(|_| CompletionTimes {
+                main: row.main_hours,
+                main_extra: row.main_extra_hours,
+                completionist: row.completionist_hours,
+            }),
+            soundtrack_store_id: row.soundtrack_store_id,
+            dlc: row.dlc.and_then(|json| serde_json::from_str(&json).ok()),
+            protondb_tier: row.protondb_tier,
+            steamgriddb_artwork: row
+                .steamgriddb_artwork
+                .and_then(|json| serde_json::from_str(&json).ok()),
+        }
+    }
+
+    /// Deserializes a JSON array string produced by `json_group_array` into a
+    /// `Vec<String>`, filtering out any SQL `NULL` entries that appear as
+    /// `null` in the JSON.
+    ///
+    /// Returns `None` if the input is `None` or the JSON cannot be parsed.
+    fn parse_json_array(json: Option<String>) -> Option<Vec<String>> {
+        json.and_then(|s| {
+            serde_json::from_str::<Vec<Option<String>>>(&s)
+                .ok()
+                .map(|v| v.into_iter().flatten().collect())
+        })
+    }
+
+    /// Deserializes a JSON array of image objects into `Vec<GameImage>`,
+    /// filtering out any entries with NULL `id` fields.
+    ///
+    /// Returns `None` if the input is `None` or the JSON cannot be parsed.
+    fn parse_json_image_array(json: Option<String>) -> Option<Vec<GameImage>> {
+        json.and_then(|s| {
+            serde_json::from_str::<Vec<serde_json::Value>>(&s)
+                .ok()
+                .map(|values| {
+                    values
+                        .into_iter()
+                        .filter_map(|v| {
+                            let id = v.get("id")?.as_str()?;
+                            let local_path = v.get("local_path")
+                                .and_then(|p| p.as_str())
+                                .map(String::from);
+                            let upscaled_path = v
+                                .get("upscaled_path")
+                                .and_then(|p| p.as_str())
+                                .map(String::from);
+
+                            Some(GameImage {
+                                id: id.to_string(),
+                                local_path,
+                                upscaled_path,
+                            })
+                        })
+                        .collect()
+                })
+        })
+    }
+
+    /// Marks the Steam games missing from `owned_store_ids` as no longer
+    /// owned, from now on, and clears the mark of those owned again.
+    ///
+    /// Games marked are kept by [`DatabaseState::clean`]; games owned again
+    /// are replaced by the refresh like any other.
+    ///
+    /// [`DatabaseState::clean`]: crate::db::DatabaseState::clean
+    pub async fn mark_removed_games(
+        &self,
+        tx: &mut Transaction<'_, Sqlite>,
+        owned_store_ids: &HashSet<String>,
+    ) -> Result<(), sqlx::Error> {
+        let games: Vec<(i64, String, Option<i64>)> = sqlx::query_as(
+            "select games.id, games_store.store_id, games.removed_at from games
+             join games_store on games_store.game_id = games.id
+             where games.id not in (select game_id from custom_games)",
+        )
+        .fetch_all(&mut **tx)
+        .await?;
+
+        for (game_id, store_id, removed_at) in games {
+            match (owned_store_ids.contains(&store_id), removed_at) {
+                (false, None) => {
+                    sqlx::query("UPDATE games SET removed_at = strftime('%s', 'now') WHERE id = ?")
+                        .bind(game_id)
+                        .execute(&mut **tx)
+                        .await?;
+                }
+                (true, Some(_)) => {
+                    sqlx::query("UPDATE games SET removed_at = NULL WHERE id = ?")
+                        .bind(game_id)
+                        .execute(&mut **tx)
+                        .await?;
+                }
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the paths of the cached images of the games no longer owned.
+    pub async fn get_removed_image_paths(&self) -> Result<Vec<String>, sqlx::Error> {
+        sqlx::query_scalar(
+            "select path from (
+                select local_path as path, game_id from covers
+                union all select local_path, game_id from artworks
+                union all select thumbnail_path, game_id from artworks
+                union all select local_path, game_id from screenshots
+             )
+             join games on games.id = game_id
+             where games.removed_at is not null and path is not null",
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// Returns the Steam store IDs of all games in the database.
+    pub async fn get_store_ids(&self) -> Result<HashSet<String>, sqlx::Error> {
+        let store_ids: Vec<String> = sqlx::query_scalar("select store_id from games_store")
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(store_ids.into_iter().collect())
+    }
+
+    /// Returns the Steam store IDs held by custom games, e.g. after a Steam
+    /// game was merged with one. Refreshes leave these IDs to them.
+    pub async fn get_custom_store_ids(&self) -> Result<HashSet<String>, sqlx::Error> {
+        let store_ids: Vec<String> = sqlx::query_scalar(
+            "select store_id from games_store
+             join custom_games on custom_games.game_id = games_store.game_id",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(store_ids.into_iter().collect())
+    }
+
+    /// Returns `true` if a game is in the library because it is owned on
+    /// Steam: it has a Steam store ID, is not a custom game, and was not
+    /// removed. Such games are inserted again by every refresh.
+    pub async fn is_owned_on_steam(&self, game_id: i64) -> Result<bool, sqlx::Error> {
+        sqlx::query_scalar(
+            "select exists (
+                select 1 from games
+                join games_store on games_store.game_id = games.id
+                where games.id = ?1 and games.removed_at is null
+                    and games.id not in (select game_id from custom_games)
+            )",
+        )
+        .bind(game_id)
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    /// Returns `true` if a game, soundtrack, or DLC with the given Steam store
+    /// ID is in the database.
+    pub async fn is_known_store_id(&self, store_id: &str) -> Result<bool, sqlx::Error> {
+        sqlx::query_scalar(
+            "select exists (select 1 from games_store where store_id = ?)
+                or exists (select 1 from soundtracks where store_id = ?)
+                or exists (select 1 from dlcs where store_id = ?)",
+        )
+        .bind(store_id)
+        .bind(store_id)
+        .bind(store_id)
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    /// Returns the Steam store IDs of the games with `flag` set.
+    pub async fn get_flagged_store_ids(
+        &self,
+        flag: UserFlag,
+    ) -> Result<HashSet<String>, sqlx::Error> {
+        let store_ids: Vec<String> = sqlx::query_scalar(&format!(
+            "select store_id from games_store
+             join games on games.id = games_store.game_id
+             where games.{} = 1",
+            flag.column()
+        ))
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(store_ids.into_iter().collect())
+    }
+
+    /// Sets `flag` on the games with the given Steam store IDs. Used to
+    /// restore user flags after a refresh re-inserts the library.
+    pub async fn set_flagged_store_ids(
+        &self,
+        tx: &mut Transaction<'_, Sqlite>,
+        flag: UserFlag,
+        store_ids: &HashSet<String>,
+    ) -> Result<(), sqlx::Error> {
+        if store_ids.is_empty() {
+            return Ok(());
+        }
+
+        let mut query: QueryBuilder<Sqlite> = QueryBuilder::new(format!(
+            "update games set {} = 1
+             where id in (select game_id from games_store where store_id in (",
+            flag.column()
+        ));
+        let mut separated = query.separated(", ");
+        for store_id in store_ids {
+            separated.push_bind(store_id);
+        }
+        separated.push_unseparated("))");
+
+        query.build().execute(&mut **tx).await?;
+
+        Ok(())
+    }
+
+    /// Flips the favorite flag of a game and returns its new value.
+    pub async fn toggle_favorite(&self, game_id: i64) -> Result<bool, sqlx::Error> {
+        let is_favorite = sqlx::query_scalar(
+            "UPDATE games SET is_favorite = not is_favorite WHERE id = ? RETURNING is_favorite",
+        )
+        .bind(game_id)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(is_favorite)
+    }
+
+    /// Marks a game as favorite, or unmarks it.
+    pub async fn set_favorite(&self, game_id: i64, is_favorite: bool) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE games SET is_favorite = ? WHERE id = ?")
+            .bind(is_favorite)
+            .bind(game_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Returns the Steam store IDs of the pinned games, in pin order.
+    pub async fn get_pinned_store_ids(&self) -> Result<Vec<String>, sqlx::Error> {
+        let store_ids = sqlx::query_scalar(
+            "select store_id from games_store
+             join games on games.id = games_store.game_id
+             where games.pin_position is not null
+             order by games.pin_position",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(store_ids)
+    }
+
+    /// Pins the games with the given Steam store IDs in the given order.
+    /// Used to restore pins after a refresh re-inserts the library.
+    pub async fn set_pinned_store_ids(
+        &self,
+        tx: &mut Transaction<'_, Sqlite>,
+        store_ids: &[String],
+    ) -> Result<(), sqlx::Error> {
+        for (position, store_id) in store_ids.iter().enumerate() {
+            sqlx::query(
+                "UPDATE games SET pin_position = ?
+                 WHERE id IN (SELECT game_id FROM games_store WHERE store_id = ?)",
+            )
+            .bind(position as i64)
+            .bind(store_id)
+            .execute(&mut **tx)
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Pins a game after the already pinned ones. Does nothing if it is
+    /// already pinned.
+    pub async fn pin_game(&self, game_id: i64) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "UPDATE games
+             SET pin_position = (SELECT coalesce(max(pin_position), -1) + 1 FROM games)
+             WHERE id = ? AND pin_position IS NULL",
+        )
+        .bind(game_id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Pins exactly the given games, in the given order, and unpins all
+    /// others, in a single transaction.
+    pub async fn reorder_pins(&self, game_ids: &[i64]) -> Result<(), sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("UPDATE games SET pin_position = NULL")
+            .execute(&mut *tx)
+            .await?;
+
+        for (position, game_id) in game_ids.iter().enumerate() {
+            sqlx::query("UPDATE games SET pin_position = ? WHERE id = ?")
+                .bind(position as i64)
+                .bind(game_id)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    /// Returns the personal notes of a game, or `None` if it has none.
+    pub async fn get_notes(&self, game_id: i64) -> Result<Option<String>, sqlx::Error> {
+        let notes = sqlx::query_scalar("select notes from games where id = ?")
+            .bind(game_id)
+            .fetch_one(&self.pool)
+            .await?;
+
+        Ok(notes)
+    }
+
+    /// Returns the personal notes of all games that have any, keyed by
+    /// database ID.
+    pub async fn get_all_notes(&self) -> Result<HashMap<i64, String>, sqlx::Error> {
+        let notes = sqlx::query_as("select id, notes from games where notes is not null")
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(notes.into_iter().collect())
+    }
+
+    /// Stores the personal notes of a game. `None` clears them.
+    pub async fn set_notes(&self, game_id: i64, notes: Option<&str>) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE games SET notes = ? WHERE id = ?")
+            .bind(notes)
+            .bind(game_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Returns the notes, ratings, statuses, and last launch dates of all
+    /// Steam games that have any, keyed by Steam store ID.
+    pub async fn get_user_data_by_store_id(
+        &self,
+    ) -> Result<HashMap<String, UserData>, sqlx::Error> {
+        let user_data = sqlx::query(
+            "select store_id, notes, user_rating, status, last_launched_at from games_store
+             join games on games.id = games_store.game_id
+             where games.notes is not null or games.user_rating is not null
+                or games.status is not null or games.last_launched_at is not null",
+        )
+        .map(|row: SqliteRow| {
+            (
+                row.get("store_id"),
+                UserData {
+                    notes: row.get("notes"),
+                    user_rating: row.get("user_rating"),
+                    status: row.get("status"),
+                    last_launched_at: row.get("last_launched_at"),
+                },
+            )
+        })
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(user_data.into_iter().collect())
+    }
+
+    /// Stores user data by Steam store ID, in the refresh's transaction.
+    /// Used to restore them after a refresh re-inserts the library.
+    pub async fn set_user_data_by_store_id(
+        &self,
+        tx: &mut Transaction<'_, Sqlite>,
+        user_data: HashMap<String, UserData>,
+    ) -> Result<(), sqlx::Error> {
+        for (store_id, data) in user_data {
+            sqlx::query(
+                "UPDATE games SET notes = ?, user_rating = ?, status = ?, last_launched_at = ?
+                 WHERE id IN (SELECT game_id FROM games_store WHERE store_id = ?)",
+            )
+            .bind(data.notes)
+            .bind(data.user_rating)
+            .bind(data.status)
+            .bind(data.last_launched_at)
+            .bind(store_id)
+            .execute(&mut **tx)
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns when each game was added to the library, by Steam store ID.
+    /// Used with [`Self::set_added_at_by_store_id`] to keep the dates across
+    /// a refresh.
+    pub async fn get_added_at_by_store_id(
+        &self,
+    ) -> Result<HashMap<String, Option<i64>>, sqlx::Error> {
+        let added_at = sqlx::query_as::<_, (String, Option<i64>)>(
+            "select store_id, added_at from games_store
+             join games on games.id = games_store.game_id",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(added_at.into_iter().collect())
+    }
+
+    /// Stores when each game was added to the library, by Steam store ID, in
+    /// the refresh's transaction. Games inserted by a refresh are dated by
+    /// the database, so only those already known before it are restored.
+    pub async fn set_added_at_by_store_id(
+        &self,
+        tx: &mut Transaction<'_, Sqlite>,
+        added_at: HashMap<String, Option<i64>>,
+    ) -> Result<(), sqlx::Error> {
+        for (store_id, added_at) in added_at {
+            sqlx::query(
+                "UPDATE games SET added_at = ?
+                 WHERE id IN (SELECT game_id FROM games_store WHERE store_id = ?)",
+            )
+            .bind(added_at)
+            .bind(store_id)
+            .execute(&mut **tx)
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Stores an image copied from the user's files as the cover of a game,
+    /// replacing its current cover.
+    ///
+    /// Returns the local path of the custom cover it replaces, if any, for
+    /// the file to be removed.
+    pub async fn set_custom_cover(
+        &self,
+        game_id: i64,
+        image_id: &str,
+        local_path: &str,
+    ) -> Result<Option<String>, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let replaced: Option<String> = sqlx::query_scalar(
+            "select local_path from covers where game_id = ? and source = 'custom'",
+        )
+        .bind(game_id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .flatten();
+
+        sqlx::query("delete from covers where game_id = ?")
+            .bind(game_id)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query(
+            "insert into covers (game_id, cover_id, local_path, source)
+             values (?, ?, ?, 'custom')",
+        )
+        .bind(game_id)
+        .bind(image_id)
+        .bind(local_path)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(replaced)
+    }
+
+    /// Adds an image copied from the user's files to the artworks of a game.
+    pub async fn add_custom_artwork(
+        &self,
+        game_id: i64,
+        image_id: &str,
+        local_path: &str,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "insert into artworks (game_id, artwork_id, local_path, source)
+             values (?, ?, ?, 'custom')",
+        )
+        .bind(game_id)
+        .bind(image_id)
+        .bind(local_path)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Returns the covers and artworks the user added to Steam games.
+    pub async fn get_custom_images(&self) -> Result<Vec<CustomImage>, sqlx::Error> {
+        let mut images = Vec::new();
+
+        for kind in [CustomImageKind::Cover, CustomImageKind::Artwork] {
+            let rows = sqlx::query(&format!(
+                "select store_id, {id} as image_id, local_path from {table}
+                 join games_store on games_store.game_id = {table}.game_id
+                 where source = 'custom'",
+                id = kind.id_column(),
+                table = kind.table(),
+            ))
+            .fetch_all(&self.pool)
+            .await?;
+
+            images.extend(rows.iter().map(|row| CustomImage {
+                store_id: row.get("store_id"),
+                kind,
+                image_id: row.get("image_id"),
+                local_path: row.get("local_path"),
+            }));
+        }
+
+        Ok(images)
+    }
+
+    /// Stores custom images by Steam store ID, in the refresh's transaction.
+    /// Used to restore them after a refresh re-inserts the library; custom
+    /// covers replace the IGDB ones.
+    pub async fn set_custom_images(
+        &self,
+        tx: &mut Transaction<'_, Sqlite>,
+        images: Vec<CustomImage>,
+    ) -> Result<(), sqlx::Error> {
+        for image in images {
+            if let CustomImageKind::Cover = image.kind {
+                sqlx::query(
+                    "delete from covers
+                     where game_id in (select game_id from games_store where store_id = ?)",
+                )
+                .bind(&image.store_id)
+                .execute(&mut **tx)
+                .await?;
+            }
+
+            sqlx::query(&format!(
+                "insert into {} (game_id, {}, local_path, source)
+                 select game_id, ?, ?, 'custom' from games_store where store_id = ?",
+                image.kind.table(),
+                image.kind.id_column(),
+            ))
+            .bind(&image.image_id)
+            .bind(&image.local_path)
+            .bind(&image.store_id)
+            .execute(&mut **tx)
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Applies the user's metadata overrides to IGDB games about to be
+    /// inserted or re-matched.
+    ///
+    /// [`Self::insert_games_bulk`] applies them on its own; this is meant
+    /// for callers downloading images first, so overridden covers are
+    /// downloaded instead of the IGDB ones.
+    pub async fn apply_metadata_overrides(
+        &self,
+        games: &mut [IgdbGame],
+    ) -> Result<(), sqlx::Error> {
+        let overrides: HashMap<String, MetadataOverride> = sqlx::query(
+            "select store_id, name, summary, release_date, cover_id from metadata_overrides",
+        )
+        .map(|row: SqliteRow| (row.get("store_id"), Self::metadata_override_from_row(&row)))
+        .fetch_all(&self.pool)
+        .await?
+        .into_iter()
+        .collect();
+
+        for game in games {
+            if let Some(metadata) = game.store_id.as_ref().and_then(|id| overrides.get(id)) {
+                metadata.apply_to(game);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Stores the user's metadata corrections of a game and applies them.
+    ///
+    /// For Steam games, `metadata` replaces the stored overrides: fields left
+    /// to `None` are no longer overridden and get their source value back on
+    /// the next refresh. Custom games are never refreshed, so they are only
+    /// updated. Returns the IGDB image ID of the new cover if it changed, for
+    /// it to be downloaded.
+    pub async fn set_metadata_override(
+        &self,
+        game_id: i64,
+        metadata: &MetadataOverride,
+    ) -> Result<Option<String>, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let store_id: Option<String> =
+            sqlx::query_scalar("select store_id from games_store where game_id = ?")
+                .bind(game_id)
+                .fetch_optional(&mut *tx)
+                .await?;
+
+        if let Some(store_id) = &store_id {
+            if metadata.is_empty() {
+                sqlx::query("delete from metadata_overrides where store_id = ?")
+                    .bind(store_id)
+                    .execute(&mut *tx)
+                    .await?;
+            } else {
+                sqlx::query(
+                    "insert or replace into metadata_overrides
+                     (store_id, name, summary, release_date, cover_id) values (?, ?, ?, ?, ?)",
+                )
+                .bind(store_id)
+                .bind(&metadata.name)
+                .bind(&metadata.summary)
+                .bind(metadata.release_date)
+                .bind(&metadata.cover)
+                .execute(&mut *tx)
+                .await?;
+            }
+        }
+
+        let new_cover = Self::write_metadata_override(&mut tx, game_id, metadata).await?;
+
+        tx.commit().await?;
+
+        Ok(new_cover)
+    }
+
+    /// Builds a [`MetadataOverride`] from a `metadata_overrides` row.
+    fn metadata_override_from_row(row: &SqliteRow) -> MetadataOverride {
+        MetadataOverride {
+            name: row.get("name"),
+            summary: row.get("summary"),
+            release_date: row.get("release_date"),
+            cover: row.get("cover_id"),
+        }
+    }
+
+    /// Writes the stored metadata overrides of a Steam game over its
+    /// freshly inserted row.
+    async fn apply_stored_override(
+        tx: &mut Transaction<'_, Sqlite>,
+        game_id: i64,
+        store_id: &str,
+    ) -> Result<(), sqlx::Error> {
+        let metadata = sqlx::query(
+            "select name, summary, release_date, cover_id from metadata_overrides
+             where store_id = ?",
+        )
+        .bind(store_id)
+        .map(|row: SqliteRow| Self::metadata_override_from_row(&row))
+        .fetch_optional(&mut **tx)
+        .await?;
+
+        if let Some(metadata) = metadata {
+            Self::write_metadata_override(tx, game_id, &metadata).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Writes the overridden fields of `metadata` to a game's row, replacing
+    /// its cover when overridden. Returns the new cover's IGDB image ID if
+    /// the cover changed.
+    async fn write_metadata_override(
+        tx: &mut Transaction<'_, Sqlite>,
+        game_id: i64,
+        metadata: &MetadataOverride,
+    ) -> Result<Option<String>, sqlx::Error> {
+        sqlx::query(
+            "update games
+             set name = coalesce(?, name), summary = coalesce(?, summary),
+                 release_date = coalesce(?, release_date)
+             where id = ?",
+        )
+        .bind(&metadata.name)
+        .bind(&metadata.summary)
+        .bind(metadata.release_date)
+        .bind(game_id)
+        .execute(&mut **tx)
+        .await?;
+
+        let Some(cover_id) = &metadata.cover else {
+            return Ok(None);
+        };
+
+        let replaced = sqlx::query("delete from covers where game_id = ? and cover_id is not ?")
+            .bind(game_id)
+            .bind(cover_id)
+            .execute(&mut **tx)
+            .await?
+            .rows_affected();
+
+        let inserted = sqlx::query(
+            "insert into covers (game_id, cover_id)
+             select ?, ? where not exists (select 1 from covers where game_id = ?)",
+        )
+        .bind(game_id)
+        .bind(cover_id)
+        .bind(game_id)
+        .execute(&mut **tx)
+        .await?
+        .rows_affected();
+
+        Ok((replaced > 0 || inserted > 0).then(|| cover_id.clone()))
+    }
+
+    /// Stores the user's rating of a game, from 0 to 10. `None` clears it.
+    pub async fn set_user_rating(
+        &self,
+        game_id: i64,
+        rating: Option<u8>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE games SET user_rating = ? WHERE id = ?")
+            .bind(rating)
+            .bind(game_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Sets the backlog status of a game. `None` clears it.
+    pub async fn set_status(
+        &self,
+        game_id: i64,
+        status: Option<GameStatus>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE games SET status = ? WHERE id = ?")
+            .bind(status.map(|status| status.key()))
+            .bind(game_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Hides a game from the library, or shows it again.
+    pub async fn set_hidden(&self, game_id: i64, hidden: bool) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE games SET hidden = ? WHERE id = ?")
+            .bind(hidden)
+            .bind(game_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Stores the SteamGridDB artwork chosen for a Steam game, replacing the
+    /// previous one.
+    pub async fn set_steamgriddb_artwork(
+        &self,
+        store_id: &str,
+        artwork: &SteamGridDbArtwork,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "insert or replace into steamgriddb_artworks (store_id, grid_path, hero_path, logo_path)
+             values (?, ?, ?, ?)",
+        )
+        .bind(store_id)
+        .bind(&artwork.grid_path)
+        .bind(&artwork.hero_path)
+        .bind(&artwork.logo_path)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Goes back to the IGDB images for a Steam game.
+    pub async fn delete_steamgriddb_artwork(&self, store_id: &str) -> Result<(), sqlx::Error> {
+        sqlx::query("delete from steamgriddb_artworks where store_id = ?")
+            .bind(store_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Returns the Steam store ID for the given game.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`sqlx::Error::RowNotFound`] if the game has no associated
+    /// store entry.
+    pub async fn get_game_store_id(&self, game_id: i64) -> Result<String, sqlx::Error> {
+        let store_id: String =
+            sqlx::query_scalar("select store_id from games_store where game_id = $1")
+                .bind(game_id)
+                .fetch_one(&self.pool)
+                .await?;
+
+        Ok(store_id)
+    }
+
+    /// Inserts a batch of games and all their related data in a single
+    /// transaction.
+    ///
+    /// The following records are created for each game, the first ones with
+    /// one multi-row statement per [`BULK_INSERT_ROWS`] rows:
+    /// - The core game row (`games` table).
+    /// - Its Steam store ID (`games_store`).
+    /// - Its cover image, if present (`covers`).
+    /// - Each artwork image (`artworks`).
+    /// - Each screenshot (`screenshots`).
+    /// - Each genre, upserted by name to avoid duplicates (`genres`), with a
+    ///   `belongs_to` link.
+    /// - Each developer company, upserted by IGDB ID (`companies`), with a
+    ///   `developed_by` link.
+    /// - Each publisher company, likewise, with a `published_by` link.
+    /// - Each franchise and series, upserted by IGDB ID (`franchises`), with a
+    ///   `game_franchises` link.
+    /// - The IGDB ID of each similar game (`similar_games`).
+    /// - Each game mode, player perspective, and alternative name
+    ///   (`game_modes`, `player_perspectives`, `alternative_names`).
+    /// - Each PEGI and ESRB age rating (`age_ratings`).
+    /// - Each video (`videos`).
+    ///
+    /// Images are stored with their local path from `cover_paths`,
+    /// `artwork_paths`, or `screenshot_paths`, keyed by IGDB image ID, when
+    /// downloaded. The user's metadata overrides for each Steam store ID are
+    /// then written over the IGDB data. Returns the database IDs of the new
+    /// games, in the order of `games`.
+    pub async fn insert_games_bulk(
+        &self,
+        tx: &mut Transaction<'_, Sqlite>,
+        games: Vec<IgdbGame>,
+        cover_paths: &HashMap<String, String>,
+        artwork_paths: &HashMap<String, String>,
+        screenshot_paths: &HashMap<String, String>,
+    ) -> Result<Vec<i64>, sqlx::Error> {
+        let mut game_ids = Vec::with_capacity(games.len());
+
+        for chunk in games.chunks(BULK_INSERT_ROWS) {
+            let mut query = QueryBuilder::<Sqlite>::new(
+                "insert into games (name, summary, storyline, release_date, game_type, igdb_id, aggregated_rating, rating_count) ",
+            );
+            query.push_values(chunk, |mut row, game| {
+                row.push_bind(&game.name)
+                    .push_bind(&game.summary)
+                    .push_bind(&game.storyline)
+                    .push_bind(game.release_date)
+                    .push_bind(game.game_type)
+                    .push_bind(game.id as i64)
+                    .push_bind(game.aggregated_rating)
+                    .push_bind(game.rating_count);
+            });
+            query.push(" returning id, igdb_id");
+
+            // Returned rows come in no particular order. Games sharing an
+            // IGDB ID have identical rows, so any of their IDs fits.
+            let mut ids: HashMap<i64, Vec<i64>> = HashMap::new();
+
+            for (id, igdb_id) in query
+                .build_query_as::<(i64, i64)>()
+                .fetch_all(&mut **tx)
+                .await?
+            {
+                ids.entry(igdb_id).or_default().push(id);
+            }
+
+            for game in chunk {
+                let id = ids
+                    .get_mut(&(game.id as i64))
+                    .and_then(Vec::pop)
+                    .ok_or(sqlx::Error::RowNotFound)?;
+                game_ids.push(id);
             }
         }
 
-        // Insert genres
+        let inserted: Vec<(i64, &IgdbGame)> = game_ids.iter().copied().zip(&games).collect();
+
+        let stores: Vec<(i64, &String)> = inserted
+            .iter()
+            .filter_map(|(id, game)| game.store_id.as_ref().map(|store_id| (*id, store_id)))
+            .collect();
+        Self::insert_rows(
+            tx,
+            "insert into games_store (game_id, store_id) ",
+            &stores,
+            |mut row, (id, store_id)| {
+                row.push_bind(*id).push_bind(*store_id);
+            },
+        )
+        .await?;
+
+        let covers: Vec<(i64, &String, Option<&String>)> = inserted
+            .iter()
+            .filter_map(|(id, game)| game.cover.as_ref().map(|cover| (*id, &cover.image_id)))
+            .map(|(id, image_id)| (id, image_id, cover_paths.get(image_id)))
+            .collect();
+        Self::insert_rows(
+            tx,
+            "insert into covers (game_id, cover_id, local_path) ",
+            &covers,
+            |mut row, (id, image_id, local_path)| {
+                row.push_bind(*id)
+                    .push_bind(*image_id)
+                    .push_bind(*local_path);
+            },
+        )
+        .await?;
+
+        for (table, id_column, images, paths) in [
+            (
+                "artworks",
+                "artwork_id",
+                Self::image_rows(&inserted, |game| &game.artworks),
+                artwork_paths,
+            ),
+            (
+                "screenshots",
+                "screenshot_id",
+                Self::image_rows(&inserted, |game| &game.screenshots),
+                screenshot_paths,
+            ),
+        ] {
+            Self::insert_rows(
+                tx,
+                &format!(
+                    "insert into {} (game_id, {}, local_path) ",
+                    table, id_column
+                ),
+                &images,
+                |mut row, (id, image_id)| {
+                    row.push_bind(*id)
+                        .push_bind(*image_id)
+                        .push_bind(paths.get(*image_id));
+                },
+            )
+            .await?;
+        }
+
+        let genre_names: Vec<&String> = inserted
+            .iter()
+            .flat_map(|(_, game)| game.genres.iter().flatten())
+            .map(|genre| &genre.name)
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect();
+        let mut genre_ids: HashMap<String, i64> = HashMap::new();
+
+        for chunk in genre_names.chunks(BULK_INSERT_ROWS) {
+            let mut query = QueryBuilder::<Sqlite>::new("insert into genres (name) ");
+            query.push_values(chunk, |mut row, name| {
+                row.push_bind(*name);
+            });
+            query.push(" on conflict(name) do update set name = name returning id, name");
+
+            for (id, name) in query
+                .build_query_as::<(i64, String)>()
+                .fetch_all(&mut **tx)
+                .await?
+            {
+                genre_ids.insert(name, id);
+            }
+        }
+
+        let genre_links: Vec<(i64, i64)> = inserted
+            .iter()
+            .flat_map(|(id, game)| {
+                game.genres
+                    .iter()
+                    .flatten()
+                    .filter_map(|genre| genre_ids.get(&genre.name))
+                    .map(move |genre_id| (*id, *genre_id))
+            })
+            .collect();
+        Self::insert_rows(
+            tx,
+            "insert into belongs_to (game_id, genre_id) ",
+            &genre_links,
+            |mut row, (id, genre_id)| {
+                row.push_bind(*id).push_bind(*genre_id);
+            },
+        )
+        .await?;
+
+        for (id, game) in &inserted {
+            Self::insert_relations(tx, *id, game).await?;
+
+            if let Some(store_id) = &game.store_id {
+                Self::apply_stored_override(tx, *id, store_id).await?;
+            }
+        }
+
+        Ok(game_ids)
+    }
+
+    /// Returns (game ID, IGDB image ID) rows of the images `images` picks
+    /// from each game.
+    fn image_rows<'a>(
+        games: &[(i64, &'a IgdbGame)],
+        images: impl Fn(&'a IgdbGame) -> &'a Option<Vec<IgdbImage>>,
+    ) -> Vec<(i64, &'a String)> {
+        games
+            .iter()
+            .flat_map(|(id, game)| {
+                images(game)
+                    .iter()
+                    .flatten()
+                    .map(move |image| (*id, &image.image_id))
+            })
+            .collect()
+    }
+
+    /// Inserts `rows` with one multi-row statement per [`BULK_INSERT_ROWS`]
+    /// rows. `insert` is the statement up to its `values` clause.
+    async fn insert_rows<'a, T>(
+        tx: &mut Transaction<'_, Sqlite>,
+        insert: &str,
+        rows: &'a [T],
+        mut push_row: impl FnMut(Separated<'_, 'a, Sqlite, &'static str>, &'a T),
+    ) -> Result<(), sqlx::Error> {
+        for chunk in rows.chunks(BULK_INSERT_ROWS) {
+            let mut query = QueryBuilder::<Sqlite>::new(insert);
+            query.push_values(chunk, &mut push_row);
+            query.build().execute(&mut **tx).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the IGDB match of every Steam game, or only of the games
+    /// without one when `unmatched_only` is set. Games matched by hand are
+    /// left out.
+    pub async fn get_igdb_matches(
+        &self,
+        unmatched_only: bool,
+    ) -> Result<Vec<IgdbMatch>, sqlx::Error> {
+        let matches = sqlx::query(
+            "select games.id as game_id, games_store.store_id as store_id, games.igdb_id as igdb_id
+             from games
+             join games_store on games_store.game_id = games.id
+             where (? = 0 or games.igdb_id is null)
+                and games_store.store_id not in (select store_id from manual_igdb_matches)",
+        )
+        .bind(unmatched_only)
+        .map(|row: SqliteRow| IgdbMatch {
+            game_id: row.get("game_id"),
+            store_id: row.get("store_id"),
+            igdb_id: row.get("igdb_id"),
+        })
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(matches)
+    }
+
+    /// Returns the IGDB matches chosen by the user, as
+    /// `(Steam App ID, IGDB game ID)` pairs.
+    pub async fn get_manual_igdb_matches(&self) -> Result<Vec<(String, u64)>, sqlx::Error> {
+        let matches = sqlx::query("select store_id, igdb_id from manual_igdb_matches")
+            .map(|row: SqliteRow| (row.get("store_id"), row.get::<i64, _>("igdb_id") as u64))
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(matches)
+    }
+
+    /// Records the IGDB match chosen by the user for a Steam game, used by
+    /// later refreshes instead of the automatic match.
+    pub async fn set_manual_igdb_match(
+        &self,
+        store_id: &str,
+        igdb_id: u64,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query("insert or replace into manual_igdb_matches (store_id, igdb_id) values (?, ?)")
+            .bind(store_id)
+            .bind(igdb_id as i64)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Replaces a game's metadata with a new IGDB match, keeping its ID and
+    /// everything attached to it (sessions, achievements, settings).
+    ///
+    /// Store page fields are cleared, and genres, developers, publishers,
+    /// franchises, similar games, game modes, player perspectives, age
+    /// ratings, alternative names, and videos are replaced.
+    /// Covers, artworks, and screenshots still used by the new match keep
+    /// their downloaded files; the others are removed. Images added by the
+    /// user are kept, and a custom cover is not replaced. Returns the images
+    /// left to download.
+    pub async fn replace_igdb_metadata(
+        &self,
+        game_id: i64,
+        game: &IgdbGame,
+    ) -> Result<NewImages, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query(
+            "update games
+             set name = ?, summary = ?, storyline = ?, release_date = ?, game_type = ?,
+                 igdb_id = ?, aggregated_rating = ?, rating_count = ?,
+                 metacritic_score = null, header_image = null
+             where id = ?",
+        )
+        .bind(&game.name)
+        .bind(&game.summary)
+        .bind(&game.storyline)
+        .bind(game.release_date)
+        .bind(game.game_type)
+        .bind(game.id as i64)
+        .bind(game.aggregated_rating)
+        .bind(game.rating_count)
+        .bind(game_id)
+        .execute(&mut *tx)
+        .await?;
+
+        for table in [
+            "belongs_to",
+            "developed_by",
+            "published_by",
+            "game_franchises",
+            "similar_games",
+            "game_modes",
+            "player_perspectives",
+            "age_ratings",
+            "alternative_names",
+            "store_categories",
+            "videos",
+        ] {
+            sqlx::query(&format!("delete from {} where game_id = ?", table))
+                .bind(game_id)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        let cover_id = game.cover.as_ref().map(|cover| cover.image_id.clone());
+
+        sqlx::query(
+            "delete from covers where game_id = ? and cover_id is not ? and source = 'igdb'",
+        )
+        .bind(game_id)
+        .bind(&cover_id)
+        .execute(&mut *tx)
+        .await?;
+
+        let kept_cover: Option<String> =
+            sqlx::query_scalar("select cover_id from covers where game_id = ?")
+                .bind(game_id)
+                .fetch_optional(&mut *tx)
+                .await?;
+
+        let new_cover = cover_id.filter(|_| kept_cover.is_none());
+
+        if let Some(cover_id) = &new_cover {
+            sqlx::query("insert into covers (game_id, cover_id) values (?, ?)")
+                .bind(game_id)
+                .bind(cover_id)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        let new_artworks =
+            Self::replace_images(&mut tx, "artworks", "artwork_id", game_id, &game.artworks)
+                .await?;
+        let new_screenshots = Self::replace_images(
+            &mut tx,
+            "screenshots",
+            "screenshot_id",
+            game_id,
+            &game.screenshots,
+        )
+        .await?;
+
+        Self::insert_genres(&mut tx, game_id, game).await?;
+        Self::insert_relations(&mut tx, game_id, game).await?;
+
+        tx.commit().await?;
+
+        Ok(NewImages {
+            cover: new_cover,
+            artworks: new_artworks,
+            screenshots: new_screenshots,
+        })
+    }
+
+    /// Replaces the IGDB images of a game stored in `table` with `images`,
+    /// keeping the rows of images still used and the images added by the
+    /// user. Returns the IDs of the images the game did not have before.
+    async fn replace_images(
+        tx: &mut Transaction<'_, Sqlite>,
+        table: &str,
+        id_column: &str,
+        game_id: i64,
+        images: &Option<Vec<IgdbImage>>,
+    ) -> Result<Vec<String>, sqlx::Error> {
+        let image_ids: Vec<String> = images
+            .iter()
+            .flatten()
+            .map(|image| image.image_id.clone())
+            .collect();
+
+        let existing: Vec<String> = sqlx::query_scalar(&format!(
+            "select {} from {} where game_id = ? and source = 'igdb'",
+            id_column, table
+        ))
+        .bind(game_id)
+        .fetch_all(&mut **tx)
+        .await?;
+
+        for image_id in existing.iter().filter(|id| !image_ids.contains(id)) {
+            sqlx::query(&format!(
+                "delete from {} where game_id = ? and {} = ?",
+                table, id_column
+            ))
+            .bind(game_id)
+            .bind(image_id)
+            .execute(&mut **tx)
+            .await?;
+        }
+
+        let new_images: Vec<String> = image_ids
+            .into_iter()
+            .filter(|id| !existing.contains(id))
+            .collect();
+
+        for image_id in &new_images {
+            sqlx::query(&format!(
+                "insert into {} (game_id, {}) values (?, ?)",
+                table, id_column
+            ))
+            .bind(game_id)
+            .bind(image_id)
+            .execute(&mut **tx)
+            .await?;
+        }
+
+        Ok(new_images)
+    }
+
+    /// Records an owned soundtrack and the game it belongs to.
+    pub async fn insert_soundtrack(
+        &self,
+        tx: &mut Transaction<'_, Sqlite>,
+        store_id: &str,
+        parent_store_id: &str,
+        name: &str,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "insert or replace into soundtracks (store_id, parent_store_id, name) values (?, ?, ?)",
+        )
+        .bind(store_id)
+        .bind(parent_store_id)
+        .bind(name)
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Records an owned DLC or expansion and the game it belongs to.
+    pub async fn insert_dlc(
+        &self,
+        tx: &mut Transaction<'_, Sqlite>,
+        store_id: &str,
+        parent_store_id: &str,
+        name: &str,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "insert or replace into dlcs (store_id, parent_store_id, name) values (?, ?, ?)",
+        )
+        .bind(store_id)
+        .bind(parent_store_id)
+        .bind(name)
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Returns the Steam store ID of the game matched to an IGDB game, or
+    /// `None` if no game of the library is.
+    pub async fn get_store_id_by_igdb_id(
+        &self,
+        igdb_id: u64,
+    ) -> Result<Option<String>, sqlx::Error> {
+        sqlx::query_scalar(
+            "select games_store.store_id
+             from games
+             join games_store on games_store.game_id = games.id
+             where games.igdb_id = ?",
+        )
+        .bind(igdb_id as i64)
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    /// Returns the Steam App ID of a game's soundtrack, or `None` if the
+    /// soundtrack is not owned.
+    pub async fn get_soundtrack_store_id(
+        &self,
+        game_id: i64,
+    ) -> Result<Option<String>, sqlx::Error> {
+        let store_id = sqlx::query_scalar(
+            "select soundtracks.store_id
+             from soundtracks
+             join games_store on games_store.store_id = soundtracks.parent_store_id
+             where games_store.game_id = ?",
+        )
+        .bind(game_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(store_id)
+    }
+
+    /// Inserts the genres of an IGDB game, upserted by name, and links them
+    /// to the game.
+    async fn insert_genres(
+        tx: &mut Transaction<'_, Sqlite>,
+        game_id: i64,
+        game: &IgdbGame,
+    ) -> Result<(), sqlx::Error> {
         for genre in game.genres.iter().flatten() {
             // Insert genre if it doesn't exist (ON CONFLICT DO UPDATE NAME)
             let genre_id = sqlx::query_scalar::<_, i64>("INSERT INTO genres (name) VALUES (?) ON CONFLICT(name) DO update set name = name returning id")
                 .bind(&genre.name)
-                .fetch_one(&mut *tx)
+                .fetch_one(&mut **tx)
                 .await?;
 
             sqlx::query("INSERT INTO belongs_to (game_id, genre_id) VALUES (?, ?)")
-                .bind(id)
+                .bind(game_id)
                 .bind(genre_id)
-                .execute(&mut *tx)
+                .execute(&mut **tx)
                 .await?;
         }
 
+        Ok(())
+    }
+
+    /// Inserts the developers, publishers, franchises, similar games, game
+    /// modes, player perspectives, alternative names, age ratings, and videos
+    /// of an IGDB game and links them to the game.
+    ///
+    /// Companies and franchises are upserted by IGDB ID.
+    async fn insert_relations(
+        tx: &mut Transaction<'_, Sqlite>,
+        game_id: i64,
+        game: &IgdbGame,
+    ) -> Result<(), sqlx::Error> {
         // Insert developers
         for developer in game.developers.iter().flatten() {
-            let company_id = sqlx::query_scalar::<_, i64>(
-                "INSERT INTO companies (igdb_id, name) VALUES (?, ?) 
+            let company_id = Self::upsert_company(tx, developer).await?;
+
+            sqlx::query("INSERT INTO developed_by (game_id, studio_id) VALUES (?, ?)")
+                .bind(game_id)
+                .bind(company_id)
+                .execute(&mut **tx)
+                .await?;
+        }
+
+        for publisher in game.publishers.iter().flatten() {
+            let company_id = Self::upsert_company(tx, publisher).await?;
+
+            sqlx::query("insert into published_by (game_id, company_id) values (?, ?)")
+                .bind(game_id)
+                .bind(company_id)
+                .execute(&mut **tx)
+                .await?;
+        }
+
+        let franchises = game.franchises.iter().flatten().map(|f| ("franchise", f));
+        let collections = game.collections.iter().flatten().map(|c| ("collection", c));
+
+        for (kind, franchise) in franchises.chain(collections) {
+            let franchise_id = sqlx::query_scalar::<_, i64>(
+                "insert into franchises (igdb_id, kind, name) values (?, ?, ?)
+                 on conflict(igdb_id, kind) do update set name = excluded.name
+                 returning id",
+            )
+            .bind(franchise.id as i64)
+            .bind(kind)
+            .bind(&franchise.name)
+            .fetch_one(&mut **tx)
+            .await?;
+
+            sqlx::query(
+                "insert or ignore into game_franchises (game_id, franchise_id) values (?, ?)",
+            )
+            .bind(game_id)
+            .bind(franchise_id)
+            .execute(&mut **tx)
+            .await?;
+        }
+
+        for similar_id in &game.similar_games {
+            sqlx::query("insert or ignore into similar_games (game_id, igdb_id) values (?, ?)")
+                .bind(game_id)
+                .bind(*similar_id as i64)
+                .execute(&mut **tx)
+                .await?;
+        }
+
+        for (table, names) in [
+            ("game_modes", &game.game_modes),
+            ("player_perspectives", &game.player_perspectives),
+            ("alternative_names", &game.alternative_names),
+        ] {
+            for name in names {
+                sqlx::query(&format!(
+                    "insert or ignore into {} (game_id, name) values (?, ?)",
+                    table
+                ))
+                .bind(game_id)
+                .bind(name)
+                .execute(&mut **tx)
+                .await?;
+            }
+        }
+
+        for age_rating in &game.age_ratings {
+            sqlx::query(
+                "insert or replace into age_ratings (game_id, organization, rating, minimum_age)
+                 values (?, ?, ?, ?)",
+            )
+            .bind(game_id)
+            .bind(&age_rating.organization)
+            .bind(&age_rating.rating)
+            .bind(age_rating.minimum_age())
+            .execute(&mut **tx)
+            .await?;
+        }
+
+        for video in game.videos.iter().flatten() {
+            sqlx::query("insert into videos (game_id, video_id, name) values (?, ?, ?)")
+                .bind(game_id)
+                .bind(&video.video_id)
+                .bind(&video.name)
+                .execute(&mut **tx)
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Inserts a company if its IGDB ID is unknown, and returns its database
+    /// ID.
+    async fn upsert_company(
+        tx: &mut Transaction<'_, Sqlite>,
+        company: &IgdbCompany,
+    ) -> Result<i64, sqlx::Error> {
+        sqlx::query_scalar::<_, i64>(
+            "INSERT INTO companies (igdb_id, name) VALUES (?, ?) 
              ON CONFLICT(igdb_id) DO UPDATE SET igdb_id = igdb_id 
              RETURNING id",
-            )
-            .bind(developer.id)
-            .bind(&developer.name)
-            .fetch_one(&mut *tx)
+        )
+        .bind(company.id)
+        .bind(&company.name)
+        .fetch_one(&mut **tx)
+        .await
+    }
+
+    /// Inserts a game from its Steam store page metadata, for games IGDB has
+    /// no match for.
+    ///
+    /// Creates the core game row with its Metacritic score and header image,
+    /// its Steam store ID, its genres (upserted by name), and its store
+    /// categories, then writes the user's metadata overrides over it.
+    /// Returns the newly created game's database ID.
+    pub async fn insert_store_game(
+        &self,
+        tx: &mut Transaction<'_, Sqlite>,
+        store_id: &str,
+        details: AppDetails,
+    ) -> Result<i64, sqlx::Error> {
+        let id = sqlx::query_scalar::<_, i64>(
+            "insert into games (name, summary, metacritic_score, header_image)
+             values (?, ?, ?, ?) returning id",
+        )
+        .bind(&details.name)
+        .bind(&details.short_description)
+        .bind(details.metacritic.map(|metacritic| metacritic.score))
+        .bind(&details.header_image)
+        .fetch_one(&mut **tx)
+        .await?;
+
+        sqlx::query("insert into games_store (game_id, store_id) values (?, ?)")
+            .bind(id)
+            .bind(store_id)
+            .execute(&mut **tx)
             .await?;
 
-            sqlx::query("INSERT INTO developed_by (game_id, studio_id) VALUES (?, ?)")
+        for genre in details.genres.iter().flatten() {
+            let genre_id = sqlx::query_scalar::<_, i64>("insert into genres (name) values (?) on conflict(name) do update set name = name returning id")
+                .bind(&genre.description)
+                .fetch_one(&mut **tx)
+                .await?;
+
+            sqlx::query("insert into belongs_to (game_id, genre_id) values (?, ?)")
                 .bind(id)
-                .bind(company_id)
-                .execute(&mut *tx)
+                .bind(genre_id)
+                .execute(&mut **tx)
                 .await?;
         }
 
-        tx.commit().await?;
+        for category in details.categories.iter().flatten() {
+            sqlx::query(
+                "insert or ignore into store_categories (game_id, category_id, name) values (?, ?, ?)",
+            )
+            .bind(id)
+            .bind(category.id)
+            .bind(&category.description)
+            .execute(&mut **tx)
+            .await?;
+        }
+
+        Self::apply_stored_override(tx, id, store_id).await?;
+
+        Ok(id)
+    }
+
+    /// Inserts a Steam game known only by its name, for games neither IGDB
+    /// nor the Steam store could fill in. The user's metadata overrides are
+    /// written over it. Returns the newly created game's database ID.
+    pub async fn insert_unmatched_game(
+        &self,
+        tx: &mut Transaction<'_, Sqlite>,
+        store_id: &str,
+        name: &str,
+    ) -> Result<i64, sqlx::Error> {
+        let id = sqlx::query_scalar::<_, i64>("insert into games (name) values (?) returning id")
+            .bind(name)
+            .fetch_one(&mut **tx)
+            .await?;
+
+        sqlx::query("insert into games_store (game_id, store_id) values (?, ?)")
+            .bind(id)
+            .bind(store_id)
+            .execute(&mut **tx)
+            .await?;
+
+        Self::apply_stored_override(tx, id, store_id).await?;
 
         Ok(id)
     }
@@ -328,6 +2968,114 @@ order by games.name
         Ok(())
     }
 
+    /// Sets a game's `last_launched_at` to the current time.
+    pub async fn update_last_launched(&self, game_id: i64) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE games SET last_launched_at = strftime('%s', 'now') WHERE id = ?")
+            .bind(game_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Marks exactly the given games as excluded and clears the flag on all
+    /// others, in a single transaction.
+    pub async fn set_excluded_games(&self, game_ids: &[i64]) -> Result<(), sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("UPDATE games SET excluded = 0")
+            .execute(&mut *tx)
+            .await?;
+
+        for game_id in game_ids {
+            sqlx::query("UPDATE games SET excluded = 1 WHERE id = ?")
+                .bind(game_id)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    /// Updates the Steam player statistics of games, matched by store ID.
+    ///
+    /// Games missing from the database are ignored.
+    pub async fn update_player_stats(
+        &self,
+        tx: &mut Transaction<'_, Sqlite>,
+        stats: Vec<PlayerStats>,
+    ) -> Result<(), sqlx::Error> {
+        for stat in stats {
+            sqlx::query(
+                "UPDATE games SET playtime_forever = ?, playtime_2weeks = ?, last_played_at = ?
+             WHERE id IN (SELECT game_id FROM games_store WHERE store_id = ?)",
+            )
+            .bind(stat.playtime_forever)
+            .bind(stat.playtime_2weeks)
+            .bind(stat.last_played_at)
+            .bind(&stat.store_id)
+            .execute(&mut **tx)
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Updates the local file path of a cover's upscaled variant.
+    ///
+    /// # Arguments
+    ///
+    /// * `image_id` — The IGDB image ID.
+    /// * `upscaled_path` — The local filesystem path of the upscaled image.
+    pub async fn update_cover_upscaled_path(
+        &self,
+        image_id: &str,
+        upscaled_path: &str,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE covers SET upscaled_path = ? WHERE cover_id = ?")
+            .bind(upscaled_path)
+            .bind(image_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Stores the accent color extracted from a cover on the games using it.
+    pub async fn update_accent_color(
+        &self,
+        image_id: &str,
+        color: &str,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "UPDATE games SET accent_color = ?
+             WHERE id IN (SELECT game_id FROM covers WHERE cover_id = ?)",
+        )
+        .bind(color)
+        .bind(image_id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Updates the thumbnail path of an artwork image.
+    pub async fn update_artwork_thumbnail_path(
+        &self,
+        image_id: &str,
+        thumbnail_path: &str,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE artworks SET thumbnail_path = ? WHERE artwork_id = ?")
+            .bind(thumbnail_path)
+            .bind(image_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
     /// Updates the local file paths for a game's artwork images.
     ///
     /// # Arguments
@@ -354,4 +3102,191 @@ order by games.name
 
         Ok(())
     }
+
+    /// Updates the local file paths for a game's screenshots.
+    ///
+    /// # Arguments
+    ///
+    /// * `game_id` — The database ID of the game.
+    /// * `paths` — List of tuples containing (image_id, local_path).
+    pub async fn update_screenshot_paths(
+        &self,
+        game_id: i64,
+        paths: Vec<(String, String)>,
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        for (image_id, local_path) in paths {
+            sqlx::query(
+                "UPDATE screenshots SET local_path = ? WHERE game_id = ? AND screenshot_id = ?",
+            )
+            .bind(&local_path)
+            .bind(game_id)
+            .bind(&image_id)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+}
+
+/// Returns the root of the union-find set of `index`, compressing the path.
+fn root(parents: &mut [usize], mut index: usize) -> usize {
+    while parents[index] != index {
+        parents[index] = parents[parents[index]];
+        index = parents[index];
+    }
+
+    index
+}
+
+/// Lowercases `name` and strips everything but letters and digits, so that
+/// "DOOM™", "Doom" and "D.O.O.M." compare equal.
+fn normalize_name(name: &str) -> String {
+    name.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cmp::Reverse;
+
+    use super::*;
+    use crate::db::DatabaseState;
+
+    /// Name, playtime, playtime over two weeks, user rating, and critic
+    /// rating of a seeded game.
+    type SeededGame = (
+        &'static str,
+        Option<i64>,
+        Option<i64>,
+        Option<i64>,
+        Option<f64>,
+    );
+
+    /// Games seeded in the database. Names and keys repeat so that windows
+    /// end on ties.
+    const GAMES: [SeededGame; 7] = [
+        ("Alpha", Some(120), Some(10), Some(8), Some(85.5)),
+        ("Beta", None, None, None, None),
+        ("Alpha", Some(120), None, Some(8), Some(85.5)),
+        ("Gamma", Some(30), Some(10), None, Some(70.0)),
+        ("delta", Some(0), Some(0), Some(3), None),
+        ("Epsilon", Some(500), Some(60), Some(10), Some(92.25)),
+        ("Zeta", None, Some(5), Some(3), Some(70.0)),
+    ];
+
+    const SORTS: [GameSort; 5] = [
+        GameSort::Name,
+        GameSort::Playtime,
+        GameSort::RecentPlaytime,
+        GameSort::UserRating,
+        GameSort::Rating,
+    ];
+
+    /// Returns a repository over an in-memory database holding [`GAMES`],
+    /// with the database IDs of the games.
+    async fn seeded_repository() -> (GameRepository, Vec<i64>) {
+        let db_state = DatabaseState::in_memory().await.unwrap();
+        let mut ids = Vec::new();
+
+        for (name, playtime, recent_playtime, user_rating, rating) in GAMES {
+            let id = sqlx::query_scalar(
+                "insert into games (name, playtime_forever, playtime_2weeks, user_rating,
+                     aggregated_rating)
+                 values (?, ?, ?, ?, ?) returning id",
+            )
+            .bind(name)
+            .bind(playtime)
+            .bind(recent_playtime)
+            .bind(user_rating)
+            .bind(rating)
+            .fetch_one(&db_state.pool)
+            .await
+            .unwrap();
+            ids.push(id);
+        }
+
+        (GameRepository::new(db_state.pool.clone()), ids)
+    }
+
+    /// Returns the IDs of [`GAMES`] in the order of `sort`.
+    fn expected_order(sort: GameSort, ids: &[i64]) -> Vec<i64> {
+        let mut games: Vec<(i64, &str, i64)> = GAMES
+            .iter()
+            .zip(ids)
+            .map(
+                |((name, playtime, recent_playtime, user_rating, rating), id)| {
+                    let key = match sort {
+                        GameSort::Name => -1,
+                        GameSort::Playtime => playtime.unwrap_or(-1),
+                        GameSort::RecentPlaytime => recent_playtime.unwrap_or(-1),
+                        GameSort::UserRating => user_rating.unwrap_or(-1),
+                        GameSort::Rating => rating.map_or(-1, |rating| (rating * 100.0) as i64),
+                    };
+                    (*id, *name, key)
+                },
+            )
+            .collect();
+
+        games.sort_by_key(|(id, name, key)| (Reverse(*key), *name, *id));
+
+        games.into_iter().map(|(id, _, _)| id).collect()
+    }
+
+    #[tokio::test]
+    async fn get_games_window_pages_through_every_sort() {
+        let (repository, ids) = seeded_repository().await;
+
+        for sort in SORTS {
+            let mut start = None;
+            let mut paged = Vec::new();
+
+            loop {
+                let window = repository
+                    .get_games_window(start, 2, sort, GameFilters::default())
+                    .await
+                    .unwrap();
+
+                assert_eq!(window.total, GAMES.len() as i64);
+                paged.extend(window.games.iter().map(|game| game.id));
+
+                match window.next {
+                    Some(next) => start = Some(next),
+                    None => break,
+                }
+            }
+
+            assert_eq!(paged, expected_order(sort, &ids), "{:?}", sort);
+        }
+    }
+
+    #[tokio::test]
+    async fn get_recently_played_leaves_out_removed_games() {
+        let (repository, ids) = seeded_repository().await;
+
+        sqlx::query("update games set last_played_at = 100 where id in (?, ?)")
+            .bind(ids[0])
+            .bind(ids[1])
+            .execute(&repository.pool)
+            .await
+            .unwrap();
+        sqlx::query("update games set removed_at = 200 where id = ?")
+            .bind(ids[1])
+            .execute(&repository.pool)
+            .await
+            .unwrap();
+
+        let played = repository.get_recently_played(10).await.unwrap();
+
+        assert_eq!(
+            played.iter().map(|game| game.id).collect::<Vec<_>>(),
+            vec![ids[0]]
+        );
+    }
 }
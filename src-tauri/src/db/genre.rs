@@ -0,0 +1,63 @@
+//! Database access layer for the genre dictionary.
+//!
+//! Genres are prefetched from IGDB and kept locally so game enrichment can
+//! resolve genre IDs without extra API calls. They are fetched again when a
+//! game has a genre missing from them.
+
+use sqlx::{Pool, Sqlite};
+
+use crate::igdb::IgdbGenre;
+
+/// Data-access object for the local genre lookup table.
+pub struct GenreRepository {
+    pool: Pool<Sqlite>,
+}
+
+impl GenreRepository {
+    /// Creates a new `GenreRepository` backed by the given connection pool.
+    pub fn new(pool: Pool<Sqlite>) -> Self {
+        Self { pool }
+    }
+
+    /// Returns every genre that has a known IGDB ID.
+    pub async fn get_genres(&self) -> Result<Vec<IgdbGenre>, sqlx::Error> {
+        let genres = sqlx::query_as::<_, (i64, String)>(
+            "select igdb_id, name from genres where igdb_id is not null",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(genres
+            .into_iter()
+            .map(|(id, name)| IgdbGenre {
+                id: id as u64,
+                name,
+            })
+            .collect())
+    }
+
+    /// Stores the IGDB genre dictionary in a single transaction.
+    ///
+    /// Genres are upserted by name so rows created before the dictionary
+    /// existed get their IGDB ID filled in, and by IGDB ID so genres renamed
+    /// on IGDB are renamed locally.
+    pub async fn insert_genres(&self, genres: &[IgdbGenre]) -> Result<(), sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        for genre in genres {
+            sqlx::query(
+                "INSERT INTO genres (igdb_id, name) VALUES (?, ?)
+             ON CONFLICT(name) DO UPDATE SET igdb_id = excluded.igdb_id
+             ON CONFLICT(igdb_id) DO UPDATE SET name = excluded.name",
+            )
+            .bind(genre.id as i64)
+            .bind(&genre.name)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+}
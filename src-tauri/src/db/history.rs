@@ -0,0 +1,187 @@
+//! Database access layer for the library history.
+//!
+//! The history is an append-only log of significant events (games added or
+//! removed by a refresh, status changes, play sessions), shown as an activity
+//! timeline. Games are deleted and inserted again by each refresh, so
+//! entries keep the name and Steam store ID of their game rather than a
+//! reference to it.
+
+use serde::{Deserialize, Serialize};
+use sqlx::{sqlite::SqliteRow, Pool, QueryBuilder, Row, Sqlite};
+
+use super::refresh_report::ReportedGame;
+
+/// Kind of a history entry.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum HistoryEvent {
+    /// A refresh added the game to the library.
+    GameAdded,
+    /// A refresh removed the game from the library.
+    GameRemoved,
+    /// The user changed the backlog status of the game. The detail holds the
+    /// new status, `None` when cleared.
+    StatusChanged,
+    /// The game was played from Rocade. The detail holds the session length
+    /// in seconds.
+    SessionPlayed,
+}
+
+impl HistoryEvent {
+    /// Returns the value stored in the `event` column.
+    fn key(&self) -> &'static str {
+        match self {
+            HistoryEvent::GameAdded => "game_added",
+            HistoryEvent::GameRemoved => "game_removed",
+            HistoryEvent::StatusChanged => "status_changed",
+            HistoryEvent::SessionPlayed => "session_played",
+        }
+    }
+
+    /// Parses a value of the `event` column. Unknown values are `None`.
+    fn from_key(key: &str) -> Option<Self> {
+        match key {
+            "game_added" => Some(HistoryEvent::GameAdded),
+            "game_removed" => Some(HistoryEvent::GameRemoved),
+            "status_changed" => Some(HistoryEvent::StatusChanged),
+            "session_played" => Some(HistoryEvent::SessionPlayed),
+            _ => None,
+        }
+    }
+}
+
+/// An entry of the library history.
+#[derive(Serialize, Debug)]
+pub struct HistoryEntry {
+    pub id: i64,
+    pub event: HistoryEvent,
+    /// Name of the game when the event happened.
+    pub game_name: String,
+    /// Steam App ID of the game, `None` for custom games.
+    pub store_id: Option<String>,
+    /// Event-specific detail, see [`HistoryEvent`].
+    pub detail: Option<String>,
+    /// Unix timestamp of the event, in seconds.
+    pub created_at: i64,
+}
+
+/// Data-access object for the library history.
+pub struct HistoryRepository {
+    pool: Pool<Sqlite>,
+}
+
+impl HistoryRepository {
+    /// Creates a new `HistoryRepository` backed by the given connection pool.
+    pub fn new(pool: Pool<Sqlite>) -> Self {
+        Self { pool }
+    }
+
+    /// Records an event of a game of the library. Does nothing if the game
+    /// does not exist.
+    pub async fn record(
+        &self,
+        event: HistoryEvent,
+        game_id: i64,
+        detail: Option<&str>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "insert into history (event, game_name, store_id, detail)
+             select ?, games.name, games_store.store_id, ?
+             from games
+             left join games_store on games_store.game_id = games.id
+             where games.id = ?",
+        )
+        .bind(event.key())
+        .bind(detail)
+        .bind(game_id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Records the same event for several games, e.g. the games added by a
+    /// refresh.
+    pub async fn record_games(
+        &self,
+        event: HistoryEvent,
+        games: &[ReportedGame],
+    ) -> Result<(), sqlx::Error> {
+        if games.is_empty() {
+            return Ok(());
+        }
+
+        let mut tx = self.pool.begin().await?;
+
+        // Stays under SQLite's limit of 32766 bound parameters
+        for chunk in games.chunks(1000) {
+            QueryBuilder::<Sqlite>::new("insert into history (event, game_name, store_id) ")
+                .push_values(chunk, |mut row, game| {
+                    row.push_bind(event.key())
+                        .push_bind(&game.name)
+                        .push_bind(&game.store_id);
+                })
+                .build()
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    /// Records the end of a play session, with its length. Does nothing if
+    /// the session or its game does not exist.
+    pub async fn record_session(&self, session_id: i64) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "insert into history (event, game_name, store_id, detail)
+             select ?, games.name, games_store.store_id, play_sessions.duration
+             from play_sessions
+             join games on games.id = play_sessions.game_id
+             left join games_store on games_store.game_id = games.id
+             where play_sessions.id = ? and play_sessions.duration is not null",
+        )
+        .bind(HistoryEvent::SessionPlayed.key())
+        .bind(session_id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Returns up to `limit` entries, most recent first. When `before` is
+    /// given, only entries older than the entry with that ID are returned,
+    /// to load the timeline page by page.
+    pub async fn get_history(
+        &self,
+        limit: u32,
+        before: Option<i64>,
+    ) -> Result<Vec<HistoryEntry>, sqlx::Error> {
+        let entries = sqlx::query(
+            "select id, event, game_name, store_id, detail, created_at
+             from history
+             where ?1 is null or id < ?1
+             order by id desc
+             limit ?2",
+        )
+        .bind(before)
+        .bind(limit)
+        .map(|row: SqliteRow| {
+            let event: String = row.get("event");
+
+            HistoryEvent::from_key(&event).map(|event| HistoryEntry {
+                id: row.get("id"),
+                event,
+                game_name: row.get("game_name"),
+                store_id: row.get("store_id"),
+                detail: row.get("detail"),
+                created_at: row.get("created_at"),
+            })
+        })
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(entries.into_iter().flatten().collect())
+    }
+}
@@ -0,0 +1,62 @@
+//! Database access layer for HowLongToBeat completion times.
+//!
+//! Completion times are keyed by game name, the key HowLongToBeat matches
+//! on, so they survive library refreshes. Misses are stored too so unmatched
+//! games are not searched again on every visit.
+
+use sqlx::{Pool, Sqlite};
+
+use crate::service::hltb::HltbGame;
+
+/// Number of seconds in an hour.
+const SECONDS_PER_HOUR: f64 = 3600.0;
+
+/// Data-access object for completion times.
+pub struct CompletionTimeRepository {
+    pool: Pool<Sqlite>,
+}
+
+impl CompletionTimeRepository {
+    /// Creates a new `CompletionTimeRepository` backed by the given
+    /// connection pool.
+    pub fn new(pool: Pool<Sqlite>) -> Self {
+        Self { pool }
+    }
+
+    /// Returns the Unix timestamp of the last lookup of a game, or `None` if
+    /// it was never looked up.
+    pub async fn get_fetched_at(&self, name: &str) -> Result<Option<i64>, sqlx::Error> {
+        let fetched_at =
+            sqlx::query_scalar("select fetched_at from completion_times where name = ?")
+                .bind(name)
+                .fetch_optional(&self.pool)
+                .await?;
+
+        Ok(fetched_at)
+    }
+
+    /// Stores the result of a lookup. `None` records that no match was
+    /// found. Unknown times, reported as `0`, are stored as `NULL`.
+    pub async fn set_completion_times(
+        &self,
+        name: &str,
+        game: Option<&HltbGame>,
+    ) -> Result<(), sqlx::Error> {
+        let hours = |seconds: i64| (seconds > 0).then(|| seconds as f64 / SECONDS_PER_HOUR);
+
+        sqlx::query(
+            "insert or replace into completion_times
+             (name, hltb_id, main_hours, main_extra_hours, completionist_hours, fetched_at)
+             values (?, ?, ?, ?, ?, strftime('%s', 'now'))",
+        )
+        .bind(name)
+        .bind(game.map(|game| game.game_id))
+        .bind(game.and_then(|game| hours(game.comp_main)))
+        .bind(game.and_then(|game| hours(game.comp_plus)))
+        .bind(game.and_then(|game| hours(game.comp_100)))
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,77 @@
+//! Database access layer for user scripting hooks.
+
+use serde::Serialize;
+use sqlx::{Pool, Sqlite};
+
+use crate::hooks::HookEvent;
+
+/// A hook command registered for an event.
+#[derive(Serialize, Debug)]
+pub struct Hook {
+    pub id: i64,
+    pub event: HookEvent,
+    /// Command line run when the event fires.
+    pub command: String,
+}
+
+/// Data-access object for hooks.
+pub struct HookRepository {
+    pool: Pool<Sqlite>,
+}
+
+impl HookRepository {
+    /// Creates a new `HookRepository` backed by the given connection pool.
+    pub fn new(pool: Pool<Sqlite>) -> Self {
+        Self { pool }
+    }
+
+    /// Returns all registered hooks.
+    pub async fn get_hooks(&self) -> Result<Vec<Hook>, sqlx::Error> {
+        let rows = sqlx::query_as::<_, (i64, String, String)>(
+            "select id, event, command from hooks order by id",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.into_iter()
+            .map(|(id, event, command)| {
+                let event = serde_json::from_value(serde_json::Value::String(event))
+                    .map_err(|e| sqlx::Error::Decode(e.into()))?;
+                Ok(Hook { id, event, command })
+            })
+            .collect()
+    }
+
+    /// Returns the commands registered for `event`.
+    pub async fn get_commands(&self, event: HookEvent) -> Result<Vec<String>, sqlx::Error> {
+        let commands = sqlx::query_scalar("select command from hooks where event = ? order by id")
+            .bind(event.name())
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(commands)
+    }
+
+    /// Registers a hook and returns its database ID.
+    pub async fn insert_hook(&self, event: HookEvent, command: &str) -> Result<i64, sqlx::Error> {
+        let id = sqlx::query_scalar::<_, i64>(
+            "INSERT INTO hooks (event, command) VALUES (?, ?) RETURNING id",
+        )
+        .bind(event.name())
+        .bind(command)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(id)
+    }
+
+    /// Deletes a hook by its database ID.
+    pub async fn delete_hook(&self, hook_id: i64) -> Result<(), sqlx::Error> {
+        sqlx::query("DELETE FROM hooks WHERE id = ?")
+            .bind(hook_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,53 @@
+//! Database access layer for cached IGDB responses.
+//!
+//! Raw response bodies are keyed by endpoint and query, so repeated
+//! refreshes and re-matches skip requests whose answer is still fresh, and
+//! fall back to older answers when IGDB cannot be reached.
+
+use sqlx::{FromRow, Pool, Sqlite};
+
+/// A cached IGDB response body.
+#[derive(FromRow, Debug)]
+pub struct CachedResponse {
+    pub body: String,
+    /// Unix timestamp of the request.
+    pub fetched_at: i64,
+}
+
+/// Data-access object for cached IGDB responses.
+#[derive(Debug)]
+pub struct IgdbCacheRepository {
+    pool: Pool<Sqlite>,
+}
+
+impl IgdbCacheRepository {
+    /// Creates a new `IgdbCacheRepository` backed by the given connection pool.
+    pub fn new(pool: Pool<Sqlite>) -> Self {
+        Self { pool }
+    }
+
+    /// Returns the cached response to `request`, or `None` if it was never
+    /// stored.
+    pub async fn get(&self, request: &str) -> Result<Option<CachedResponse>, sqlx::Error> {
+        sqlx::query_as::<_, CachedResponse>(
+            "select body, fetched_at from igdb_responses where request = ?",
+        )
+        .bind(request)
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    /// Stores the response to `request`, replacing any previous one.
+    pub async fn set(&self, request: &str, body: &str) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "insert or replace into igdb_responses (request, body, fetched_at)
+             values (?, ?, strftime('%s', 'now'))",
+        )
+        .bind(request)
+        .bind(body)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+}
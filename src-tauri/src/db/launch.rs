@@ -0,0 +1,98 @@
+//! Database access layer for per-game launch settings.
+
+use serde::{Deserialize, Serialize};
+use sqlx::{sqlite::SqliteRow, FromRow, Pool, Row, Sqlite, Transaction};
+
+/// Options applied when a game is launched from Rocade.
+#[derive(Serialize, Deserialize, FromRow, Debug, Default)]
+pub struct LaunchSettings {
+    /// Controller-remapping profile (e.g. an AntiMicroX profile path) loaded
+    /// while the game runs.
+    pub controller_profile: Option<String>,
+}
+
+/// Data-access object for launch settings.
+pub struct LaunchSettingsRepository {
+    pool: Pool<Sqlite>,
+}
+
+impl LaunchSettingsRepository {
+    /// Creates a new `LaunchSettingsRepository` backed by the given
+    /// connection pool.
+    pub fn new(pool: Pool<Sqlite>) -> Self {
+        Self { pool }
+    }
+
+    /// Returns the launch settings of a game, or the defaults if none were
+    /// stored.
+    pub async fn get_launch_settings(&self, game_id: i64) -> Result<LaunchSettings, sqlx::Error> {
+        let settings = sqlx::query_as::<_, LaunchSettings>(
+            "select controller_profile from launch_settings where game_id = ?",
+        )
+        .bind(game_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(settings.unwrap_or_default())
+    }
+
+    /// Stores the launch settings of a game, replacing any previous ones.
+    pub async fn set_launch_settings(
+        &self,
+        game_id: i64,
+        settings: &LaunchSettings,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "INSERT INTO launch_settings (game_id, controller_profile) VALUES (?, ?)
+             ON CONFLICT(game_id) DO UPDATE SET controller_profile = excluded.controller_profile",
+        )
+        .bind(game_id)
+        .bind(&settings.controller_profile)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Returns the launch settings of the owned Steam games with their Steam
+    /// store ID. Used with [`Self::set_launch_settings_by_store_id`] to keep
+    /// them across a refresh, which re-inserts these games.
+    pub async fn get_launch_settings_by_store_id(
+        &self,
+    ) -> Result<Vec<(String, LaunchSettings)>, sqlx::Error> {
+        let settings = sqlx::query(
+            "select games_store.store_id, launch_settings.controller_profile
+             from launch_settings
+             join games_store on games_store.game_id = launch_settings.game_id
+             join games on games.id = launch_settings.game_id
+             where games.removed_at is null",
+        )
+        .try_map(|row: SqliteRow| Ok((row.try_get("store_id")?, LaunchSettings::from_row(&row)?)))
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(settings)
+    }
+
+    /// Stores launch settings for the games with the given Steam store IDs,
+    /// in the refresh's transaction. Unknown store IDs are ignored.
+    pub async fn set_launch_settings_by_store_id(
+        &self,
+        tx: &mut Transaction<'_, Sqlite>,
+        settings: &[(String, LaunchSettings)],
+    ) -> Result<(), sqlx::Error> {
+        for (store_id, settings) in settings {
+            sqlx::query(
+                "INSERT INTO launch_settings (game_id, controller_profile)
+                 SELECT game_id, ? FROM games_store WHERE store_id = ?
+                 ON CONFLICT(game_id) DO UPDATE SET controller_profile = excluded.controller_profile",
+            )
+            .bind(&settings.controller_profile)
+            .bind(store_id)
+            .execute(&mut **tx)
+            .await?;
+        }
+
+        Ok(())
+    }
+}
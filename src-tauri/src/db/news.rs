@@ -0,0 +1,96 @@
+//! Database access layer for cached Steam news.
+//!
+//! News items are keyed by Steam App ID rather than game ID so the cache
+//! survives library refreshes and stays readable offline.
+
+use serde::Serialize;
+use sqlx::{FromRow, Pool, Sqlite};
+
+use crate::service::steam::NewsItem;
+
+/// A cached news item of a game.
+#[derive(Serialize, FromRow, Debug)]
+pub struct GameNews {
+    pub gid: String,
+    pub title: String,
+    pub url: String,
+    pub author: Option<String>,
+    pub contents: String,
+    pub feed_label: Option<String>,
+    /// Unix timestamp of the publication.
+    pub date: i64,
+}
+
+/// Data-access object for cached news.
+pub struct NewsRepository {
+    pool: Pool<Sqlite>,
+}
+
+impl NewsRepository {
+    /// Creates a new `NewsRepository` backed by the given connection pool.
+    pub fn new(pool: Pool<Sqlite>) -> Self {
+        Self { pool }
+    }
+
+    /// Returns the cached news of a game, most recent first.
+    pub async fn get_news(&self, store_id: &str) -> Result<Vec<GameNews>, sqlx::Error> {
+        let news = sqlx::query_as::<_, GameNews>(
+            "select gid, title, url, author, contents, feed_label, date
+             from news
+             where store_id = ?
+             order by date desc",
+        )
+        .bind(store_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(news)
+    }
+
+    /// Returns the Unix timestamp of the last fetch of a game's news, or
+    /// `None` if it was never fetched.
+    pub async fn get_fetched_at(&self, store_id: &str) -> Result<Option<i64>, sqlx::Error> {
+        let fetched_at = sqlx::query_scalar("select max(fetched_at) from news where store_id = ?")
+            .bind(store_id)
+            .fetch_one(&self.pool)
+            .await?;
+
+        Ok(fetched_at)
+    }
+
+    /// Replaces the cached news of a game in a single transaction.
+    pub async fn replace_news(
+        &self,
+        store_id: &str,
+        news: Vec<NewsItem>,
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("DELETE FROM news WHERE store_id = ?")
+            .bind(store_id)
+            .execute(&mut *tx)
+            .await?;
+
+        for item in news {
+            sqlx::query(
+                "INSERT OR REPLACE INTO news
+                 (gid, store_id, title, url, author, contents, feed_label, date, fetched_at)
+                 VALUES (?, ?, ?, ?, ?, ?, ?, ?, strftime('%s', 'now'))",
+            )
+            .bind(item.gid)
+            .bind(store_id)
+            .bind(item.title)
+            .bind(item.url)
+            .bind(item.author)
+            .bind(item.contents)
+            .bind(item.feedlabel)
+            .bind(item.date)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,85 @@
+//! Database access layer for playtime snapshots.
+//!
+//! Every player stats sync records the total playtime reported by Steam, so
+//! playtime over time can be charted from the deltas even for games never
+//! launched from Rocade. A snapshot is only recorded when the playtime
+//! changed. Snapshots are keyed by Steam App ID so they survive library
+//! refreshes.
+
+use serde::Serialize;
+use sqlx::{FromRow, Pool, Sqlite};
+
+use super::game::PlayerStats;
+
+/// Total playtime of a game at a point in time.
+#[derive(Serialize, FromRow, Debug)]
+pub struct PlaytimeSnapshot {
+    /// Unix timestamp of the sync.
+    pub taken_at: i64,
+    /// Total Steam playtime in minutes.
+    pub playtime_forever: i64,
+}
+
+/// Data-access object for playtime snapshots.
+pub struct PlaytimeRepository {
+    pool: Pool<Sqlite>,
+}
+
+impl PlaytimeRepository {
+    /// Creates a new `PlaytimeRepository` backed by the given connection
+    /// pool.
+    pub fn new(pool: Pool<Sqlite>) -> Self {
+        Self { pool }
+    }
+
+    /// Records the playtime of every game whose playtime changed since its
+    /// last snapshot, in a single transaction. Games never played are
+    /// skipped.
+    pub async fn record_snapshots(&self, stats: &[PlayerStats]) -> Result<(), sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        for stat in stats {
+            let Some(playtime_forever) = stat.playtime_forever else {
+                continue;
+            };
+
+            sqlx::query(
+                "insert or ignore into playtime_snapshots (store_id, taken_at, playtime_forever)
+                 select ?, strftime('%s', 'now'), ?
+                 where ? is not (
+                     select playtime_forever from playtime_snapshots
+                     where store_id = ?
+                     order by taken_at desc
+                     limit 1
+                 )",
+            )
+            .bind(&stat.store_id)
+            .bind(playtime_forever)
+            .bind(playtime_forever)
+            .bind(&stat.store_id)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    /// Returns the snapshots of a game, oldest first.
+    pub async fn get_snapshots(
+        &self,
+        store_id: &str,
+    ) -> Result<Vec<PlaytimeSnapshot>, sqlx::Error> {
+        let snapshots = sqlx::query_as::<_, PlaytimeSnapshot>(
+            "select taken_at, playtime_forever from playtime_snapshots
+             where store_id = ?
+             order by taken_at",
+        )
+        .bind(store_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(snapshots)
+    }
+}
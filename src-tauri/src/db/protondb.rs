@@ -0,0 +1,49 @@
+//! Database access layer for cached ProtonDB tiers.
+//!
+//! Tiers are keyed by Steam App ID so the cache survives library refreshes.
+
+use sqlx::{Pool, Sqlite};
+
+/// Data-access object for cached ProtonDB tiers.
+pub struct ProtonDbRepository {
+    pool: Pool<Sqlite>,
+}
+
+impl ProtonDbRepository {
+    /// Creates a new `ProtonDbRepository` backed by the given connection pool.
+    pub fn new(pool: Pool<Sqlite>) -> Self {
+        Self { pool }
+    }
+
+    /// Returns the Steam App IDs of the library whose tier was never fetched
+    /// or was fetched more than `max_age` seconds ago.
+    pub async fn get_stale_store_ids(&self, max_age: i64) -> Result<Vec<String>, sqlx::Error> {
+        let store_ids = sqlx::query_scalar(
+            "select games_store.store_id
+             from games_store
+             left join protondb_tiers on protondb_tiers.store_id = games_store.store_id
+             where protondb_tiers.fetched_at is null
+                or protondb_tiers.fetched_at <= strftime('%s', 'now') - ?",
+        )
+        .bind(max_age)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(store_ids)
+    }
+
+    /// Stores the tier of a game. `None` records that the game has no
+    /// reports yet.
+    pub async fn set_tier(&self, store_id: &str, tier: Option<&str>) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "insert or replace into protondb_tiers (store_id, tier, fetched_at)
+             values (?, ?, strftime('%s', 'now'))",
+        )
+        .bind(store_id)
+        .bind(tier)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,86 @@
+//! Database access layer for the report of the last library refresh.
+//!
+//! The report lists what a refresh changed, so users can audit a sync that
+//! went wrong (an IGDB mismatch, a partial Steam response) and undo it. Only
+//! the report of the last refresh is kept.
+
+use serde::{Deserialize, Serialize};
+use sqlx::{Pool, Sqlite};
+
+/// A game added or removed by a refresh.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ReportedGame {
+    /// Steam App ID of the game.
+    pub store_id: String,
+    pub name: String,
+}
+
+/// A game whose metadata a refresh changed.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ChangedGame {
+    /// Steam App ID of the game.
+    pub store_id: String,
+    /// Name of the game after the refresh.
+    pub name: String,
+    /// Names of the changed fields, e.g. `summary` or `genres`.
+    pub fields: Vec<String>,
+}
+
+/// What a library refresh changed.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct RefreshReport {
+    /// Unix timestamp of the end of the refresh, in seconds.
+    pub finished_at: i64,
+    /// Games new to the library.
+    pub added: Vec<ReportedGame>,
+    /// Games no longer in the library.
+    pub removed: Vec<ReportedGame>,
+    /// Games kept with changed metadata.
+    pub changed: Vec<ChangedGame>,
+    /// Number of images downloaded again rather than kept from the previous
+    /// refresh.
+    pub images_downloaded: usize,
+}
+
+/// Data-access object for refresh reports.
+pub struct RefreshReportRepository {
+    pool: Pool<Sqlite>,
+}
+
+impl RefreshReportRepository {
+    /// Creates a new `RefreshReportRepository` backed by the given
+    /// connection pool.
+    pub fn new(pool: Pool<Sqlite>) -> Self {
+        Self { pool }
+    }
+
+    /// Returns the report of the last refresh, or `None` if the library was
+    /// never refreshed or the report cannot be read.
+    pub async fn get_last(&self) -> Result<Option<RefreshReport>, sqlx::Error> {
+        let report: Option<String> = sqlx::query_scalar(
+            "select report from refresh_reports order by finished_at desc limit 1",
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(report.and_then(|report| serde_json::from_str(&report).ok()))
+    }
+
+    /// Stores the report of the last refresh, replacing the previous one.
+    pub async fn set_last(&self, report: &RefreshReport) -> Result<(), sqlx::Error> {
+        let json = serde_json::to_string(report).map_err(|e| sqlx::Error::Encode(e.into()))?;
+
+        let mut tx = self.pool.begin().await?;
+        sqlx::query("delete from refresh_reports")
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query("insert into refresh_reports (finished_at, report) values (?, ?)")
+            .bind(report.finished_at)
+            .bind(json)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,68 @@
+//! Database access layer for exclusion rules.
+//!
+//! Rules are stored as JSON in the `exclusion_rules` table.
+
+use serde::Serialize;
+use sqlx::{Pool, Sqlite};
+
+use crate::rules::ExclusionRule;
+
+/// An exclusion rule together with its database ID.
+#[derive(Serialize, Debug)]
+pub struct StoredExclusionRule {
+    pub id: i64,
+    pub rule: ExclusionRule,
+}
+
+/// Data-access object for exclusion rules.
+pub struct ExclusionRuleRepository {
+    pool: Pool<Sqlite>,
+}
+
+impl ExclusionRuleRepository {
+    /// Creates a new `ExclusionRuleRepository` backed by the given connection
+    /// pool.
+    pub fn new(pool: Pool<Sqlite>) -> Self {
+        Self { pool }
+    }
+
+    /// Returns all stored rules in creation order.
+    pub async fn get_rules(&self) -> Result<Vec<StoredExclusionRule>, sqlx::Error> {
+        let rows =
+            sqlx::query_as::<_, (i64, String)>("select id, rule from exclusion_rules order by id")
+                .fetch_all(&self.pool)
+                .await?;
+
+        rows.into_iter()
+            .map(|(id, rule)| {
+                let rule =
+                    serde_json::from_str(&rule).map_err(|e| sqlx::Error::Decode(e.into()))?;
+                Ok(StoredExclusionRule { id, rule })
+            })
+            .collect()
+    }
+
+    /// Stores a new rule and returns its database ID.
+    pub async fn insert_rule(&self, rule: &ExclusionRule) -> Result<i64, sqlx::Error> {
+        let rule = serde_json::to_string(rule).map_err(|e| sqlx::Error::Encode(e.into()))?;
+
+        let id = sqlx::query_scalar::<_, i64>(
+            "INSERT INTO exclusion_rules (rule) VALUES (?) RETURNING id",
+        )
+        .bind(rule)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(id)
+    }
+
+    /// Deletes a rule by its database ID.
+    pub async fn delete_rule(&self, rule_id: i64) -> Result<(), sqlx::Error> {
+        sqlx::query("DELETE FROM exclusion_rules WHERE id = ?")
+            .bind(rule_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,150 @@
+//! Database access layer for play sessions.
+//!
+//! A session is opened when a game is launched from Rocade and closed once
+//! the game is no longer running. Timestamps are Unix timestamps computed by
+//! SQLite.
+
+use serde::Serialize;
+use sqlx::{sqlite::SqliteRow, FromRow, Pool, Row, Sqlite, Transaction};
+
+/// A recorded play session.
+#[derive(Serialize, FromRow, Debug)]
+pub struct PlaySession {
+    pub id: i64,
+    pub game_id: i64,
+    /// Unix timestamp of the launch.
+    pub started_at: i64,
+    /// Unix timestamp of the game exit, `None` while the session is running.
+    pub ended_at: Option<i64>,
+    /// Session length in seconds, `None` while the session is running.
+    pub duration: Option<i64>,
+}
+
+/// Data-access object for play sessions.
+pub struct SessionRepository {
+    pool: Pool<Sqlite>,
+}
+
+impl SessionRepository {
+    /// Creates a new `SessionRepository` backed by the given connection pool.
+    pub fn new(pool: Pool<Sqlite>) -> Self {
+        Self { pool }
+    }
+
+    /// Opens a session for the given game, starting now, and returns its ID.
+    pub async fn start_session(&self, game_id: i64) -> Result<i64, sqlx::Error> {
+        let id = sqlx::query_scalar::<_, i64>(
+            "INSERT INTO play_sessions (game_id, started_at)
+             VALUES (?, strftime('%s', 'now'))
+             RETURNING id",
+        )
+        .bind(game_id)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(id)
+    }
+
+    /// Closes a session, recording the end time and duration.
+    pub async fn end_session(&self, session_id: i64) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "UPDATE play_sessions
+             SET ended_at = strftime('%s', 'now'),
+                 duration = strftime('%s', 'now') - started_at
+             WHERE id = ?",
+        )
+        .bind(session_id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Closes every session still running, ending them now. Returns the
+    /// number of sessions closed.
+    pub async fn end_open_sessions(&self) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query(
+            "UPDATE play_sessions
+             SET ended_at = strftime('%s', 'now'),
+                 duration = strftime('%s', 'now') - started_at
+             WHERE ended_at IS NULL",
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Deletes a session, used when the game never started.
+    pub async fn delete_session(&self, session_id: i64) -> Result<(), sqlx::Error> {
+        sqlx::query("DELETE FROM play_sessions WHERE id = ?")
+            .bind(session_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Returns all sessions of a game, most recent first.
+    pub async fn get_sessions(&self, game_id: i64) -> Result<Vec<PlaySession>, sqlx::Error> {
+        let sessions = sqlx::query_as::<_, PlaySession>(
+            "select id, game_id, started_at, ended_at, duration
+             from play_sessions
+             where game_id = ?
+             order by started_at desc",
+        )
+        .bind(game_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(sessions)
+    }
+
+    /// Returns the sessions of the owned Steam games with their Steam store
+    /// ID. Used with [`Self::set_sessions_by_store_id`] to keep them across
+    /// a refresh, which re-inserts these games.
+    pub async fn get_sessions_by_store_id(
+        &self,
+    ) -> Result<Vec<(String, PlaySession)>, sqlx::Error> {
+        let sessions = sqlx::query(
+            "select games_store.store_id, play_sessions.id, play_sessions.game_id,
+                 started_at, ended_at, duration
+             from play_sessions
+             join games_store on games_store.game_id = play_sessions.game_id
+             join games on games.id = play_sessions.game_id
+             where games.removed_at is null",
+        )
+        .try_map(|row: SqliteRow| Ok((row.try_get("store_id")?, PlaySession::from_row(&row)?)))
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(sessions)
+    }
+
+    /// Stores sessions for the games with the given Steam store IDs, in
+    /// the refresh's transaction. Unknown store IDs are ignored.
+    ///
+    /// Sessions keep their ID, so the watchers of running games still close
+    /// them.
+    pub async fn set_sessions_by_store_id(
+        &self,
+        tx: &mut Transaction<'_, Sqlite>,
+        sessions: &[(String, PlaySession)],
+    ) -> Result<(), sqlx::Error> {
+        for (store_id, session) in sessions {
+            sqlx::query(
+                "INSERT OR IGNORE INTO play_sessions (id, game_id, started_at, ended_at, duration)
+                 SELECT ?, game_id, ?, ?, ? FROM games_store WHERE store_id = ?",
+            )
+            .bind(session.id)
+            .bind(session.started_at)
+            .bind(session.ended_at)
+            .bind(session.duration)
+            .bind(store_id)
+            .execute(&mut **tx)
+            .await?;
+        }
+
+        Ok(())
+    }
+}
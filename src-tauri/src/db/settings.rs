@@ -0,0 +1,194 @@
+//! Database access layer for application settings.
+//!
+//! Settings are stored as key/value pairs in the `settings` table. Sensitive
+//! values live in the OS keychain instead, see [`crate::secrets`].
+
+use std::env;
+
+use serde::Deserialize;
+use sqlx::{Pool, Sqlite};
+
+/// A setting persisted in the `settings` table.
+#[derive(Deserialize, Debug, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum Setting {
+    /// SteamID64 of the profile whose library is imported.
+    SteamProfileId,
+    /// Twitch application client ID, used to query IGDB.
+    TwitchClientId,
+    /// Cover upscaling method: `lanczos`, `external`, or unset to disable.
+    CoverUpscaling,
+    /// Command line run when `CoverUpscaling` is `external`.
+    CoverUpscaleCommand,
+    /// Minutes between two background player stats syncs. `0` disables the
+    /// scheduled sync.
+    PlayerStatsSyncInterval,
+    /// `true` to skip image downloads and strip image fields from game
+    /// payloads, for screen readers or constrained hardware.
+    LowGraphicsMode,
+    /// Command loading a controller-remapping profile, with `{profile}`
+    /// replaced by the profile path. Defaults to AntiMicroX.
+    ControllerProfileLoadCommand,
+    /// Command unloading the current controller-remapping profile.
+    ControllerProfileUnloadCommand,
+    /// Comma-separated search pre-filters applied on large libraries
+    /// (`first_trigram`, `length_window`), or `none`. Defaults to all.
+    SearchPrefilters,
+    /// Steam storefront language of descriptions and categories fetched for
+    /// games without an IGDB match (e.g. `french`), and of the summaries
+    /// returned by `get_game`. Defaults to English.
+    StoreLanguage,
+    /// `true` to show the game being played as Discord Rich Presence.
+    DiscordRichPresence,
+    /// ID of the Discord application the Rich Presence activity is shown
+    /// under. Required for the integration to run.
+    DiscordApplicationId,
+    /// Days an IGDB response is reused before IGDB is queried again. `0`
+    /// disables the cache. Defaults to 7.
+    IgdbCacheTtl,
+    /// Quality, from 1 to 100, at which downloaded covers, artworks, and
+    /// screenshots are re-encoded to WebP. Unset keeps them as JPEG.
+    WebpQuality,
+    /// Number of images downloaded in parallel. Defaults to 5. Applied on
+    /// the next start.
+    DownloadConcurrency,
+    /// Download rate limit shared by all image downloads, in KiB per second.
+    /// Unset or `0` for no limit.
+    DownloadRateLimit,
+    /// Attempts made for an image download or a Steam or IGDB request that
+    /// fails with a transient error, from 1 to 10. Defaults to 3. Applied on
+    /// the next start.
+    RetryAttempts,
+    /// Milliseconds waited before the first retry, doubled for each
+    /// following one, up to 60000. Defaults to 1000. Applied on the next
+    /// start.
+    RetryDelay,
+    /// Highest minimum player age, in years, of the games listed by
+    /// `get_games`. Unset to list every game. Only changed through the
+    /// PIN-gated `set_mature_content_filter`.
+    MaxAgeRating,
+    /// Name of the database backup taken by the last refresh, the only one
+    /// `undo_last_refresh` restores.
+    LastRefreshBackup,
+}
+
+impl Setting {
+    /// Returns the key under which this setting is stored.
+    fn key(&self) -> &'static str {
+        match self {
+            Setting::SteamProfileId => "steam_profile_id",
+            Setting::TwitchClientId => "twitch_client_id",
+            Setting::CoverUpscaling => "cover_upscaling",
+            Setting::CoverUpscaleCommand => "cover_upscale_command",
+            Setting::PlayerStatsSyncInterval => "player_stats_sync_interval",
+            Setting::LowGraphicsMode => "low_graphics_mode",
+            Setting::ControllerProfileLoadCommand => "controller_profile_load_command",
+            Setting::ControllerProfileUnloadCommand => "controller_profile_unload_command",
+            Setting::SearchPrefilters => "search_prefilters",
+            Setting::StoreLanguage => "store_language",
+            Setting::DiscordRichPresence => "discord_rich_presence",
+            Setting::DiscordApplicationId => "discord_application_id",
+            Setting::IgdbCacheTtl => "igdb_cache_ttl",
+            Setting::WebpQuality => "webp_quality",
+            Setting::DownloadConcurrency => "download_concurrency",
+            Setting::DownloadRateLimit => "download_rate_limit",
+            Setting::RetryAttempts => "retry_attempts",
+            Setting::RetryDelay => "retry_delay",
+            Setting::MaxAgeRating => "max_age_rating",
+            Setting::LastRefreshBackup => "last_refresh_backup",
+        }
+    }
+
+    /// Returns `true` if the setting can be changed from the settings screen.
+    ///
+    /// Credentials are excluded: they are only written by the setup wizard
+    /// after validation. So is the mature-content filter, which requires the
+    /// parental PIN, and the backup of the last refresh, written by the
+    /// refresh itself.
+    pub fn is_user_editable(&self) -> bool {
+        !matches!(
+            self,
+            Setting::SteamProfileId
+                | Setting::TwitchClientId
+                | Setting::MaxAgeRating
+                | Setting::LastRefreshBackup
+        )
+    }
+
+    /// Returns the legacy environment variable holding this setting, if any.
+    fn env_var(&self) -> Option<&'static str> {
+        match self {
+            Setting::SteamProfileId => Some("STEAM_PROFILE_ID"),
+            Setting::TwitchClientId => Some("TWITCH_CLIENT_ID"),
+            _ => None,
+        }
+    }
+}
+
+/// Data-access object for application settings.
+#[derive(Debug)]
+pub struct SettingsRepository {
+    pool: Pool<Sqlite>,
+}
+
+impl SettingsRepository {
+    /// Creates a new `SettingsRepository` backed by the given connection pool.
+    pub fn new(pool: Pool<Sqlite>) -> Self {
+        Self { pool }
+    }
+
+    /// Returns the stored value for `setting`, or `None` if it was never set.
+    pub async fn get(&self, setting: Setting) -> Result<Option<String>, sqlx::Error> {
+        let value: Option<String> = sqlx::query_scalar("select value from settings where key = ?")
+            .bind(setting.key())
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(value)
+    }
+
+    /// Returns `true` if a boolean setting is set to `true`.
+    pub async fn is_enabled(&self, setting: Setting) -> Result<bool, sqlx::Error> {
+        Ok(self.get(setting).await?.as_deref() == Some("true"))
+    }
+
+    /// Stores `value` for `setting`, replacing any previous value.
+    pub async fn set(&self, setting: Setting, value: &str) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "INSERT INTO settings (key, value) VALUES (?, ?)
+             ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+        )
+        .bind(setting.key())
+        .bind(value)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Removes the stored value for `setting`. Does nothing if it is not set.
+    pub async fn delete(&self, setting: Setting) -> Result<(), sqlx::Error> {
+        sqlx::query("DELETE FROM settings WHERE key = ?")
+            .bind(setting.key())
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Returns the value for `setting`, migrating it from the environment if
+    /// it was never stored.
+    pub async fn get_or_migrate(&self, setting: Setting) -> Result<Option<String>, sqlx::Error> {
+        if let Some(value) = self.get(setting).await? {
+            return Ok(Some(value));
+        }
+
+        let Some(value) = setting.env_var().and_then(|var| env::var(var).ok()) else {
+            return Ok(None);
+        };
+
+        self.set(setting, &value).await?;
+
+        Ok(Some(value))
+    }
+}
@@ -0,0 +1,236 @@
+//! Database access layer for subscription services.
+//!
+//! Users record the subscriptions they pay for (Game Pass, PS Plus, …) and
+//! which library entries come from them. Play sessions of those games are
+//! then compared with the subscription cost, month by month.
+
+use serde::{Deserialize, Serialize};
+use sqlx::{sqlite::SqliteRow, FromRow, Pool, Row, Sqlite, Transaction};
+
+/// A subscription service and the games it provides.
+#[derive(Serialize, Debug)]
+pub struct Subscription {
+    pub id: i64,
+    pub name: String,
+    /// Price paid per month, in the user's currency.
+    pub monthly_cost: f64,
+    /// Unix timestamp of the subscription start.
+    pub started_at: i64,
+    /// Unix timestamp of the cancellation, `None` while active.
+    pub ended_at: Option<i64>,
+    /// Database IDs of the games played through the subscription.
+    pub game_ids: Vec<i64>,
+}
+
+/// Fields of a new subscription.
+#[derive(Deserialize, Debug)]
+pub struct NewSubscription {
+    pub name: String,
+    pub monthly_cost: f64,
+    pub started_at: i64,
+    pub ended_at: Option<i64>,
+}
+
+/// Value of a subscription over one calendar month.
+#[derive(Serialize, FromRow, Debug)]
+pub struct SubscriptionMonth {
+    pub subscription_id: i64,
+    pub subscription_name: String,
+    /// Month in `YYYY-MM` format.
+    pub month: String,
+    pub monthly_cost: f64,
+    /// Seconds played on the subscription's games during the month, from
+    /// sessions tracked by Rocade.
+    pub seconds_played: i64,
+}
+
+/// Data-access object for subscriptions.
+pub struct SubscriptionRepository {
+    pool: Pool<Sqlite>,
+}
+
+impl SubscriptionRepository {
+    /// Creates a new `SubscriptionRepository` backed by the given connection
+    /// pool.
+    pub fn new(pool: Pool<Sqlite>) -> Self {
+        Self { pool }
+    }
+
+    /// Returns all subscriptions with their games, ordered by name.
+    pub async fn get_subscriptions(&self) -> Result<Vec<Subscription>, sqlx::Error> {
+        let subscriptions = sqlx::query(
+            "select
+                subscriptions.id as id, name, monthly_cost, started_at, ended_at,
+                json_group_array(subscription_games.game_id) as game_ids
+             from subscriptions
+             left join subscription_games on subscription_games.subscription_id = subscriptions.id
+             group by subscriptions.id
+             order by name",
+        )
+        .map(|row: SqliteRow| Subscription {
+            id: row.get("id"),
+            name: row.get("name"),
+            monthly_cost: row.get("monthly_cost"),
+            started_at: row.get("started_at"),
+            ended_at: row.get("ended_at"),
+            game_ids: serde_json::from_str::<Vec<Option<i64>>>(row.get("game_ids"))
+                .map(|ids| ids.into_iter().flatten().collect())
+                .unwrap_or_default(),
+        })
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(subscriptions)
+    }
+
+    /// Stores a new subscription and returns its database ID.
+    pub async fn insert_subscription(
+        &self,
+        subscription: &NewSubscription,
+    ) -> Result<i64, sqlx::Error> {
+        let id = sqlx::query_scalar::<_, i64>(
+            "INSERT INTO subscriptions (name, monthly_cost, started_at, ended_at)
+             VALUES (?, ?, ?, ?)
+             RETURNING id",
+        )
+        .bind(&subscription.name)
+        .bind(subscription.monthly_cost)
+        .bind(subscription.started_at)
+        .bind(subscription.ended_at)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(id)
+    }
+
+    /// Records the cancellation date of a subscription, or clears it when
+    /// `ended_at` is `None`.
+    pub async fn set_ended_at(
+        &self,
+        subscription_id: i64,
+        ended_at: Option<i64>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE subscriptions SET ended_at = ? WHERE id = ?")
+            .bind(ended_at)
+            .bind(subscription_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Deletes a subscription and its game associations.
+    pub async fn delete_subscription(&self, subscription_id: i64) -> Result<(), sqlx::Error> {
+        sqlx::query("DELETE FROM subscriptions WHERE id = ?")
+            .bind(subscription_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Marks a game as coming from a subscription. Does nothing if it
+    /// already is.
+    pub async fn add_game(&self, subscription_id: i64, game_id: i64) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "INSERT OR IGNORE INTO subscription_games (subscription_id, game_id) VALUES (?, ?)",
+        )
+        .bind(subscription_id)
+        .bind(game_id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Removes a game from a subscription.
+    pub async fn remove_game(&self, subscription_id: i64, game_id: i64) -> Result<(), sqlx::Error> {
+        sqlx::query("DELETE FROM subscription_games WHERE subscription_id = ? AND game_id = ?")
+            .bind(subscription_id)
+            .bind(game_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Returns the subscription games of the owned Steam games, as
+    /// subscription ID and Steam store ID pairs. Used with
+    /// [`Self::set_store_games`] to keep them across a refresh, which
+    /// re-inserts these games.
+    pub async fn get_store_games(&self) -> Result<Vec<(i64, String)>, sqlx::Error> {
+        let games = sqlx::query_as::<_, (i64, String)>(
+            "select subscription_games.subscription_id, games_store.store_id
+             from subscription_games
+             join games_store on games_store.game_id = subscription_games.game_id
+             join games on games.id = subscription_games.game_id
+             where games.removed_at is null",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(games)
+    }
+
+    /// Marks the games with the given Steam store IDs as coming from their
+    /// subscription, in the refresh's transaction. Unknown store IDs are
+    /// ignored.
+    pub async fn set_store_games(
+        &self,
+        tx: &mut Transaction<'_, Sqlite>,
+        games: &[(i64, String)],
+    ) -> Result<(), sqlx::Error> {
+        for (subscription_id, store_id) in games {
+            sqlx::query(
+                "INSERT OR IGNORE INTO subscription_games (subscription_id, game_id)
+                 SELECT ?, game_id FROM games_store WHERE store_id = ?",
+            )
+            .bind(subscription_id)
+            .bind(store_id)
+            .execute(&mut **tx)
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the playtime of every subscription for each month it was
+    /// active, most recent month first.
+    ///
+    /// Months without any session are included with zero playtime.
+    pub async fn get_monthly_playtime(&self) -> Result<Vec<SubscriptionMonth>, sqlx::Error> {
+        let months = sqlx::query_as::<_, SubscriptionMonth>(
+            "with recursive months(subscription_id, month, end_month) as (
+                select
+                    id,
+                    date(started_at, 'unixepoch', 'start of month'),
+                    date(coalesce(ended_at, strftime('%s', 'now')), 'unixepoch', 'start of month')
+                from subscriptions
+                union all
+                select subscription_id, date(month, '+1 month'), end_month
+                from months
+                where month < end_month
+            )
+            select
+                months.subscription_id as subscription_id,
+                subscriptions.name as subscription_name,
+                strftime('%Y-%m', months.month) as month,
+                subscriptions.monthly_cost as monthly_cost,
+                coalesce((
+                    select sum(play_sessions.duration)
+                    from play_sessions
+                    join subscription_games on subscription_games.game_id = play_sessions.game_id
+                    where subscription_games.subscription_id = months.subscription_id
+                        and play_sessions.duration is not null
+                        and date(play_sessions.started_at, 'unixepoch', 'start of month') = months.month
+                ), 0) as seconds_played
+            from months
+            join subscriptions on subscriptions.id = months.subscription_id
+            order by months.month desc, subscriptions.name",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(months)
+    }
+}
@@ -0,0 +1,70 @@
+//! Database access layer for translated game summaries.
+//!
+//! Summaries come from IGDB in English. Translations are fetched from the
+//! Steam store page in the requested language and keyed by Steam App ID and
+//! language, so they survive library refreshes. Misses are stored too so
+//! untranslated games are not looked up again on every visit.
+
+use sqlx::{FromRow, Pool, Sqlite};
+
+/// A stored summary lookup.
+#[derive(FromRow, Debug)]
+pub struct SummaryTranslation {
+    /// Translated summary, `None` when the store page has none.
+    pub summary: Option<String>,
+    /// Unix timestamp of the lookup.
+    pub fetched_at: i64,
+}
+
+/// Data-access object for translated summaries.
+pub struct TranslationRepository {
+    pool: Pool<Sqlite>,
+}
+
+impl TranslationRepository {
+    /// Creates a new `TranslationRepository` backed by the given connection
+    /// pool.
+    pub fn new(pool: Pool<Sqlite>) -> Self {
+        Self { pool }
+    }
+
+    /// Returns the last lookup of a game's summary in `language`, or `None`
+    /// if it was never looked up.
+    pub async fn get_summary(
+        &self,
+        store_id: &str,
+        language: &str,
+    ) -> Result<Option<SummaryTranslation>, sqlx::Error> {
+        let translation = sqlx::query_as::<_, SummaryTranslation>(
+            "select summary, fetched_at from summary_translations
+             where store_id = ? and language = ?",
+        )
+        .bind(store_id)
+        .bind(language)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(translation)
+    }
+
+    /// Stores the result of a lookup. `None` records that no translation
+    /// was found.
+    pub async fn set_summary(
+        &self,
+        store_id: &str,
+        language: &str,
+        summary: Option<&str>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "insert or replace into summary_translations (store_id, language, summary, fetched_at)
+             values (?, ?, ?, strftime('%s', 'now'))",
+        )
+        .bind(store_id)
+        .bind(language)
+        .bind(summary)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+}
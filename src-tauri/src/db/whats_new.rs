@@ -0,0 +1,51 @@
+//! Database access layer for "what's new" notes.
+//!
+//! Migrations that change user-visible behavior insert a note into the
+//! `whats_new` table. Notes stay unseen until the user dismisses them; on a
+//! fresh install they are marked seen right away.
+
+use serde::Serialize;
+use sqlx::{FromRow, Pool, Sqlite};
+
+/// A user-facing note describing a change.
+#[derive(Serialize, FromRow, Debug)]
+pub struct WhatsNewNote {
+    pub id: i64,
+    /// Version of the migration that introduced the change.
+    pub version: i64,
+    pub title: String,
+    pub body: String,
+}
+
+/// Data-access object for "what's new" notes.
+pub struct WhatsNewRepository {
+    pool: Pool<Sqlite>,
+}
+
+impl WhatsNewRepository {
+    /// Creates a new `WhatsNewRepository` backed by the given connection
+    /// pool.
+    pub fn new(pool: Pool<Sqlite>) -> Self {
+        Self { pool }
+    }
+
+    /// Returns the notes the user has not dismissed, oldest change first.
+    pub async fn get_unseen(&self) -> Result<Vec<WhatsNewNote>, sqlx::Error> {
+        let notes = sqlx::query_as::<_, WhatsNewNote>(
+            "select id, version, title, body from whats_new where seen = 0 order by version, id",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(notes)
+    }
+
+    /// Marks every note as seen.
+    pub async fn mark_all_seen(&self) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE whats_new SET seen = 1")
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,112 @@
+//! Discord Rich Presence for the game being played.
+//!
+//! Talks to the local Discord client over IPC to show "Playing <game> via
+//! Rocade" with the game's cover. The integration is optional: it is enabled
+//! in the settings, along with the ID of the Discord application to show the
+//! activity under.
+
+use std::{
+    sync::Mutex,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use discord_rich_presence::{
+    activity::{Activity, Assets, Timestamps},
+    DiscordIpc, DiscordIpcClient,
+};
+
+/// Errors that can occur while updating the Discord activity.
+#[derive(Debug, thiserror::Error)]
+pub enum DiscordError {
+    /// The Discord client is not running or rejected the request.
+    #[error("discord ipc error: {0}")]
+    Ipc(#[from] discord_rich_presence::error::Error),
+}
+
+/// Connection to the local Discord client, opened on first use.
+pub struct DiscordPresence {
+    client: Mutex<Option<DiscordIpcClient>>,
+}
+
+impl DiscordPresence {
+    /// Creates a new, not yet connected, `DiscordPresence`.
+    pub fn new() -> Self {
+        DiscordPresence {
+            client: Mutex::new(None),
+        }
+    }
+
+    /// Shows `game` as the current activity, with its IGDB cover if any.
+    ///
+    /// Connects to Discord under `application_id` first when needed. A
+    /// connection made under another application ID is replaced.
+    pub fn set_playing(
+        &self,
+        application_id: &str,
+        game: &str,
+        cover_id: Option<&str>,
+    ) -> Result<(), DiscordError> {
+        let mut client = self.client.lock().unwrap();
+
+        if client
+            .as_ref()
+            .is_none_or(|client| client.client_id != application_id)
+        {
+            if let Some(mut previous) = client.take() {
+                let _ = previous.close();
+            }
+
+            let mut new_client = DiscordIpcClient::new(application_id);
+            new_client.connect()?;
+            *client = Some(new_client);
+        }
+
+        let started_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as i64)
+            .unwrap_or_default();
+
+        let cover_url = cover_id.map(|id| {
+            format!(
+                "https://images.igdb.com/igdb/image/upload/t_cover_big/{}.jpg",
+                id
+            )
+        });
+
+        let mut assets = Assets::new().large_text(game);
+        if let Some(cover_url) = &cover_url {
+            assets = assets.large_image(cover_url);
+        }
+
+        let activity = Activity::new()
+            .details(game)
+            .state("via Rocade")
+            .assets(assets)
+            .timestamps(Timestamps::new().start(started_at));
+
+        let Some(connected) = client.as_mut() else {
+            return Ok(());
+        };
+
+        if let Err(e) = connected.set_activity(activity) {
+            // The Discord client was likely restarted: reconnect next time
+            *client = None;
+            return Err(e.into());
+        }
+
+        Ok(())
+    }
+
+    /// Clears the current activity. Failures are ignored.
+    pub fn clear(&self) {
+        if let Some(client) = self.client.lock().unwrap().as_mut() {
+            let _ = client.clear_activity();
+        }
+    }
+}
+
+impl Default for DiscordPresence {
+    fn default() -> Self {
+        Self::new()
+    }
+}
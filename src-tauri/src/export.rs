@@ -0,0 +1,161 @@
+//! Export of the library to JSON or CSV, and import of JSON exports.
+//!
+//! The export holds what the user curated (statuses, ratings, notes,
+//! collections, favorites) alongside the metadata needed to recognize each
+//! game, so it can be kept as a record or loaded into a spreadsheet. Images
+//! and IGDB details are left out. JSON exports can be imported back to
+//! restore the curation on another machine or after a reinstall.
+
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::db::game::GameStatus;
+
+/// Separator of the values of list columns in CSV exports.
+const CSV_LIST_SEPARATOR: &str = "; ";
+
+/// Header row of CSV exports, in the order of [`LibraryEntry::csv_row`].
+const CSV_HEADER: [&str; 12] = [
+    "name",
+    "store_id",
+    "genres",
+    "developers",
+    "tags",
+    "notes",
+    "status",
+    "user_rating",
+    "is_favorite",
+    "hidden",
+    "playtime_minutes",
+    "last_played_at",
+];
+
+/// Errors that can occur while exporting or importing the library.
+#[derive(Debug, thiserror::Error)]
+pub enum ExportError {
+    /// The export file could not be written.
+    #[error("unable to write export: {0}")]
+    Write(#[from] std::io::Error),
+
+    /// The export file could not be read.
+    #[error("unable to read export: {0}")]
+    Read(std::io::Error),
+
+    /// The library could not be serialized, or the imported file is not a
+    /// valid JSON export.
+    #[error("invalid library data: {0}")]
+    InvalidData(#[from] serde_json::Error),
+}
+
+/// File format of a library export.
+#[derive(Deserialize, Clone, Copy, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportFormat {
+    /// A JSON array of games, with list fields as arrays.
+    Json,
+    /// One row per game, with list fields joined by `; `.
+    Csv,
+}
+
+/// A game as written to a library export.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct LibraryEntry {
+    pub name: String,
+    /// Steam App ID, `None` for custom games.
+    pub store_id: Option<String>,
+    #[serde(default)]
+    pub genres: Vec<String>,
+    #[serde(default)]
+    pub developers: Vec<String>,
+    /// Names of the collections the game belongs to.
+    #[serde(default)]
+    pub tags: Vec<String>,
+    pub notes: Option<String>,
+    pub status: Option<GameStatus>,
+    pub user_rating: Option<i64>,
+    #[serde(default)]
+    pub is_favorite: bool,
+    #[serde(default)]
+    pub hidden: bool,
+    /// Total playtime, in minutes.
+    pub playtime_minutes: Option<i64>,
+    /// Unix timestamp of the last play session, in seconds.
+    pub last_played_at: Option<i64>,
+}
+
+impl LibraryEntry {
+    /// Returns the CSV fields of the entry, in the order of [`CSV_HEADER`].
+    fn csv_row(&self) -> [String; 12] {
+        let optional = |value: Option<i64>| value.map(|v| v.to_string()).unwrap_or_default();
+
+        [
+            self.name.clone(),
+            self.store_id.clone().unwrap_or_default(),
+            self.genres.join(CSV_LIST_SEPARATOR),
+            self.developers.join(CSV_LIST_SEPARATOR),
+            self.tags.join(CSV_LIST_SEPARATOR),
+            self.notes.clone().unwrap_or_default(),
+            self.status
+                .map(|status| status.key().to_string())
+                .unwrap_or_default(),
+            optional(self.user_rating),
+            self.is_favorite.to_string(),
+            self.hidden.to_string(),
+            optional(self.playtime_minutes),
+            optional(self.last_played_at),
+        ]
+    }
+}
+
+/// Writes `entries` to `path` in the given format.
+pub async fn export(
+    path: &Path,
+    format: ExportFormat,
+    entries: &[LibraryEntry],
+) -> Result<(), ExportError> {
+    let contents = match format {
+        ExportFormat::Json => serde_json::to_string_pretty(entries)?,
+        ExportFormat::Csv => to_csv(entries),
+    };
+
+    tokio::fs::write(path, contents).await?;
+
+    Ok(())
+}
+
+/// Reads the games of the JSON export at `path`.
+pub async fn import(path: &Path) -> Result<Vec<LibraryEntry>, ExportError> {
+    let contents = tokio::fs::read_to_string(path)
+        .await
+        .map_err(ExportError::Read)?;
+
+    Ok(serde_json::from_str(&contents)?)
+}
+
+/// Formats `entries` as CSV with a header row, following RFC 4180.
+fn to_csv(entries: &[LibraryEntry]) -> String {
+    let mut csv = csv_line(CSV_HEADER.iter().copied());
+
+    for entry in entries {
+        csv.push_str(&csv_line(entry.csv_row().iter().map(String::as_str)));
+    }
+
+    csv
+}
+
+/// Formats one CSV line, ending with CRLF. Fields holding a comma, a quote,
+/// or a line break are quoted, with their quotes doubled.
+fn csv_line<'a>(fields: impl Iterator<Item = &'a str>) -> String {
+    let fields: Vec<String> = fields
+        .map(|field| {
+            if field.contains([',', '"', '\n', '\r']) {
+                format!("\"{}\"", field.replace('"', "\"\""))
+            } else {
+                field.to_string()
+            }
+        })
+        .collect();
+
+    format!("{}\r\n", fields.join(","))
+}
@@ -0,0 +1,103 @@
+//! User scripting hooks.
+//!
+//! Lets power users run their own executables or scripts when something
+//! happens in Rocade. Each hook receives a JSON document on stdin describing
+//! the event, and the event name in the `ROCADE_EVENT` environment variable.
+
+use std::process::Stdio;
+
+use serde::{Deserialize, Serialize};
+use tokio::{io::AsyncWriteExt, process::Command};
+
+/// Events that can trigger a hook.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum HookEvent {
+    /// A game appeared in the library during a refresh.
+    GameAdded,
+    /// A game was launched from Rocade.
+    GameLaunched,
+    /// A library refresh completed.
+    RefreshFinished,
+}
+
+impl HookEvent {
+    /// Returns the name under which the event is stored and sent to hooks.
+    pub fn name(&self) -> &'static str {
+        match self {
+            HookEvent::GameAdded => "game_added",
+            HookEvent::GameLaunched => "game_launched",
+            HookEvent::RefreshFinished => "refresh_finished",
+        }
+    }
+}
+
+/// JSON document written to a hook's stdin.
+#[derive(Serialize)]
+struct HookPayload<'a, T: Serialize> {
+    event: HookEvent,
+    data: &'a T,
+}
+
+/// Errors that can occur while running a hook.
+#[derive(Debug, thiserror::Error)]
+pub enum HookError {
+    /// The hook command is empty.
+    #[error("empty hook command")]
+    EmptyCommand,
+
+    /// The hook command has unbalanced quotes.
+    #[error("unbalanced quotes in hook command")]
+    InvalidCommand,
+
+    /// The hook process could not be started or written to.
+    #[error("unable to run hook: {0}")]
+    Process(#[from] std::io::Error),
+
+    /// The event data could not be serialized.
+    #[error("unable to serialize hook payload: {0}")]
+    Payload(#[from] serde_json::Error),
+}
+
+/// Runs every command with the event payload, in the background.
+///
+/// Hooks are fire-and-forget: failures are ignored so a broken script never
+/// interrupts the action that triggered it.
+pub fn run_hooks<T: Serialize>(commands: Vec<String>, event: HookEvent, data: &T) {
+    let payload = match serde_json::to_vec(&HookPayload { event, data }) {
+        Ok(payload) => payload,
+        Err(_) => return,
+    };
+
+    for command in commands {
+        let payload = payload.clone();
+        tauri::async_runtime::spawn(async move {
+            let _ = run_hook(&command, event, &payload).await;
+        });
+    }
+}
+
+/// Runs a single hook command and waits for it to exit.
+///
+/// The command line is split into arguments like a POSIX shell does, so
+/// paths with spaces must be quoted.
+async fn run_hook(command: &str, event: HookEvent, payload: &[u8]) -> Result<(), HookError> {
+    let parts = shlex::split(command).ok_or(HookError::InvalidCommand)?;
+    let mut parts = parts.into_iter();
+    let program = parts.next().ok_or(HookError::EmptyCommand)?;
+
+    let mut child = Command::new(program)
+        .args(parts)
+        .env("ROCADE_EVENT", event.name())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .spawn()?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(payload).await?;
+    }
+
+    child.wait().await?;
+
+    Ok(())
+}
@@ -4,82 +4,335 @@
 //! [IGDB API](https://api-docs.igdb.com/). Authentication is handled via a
 //! Twitch OAuth token managed by [`TwitchApiClient`].
 
-use std::collections::HashMap;
+use std::{
+    collections::HashMap,
+    fmt::Display,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
 
-use crate::twitch::{TwitchApiClient, TwitchError};
-use serde::{Deserialize, Serialize};
-use tauri::http::{HeaderMap, HeaderValue, StatusCode};
+use crate::{
+    db::{
+        igdb_cache::IgdbCacheRepository,
+        settings::{Setting, SettingsRepository},
+    },
+    retry::RetryPolicy,
+    twitch::{TwitchApiClient, TwitchError},
+};
+use async_trait::async_trait;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use tauri::http::{header::RETRY_AFTER, HeaderMap, HeaderValue, StatusCode};
 use tauri_plugin_http::reqwest::{self, Client, Response};
 
-/// A game genre as returned by the IGDB API.
-#[derive(Serialize, Deserialize, Debug)]
+use self::query::IgdbQuery;
+
+mod query;
+
+/// External game source ID of Steam in IGDB.
+const STEAM_SOURCE: u64 = 1;
+
+/// Fields requested for full game records: every standard field, plus the
+/// nested records the library stores. Genres and companies are returned as
+/// IDs, resolved against the local dictionaries.
+const GAME_FIELDS: &[&str] = &[
+    "*",
+    "artworks.image_id",
+    "screenshots.image_id",
+    "videos.video_id",
+    "videos.name",
+    "franchises.name",
+    "collections.name",
+    "game_modes.name",
+    "player_perspectives.name",
+    "age_ratings.organization.name",
+    "age_ratings.rating_category.rating",
+    "alternative_names.name",
+    "cover.image_id",
+    "involved_companies.company",
+    "involved_companies.developer",
+    "involved_companies.publisher",
+];
+
+/// Fields requested for game previews and search results.
+const PREVIEW_FIELDS: &[&str] = &["name", "first_release_date", "cover.image_id"];
+
+/// A game genre as returned by the IGDB `/genres` endpoint.
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct IgdbGenre {
+    pub id: u64,
     pub name: String,
 }
 
-/// An image asset (cover art or artwork) as returned by the IGDB API.
+/// An image asset (cover art, artwork, or screenshot) as returned by the
+/// IGDB API.
 ///
 /// The `image_id` can be used to build an image URL via the
 /// [IGDB Images endpoint](https://api-docs.igdb.com/#images).
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct IgdbImage {
     pub image_id: String,
 }
 
-/// A game company (publisher or developer) as returned by the IGDB API.
+/// A video (trailer, gameplay, ...) as returned by the IGDB `game_videos`
+/// endpoint.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct IgdbVideo {
+    /// YouTube video ID.
+    pub video_id: String,
+    /// Title of the video, e.g. "Trailer".
+    pub name: Option<String>,
+}
+
+/// A franchise or series (IGDB `collection`) a game belongs to.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct IgdbFranchise {
+    /// IGDB franchise or collection ID.
+    pub id: u64,
+    pub name: String,
+}
+
+/// A named IGDB entry, such as a game mode, a player perspective, or an
+/// alternative name.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct IgdbCategory {
+    pub name: String,
+}
+
+/// The rating of an IGDB `age_rating` entry.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct IgdbAgeRatingCategory {
+    rating: String,
+}
+
+/// Raw IGDB `age_rating` entry, with its organization and rating expanded.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct IgdbAgeRatingInfo {
+    organization: Option<IgdbCategory>,
+    rating_category: Option<IgdbAgeRatingCategory>,
+}
+
+/// A PEGI or ESRB age rating.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct IgdbAgeRating {
+    /// Rating board: `PEGI` or `ESRB`.
+    pub organization: String,
+    /// Rating as named by IGDB, e.g. `Sixteen` or `M`.
+    pub rating: String,
+}
+
+impl IgdbAgeRating {
+    /// Returns the minimum player age the rating stands for, or `None` for
+    /// pending or unknown ratings.
+    pub fn minimum_age(&self) -> Option<i64> {
+        let age = match self.rating.as_str() {
+            "Three" | "3" | "EC" => 3,
+            "E" => 6,
+            "Seven" | "7" => 7,
+            "E10" | "E10+" => 10,
+            "Twelve" | "12" => 12,
+            "T" => 13,
+            "Sixteen" | "16" => 16,
+            "M" => 17,
+            "Eighteen" | "18" | "AO" => 18,
+            _ => return None,
+        };
+
+        Some(age)
+    }
+}
+
+/// A game company (publisher or developer) as returned by the IGDB
+/// `/companies` endpoint.
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct IgdbCompany {
     pub id: i64,
     pub name: String,
-    /// IDs of games this company has published.
-    published: Option<Vec<u64>>,
-    /// IDs of games this company has developed.
-    developed: Option<Vec<u64>>,
 }
 
 /// An IGDB `involved_company` entry, linking a game to a company with a role.
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct IgdbInvolvedCompany {
-    company: IgdbCompany,
+    /// IGDB company ID, resolved against the local company dictionary.
+    company: u64,
+    #[serde(default)]
+    developer: bool,
+    #[serde(default)]
+    publisher: bool,
 }
 
 /// Raw game data as returned directly by the IGDB `/games` endpoint.
 ///
 /// This is an intermediate representation. Use [`IgdbGame`] for the
 /// processed, caller-facing version.
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct IgdbGameInfo {
     id: u64,
     name: String,
     cover: Option<IgdbImage>,
-    genres: Option<Vec<IgdbGenre>>,
+    /// IGDB genre IDs, resolved against the local genre dictionary.
+    genres: Option<Vec<u64>>,
     storyline: Option<String>,
     involved_companies: Option<Vec<IgdbInvolvedCompany>>,
     summary: Option<String>,
     artworks: Option<Vec<IgdbImage>>,
+    screenshots: Option<Vec<IgdbImage>>,
+    videos: Option<Vec<IgdbVideo>>,
+    franchises: Option<Vec<IgdbFranchise>>,
+    collections: Option<Vec<IgdbFranchise>>,
     /// Unix timestamp of the game's first release.
     first_release_date: Option<i64>,
+    /// IGDB game type ID (main game, DLC, bundle, ...).
+    game_type: Option<i64>,
+    /// IGDB ID of the game a DLC or expansion belongs to.
+    parent_game: Option<u64>,
+    /// IGDB IDs of the game's DLC.
+    dlcs: Option<Vec<u64>>,
+    /// IGDB IDs of the game's expansions.
+    expansions: Option<Vec<u64>>,
+    /// IGDB IDs of games similar to this one.
+    similar_games: Option<Vec<u64>>,
+    game_modes: Option<Vec<IgdbCategory>>,
+    player_perspectives: Option<Vec<IgdbCategory>>,
+    age_ratings: Option<Vec<IgdbAgeRatingInfo>>,
+    alternative_names: Option<Vec<IgdbCategory>>,
+    /// Average critic score, from 0 to 100.
+    aggregated_rating: Option<f64>,
+    /// Number of critic scores behind `aggregated_rating`.
+    aggregated_rating_count: Option<i64>,
+}
+
+/// A short preview of an IGDB game: a candidate match returned by a name
+/// search, or a suggestion.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct IgdbSearchResult {
+    /// IGDB game ID.
+    pub id: u64,
+    pub name: String,
+    /// Unix timestamp of the game's first release.
+    #[serde(rename(deserialize = "first_release_date"))]
+    pub release_date: Option<i64>,
+    pub cover: Option<IgdbImage>,
+}
+
+/// Maximum number of records IGDB returns per query.
+const MAX_QUERY_LIMIT: usize = 500;
+
+/// Maximum number of queries IGDB accepts in a single multiquery request.
+const MAX_MULTIQUERY_SIZE: usize = 10;
+
+/// The result of one named query of a multiquery request.
+#[derive(Deserialize)]
+struct IgdbMultiqueryResult<T> {
+    result: Vec<T>,
 }
 
 /// Processed game metadata ready for use by the rest of the application.
 ///
 /// Constructed from [`IgdbGameInfo`] after resolving companies and
 /// mapping the Steam store ID.
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Default)]
 pub struct IgdbGame {
-    id: u64,
+    /// IGDB game ID.
+    pub id: u64,
     pub name: String,
     /// Steam store ID associated with this game entry, if available.
     pub store_id: Option<String>,
-    storyline: Option<String>,
+    pub storyline: Option<String>,
     pub summary: Option<String>,
     pub genres: Option<Vec<IgdbGenre>>,
     pub cover: Option<IgdbImage>,
     pub artworks: Option<Vec<IgdbImage>>,
+    /// In-game screenshots.
+    pub screenshots: Option<Vec<IgdbImage>>,
+    /// Trailers and other videos hosted on YouTube.
+    pub videos: Option<Vec<IgdbVideo>>,
+    pub franchises: Option<Vec<IgdbFranchise>>,
+    /// Series the game belongs to, narrower than franchises.
+    pub collections: Option<Vec<IgdbFranchise>>,
     pub publishers: Option<Vec<IgdbCompany>>,
     pub developers: Option<Vec<IgdbCompany>>,
     /// Unix timestamp of the game's first release.
     pub release_date: Option<i64>,
+    /// IGDB game type ID, see [`game_type_name`].
+    pub game_type: Option<i64>,
+    /// IGDB ID of the game a DLC or expansion belongs to.
+    pub parent_game: Option<u64>,
+    /// IGDB IDs of the game's DLC and expansions.
+    #[serde(default)]
+    pub dlcs: Vec<u64>,
+    /// IGDB IDs of games similar to this one.
+    #[serde(default)]
+    pub similar_games: Vec<u64>,
+    /// Game mode names (e.g. "Single player", "Co-operative").
+    #[serde(default)]
+    pub game_modes: Vec<String>,
+    /// Player perspective names (e.g. "First person").
+    #[serde(default)]
+    pub player_perspectives: Vec<String>,
+    /// PEGI and ESRB age ratings. Other rating boards are left out.
+    #[serde(default)]
+    pub age_ratings: Vec<IgdbAgeRating>,
+    /// Other names of the game, such as abbreviations or localized titles.
+    #[serde(default)]
+    pub alternative_names: Vec<String>,
+    /// Average critic score, from 0 to 100.
+    pub aggregated_rating: Option<f64>,
+    /// Number of critic scores behind `aggregated_rating`.
+    pub rating_count: Option<i64>,
+}
+
+/// Returns the names of a list of named IGDB entries.
+fn category_names(categories: Option<Vec<IgdbCategory>>) -> Vec<String> {
+    categories
+        .into_iter()
+        .flatten()
+        .map(|category| category.name)
+        .collect()
+}
+
+/// Returns the PEGI and ESRB ratings among a list of IGDB age ratings.
+fn pegi_esrb_ratings(ratings: Option<Vec<IgdbAgeRatingInfo>>) -> Vec<IgdbAgeRating> {
+    ratings
+        .into_iter()
+        .flatten()
+        .filter_map(|rating| {
+            let organization = rating.organization?.name;
+
+            if organization != "PEGI" && organization != "ESRB" {
+                return None;
+            }
+
+            Some(IgdbAgeRating {
+                organization,
+                rating: rating.rating_category?.rating,
+            })
+        })
+        .collect()
+}
+
+/// Returns the name of an IGDB game type ID, or `None` if it is unknown.
+///
+/// See the [IGDB game types](https://api-docs.igdb.com/#game-type) reference.
+pub fn game_type_name(game_type: i64) -> Option<&'static str> {
+    let name = match game_type {
+        0 => "main_game",
+        1 => "dlc_addon",
+        2 => "expansion",
+        3 => "bundle",
+        4 => "standalone_expansion",
+        5 => "mod",
+        6 => "episode",
+        7 => "season",
+        8 => "remake",
+        9 => "remaster",
+        10 => "expanded_game",
+        11 => "port",
+        12 => "fork",
+        13 => "pack",
+        14 => "update",
+        _ => return None,
+    };
+
+    Some(name)
 }
 
 /// Errors that can occur while using the IGDB API client.
@@ -101,11 +354,149 @@ pub enum IgdbError {
     #[error("unable to find game: {0}")]
     NoData(String),
 
+    /// IGDB kept rejecting requests for exceeding its rate limit.
+    #[error("igdb rate limit exceeded")]
+    RateLimited,
+
     /// Client error
     #[error("igdb client error: {0}")]
     Client(String),
 }
 
+/// Number of requests per second allowed by IGDB.
+const REQUESTS_PER_SECOND: f64 = 4.0;
+
+/// Number of times a request rejected with `429 Too Many Requests` is
+/// retried.
+const MAX_RATE_LIMIT_RETRIES: u32 = 3;
+
+/// Wait before retrying a rate-limited request without a `Retry-After`
+/// header.
+const DEFAULT_RETRY_AFTER: Duration = Duration::from_secs(1);
+
+/// Token bucket spacing requests to [`REQUESTS_PER_SECOND`].
+///
+/// The bucket holds up to one second of requests, so short bursts are sent
+/// right away.
+#[derive(Debug)]
+struct RateLimiter {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl RateLimiter {
+    fn new() -> Self {
+        RateLimiter {
+            tokens: REQUESTS_PER_SECOND,
+            last_refill: Instant::now(),
+        }
+    }
+
+    /// Waits until a request can be sent, and takes its token.
+    async fn acquire(&mut self) {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.last_refill).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * REQUESTS_PER_SECOND).min(REQUESTS_PER_SECOND);
+        self.last_refill = now;
+
+        if self.tokens < 1.0 {
+            let wait = Duration::from_secs_f64((1.0 - self.tokens) / REQUESTS_PER_SECOND);
+            tokio::time::sleep(wait).await;
+
+            self.tokens = 1.0;
+            self.last_refill = Instant::now();
+        }
+
+        self.tokens -= 1.0;
+    }
+}
+
+/// Number of days a cached response is reused when
+/// [`Setting::IgdbCacheTtl`] is unset.
+const DEFAULT_CACHE_TTL_DAYS: u64 = 7;
+
+/// Persistent cache of IGDB responses, stored in the database.
+#[derive(Debug)]
+pub struct IgdbResponseCache {
+    repository: IgdbCacheRepository,
+    settings_repository: SettingsRepository,
+}
+
+impl IgdbResponseCache {
+    pub fn new(repository: IgdbCacheRepository, settings_repository: SettingsRepository) -> Self {
+        IgdbResponseCache {
+            repository,
+            settings_repository,
+        }
+    }
+
+    /// Returns how long responses are reused, from [`Setting::IgdbCacheTtl`],
+    /// or `None` when the cache is disabled.
+    async fn ttl(&self) -> Option<Duration> {
+        let days = self
+            .settings_repository
+            .get(Setting::IgdbCacheTtl)
+            .await
+            .ok()
+            .flatten()
+            .and_then(|days| days.trim().parse().ok())
+            .unwrap_or(DEFAULT_CACHE_TTL_DAYS);
+
+        (days > 0).then(|| Duration::from_secs(days * 24 * 60 * 60))
+    }
+}
+
+/// Returns `true` if a response fetched at `fetched_at` is older than `ttl`.
+fn is_expired(fetched_at: i64, ttl: Duration) -> bool {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |now| now.as_secs() as i64);
+
+    now - fetched_at >= ttl.as_secs() as i64
+}
+
+/// IGDB requests made by the commands.
+///
+/// Implemented by [`IgdbApiClient`]; commands get it from the Tauri state as
+/// a `Mutex<Box<dyn IgdbApi>>`, so another implementation can stand in for it.
+#[async_trait]
+pub trait IgdbApi: Send {
+    /// Loads the genre dictionary used to resolve genre IDs.
+    fn set_genres(&mut self, genres: Vec<IgdbGenre>);
+
+    /// Loads the company dictionary used to resolve company IDs.
+    fn set_companies(&mut self, companies: Vec<IgdbCompany>);
+
+    /// Returns the genre dictionary if it was fetched again since the last
+    /// call, because a game had a genre missing from it.
+    fn take_refetched_genres(&mut self) -> Option<Vec<IgdbGenre>>;
+
+    /// Fetches the full list of genres.
+    async fn get_genres(&mut self) -> Result<Vec<IgdbGenre>, IgdbError>;
+
+    /// Fetches the metadata of a game identified by its Steam App ID.
+    async fn get_game(&mut self, steam_game_id: u64) -> Result<IgdbGame, IgdbError>;
+
+    /// Fetches the metadata of games given as
+    /// `(Steam App ID, IGDB game ID)` pairs.
+    async fn get_games_by_igdb_ids(
+        &mut self,
+        matches: Vec<(String, u64)>,
+    ) -> Result<Vec<IgdbGame>, IgdbError>;
+
+    /// Searches games by name.
+    async fn search_games(&mut self, name: &str) -> Result<Vec<IgdbSearchResult>, IgdbError>;
+
+    /// Fetches the name, release date, and cover of games.
+    async fn get_game_previews(
+        &mut self,
+        igdb_ids: &[u64],
+    ) -> Result<Vec<IgdbSearchResult>, IgdbError>;
+
+    /// Fetches the metadata of games identified by their Steam App IDs.
+    async fn get_games(&mut self, steam_games_ids: Vec<u64>) -> Result<Vec<IgdbGame>, IgdbError>;
+}
+
 /// Async client for the IGDB API.
 ///
 /// Uses a [`TwitchApiClient`] to obtain and refresh Bearer tokens, which are
@@ -114,6 +505,17 @@ pub enum IgdbError {
 pub struct IgdbApiClient {
     twitch_client: TwitchApiClient,
     client: Client,
+    /// Genre dictionary mapping IGDB genre IDs to names.
+    genres: HashMap<u64, String>,
+    /// Genres fetched again for an unknown genre ID, until taken to be
+    /// stored.
+    refetched_genres: Option<Vec<IgdbGenre>>,
+    /// Company dictionary mapping IGDB company IDs to names.
+    companies: HashMap<u64, String>,
+    rate_limiter: RateLimiter,
+    /// Cache of raw responses, `None` to always query IGDB.
+    response_cache: Option<IgdbResponseCache>,
+    retry_policy: RetryPolicy,
 }
 
 /// An IGDB external-game record that maps an IGDB game ID to a Steam UID.
@@ -147,57 +549,104 @@ impl IgdbApiClient {
                 .default_headers(headers)
                 .build()
                 .map_err(|e| IgdbError::Client(e.to_string()))?,
+            genres: HashMap::new(),
+            refetched_genres: None,
+            companies: HashMap::new(),
+            rate_limiter: RateLimiter::new(),
+            response_cache: None,
+            retry_policy: RetryPolicy::default(),
         })
     }
 
-    /// Fetches IGDB metadata for a single game identified by its Steam App ID.
-    ///
-    /// Resolves the Steam ID to an IGDB game ID via the external-games
-    /// endpoint, then retrieves the full game record including cover art,
-    /// genres, artworks, and company roles.
-    ///
-    /// # Errors
+    /// Sets the policy applied to requests failing with a transient error.
+    pub fn set_retry_policy(&mut self, retry_policy: RetryPolicy) {
+        self.retry_policy = retry_policy;
+    }
+
+    /// Sets the cache responses are read from and stored to.
+    pub fn set_response_cache(&mut self, cache: IgdbResponseCache) {
+        self.response_cache = Some(cache);
+    }
+
+    /// Resolves IGDB genre IDs to genres using the local dictionary.
     ///
-    /// Returns [`IgdbError::NoData`] if no IGDB entry is linked to the given
-    /// Steam App ID.
-    pub async fn get_game(&mut self, steam_game_id: u64) -> Result<IgdbGame, IgdbError> {
-        let steam_game = self.get_steam_game(steam_game_id).await?;
+    /// IDs missing from the dictionary are skipped.
+    fn resolve_genres(&self, genre_ids: Option<Vec<u64>>) -> Option<Vec<IgdbGenre>> {
+        genre_ids.map(|ids| {
+            ids.into_iter()
+                .filter_map(|id| {
+                    self.genres.get(&id).map(|name| IgdbGenre {
+                        id,
+                        name: name.clone(),
+                    })
+                })
+                .collect()
+        })
+    }
 
-        let store_id = steam_game_id.to_string();
+    /// Builds one [`IgdbGame`] per Steam store ID matched to a raw IGDB
+    /// record, or a single one without store ID if none is.
+    fn to_igdb_games(&self, game: IgdbGameInfo, store_ids: &[String]) -> Vec<IgdbGame> {
+        let Some((last, rest)) = store_ids.split_last() else {
+            return vec![self.to_igdb_game(game, None)];
+        };
 
-        let game_info = self.get_game_info(steam_game.id).await?;
+        let mut games: Vec<IgdbGame> = rest
+            .iter()
+            .map(|store_id| self.to_igdb_game(game.clone(), Some(store_id.clone())))
+            .collect();
+        games.push(self.to_igdb_game(game, Some(last.clone())));
 
-        let (publishers, developers) =
-            self.extract_game_companies(game_info.involved_companies, game_info.id);
+        games
+    }
+
+    /// Builds an [`IgdbGame`] from a raw IGDB record, resolving its genres
+    /// and companies.
+    fn to_igdb_game(&self, game: IgdbGameInfo, store_id: Option<String>) -> IgdbGame {
+        let (publishers, developers) = self.extract_game_companies(game.involved_companies);
 
-        let game = IgdbGame {
-            name: game_info.name,
-            store_id: Some(store_id),
-            summary: game_info.summary,
-            storyline: game_info.storyline,
-            genres: game_info.genres,
-            cover: game_info.cover,
+        IgdbGame {
+            name: game.name,
+            store_id,
+            summary: game.summary,
+            storyline: game.storyline,
+            genres: self.resolve_genres(game.genres),
+            cover: game.cover,
             publishers,
             developers,
-            artworks: game_info.artworks,
-            id: game_info.id,
-            release_date: game_info.first_release_date,
-        };
-
-        Ok(game)
+            artworks: game.artworks,
+            screenshots: game.screenshots,
+            videos: game.videos,
+            franchises: game.franchises,
+            collections: game.collections,
+            id: game.id,
+            release_date: game.first_release_date,
+            game_type: game.game_type,
+            parent_game: game.parent_game,
+            dlcs: game
+                .dlcs
+                .into_iter()
+                .chain(game.expansions)
+                .flatten()
+                .collect(),
+            similar_games: game.similar_games.unwrap_or_default(),
+            game_modes: category_names(game.game_modes),
+            player_perspectives: category_names(game.player_perspectives),
+            age_ratings: pegi_esrb_ratings(game.age_ratings),
+            alternative_names: category_names(game.alternative_names),
+            aggregated_rating: game.aggregated_rating,
+            rating_count: game.aggregated_rating_count,
+        }
     }
 
-    /// Splits a list of involved companies into publishers and developers for a given game.
+    /// Splits a game's involved companies into publishers and developers,
+    /// resolving their names with the local dictionary.
     ///
-    /// - A company is a **developer** if `game_id` appears in its `developed` list.
-    /// - A company is a **publisher** if `game_id` appears in its `published` list.
-    ///
-    /// A company can appear in both lists. Returns `(None, None)` when
-    /// `companies` is `None`.
+    /// A company can appear in both lists. IDs missing from the dictionary
+    /// are skipped. Returns `(None, None)` when `companies` is `None`.
     fn extract_game_companies(
         &self,
         companies: Option<Vec<IgdbInvolvedCompany>>,
-        game_id: u64,
     ) -> (Option<Vec<IgdbCompany>>, Option<Vec<IgdbCompany>>) {
         let mut publishers: Vec<IgdbCompany> = Vec::new();
         let mut developers: Vec<IgdbCompany> = Vec::new();
@@ -207,99 +656,93 @@ impl IgdbApiClient {
         }
 
         for involved in companies.iter().flatten() {
-            if let Some(published) = &involved.company.published {
-                if published.contains(&game_id) {
-                    publishers.push(involved.company.clone());
-                }
+            let Some(name) = self.companies.get(&involved.company) else {
+                continue;
+            };
+            let company = IgdbCompany {
+                id: involved.company as i64,
+                name: name.clone(),
+            };
+
+            if involved.publisher {
+                publishers.push(company.clone());
             }
 
-            if let Some(developed) = &involved.company.developed {
-                if developed.contains(&game_id) {
-                    developers.push(involved.company.clone());
-                }
+            if involved.developer {
+                developers.push(company);
             }
         }
 
         (Some(publishers), Some(developers))
     }
 
-    /// Fetches IGDB metadata for multiple games identified by their Steam App IDs.
+    /// Fetches the genres and companies of `games` missing from the local
+    /// dictionaries, so they can be resolved.
     ///
-    /// Resolves all Steam IDs to IGDB game IDs in a single batch request, then
-    /// retrieves full game records for all of them in a second batch request.
-    /// Games that have no corresponding IGDB entry are silently omitted from
-    /// the result.
-    pub async fn get_games(
-        &mut self,
-        steam_games_ids: Vec<u64>,
-    ) -> Result<Vec<IgdbGame>, IgdbError> {
-        let steam_games = self.get_steam_games(steam_games_ids).await?;
+    /// The genre list is short and fetched again in full, then kept for
+    /// [`IgdbApi::take_refetched_genres`]. Companies are fetched by ID,
+    /// through [`Self::multiquery`].
+    async fn load_missing_entries(&mut self, games: &[IgdbGameInfo]) -> Result<(), IgdbError> {
+        let has_unknown_genre = games
+            .iter()
+            .flat_map(|game| game.genres.iter().flatten())
+            .any(|id| !self.genres.contains_key(id));
 
-        let mut steam_ids_map = HashMap::new();
-
-        for game in &steam_games {
-            steam_ids_map.insert(game.id, game.uid.clone());
+        if has_unknown_genre {
+            let genres = self.get_genres().await?;
+            self.set_genres(genres.clone());
+            self.refetched_genres = Some(genres);
         }
 
-        let mut all_games_infos = Vec::new();
-        for chunck in steam_games.chunks(500) {
-            let games_infos = self
-                .get_games_infos(chunck.iter().map(|game| game.id).collect())
-                .await?;
-            all_games_infos.extend(games_infos);
+        let mut company_ids: Vec<u64> = games
+            .iter()
+            .flat_map(|game| game.involved_companies.iter().flatten())
+            .map(|involved| involved.company)
+            .filter(|id| !self.companies.contains_key(id))
+            .collect();
+        company_ids.sort_unstable();
+        company_ids.dedup();
+
+        if company_ids.is_empty() {
+            return Ok(());
         }
 
-        let parsed: Vec<_> = all_games_infos
-            .into_iter()
-            .map(|game| {
-                let store_id = steam_ids_map.get(&game.id).cloned();
-
-                let (publishers, developers) =
-                    self.extract_game_companies(game.involved_companies, game.id);
-
-                IgdbGame {
-                    name: game.name,
-                    store_id,
-                    summary: game.summary,
-                    storyline: game.storyline,
-                    genres: game.genres,
-                    cover: game.cover,
-                    developers,
-                    publishers,
-                    artworks: game.artworks,
-                    id: game.id,
-                    release_date: game.first_release_date,
-                }
+        let queries = company_ids
+            .chunks(MAX_QUERY_LIMIT)
+            .map(|chunk| {
+                IgdbQuery::fields(&["id", "name"])
+                    .where_id_in(chunk)
+                    .limit(chunk.len())
             })
             .collect();
 
-        Ok(parsed)
+        for company in self.multiquery::<IgdbCompany>("companies", queries).await? {
+            self.companies.insert(company.id as u64, company.name);
+        }
+
+        Ok(())
     }
 
     /// Resolves a batch of Steam App IDs to IGDB external-game records.
     ///
     /// Queries the IGDB `/external_games` endpoint filtering by
-    /// `external_game_source = 1` (Steam) and the provided UIDs.
+    /// `external_game_source = 1` (Steam) and the provided UIDs, through
+    /// [`Self::multiquery`].
     async fn get_steam_games(
         &mut self,
         game_ids: Vec<u64>,
     ) -> Result<Vec<IgdbAlternativeGame>, IgdbError> {
-        const URL: &str = "https://api.igdb.com/v4/external_games";
-        let steam_urls: Vec<_> = game_ids.iter().map(|id| format!(r#""{}""#, &id)).collect();
-
-        let query = format!(
-            "fields *;  where external_game_source = 1 & uid = ({}); limit {};",
-            steam_urls.join(","),
-            game_ids.len()
-        );
-
-        let res = self.request_with_retry(URL, &query).await?;
-
-        let body = res.text().await?;
-
-        let parsed = serde_json::from_str::<Vec<IgdbAlternativeGame>>(&body)?;
+        let queries = game_ids
+            .chunks(MAX_QUERY_LIMIT)
+            .map(|chunk| {
+                IgdbQuery::fields(&["game", "uid"])
+                    .where_eq("external_game_source", STEAM_SOURCE)
+                    .where_in("uid", chunk.iter().map(|id| id.to_string()))
+                    .limit(chunk.len())
+            })
+            .collect();
 
-        Ok(parsed)
+        self.multiquery("external_games", queries).await
     }
 
     /// Resolves a single Steam App ID to its IGDB external-game record.
@@ -312,13 +755,14 @@ impl IgdbApiClient {
     /// Steam App ID.
     async fn get_steam_game(&mut self, game_id: u64) -> Result<IgdbAlternativeGame, IgdbError> {
         const URL: &str = "https://api.igdb.com/v4/external_games";
-        let query = format!(
-            "fields *;  where external_game_source = 1 & url = \"https://store.steampowered.com/app/{}\"; limit 1;",
-            game_id
-        );
-        let res = self.request_with_retry(URL, &query).await?;
-
-        let body = res.text().await?;
+        let query = IgdbQuery::fields(&["*"])
+            .where_eq("external_game_source", STEAM_SOURCE)
+            .where_eq(
+                "url",
+                format!("https://store.steampowered.com/app/{}", game_id),
+            )
+            .limit(1);
+        let body = self.query(URL, &query).await?;
 
         let mut parsed = serde_json::from_str::<Vec<IgdbAlternativeGame>>(&body)?;
 
@@ -329,21 +773,17 @@ impl IgdbApiClient {
 
     /// Fetches the full game record from IGDB for a single IGDB game ID.
     ///
-    /// Requests all standard fields plus nested `genres`, `artworks`,
-    /// `cover`, and `involved_companies` in one query.
+    /// Requests the [`GAME_FIELDS`] in one query.
     ///
     /// # Errors
     ///
     /// Returns [`IgdbError::NoData`] if no game with the given ID exists.
     async fn get_game_info(&mut self, igdb_game_id: u64) -> Result<IgdbGameInfo, IgdbError> {
         const URL: &str = "https://api.igdb.com/v4/games";
-        let query = format!(
-            "fields *, genres.name, artworks.image_id, involved_companies.company.*, cover.image_id; where id = {}; limit 1;",
-            igdb_game_id
-        );
-        let res = self.request_with_retry(URL, &query).await?;
-
-        let body = res.text().await?;
+        let query = IgdbQuery::fields(GAME_FIELDS)
+            .where_id_in(&[igdb_game_id])
+            .limit(1);
+        let body = self.query(URL, &query).await?;
 
         let mut parsed = serde_json::from_str::<Vec<IgdbGameInfo>>(&body)?;
 
@@ -354,27 +794,51 @@ impl IgdbApiClient {
 
     /// Fetches full game records from IGDB for a batch of IGDB game IDs.
     ///
-    /// Requests all standard fields plus nested `genres`, `artworks`,
-    /// `cover`, and `involved_companies` in a single query.
+    /// Requests the [`GAME_FIELDS`], through [`Self::multiquery`].
     async fn get_games_infos(
         &mut self,
         igdb_game_ids: Vec<u64>,
     ) -> Result<Vec<IgdbGameInfo>, IgdbError> {
-        const URL: &str = "https://api.igdb.com/v4/games";
-        let ids: Vec<_> = igdb_game_ids.iter().map(|id| id.to_string()).collect();
-        let query = format!(
-            r#"fields *, genres.name, artworks.image_id, cover.image_id, involved_companies.company.*; where id = ({}); limit {};"#,
-            ids.join(","),
-            igdb_game_ids.len()
-        );
+        let queries = igdb_game_ids
+            .chunks(MAX_QUERY_LIMIT)
+            .map(|chunk| {
+                IgdbQuery::fields(GAME_FIELDS)
+                    .where_id_in(chunk)
+                    .limit(chunk.len())
+            })
+            .collect();
+
+        self.multiquery("games", queries).await
+    }
+
+    /// Runs queries against an IGDB endpoint through the `/multiquery`
+    /// endpoint, [`MAX_MULTIQUERY_SIZE`] queries per request, and returns
+    /// all their results.
+    ///
+    /// Each query must fit in [`MAX_QUERY_LIMIT`] records.
+    async fn multiquery<T: DeserializeOwned>(
+        &mut self,
+        endpoint: &str,
+        queries: Vec<IgdbQuery>,
+    ) -> Result<Vec<T>, IgdbError> {
+        const URL: &str = "https://api.igdb.com/v4/multiquery";
+        let mut results = Vec::new();
 
-        let res = self.request_with_retry(URL, &query).await?;
+        for batch in queries.chunks(MAX_MULTIQUERY_SIZE) {
+            let body: String = batch
+                .iter()
+                .enumerate()
+                .map(|(index, query)| format!(r#"query {} "{}" {{ {} }};"#, endpoint, index, query))
+                .collect();
 
-        let body = res.text().await?;
+            let body = self.query(URL, &body).await?;
 
-        let parsed = serde_json::from_str::<Vec<IgdbGameInfo>>(&body)?;
+            let parsed = serde_json::from_str::<Vec<IgdbMultiqueryResult<T>>>(&body)?;
 
-        Ok(parsed)
+            results.extend(parsed.into_iter().flat_map(|query| query.result));
+        }
+
+        Ok(results)
     }
 
     /// Returns a valid Twitch access token, refreshing it if one is not cached or is expired.
@@ -386,32 +850,379 @@ impl IgdbApiClient {
         Ok(self.twitch_client.refresh_access_token().await?.to_string())
     }
 
+    /// Sends an Apicalypse `query` to an IGDB endpoint and returns the
+    /// response body, going through the response cache when one is set.
+    ///
+    /// Responses younger than the cache lifetime are returned without a
+    /// request. When the request fails, an older cached response is returned
+    /// instead, if any, so enrichment partially works offline.
+    #[tracing::instrument(skip(self, query), err)]
+    async fn query(&mut self, url: &str, query: impl Display) -> Result<String, IgdbError> {
+        let query = query.to_string();
+        let request = format!("{}\n{}", url, query);
+
+        let (ttl, cached) = match &self.response_cache {
+            Some(cache) => match cache.ttl().await {
+                Some(ttl) => (
+                    Some(ttl),
+                    cache.repository.get(&request).await.ok().flatten(),
+                ),
+                None => (None, None),
+            },
+            None => (None, None),
+        };
+
+        if let (Some(ttl), Some(cached)) = (ttl, &cached) {
+            if !is_expired(cached.fetched_at, ttl) {
+                return Ok(cached.body.clone());
+            }
+        }
+
+        let body = match self.request_with_retry(url, &query).await {
+            Ok(res) => res.text().await.map_err(IgdbError::from),
+            Err(e) => Err(e),
+        };
+
+        match body {
+            Ok(body) => {
+                if let (Some(cache), Some(_)) = (&self.response_cache, ttl) {
+                    // Best effort: a failed write only costs a request later
+                    let _ = cache.repository.set(&request, &body).await;
+                }
+
+                Ok(body)
+            }
+            Err(e) => cached.map(|cached| cached.body).ok_or(e),
+        }
+    }
+
     /// Sends a POST request to an IGDB endpoint with an Apicalypse `query` body.
     ///
-    /// If the first attempt returns `401 Unauthorized`, the Twitch token is
-    /// refreshed and the request is retried once with the new token.
+    /// Requests are spaced to IGDB's rate limit. If the first attempt returns
+    /// `401 Unauthorized`, the Twitch token is refreshed and the request is
+    /// retried once with the new token. Requests rejected with
+    /// `429 Too Many Requests` are retried after the `Retry-After` delay, up
+    /// to [`MAX_RATE_LIMIT_RETRIES`] times. Connection failures, timeouts,
+    /// and `5xx` responses are retried according to the [`RetryPolicy`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`IgdbError::RateLimited`] when the retries are exhausted,
+    /// and [`IgdbError::Request`] for other error statuses.
     async fn request_with_retry(&mut self, url: &str, query: &str) -> Result<Response, IgdbError> {
-        let token = self.get_twitch_access_token().await?;
-
-        let response = self
-            .client
-            .post(url)
-            .bearer_auth(&token)
-            .body(query.to_string())
-            .send()
+        let mut token_refreshed = false;
+        let mut rate_limit_retries = 0;
+
+        loop {
+            self.rate_limiter.acquire().await;
+            let token = self.get_twitch_access_token().await?;
+
+            let response = self
+                .retry_policy
+                .send(
+                    self.client
+                        .post(url)
+                        .bearer_auth(&token)
+                        .body(query.to_string()),
+                )
+                .await?;
+
+            match response.status() {
+                StatusCode::UNAUTHORIZED if !token_refreshed => {
+                    self.twitch_client.refresh_access_token().await?;
+                    token_refreshed = true;
+                }
+                StatusCode::TOO_MANY_REQUESTS if rate_limit_retries < MAX_RATE_LIMIT_RETRIES => {
+                    rate_limit_retries += 1;
+                    tokio::time::sleep(retry_after(&response)).await;
+                }
+                StatusCode::TOO_MANY_REQUESTS => return Err(IgdbError::RateLimited),
+                _ => return Ok(response.error_for_status()?),
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl IgdbApi for IgdbApiClient {
+    /// Loads the genre dictionary used to resolve genre IDs during enrichment.
+    fn set_genres(&mut self, genres: Vec<IgdbGenre>) {
+        self.genres = genres
+            .into_iter()
+            .map(|genre| (genre.id, genre.name))
+            .collect();
+    }
+
+    /// Loads the company dictionary used to resolve company IDs during
+    /// enrichment.
+    fn set_companies(&mut self, companies: Vec<IgdbCompany>) {
+        self.companies = companies
+            .into_iter()
+            .map(|company| (company.id as u64, company.name))
+            .collect();
+    }
+
+    fn take_refetched_genres(&mut self) -> Option<Vec<IgdbGenre>> {
+        self.refetched_genres.take()
+    }
+
+    /// Fetches the full list of genres from the IGDB `/genres` endpoint.
+    ///
+    /// IGDB has a few dozen genres, so a single request covers all of them.
+    #[tracing::instrument(skip(self), err)]
+    async fn get_genres(&mut self) -> Result<Vec<IgdbGenre>, IgdbError> {
+        const URL: &str = "https://api.igdb.com/v4/genres";
+        let query = IgdbQuery::fields(&["id", "name"]).limit(MAX_QUERY_LIMIT);
+        let body = self.query(URL, &query).await?;
+
+        let parsed = serde_json::from_str::<Vec<IgdbGenre>>(&body)?;
+
+        Ok(parsed)
+    }
+
+    /// Fetches IGDB metadata for a single game identified by its Steam App ID.
+    ///
+    /// Resolves the Steam ID to an IGDB game ID via the external-games
+    /// endpoint, then retrieves the full game record including cover art,
+    /// genres, artworks, screenshots, videos, franchises, and company roles.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`IgdbError::NoData`] if no IGDB entry is linked to the given
+    /// Steam App ID.
+    #[tracing::instrument(skip(self), err)]
+    async fn get_game(&mut self, steam_game_id: u64) -> Result<IgdbGame, IgdbError> {
+        let steam_game = self.get_steam_game(steam_game_id).await?;
+
+        let store_id = steam_game_id.to_string();
+
+        let game_info = self.get_game_info(steam_game.id).await?;
+        self.load_missing_entries(std::slice::from_ref(&game_info))
             .await?;
 
-        if response.status() == StatusCode::UNAUTHORIZED {
-            let new_token = self.twitch_client.refresh_access_token().await?;
-            Ok(self
-                .client
-                .post(url)
-                .bearer_auth(&new_token)
-                .body(query.to_string())
-                .send()
-                .await?)
-        } else {
-            Ok(response)
+        Ok(self.to_igdb_game(game_info, Some(store_id)))
+    }
+
+    /// Fetches IGDB metadata for games chosen by the user, given as
+    /// `(Steam App ID, IGDB game ID)` pairs.
+    ///
+    /// Skips the Steam ID resolution of [`Self::get_games`]. IGDB IDs
+    /// without a game are silently omitted from the result, and an IGDB game
+    /// matched by several Steam App IDs is returned once for each.
+    #[tracing::instrument(skip_all, fields(count = matches.len()), err)]
+    async fn get_games_by_igdb_ids(
+        &mut self,
+        matches: Vec<(String, u64)>,
+    ) -> Result<Vec<IgdbGame>, IgdbError> {
+        if matches.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut store_ids: HashMap<u64, Vec<String>> = HashMap::new();
+
+        for (store_id, igdb_id) in matches {
+            store_ids.entry(igdb_id).or_default().push(store_id);
+        }
+
+        let games = self
+            .get_games_infos(store_ids.keys().copied().collect())
+            .await?;
+        self.load_missing_entries(&games).await?;
+
+        Ok(games
+            .into_iter()
+            .flat_map(|game| {
+                let store_ids = store_ids.get(&game.id).map(Vec::as_slice);
+                self.to_igdb_games(game, store_ids.unwrap_or_default())
+            })
+            .collect())
+    }
+
+    /// Searches IGDB games by name, for the user to pick a match.
+    ///
+    /// Returns at most 20 candidates, in IGDB relevance order.
+    #[tracing::instrument(skip(self), err)]
+    async fn search_games(&mut self, name: &str) -> Result<Vec<IgdbSearchResult>, IgdbError> {
+        const URL: &str = "https://api.igdb.com/v4/games";
+        let query = IgdbQuery::fields(PREVIEW_FIELDS).search(name).limit(20);
+
+        let body = self.query(URL, &query).await?;
+
+        let parsed = serde_json::from_str::<Vec<IgdbSearchResult>>(&body)?;
+
+        Ok(parsed)
+    }
+
+    /// Fetches the name, release date, and cover of IGDB games, in the order
+    /// of `igdb_ids`. Unknown IDs are left out, and at most
+    /// [`MAX_QUERY_LIMIT`] previews are returned.
+    #[tracing::instrument(skip_all, fields(count = igdb_ids.len()), err)]
+    async fn get_game_previews(
+        &mut self,
+        igdb_ids: &[u64],
+    ) -> Result<Vec<IgdbSearchResult>, IgdbError> {
+        if igdb_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        const URL: &str = "https://api.igdb.com/v4/games";
+        let query = IgdbQuery::fields(PREVIEW_FIELDS)
+            .where_id_in(igdb_ids)
+            .limit(igdb_ids.len().min(MAX_QUERY_LIMIT));
+
+        let body = self.query(URL, &query).await?;
+
+        let mut parsed = serde_json::from_str::<Vec<IgdbSearchResult>>(&body)?;
+        parsed.sort_by_key(|game| igdb_ids.iter().position(|id| *id == game.id));
+
+        Ok(parsed)
+    }
+
+    /// Fetches IGDB metadata for multiple games identified by their Steam App IDs.
+    ///
+    /// Resolves all Steam IDs to IGDB game IDs in batches, then retrieves full
+    /// game records for all of them in batches, so a library of a few
+    /// thousand games needs a handful of requests. Games that have no
+    /// corresponding IGDB entry are silently omitted from the result, and an
+    /// IGDB game several Steam games resolve to, such as editions of the
+    /// same game, is returned once for each.
+    #[tracing::instrument(skip_all, fields(count = steam_games_ids.len()), err)]
+    async fn get_games(&mut self, steam_games_ids: Vec<u64>) -> Result<Vec<IgdbGame>, IgdbError> {
+        let steam_games = self.get_steam_games(steam_games_ids).await?;
+
+        let mut steam_ids_map: HashMap<u64, Vec<String>> = HashMap::new();
+
+        for game in &steam_games {
+            steam_ids_map
+                .entry(game.id)
+                .or_default()
+                .push(game.uid.clone());
+        }
+
+        let all_games_infos = self
+            .get_games_infos(steam_ids_map.keys().copied().collect())
+            .await?;
+        self.load_missing_entries(&all_games_infos).await?;
+
+        let parsed: Vec<_> = all_games_infos
+            .into_iter()
+            .flat_map(|game| {
+                let store_ids = steam_ids_map.get(&game.id).map(Vec::as_slice);
+                self.to_igdb_games(game, store_ids.unwrap_or_default())
+            })
+            .collect();
+
+        Ok(parsed)
+    }
+}
+
+/// Returns the delay requested by the `Retry-After` header of a rate-limited
+/// response, in seconds, or [`DEFAULT_RETRY_AFTER`] when it is missing.
+fn retry_after(response: &Response) -> Duration {
+    response
+        .headers()
+        .get(RETRY_AFTER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().parse().ok())
+        .map(Duration::from_secs)
+        .unwrap_or(DEFAULT_RETRY_AFTER)
+}
+
+#[cfg(test)]
+pub(crate) mod mock {
+    use async_trait::async_trait;
+
+    use super::*;
+
+    /// Genres served by [`MockIgdbApi`].
+    pub const GENRES: [(u64, &str); 2] = [(5, "Shooter"), (12, "Role-playing (RPG)")];
+
+    /// An [`IgdbApi`] matching every Steam game to an IGDB game of the same
+    /// ID, named `IGDB <id>`, without network access. Games have no images,
+    /// so refreshes download nothing.
+    #[derive(Default)]
+    pub struct MockIgdbApi {
+        genres: HashMap<u64, String>,
+    }
+
+    impl MockIgdbApi {
+        fn game(&self, igdb_id: u64, store_id: String) -> IgdbGame {
+            IgdbGame {
+                id: igdb_id,
+                name: format!("IGDB {igdb_id}"),
+                store_id: Some(store_id),
+                genres: Some(
+                    self.genres
+                        .iter()
+                        .map(|(id, name)| IgdbGenre {
+                            id: *id,
+                            name: name.clone(),
+                        })
+                        .collect(),
+                ),
+                ..IgdbGame::default()
+            }
+        }
+    }
+
+    #[async_trait]
+    impl IgdbApi for MockIgdbApi {
+        fn set_genres(&mut self, genres: Vec<IgdbGenre>) {
+            self.genres = genres
+                .into_iter()
+                .map(|genre| (genre.id, genre.name))
+                .collect();
+        }
+
+        fn set_companies(&mut self, _companies: Vec<IgdbCompany>) {}
+
+        fn take_refetched_genres(&mut self) -> Option<Vec<IgdbGenre>> {
+            None
+        }
+
+        async fn get_genres(&mut self) -> Result<Vec<IgdbGenre>, IgdbError> {
+            Ok(GENRES
+                .iter()
+                .map(|(id, name)| IgdbGenre {
+                    id: *id,
+                    name: name.to_string(),
+                })
+                .collect())
+        }
+
+        async fn get_game(&mut self, steam_game_id: u64) -> Result<IgdbGame, IgdbError> {
+            Ok(self.game(steam_game_id, steam_game_id.to_string()))
+        }
+
+        async fn get_games_by_igdb_ids(
+            &mut self,
+            matches: Vec<(String, u64)>,
+        ) -> Result<Vec<IgdbGame>, IgdbError> {
+            Ok(matches
+                .into_iter()
+                .map(|(store_id, igdb_id)| self.game(igdb_id, store_id))
+                .collect())
+        }
+
+        async fn search_games(&mut self, _name: &str) -> Result<Vec<IgdbSearchResult>, IgdbError> {
+            Ok(Vec::new())
+        }
+
+        async fn get_game_previews(
+            &mut self,
+            _igdb_ids: &[u64],
+        ) -> Result<Vec<IgdbSearchResult>, IgdbError> {
+            Ok(Vec::new())
+        }
+
+        async fn get_games(
+            &mut self,
+            steam_games_ids: Vec<u64>,
+        ) -> Result<Vec<IgdbGame>, IgdbError> {
+            Ok(steam_games_ids
+                .into_iter()
+                .map(|appid| self.game(appid, appid.to_string()))
+                .collect())
         }
     }
 }
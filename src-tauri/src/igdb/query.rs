@@ -0,0 +1,131 @@
+//! Builder for IGDB Apicalypse queries.
+//!
+//! Values are escaped when the query is built, so names containing quotes or
+//! backslashes cannot break out of their string literal.
+
+use std::fmt::{self, Display, Formatter};
+
+/// A value compared against a field in a `where` clause.
+#[derive(Debug, Clone)]
+pub enum IgdbValue {
+    Number(u64),
+    Text(String),
+}
+
+impl From<u64> for IgdbValue {
+    fn from(value: u64) -> Self {
+        IgdbValue::Number(value)
+    }
+}
+
+impl From<String> for IgdbValue {
+    fn from(value: String) -> Self {
+        IgdbValue::Text(value)
+    }
+}
+
+impl From<&str> for IgdbValue {
+    fn from(value: &str) -> Self {
+        IgdbValue::Text(value.to_string())
+    }
+}
+
+impl Display for IgdbValue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            IgdbValue::Number(value) => write!(f, "{}", value),
+            IgdbValue::Text(value) => write!(f, "\"{}\"", escape(value)),
+        }
+    }
+}
+
+/// An Apicalypse query, e.g.
+/// `IgdbQuery::fields(&["name"]).where_id_in(&[1, 2]).limit(2)`.
+///
+/// Conditions are combined with `&`. The query text is produced by its
+/// [`Display`] implementation.
+#[derive(Debug, Clone)]
+pub struct IgdbQuery {
+    search: Option<String>,
+    fields: Vec<String>,
+    conditions: Vec<String>,
+    limit: Option<usize>,
+}
+
+impl IgdbQuery {
+    /// Starts a query returning the given fields. Nested fields use the
+    /// dotted IGDB syntax, e.g. `cover.image_id`.
+    pub fn fields(fields: &[&str]) -> Self {
+        IgdbQuery {
+            search: None,
+            fields: fields.iter().map(|field| field.to_string()).collect(),
+            conditions: Vec::new(),
+            limit: None,
+        }
+    }
+
+    /// Searches records by name, in IGDB relevance order.
+    pub fn search(mut self, term: &str) -> Self {
+        self.search = Some(term.to_string());
+        self
+    }
+
+    /// Keeps records whose `field` equals `value`.
+    pub fn where_eq(mut self, field: &str, value: impl Into<IgdbValue>) -> Self {
+        self.conditions
+            .push(format!("{} = {}", field, value.into()));
+        self
+    }
+
+    /// Keeps records whose `field` equals one of `values`.
+    pub fn where_in<V: Into<IgdbValue>>(
+        mut self,
+        field: &str,
+        values: impl IntoIterator<Item = V>,
+    ) -> Self {
+        let values: Vec<String> = values
+            .into_iter()
+            .map(|value| value.into().to_string())
+            .collect();
+
+        self.conditions
+            .push(format!("{} = ({})", field, values.join(",")));
+        self
+    }
+
+    /// Keeps the records with the given IDs.
+    pub fn where_id_in(self, ids: &[u64]) -> Self {
+        self.where_in("id", ids.iter().copied())
+    }
+
+    /// Returns at most `limit` records.
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+}
+
+impl Display for IgdbQuery {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if let Some(term) = &self.search {
+            write!(f, "search \"{}\"; ", escape(term))?;
+        }
+
+        write!(f, "fields {};", self.fields.join(", "))?;
+
+        if !self.conditions.is_empty() {
+            write!(f, " where {};", self.conditions.join(" & "))?;
+        }
+
+        if let Some(limit) = self.limit {
+            write!(f, " limit {};", limit)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Escapes backslashes and double quotes for an Apicalypse string literal.
+fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
@@ -1,32 +1,105 @@
-use std::env;
-
-use tauri::{async_runtime::Mutex, Manager};
+use tauri::{async_runtime::Mutex, AppHandle, Manager, RunEvent};
 
 use crate::{
-    assets::AssetManager,
-    client::steam::SteamClient,
+    assets::{AssetManager, DEFAULT_DOWNLOAD_CONCURRENCY},
+    cache::GameCache,
+    client::{steam::SteamClient, watcher::SteamLibraryWatcher},
     config::{RocadeConfig, RocadeConfigError},
-    db::{game::GameRepository, DatabaseState},
-    igdb::IgdbApiClient,
-    service::steam::SteamApiClient,
+    db::{
+        achievement::AchievementRepository,
+        asset_validator::AssetValidatorRepository,
+        collection::CollectionRepository,
+        company::CompanyRepository,
+        custom::CustomGameRepository,
+        deals::DealRepository,
+        game::GameRepository,
+        genre::GenreRepository,
+        history::HistoryRepository,
+        hltb::CompletionTimeRepository,
+        hook::HookRepository,
+        igdb_cache::IgdbCacheRepository,
+        launch::LaunchSettingsRepository,
+        news::NewsRepository,
+        playtime::PlaytimeRepository,
+        protondb::ProtonDbRepository,
+        refresh_report::RefreshReportRepository,
+        rule::ExclusionRuleRepository,
+        session::SessionRepository,
+        settings::{Setting, SettingsRepository},
+        subscription::SubscriptionRepository,
+        translation::TranslationRepository,
+        whats_new::WhatsNewRepository,
+        DatabaseState,
+    },
+    discord::DiscordPresence,
+    igdb::{IgdbApi, IgdbApiClient, IgdbResponseCache},
+    logging::LogFiles,
+    retry::RetryPolicy,
+    secrets::SecretStore,
+    service::{
+        deals::DealsClient,
+        hltb::HltbClient,
+        protondb::{ProtonDbApi, ProtonDbClient},
+        steam::{SteamApi, SteamApiClient},
+        steam_store::{SteamStoreApi, SteamStoreClient},
+    },
+    shutdown::RefreshTracker,
     twitch::TwitchApiClient,
 };
 
+mod archive;
 mod assets;
+mod bundle;
+mod cache;
 mod client;
 mod commands;
 mod config;
+mod controller;
 mod db;
+mod discord;
+mod export;
+mod hooks;
 mod igdb;
+mod logging;
+mod protocol;
+mod retry;
+mod rules;
+mod secrets;
 mod service;
+mod shutdown;
 mod twitch;
+mod wine;
 
-pub use commands::{get_game, get_games, install_game, refresh_games, uninstall_game};
+pub use commands::{
+    add_custom_artwork, add_custom_game, add_exclusion_rule, add_game_to_collection, add_hook,
+    add_subscription, add_subscription_game, benchmark_refresh, check_db_integrity, complete_setup,
+    create_collection, create_full_backup, create_wine_prefix, delete_exclusion_rule, delete_hook,
+    delete_subscription, dismiss_whats_new, edit_game_metadata, end_subscription,
+    ensure_game_artworks, export_custom_games, export_library, find_duplicate_games,
+    get_all_developers, get_all_genres, get_all_release_years, get_cache_stats, get_collections,
+    get_common_games, get_completion_times, get_deals, get_exclusion_rules, get_franchise_games,
+    get_friend_libraries, get_game, get_game_achievements, get_game_news, get_game_notes,
+    get_games, get_games_window, get_history, get_hooks, get_install_progress,
+    get_last_refresh_report, get_launch_settings, get_mature_content_filter, get_play_sessions,
+    get_playtime_history, get_profile_summary, get_recent_logs, get_recently_added,
+    get_recently_played, get_setting, get_similar_games, get_subscription_report,
+    get_subscriptions, get_unmatched_games, get_whats_new, get_wine_config, import_custom_games,
+    import_library, install_game, is_setup_complete, launch_game, list_backups, merge_games,
+    open_wine_prefix, pick_random_game, pin_game, play_soundtrack, prefetch_game,
+    preview_exclusion_rule, rate_game, refresh_games, rematch_all, rematch_game,
+    remove_game_from_collection, remove_subscription_game, rename_collection, reorder_pins,
+    restore_backup, restore_full_backup, run_db_maintenance, search_igdb, set_artwork_source,
+    set_custom_cover, set_game_hidden, set_game_notes, set_game_status, set_launch_settings,
+    set_mature_content_filter, set_setting, set_steamgriddb_api_key, set_wine_config,
+    sync_achievements, sync_player_stats, toggle_favorite, undo_last_refresh, uninstall_game,
+    validate_steam_credentials, validate_twitch_credentials, verify_assets,
+};
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     tauri::Builder::default()
         .plugin(tauri_plugin_http::init())
+        .register_asynchronous_uri_scheme_protocol(protocol::SCHEME, protocol::handle_asset_request)
         .setup(|app| {
             #[cfg(debug_assertions)]
             {
@@ -37,41 +110,124 @@ pub fn run() {
 
             dotenvy::dotenv().ok();
 
-            let rocade_config = RocadeConfig {
-                steam_api_key: env::var("STEAM_API_KEY").map_err(|_| {
-                    RocadeConfigError::EnvError("STEAM_API_KEY not set".to_string())
-                })?,
-                steam_profile_id: env::var("STEAM_PROFILE_ID").map_err(|_| {
-                    RocadeConfigError::EnvError("STEAM_PROFILE_ID not set".to_string())
-                })?,
-                twitch_client_id: env::var("TWITCH_CLIENT_ID").map_err(|_| {
-                    RocadeConfigError::EnvError("TWITCH_CLIENT_ID not set".to_string())
-                })?,
-                twitch_client_secret: env::var("TWITCH_CLIENT_SECRET").map_err(|_| {
-                    RocadeConfigError::EnvError("TWITCH_CLIENT_SECRET not set".to_string())
-                })?,
-            };
-
-            tauri::async_runtime::block_on(async {
+            let secret_store = SecretStore::new();
+
+            let rocade_config = tauri::async_runtime::block_on(async {
                 let app_dir = app.app_handle().path().app_data_dir().map_err(|_| {
                     RocadeConfigError::ConfigError("unable to get app directory".to_string())
                 })?;
+                let log_files = LogFiles::init(&app_dir);
                 let db_state = db::DatabaseState::new(app_dir.clone()).await?;
                 let game_repository = GameRepository::new(db_state.pool.clone());
+                let genre_repository = GenreRepository::new(db_state.pool.clone());
+                let company_repository = CompanyRepository::new(db_state.pool.clone());
+                let settings_repository = SettingsRepository::new(db_state.pool.clone());
+                let rule_repository = ExclusionRuleRepository::new(db_state.pool.clone());
+                let session_repository = SessionRepository::new(db_state.pool.clone());
+                let hook_repository = HookRepository::new(db_state.pool.clone());
+                let achievement_repository = AchievementRepository::new(db_state.pool.clone());
+                let custom_game_repository = CustomGameRepository::new(db_state.pool.clone());
+                let subscription_repository = SubscriptionRepository::new(db_state.pool.clone());
+                let collection_repository = CollectionRepository::new(db_state.pool.clone());
+                let whats_new_repository = WhatsNewRepository::new(db_state.pool.clone());
+                let translation_repository = TranslationRepository::new(db_state.pool.clone());
+                let news_repository = NewsRepository::new(db_state.pool.clone());
+                let playtime_repository = PlaytimeRepository::new(db_state.pool.clone());
+                let deal_repository = DealRepository::new(db_state.pool.clone());
+                let protondb_repository = ProtonDbRepository::new(db_state.pool.clone());
+                let completion_time_repository =
+                    CompletionTimeRepository::new(db_state.pool.clone());
+                let launch_settings_repository =
+                    LaunchSettingsRepository::new(db_state.pool.clone());
+                let asset_validator_repository =
+                    AssetValidatorRepository::new(db_state.pool.clone());
+                let refresh_report_repository = RefreshReportRepository::new(db_state.pool.clone());
+                let history_repository = HistoryRepository::new(db_state.pool.clone());
+
+                // A missing configuration is not fatal: the frontend runs the
+                // setup wizard, which calls `complete_setup`.
+                let rocade_config = RocadeConfig::load(&secret_store, &settings_repository)
+                    .await
+                    .ok();
+
+                let text_only = settings_repository
+                    .is_enabled(Setting::LowGraphicsMode)
+                    .await?;
+                game_repository.set_text_only(text_only);
+
                 app.manage::<DatabaseState>(db_state);
+                app.manage::<LogFiles>(log_files);
                 app.manage::<GameRepository>(game_repository);
+                app.manage::<GameCache>(GameCache::new());
+                app.manage::<RefreshTracker>(RefreshTracker::new());
+                app.manage::<DiscordPresence>(DiscordPresence::new());
+                app.manage::<GenreRepository>(genre_repository);
+                app.manage::<CompanyRepository>(company_repository);
 
-                let asset_manager = AssetManager::new(app_dir).await.map_err(|e| {
-                    RocadeConfigError::ConfigError(format!("failed to initialize asset manager: {}", e))
-                })?;
+                let retry_policy = RetryPolicy::load(&settings_repository).await?;
+                let download_concurrency = settings_repository
+                    .get(Setting::DownloadConcurrency)
+                    .await?
+                    .and_then(|concurrency| concurrency.trim().parse().ok())
+                    .unwrap_or(DEFAULT_DOWNLOAD_CONCURRENCY);
+
+                let mut asset_manager =
+                    AssetManager::new(app_dir, download_concurrency, retry_policy)
+                        .await
+                        .map_err(|e| {
+                            RocadeConfigError::ConfigError(format!(
+                                "failed to initialize asset manager: {}",
+                                e
+                            ))
+                        })?;
+                asset_manager.set_text_only(text_only);
+                asset_manager.set_validator_repository(asset_validator_repository);
+                asset_manager.set_app_handle(app.handle().clone());
+                asset_manager.set_webp_quality(
+                    settings_repository
+                        .get(Setting::WebpQuality)
+                        .await?
+                        .and_then(|quality| quality.parse().ok()),
+                );
+                asset_manager.set_download_rate_limit(
+                    settings_repository
+                        .get(Setting::DownloadRateLimit)
+                        .await?
+                        .and_then(|limit| limit.trim().parse().ok()),
+                );
                 app.manage::<AssetManager>(asset_manager);
+                app.manage::<RetryPolicy>(retry_policy);
+                app.manage::<SettingsRepository>(settings_repository);
+                app.manage::<ExclusionRuleRepository>(rule_repository);
+                app.manage::<SessionRepository>(session_repository);
+                app.manage::<HookRepository>(hook_repository);
+                app.manage::<AchievementRepository>(achievement_repository);
+                app.manage::<CustomGameRepository>(custom_game_repository);
+                app.manage::<LaunchSettingsRepository>(launch_settings_repository);
+                app.manage::<SubscriptionRepository>(subscription_repository);
+                app.manage::<CollectionRepository>(collection_repository);
+                app.manage::<WhatsNewRepository>(whats_new_repository);
+                app.manage::<NewsRepository>(news_repository);
+                app.manage::<PlaytimeRepository>(playtime_repository);
+                app.manage::<Box<dyn SteamStoreApi>>(Box::new(SteamStoreClient::new()));
+                app.manage::<TranslationRepository>(translation_repository);
+                app.manage::<DealRepository>(deal_repository);
+                app.manage::<RefreshReportRepository>(refresh_report_repository);
+                app.manage::<HistoryRepository>(history_repository);
+                app.manage::<DealsClient>(DealsClient::new());
+                app.manage::<CompletionTimeRepository>(completion_time_repository);
+                app.manage::<HltbClient>(HltbClient::new());
+                app.manage::<ProtonDbRepository>(protondb_repository);
+                app.manage::<Box<dyn ProtonDbApi>>(Box::new(ProtonDbClient::new()));
 
-                Ok::<(), RocadeConfigError>(())
+                Ok::<_, RocadeConfigError>(rocade_config)
             })?;
 
-            let steam_api_client =
-                SteamApiClient::new(rocade_config.steam_api_key, rocade_config.steam_profile_id);
-            app.manage::<SteamApiClient>(steam_api_client);
+            app.manage::<SecretStore>(secret_store);
+
+            if let Some(rocade_config) = rocade_config {
+                manage_api_clients(app.handle(), rocade_config)?;
+            }
 
             let home_path = app.path().home_dir().map_err(|_| {
                 RocadeConfigError::ConfigError("unable to get home directory".to_string())
@@ -100,19 +256,21 @@ pub fn run() {
                 }
             }
 
-            let steam_client = SteamClient::new(steam_path);
+            let registry_path = home_path.join(".steam").join("registry.vdf");
+
+            let steam_client = SteamClient::new(steam_path, registry_path);
             app.manage::<SteamClient>(steam_client);
 
-            let twitch_api_client = TwitchApiClient::new(
-                rocade_config.twitch_client_id,
-                rocade_config.twitch_client_secret,
-            );
-            let igdb_api_client = Mutex::new(
-                IgdbApiClient::new(twitch_api_client)
-                    .map_err(|e| RocadeConfigError::ConfigError(e.to_string()))?,
-            );
+            // Without Steam, or without access to its libraries, install
+            // changes are only picked up when a game is opened again
+            match SteamLibraryWatcher::start(app.handle().clone()) {
+                Ok(library_watcher) => {
+                    app.manage::<SteamLibraryWatcher>(library_watcher);
+                }
+                Err(e) => tracing::warn!("failed to watch Steam libraries: {e}"),
+            }
 
-            app.manage::<Mutex<IgdbApiClient>>(igdb_api_client);
+            commands::schedule_player_stats_sync(app.handle().clone());
 
             Ok(())
         })
@@ -122,8 +280,143 @@ pub fn run() {
             refresh_games,
             get_game,
             install_game,
-            uninstall_game
+            uninstall_game,
+            validate_steam_credentials,
+            validate_twitch_credentials,
+            complete_setup,
+            is_setup_complete,
+            get_setting,
+            set_setting,
+            get_exclusion_rules,
+            add_exclusion_rule,
+            delete_exclusion_rule,
+            preview_exclusion_rule,
+            launch_game,
+            get_play_sessions,
+            get_hooks,
+            add_hook,
+            delete_hook,
+            get_install_progress,
+            sync_player_stats,
+            prefetch_game,
+            sync_achievements,
+            get_game_achievements,
+            get_games_window,
+            get_recently_played,
+            get_friend_libraries,
+            get_common_games,
+            add_custom_game,
+            get_wine_config,
+            set_wine_config,
+            create_wine_prefix,
+            open_wine_prefix,
+            get_launch_settings,
+            set_launch_settings,
+            get_profile_summary,
+            get_subscriptions,
+            add_subscription,
+            end_subscription,
+            delete_subscription,
+            add_subscription_game,
+            remove_subscription_game,
+            get_subscription_report,
+            get_whats_new,
+            dismiss_whats_new,
+            get_game_news,
+            rematch_all,
+            get_deals,
+            get_completion_times,
+            play_soundtrack,
+            benchmark_refresh,
+            get_collections,
+            create_collection,
+            rename_collection,
+            add_game_to_collection,
+            remove_game_from_collection,
+            toggle_favorite,
+            get_playtime_history,
+            set_game_hidden,
+            pin_game,
+            reorder_pins,
+            export_custom_games,
+            import_custom_games,
+            get_game_notes,
+            set_game_notes,
+            rate_game,
+            set_game_status,
+            edit_game_metadata,
+            search_igdb,
+            rematch_game,
+            get_unmatched_games,
+            get_franchise_games,
+            get_similar_games,
+            get_mature_content_filter,
+            set_mature_content_filter,
+            set_artwork_source,
+            set_steamgriddb_api_key,
+            add_custom_artwork,
+            set_custom_cover,
+            get_cache_stats,
+            ensure_game_artworks,
+            verify_assets,
+            check_db_integrity,
+            run_db_maintenance,
+            list_backups,
+            restore_backup,
+            export_library,
+            import_library,
+            create_full_backup,
+            restore_full_backup,
+            undo_last_refresh,
+            get_last_refresh_report,
+            get_history,
+            find_duplicate_games,
+            merge_games,
+            pick_random_game,
+            get_recently_added,
+            get_all_genres,
+            get_all_developers,
+            get_all_release_years,
+            get_recent_logs
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app, event| {
+            if let RunEvent::Exit = event {
+                tauri::async_runtime::block_on(shutdown::flush(app));
+            }
+        });
+}
+
+/// Builds the Steam and IGDB API clients from `config` and registers them as
+/// managed state. The IGDB client caches its responses in the database.
+///
+/// Called at startup when a stored configuration exists, or by
+/// `complete_setup` once the setup wizard has validated the credentials.
+pub(crate) fn manage_api_clients(
+    app: &AppHandle,
+    config: RocadeConfig,
+) -> Result<(), RocadeConfigError> {
+    let retry_policy = *app.state::<RetryPolicy>();
+
+    let mut steam_api_client = SteamApiClient::new(config.steam_api_key, config.steam_profile_id);
+    steam_api_client.set_retry_policy(retry_policy);
+    app.manage::<Box<dyn SteamApi>>(Box::new(steam_api_client));
+
+    let mut twitch_api_client =
+        TwitchApiClient::new(config.twitch_client_id, config.twitch_client_secret);
+    twitch_api_client.set_token_store(SecretStore::new());
+    let mut igdb_api_client = IgdbApiClient::new(twitch_api_client)
+        .map_err(|e| RocadeConfigError::ConfigError(e.to_string()))?;
+    igdb_api_client.set_retry_policy(retry_policy);
+
+    let pool = &app.state::<DatabaseState>().pool;
+    igdb_api_client.set_response_cache(IgdbResponseCache::new(
+        IgdbCacheRepository::new(pool.clone()),
+        SettingsRepository::new(pool.clone()),
+    ));
+
+    app.manage::<Mutex<Box<dyn IgdbApi>>>(Mutex::new(Box::new(igdb_api_client)));
+
+    Ok(())
 }
@@ -0,0 +1,139 @@
+//! Logging to the console and to daily log files.
+//!
+//! Log files are written to the `logs` directory of the app data directory,
+//! one per day, and the most recent ones are kept so users can attach them
+//! to bug reports. Records of the `log` crate, used by Tauri and sqlx, are
+//! forwarded to the same files.
+
+use std::{
+    collections::VecDeque,
+    io,
+    path::{Path, PathBuf},
+};
+
+use tracing_appender::rolling::{InitError, RollingFileAppender, Rotation};
+use tracing_subscriber::{
+    filter::LevelFilter, fmt, layer::SubscriberExt, util::SubscriberInitExt, util::TryInitError,
+};
+
+/// Directory of the log files, in the app data directory.
+const LOG_DIR: &str = "logs";
+
+/// Prefix of the log file names, followed by their date.
+const LOG_FILE_PREFIX: &str = "rocade";
+
+/// Extension of the log files.
+const LOG_FILE_SUFFIX: &str = "log";
+
+/// Number of daily log files kept; older ones are removed on rotation.
+const LOG_FILES_KEPT: usize = 7;
+
+/// Errors that can occur while setting up logging or reading the logs.
+#[derive(Debug, thiserror::Error)]
+pub enum LogError {
+    /// The log directory could not be created.
+    #[error("unable to open log file: {0}")]
+    Appender(#[from] InitError),
+
+    /// Another logger was installed first.
+    #[error("unable to install logger: {0}")]
+    Subscriber(#[from] TryInitError),
+
+    /// The log files could not be read.
+    #[error("unable to read logs: {0}")]
+    Read(#[from] io::Error),
+}
+
+/// The log files of the app.
+pub struct LogFiles {
+    /// Directory of the log files, `None` when only logging to the console.
+    dir: Option<PathBuf>,
+}
+
+impl LogFiles {
+    /// Installs the global logger, writing records of level `INFO` and
+    /// above to the console and to the log files under `app_dir`.
+    ///
+    /// When the log files cannot be opened, logs to the console only rather
+    /// than failing. Must be called once, before anything is logged.
+    pub fn init(app_dir: &Path) -> LogFiles {
+        match Self::init_with_files(app_dir) {
+            Ok(log_files) => log_files,
+            Err(e) => {
+                // Only fails if another logger was installed, which then
+                // receives the records
+                let _ = tracing_subscriber::registry()
+                    .with(fmt::layer())
+                    .with(LevelFilter::INFO)
+                    .try_init();
+                tracing::warn!("logging to the console only: {e}");
+
+                LogFiles { dir: None }
+            }
+        }
+    }
+
+    /// Installs the global logger writing to the console and to the log
+    /// files under `app_dir`.
+    fn init_with_files(app_dir: &Path) -> Result<LogFiles, LogError> {
+        let dir = app_dir.join(LOG_DIR);
+
+        let appender = RollingFileAppender::builder()
+            .rotation(Rotation::DAILY)
+            .filename_prefix(LOG_FILE_PREFIX)
+            .filename_suffix(LOG_FILE_SUFFIX)
+            .max_log_files(LOG_FILES_KEPT)
+            .build(&dir)?;
+
+        tracing_subscriber::registry()
+            .with(fmt::layer())
+            .with(fmt::layer().with_ansi(false).with_writer(appender))
+            .with(LevelFilter::INFO)
+            .try_init()?;
+
+        Ok(LogFiles { dir: Some(dir) })
+    }
+
+    /// Returns up to `count` of the last logged lines, oldest first, read
+    /// across the kept log files. Empty when only logging to the console.
+    pub async fn recent_lines(&self, count: usize) -> Result<Vec<String>, LogError> {
+        let Some(dir) = &self.dir else {
+            return Ok(Vec::new());
+        };
+
+        let mut files = Vec::new();
+        let mut entries = tokio::fs::read_dir(dir).await?;
+
+        while let Some(entry) = entries.next_entry().await? {
+            if entry
+                .file_name()
+                .to_string_lossy()
+                .starts_with(LOG_FILE_PREFIX)
+            {
+                files.push(entry.path());
+            }
+        }
+
+        // File names end with their date, so they sort chronologically
+        files.sort();
+
+        let mut lines = VecDeque::with_capacity(count);
+
+        for file in files.iter().rev() {
+            if lines.len() == count {
+                break;
+            }
+
+            let contents = tokio::fs::read(file).await?;
+
+            for line in String::from_utf8_lossy(&contents).lines().rev() {
+                if lines.len() == count {
+                    break;
+                }
+                lines.push_front(line.to_string());
+            }
+        }
+
+        Ok(lines.into())
+    }
+}
@@ -0,0 +1,131 @@
+//! `rocade-asset://` protocol serving the cached images.
+//!
+//! Image fields of games hold filesystem paths. The frontend loads them as
+//! `rocade-asset://localhost/<path>` (`http://rocade-asset.localhost/<path>`
+//! on Windows) with the path URL-encoded, instead of converting them to
+//! `asset:` URLs. Paths may be absolute or relative to the assets directory;
+//! files outside of it are not served. Single `Range` requests are honored.
+
+use std::path::Path;
+
+use percent_encoding::percent_decode_str;
+use tauri::{
+    http::{
+        header::{ACCEPT_RANGES, CONTENT_LENGTH, CONTENT_RANGE, CONTENT_TYPE, RANGE},
+        Request, Response, StatusCode,
+    },
+    AppHandle, Manager, Runtime, UriSchemeContext, UriSchemeResponder,
+};
+use tokio::fs;
+
+use crate::assets::AssetManager;
+
+/// Name of the protocol.
+pub const SCHEME: &str = "rocade-asset";
+
+/// Answers a request to the protocol without blocking the webview.
+pub fn handle_asset_request<R: Runtime>(
+    ctx: UriSchemeContext<'_, R>,
+    request: Request<Vec<u8>>,
+    responder: UriSchemeResponder,
+) {
+    let app = ctx.app_handle().clone();
+
+    tauri::async_runtime::spawn(async move {
+        responder.respond(serve(&app, &request).await);
+    });
+}
+
+/// Reads the requested file, or the requested range of it.
+async fn serve<R: Runtime>(app: &AppHandle<R>, request: &Request<Vec<u8>>) -> Response<Vec<u8>> {
+    let Some(asset_manager) = app.try_state::<AssetManager>() else {
+        return empty(StatusCode::SERVICE_UNAVAILABLE);
+    };
+
+    let encoded = request.uri().path().trim_start_matches('/');
+    let decoded = percent_decode_str(encoded).decode_utf8_lossy();
+
+    let Some(path) = asset_manager.resolve(Path::new(decoded.as_ref())) else {
+        return empty(StatusCode::FORBIDDEN);
+    };
+
+    let Ok(body) = fs::read(&path).await else {
+        return empty(StatusCode::NOT_FOUND);
+    };
+
+    let len = body.len();
+    let range = request
+        .headers()
+        .get(RANGE)
+        .and_then(|value| value.to_str().ok())
+        .map(|value| parse_range(value, len));
+
+    let builder = Response::builder()
+        .header(CONTENT_TYPE, mime_type(&path))
+        .header(ACCEPT_RANGES, "bytes");
+
+    let response = match range {
+        None => builder
+            .status(StatusCode::OK)
+            .header(CONTENT_LENGTH, len)
+            .body(body),
+        Some(Some((start, end))) => builder
+            .status(StatusCode::PARTIAL_CONTENT)
+            .header(CONTENT_RANGE, format!("bytes {}-{}/{}", start, end, len))
+            .header(CONTENT_LENGTH, end - start + 1)
+            .body(body[start..=end].to_vec()),
+        Some(None) => builder
+            .status(StatusCode::RANGE_NOT_SATISFIABLE)
+            .header(CONTENT_RANGE, format!("bytes */{}", len))
+            .body(Vec::new()),
+    };
+
+    response.unwrap_or_else(|_| empty(StatusCode::INTERNAL_SERVER_ERROR))
+}
+
+/// Parses a `Range` header into the inclusive bounds of the bytes to send.
+///
+/// Only the first range of the header is served. Returns `None` if the
+/// range is malformed or outside a file of `len` bytes.
+fn parse_range(header: &str, len: usize) -> Option<(usize, usize)> {
+    let spec = header.strip_prefix("bytes=")?.split(',').next()?.trim();
+    let (start, end) = spec.split_once('-')?;
+    let last = len.checked_sub(1)?;
+
+    let (start, end) = if start.is_empty() {
+        // Suffix range: the last `end` bytes
+        let suffix: usize = end.parse().ok().filter(|suffix| *suffix > 0)?;
+        (len - suffix.min(len), last)
+    } else {
+        let start: usize = start.parse().ok()?;
+        let end = match end {
+            "" => last,
+            end => end.parse::<usize>().ok()?.min(last),
+        };
+        (start, end)
+    };
+
+    (start <= end).then_some((start, end))
+}
+
+/// Returns the MIME type of a cached file from its extension.
+fn mime_type(path: &Path) -> &'static str {
+    match path
+        .extension()
+        .map(|extension| extension.to_string_lossy().to_ascii_lowercase())
+        .as_deref()
+    {
+        Some("jpg" | "jpeg") => "image/jpeg",
+        Some("png") => "image/png",
+        Some("webp") => "image/webp",
+        Some("gif") => "image/gif",
+        _ => "application/octet-stream",
+    }
+}
+
+/// Returns a response with no body.
+fn empty(status: StatusCode) -> Response<Vec<u8>> {
+    let mut response = Response::new(Vec::new());
+    *response.status_mut() = status;
+    response
+}
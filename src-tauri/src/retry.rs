@@ -0,0 +1,199 @@
+//! Retry policy shared by image downloads and Steam/IGDB requests.
+//!
+//! Failed attempts are retried with exponential backoff: the first retry
+//! waits the base delay, and each following one waits twice as long, up to
+//! [`MAX_BACKOFF`].
+
+use std::future::Future;
+use std::time::Duration;
+
+use tauri_plugin_http::reqwest::{self, RequestBuilder, Response};
+
+use crate::db::settings::{Setting, SettingsRepository};
+
+/// Attempts made when [`Setting::RetryAttempts`] is unset.
+const DEFAULT_ATTEMPTS: u32 = 3;
+
+/// Delay before the first retry when [`Setting::RetryDelay`] is unset, in
+/// milliseconds.
+const DEFAULT_DELAY_MS: u64 = 1000;
+
+/// Highest value accepted for [`Setting::RetryAttempts`].
+pub const MAX_ATTEMPTS: u32 = 10;
+
+/// Highest value accepted for [`Setting::RetryDelay`], in milliseconds.
+pub const MAX_DELAY_MS: u64 = 60_000;
+
+/// Longest wait between two attempts, whatever the base delay and attempt.
+const MAX_BACKOFF: Duration = Duration::from_secs(300);
+
+/// How many times, and how long apart, a failed operation is attempted.
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+    /// Attempts made in total, at least one.
+    max_attempts: u32,
+    /// Delay before the first retry, doubled for each following one.
+    base_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy::new(DEFAULT_ATTEMPTS, Duration::from_millis(DEFAULT_DELAY_MS))
+    }
+}
+
+impl RetryPolicy {
+    /// Creates a policy making `max_attempts` attempts, `base_delay` apart
+    /// for the first retry. `0` attempts is treated as one.
+    pub fn new(max_attempts: u32, base_delay: Duration) -> Self {
+        RetryPolicy {
+            max_attempts: max_attempts.max(1),
+            base_delay,
+        }
+    }
+
+    /// Reads the policy from [`Setting::RetryAttempts`] and
+    /// [`Setting::RetryDelay`], falling back to 3 attempts starting 1s
+    /// apart. Invalid values are ignored and values stored before they were
+    /// validated are capped at [`MAX_ATTEMPTS`] and [`MAX_DELAY_MS`].
+    pub async fn load(settings_repository: &SettingsRepository) -> Result<Self, sqlx::Error> {
+        let max_attempts = settings_repository
+            .get(Setting::RetryAttempts)
+            .await?
+            .and_then(|attempts| attempts.trim().parse().ok())
+            .unwrap_or(DEFAULT_ATTEMPTS);
+        let delay_ms = settings_repository
+            .get(Setting::RetryDelay)
+            .await?
+            .and_then(|delay| delay.trim().parse().ok())
+            .unwrap_or(DEFAULT_DELAY_MS);
+
+        Ok(RetryPolicy::new(
+            max_attempts.min(MAX_ATTEMPTS),
+            Duration::from_millis(delay_ms.min(MAX_DELAY_MS)),
+        ))
+    }
+
+    /// Returns the number of attempts made in total.
+    pub fn max_attempts(&self) -> u32 {
+        self.max_attempts
+    }
+
+    /// Runs `operation` until it succeeds, fails with an error `is_transient`
+    /// rejects, or the attempts run out.
+    ///
+    /// Returns the error of the last attempt.
+    pub async fn run<T, E, F, Fut>(
+        &self,
+        mut operation: F,
+        is_transient: impl Fn(&E) -> bool,
+    ) -> Result<T, E>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, E>>,
+    {
+        let mut attempt = 0;
+
+        loop {
+            match operation().await {
+                Ok(value) => return Ok(value),
+                Err(e) if attempt + 1 < self.max_attempts && is_transient(&e) => {
+                    tokio::time::sleep(self.backoff(attempt)).await;
+                    attempt += 1;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    /// Returns the wait before retrying after the failed attempt numbered
+    /// `attempt`, counted from zero, capped at [`MAX_BACKOFF`].
+    fn backoff(&self, attempt: u32) -> Duration {
+        2_u32
+            .checked_pow(attempt)
+            .and_then(|factor| self.base_delay.checked_mul(factor))
+            .map_or(MAX_BACKOFF, |delay| delay.min(MAX_BACKOFF))
+    }
+
+    /// Sends a request, retrying connection failures, timeouts, and `5xx`
+    /// responses.
+    ///
+    /// A `5xx` status of the last attempt is returned as an error. Other
+    /// error statuses are left to the caller.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the request has a streaming body, which cannot be sent
+    /// twice. Steam and IGDB requests have none.
+    pub async fn send(&self, request: RequestBuilder) -> Result<Response, reqwest::Error> {
+        self.run(
+            || async {
+                let res = request
+                    .try_clone()
+                    .expect("request bodies are never streamed")
+                    .send()
+                    .await?;
+
+                if res.status().is_server_error() {
+                    return res.error_for_status();
+                }
+
+                Ok(res)
+            },
+            is_transient,
+        )
+        .await
+    }
+}
+
+/// Returns `true` if a failed request is worth retrying: the server could
+/// not be reached, timed out, or answered with a `5xx` status.
+pub(crate) fn is_transient(error: &reqwest::Error) -> bool {
+    match error.status() {
+        Some(status) => status.is_server_error(),
+        None => error.is_connect() || error.is_timeout() || error.is_request(),
+    }
+}
+
+/// Checks that `value` is accepted for [`Setting::RetryAttempts`] or
+/// [`Setting::RetryDelay`], returning why it is not. Other settings are
+/// always accepted.
+pub fn validate_setting(setting: Setting, value: &str) -> Result<(), String> {
+    match setting {
+        Setting::RetryAttempts => match value.trim().parse::<u32>() {
+            Ok(attempts) if (1..=MAX_ATTEMPTS).contains(&attempts) => Ok(()),
+            _ => Err(format!(
+                "retry attempts must be a number from 1 to {}",
+                MAX_ATTEMPTS
+            )),
+        },
+        Setting::RetryDelay => match value.trim().parse::<u64>() {
+            Ok(delay) if delay <= MAX_DELAY_MS => Ok(()),
+            _ => Err(format!(
+                "retry delay must be a number of milliseconds up to {}",
+                MAX_DELAY_MS
+            )),
+        },
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backoff_is_capped_instead_of_overflowing() {
+        let policy = RetryPolicy::new(u32::MAX, Duration::from_millis(u64::MAX));
+
+        assert_eq!(policy.backoff(0), MAX_BACKOFF);
+        assert_eq!(policy.backoff(40), MAX_BACKOFF);
+
+        let policy = RetryPolicy::default();
+
+        assert_eq!(policy.backoff(0), Duration::from_secs(1));
+        assert_eq!(policy.backoff(2), Duration::from_secs(4));
+        assert_eq!(policy.backoff(31), MAX_BACKOFF);
+        assert_eq!(policy.backoff(32), MAX_BACKOFF);
+    }
+}
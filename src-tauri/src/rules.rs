@@ -0,0 +1,100 @@
+//! Exclusion rules evaluated against the game library.
+//!
+//! Rules automatically hide entries such as soundtracks, tools, or games
+//! that were never played, without maintaining a manual exclusion list.
+//! They are re-evaluated after every refresh and whenever a rule changes.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+
+use crate::db::game::Game;
+
+/// Average length of a year in seconds, used for age-based rules.
+const SECONDS_PER_YEAR: i64 = 31_557_600;
+
+/// Errors that can occur while compiling an exclusion rule.
+#[derive(Debug, thiserror::Error)]
+pub enum RuleError {
+    /// The name pattern is not a valid regular expression.
+    #[error("invalid name pattern: {0}")]
+    InvalidPattern(#[from] regex::Error),
+}
+
+/// A user-defined rule that hides matching games from the library.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ExclusionRule {
+    /// Matches games whose name matches a case-insensitive regular expression.
+    NameRegex { pattern: String },
+    /// Matches games of the given IGDB game types (e.g. `bundle`, `mod`).
+    GameType { game_types: Vec<String> },
+    /// Matches never-played games released more than `years` years ago.
+    UnplayedOlderThan { years: u32 },
+    /// Matches games by Steam App ID.
+    StoreIds { store_ids: Vec<String> },
+}
+
+impl ExclusionRule {
+    /// Compiles the rule into a [`RuleMatcher`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RuleError::InvalidPattern`] if a name pattern does not
+    /// compile.
+    pub fn compile(&self) -> Result<RuleMatcher, RuleError> {
+        let matcher = match self {
+            ExclusionRule::NameRegex { pattern } => {
+                RuleMatcher::NameRegex(RegexBuilder::new(pattern).case_insensitive(true).build()?)
+            }
+            ExclusionRule::GameType { game_types } => RuleMatcher::GameType(game_types.clone()),
+            ExclusionRule::UnplayedOlderThan { years } => {
+                let now = SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .map(|d| d.as_secs() as i64)
+                    .unwrap_or_default();
+
+                RuleMatcher::UnplayedReleasedBefore(now - *years as i64 * SECONDS_PER_YEAR)
+            }
+            ExclusionRule::StoreIds { store_ids } => RuleMatcher::StoreIds(store_ids.clone()),
+        };
+
+        Ok(matcher)
+    }
+}
+
+/// A compiled [`ExclusionRule`], ready to be evaluated against games.
+pub enum RuleMatcher {
+    NameRegex(Regex),
+    GameType(Vec<String>),
+    /// Holds the release-date cutoff as a Unix timestamp.
+    UnplayedReleasedBefore(i64),
+    StoreIds(Vec<String>),
+}
+
+impl RuleMatcher {
+    /// Returns `true` if `game` is matched by this rule.
+    ///
+    /// Games with unknown data never match a rule depending on it; for
+    /// example a game without a release date is never considered old.
+    pub fn matches(&self, game: &Game) -> bool {
+        match self {
+            RuleMatcher::NameRegex(regex) => regex.is_match(&game.name),
+            RuleMatcher::GameType(game_types) => game
+                .game_type
+                .as_ref()
+                .is_some_and(|game_type| game_types.contains(game_type)),
+            RuleMatcher::UnplayedReleasedBefore(cutoff) => {
+                game.playtime_forever.unwrap_or_default() == 0
+                    && game
+                        .release_date
+                        .is_some_and(|release_date| release_date < *cutoff)
+            }
+            RuleMatcher::StoreIds(store_ids) => game
+                .store_id
+                .as_ref()
+                .is_some_and(|store_id| store_ids.contains(store_id)),
+        }
+    }
+}
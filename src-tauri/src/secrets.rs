@@ -0,0 +1,133 @@
+//! Secure storage for API secrets.
+//!
+//! Stores sensitive credentials (Steam Web API key, Twitch client secret) in
+//! the OS keychain instead of a plaintext `.env` file, and migrates values
+//! found in the environment into the keychain on first use.
+
+use std::env;
+
+/// Keychain service name under which all Rocade secrets are stored.
+const SERVICE_NAME: &str = "rocade";
+
+/// Errors that can occur while reading or writing secrets.
+#[derive(Debug, thiserror::Error)]
+pub enum SecretError {
+    /// The OS keychain rejected or failed the operation.
+    #[error("keychain error: {0}")]
+    Keyring(#[from] keyring::Error),
+
+    /// The secret is neither in the keychain nor in the environment.
+    #[error("secret not set: {0}")]
+    Missing(&'static str),
+}
+
+/// A secret managed by the [`SecretStore`].
+#[derive(Debug, Clone, Copy)]
+#[allow(clippy::enum_variant_names)]
+pub enum Secret {
+    /// Steam Web API key.
+    SteamApiKey,
+    /// Twitch application client secret, used to obtain IGDB tokens.
+    TwitchClientSecret,
+    /// Last Twitch access token with its expiry, reused across launches.
+    TwitchAccessToken,
+    /// PIN required to change the mature-content filter or bypass it.
+    ParentalPin,
+    /// SteamGridDB API key, used to fetch alternative artwork.
+    SteamGridDbApiKey,
+}
+
+impl Secret {
+    /// Returns the keychain account name for this secret.
+    fn key(&self) -> &'static str {
+        match self {
+            Secret::SteamApiKey => "steam_api_key",
+            Secret::TwitchClientSecret => "twitch_client_secret",
+            Secret::TwitchAccessToken => "twitch_access_token",
+            Secret::ParentalPin => "parental_pin",
+            Secret::SteamGridDbApiKey => "steamgriddb_api_key",
+        }
+    }
+
+    /// Returns the legacy environment variable holding this secret, if any.
+    fn env_var(&self) -> Option<&'static str> {
+        match self {
+            Secret::SteamApiKey => Some("STEAM_API_KEY"),
+            Secret::TwitchClientSecret => Some("TWITCH_CLIENT_SECRET"),
+            Secret::TwitchAccessToken | Secret::ParentalPin | Secret::SteamGridDbApiKey => None,
+        }
+    }
+}
+
+/// Reads and writes secrets in the OS keychain.
+///
+/// Backed by the platform credential store (Keychain on macOS, Credential
+/// Manager on Windows, Secret Service on Linux).
+#[derive(Debug)]
+pub struct SecretStore {
+    service: String,
+}
+
+impl SecretStore {
+    /// Creates a new `SecretStore` using the default Rocade service name.
+    pub fn new() -> Self {
+        SecretStore {
+            service: SERVICE_NAME.to_string(),
+        }
+    }
+
+    /// Returns the keychain entry backing `secret`.
+    fn entry(&self, secret: Secret) -> Result<keyring::Entry, SecretError> {
+        Ok(keyring::Entry::new(&self.service, secret.key())?)
+    }
+
+    /// Returns the stored value for `secret`, or `None` if it was never set.
+    pub fn get(&self, secret: Secret) -> Result<Option<String>, SecretError> {
+        match self.entry(secret)?.get_password() {
+            Ok(value) => Ok(Some(value)),
+            Err(keyring::Error::NoEntry) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Stores `value` for `secret`, replacing any previous value.
+    pub fn set(&self, secret: Secret, value: &str) -> Result<(), SecretError> {
+        self.entry(secret)?.set_password(value)?;
+        Ok(())
+    }
+
+    /// Returns the value for `secret`, migrating it from the environment if
+    /// needed.
+    ///
+    /// The keychain is checked first. When it has no entry, the legacy
+    /// environment variable (e.g. from `.env`) is read and copied into the
+    /// keychain so later launches no longer depend on the plaintext file. If
+    /// the keychain is unavailable, the environment value is still returned.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SecretError::Missing`] if the secret is found in neither
+    /// location.
+    pub fn get_or_migrate(&self, secret: Secret) -> Result<String, SecretError> {
+        let stored = self.get(secret);
+
+        if let Ok(Some(value)) = stored {
+            return Ok(value);
+        }
+
+        let env_var = secret.env_var().ok_or(SecretError::Missing(secret.key()))?;
+        let value = env::var(env_var).map_err(|_| SecretError::Missing(env_var))?;
+
+        if stored.is_ok() {
+            self.set(secret, &value)?;
+        }
+
+        Ok(value)
+    }
+}
+
+impl Default for SecretStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
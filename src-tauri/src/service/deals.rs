@@ -0,0 +1,101 @@
+//! Steam price client for deal tracking.
+//!
+//! Reads current prices from the storefront `appdetails` endpoint filtered on
+//! `price_overview`, the only filter for which the endpoint accepts several
+//! App IDs per request. Steam has no price history, so historical lows are
+//! recorded locally from the prices seen over time.
+
+use std::collections::HashMap;
+
+use serde::Deserialize;
+use tauri_plugin_http::reqwest::Client;
+
+use super::steam::SteamError;
+
+/// Maximum number of App IDs per price request.
+const PRICE_BATCH_SIZE: usize = 100;
+
+/// Current price of an app on the Steam store.
+#[derive(Deserialize, Debug)]
+pub struct PriceOverview {
+    /// ISO 4217 currency code.
+    pub currency: String,
+    /// Price before discount, in cents.
+    pub initial: i64,
+    /// Price after discount, in cents.
+    #[serde(rename = "final")]
+    pub final_price: i64,
+    pub discount_percent: i64,
+}
+
+/// A single entry of the price response. `data` is an empty array for free
+/// apps, so it is parsed leniently.
+#[derive(Deserialize)]
+struct PriceResult {
+    success: bool,
+    data: Option<serde_json::Value>,
+}
+
+/// Client reading Steam store prices.
+pub struct DealsClient {
+    client: Client,
+}
+
+impl DealsClient {
+    /// Creates a new price client.
+    pub fn new() -> Self {
+        DealsClient {
+            client: Client::new(),
+        }
+    }
+
+    /// Fetches the current prices of apps, in the currency of the user's
+    /// region.
+    ///
+    /// Free apps and apps without a store page are left out of the result.
+    pub async fn get_prices(
+        &self,
+        appids: &[u64],
+    ) -> Result<HashMap<u64, PriceOverview>, SteamError> {
+        let url = "https://store.steampowered.com/api/appdetails";
+        let mut prices = HashMap::new();
+
+        for chunk in appids.chunks(PRICE_BATCH_SIZE) {
+            let ids: Vec<String> = chunk.iter().map(|id| id.to_string()).collect();
+
+            let res = self
+                .client
+                .get(url)
+                .query(&[
+                    ("appids", ids.join(",")),
+                    ("filters", "price_overview".to_string()),
+                ])
+                .send()
+                .await?;
+
+            let body = res.error_for_status()?.text().await?;
+
+            let parsed: HashMap<String, PriceResult> = serde_json::from_str(&body)?;
+
+            for (appid, result) in parsed {
+                let price = result
+                    .data
+                    .filter(|_| result.success)
+                    .and_then(|mut data| data.get_mut("price_overview").map(|p| p.take()))
+                    .and_then(|price| serde_json::from_value::<PriceOverview>(price).ok());
+
+                if let (Ok(appid), Some(price)) = (appid.parse(), price) {
+                    prices.insert(appid, price);
+                }
+            }
+        }
+
+        Ok(prices)
+    }
+}
+
+impl Default for DealsClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
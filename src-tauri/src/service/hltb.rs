@@ -0,0 +1,106 @@
+//! HowLongToBeat client.
+//!
+//! HowLongToBeat has no public API; this client calls the search endpoint
+//! used by its website, which expects browser-like headers. Games are matched
+//! by name, so the best result is picked by trigram similarity.
+
+use serde::Deserialize;
+use tauri_plugin_http::reqwest::{self, Client};
+
+/// Search endpoint of the HowLongToBeat website.
+const SEARCH_URL: &str = "https://howlongtobeat.com/api/search";
+
+/// Errors that can occur when querying HowLongToBeat.
+#[derive(Debug, thiserror::Error)]
+pub enum HltbError {
+    /// An HTTP request to HowLongToBeat failed.
+    #[error("http request failed: {0}")]
+    Request(#[from] reqwest::Error),
+
+    /// The response body could not be deserialized into the expected type.
+    #[error("unable to parse hltb data: {0}")]
+    InvalidData(#[from] serde_json::Error),
+}
+
+/// A search result, with completion times in seconds (`0` when unknown).
+#[derive(Deserialize, Debug)]
+pub struct HltbGame {
+    pub game_id: i64,
+    pub game_name: String,
+    /// Main story.
+    pub comp_main: i64,
+    /// Main story and extras.
+    pub comp_plus: i64,
+    /// Completionist.
+    pub comp_100: i64,
+}
+
+/// Top-level wrapper for the search response.
+#[derive(Deserialize)]
+struct HltbSearchResponse {
+    data: Vec<HltbGame>,
+}
+
+/// Client for HowLongToBeat searches.
+pub struct HltbClient {
+    client: Client,
+}
+
+impl HltbClient {
+    /// Creates a new HowLongToBeat client.
+    pub fn new() -> Self {
+        HltbClient {
+            client: Client::new(),
+        }
+    }
+
+    /// Searches games by name, most popular first.
+    pub async fn search(&self, name: &str) -> Result<Vec<HltbGame>, HltbError> {
+        let terms: Vec<&str> = name.split_whitespace().collect();
+
+        let body = serde_json::json!({
+            "searchType": "games",
+            "searchTerms": terms,
+            "searchPage": 1,
+            "size": 20,
+            "searchOptions": {
+                "games": {
+                    "userId": 0,
+                    "platform": "",
+                    "sortCategory": "popular",
+                    "rangeCategory": "main",
+                    "rangeTime": { "min": null, "max": null },
+                    "gameplay": { "perspective": "", "flow": "", "genre": "" },
+                    "rangeYear": { "min": "", "max": "" },
+                    "modifier": "",
+                },
+                "users": { "sortCategory": "postcount" },
+                "filter": "",
+                "sort": 0,
+                "randomizer": 0,
+            },
+        });
+
+        let res = self
+            .client
+            .post(SEARCH_URL)
+            .header("Referer", "https://howlongtobeat.com")
+            .header("User-Agent", "Mozilla/5.0")
+            .header("Content-Type", "application/json")
+            .body(body.to_string())
+            .send()
+            .await?;
+
+        let body = res.error_for_status()?.text().await?;
+
+        let parsed: HltbSearchResponse = serde_json::from_str(&body)?;
+
+        Ok(parsed.data)
+    }
+}
+
+impl Default for HltbClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -1 +1,6 @@
+pub mod deals;
+pub mod hltb;
+pub mod protondb;
 pub mod steam;
+pub mod steam_store;
+pub mod steamgriddb;
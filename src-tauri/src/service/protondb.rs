@@ -0,0 +1,104 @@
+//! ProtonDB client.
+//!
+//! Reads the community compatibility summary ProtonDB publishes for each
+//! Steam App ID. The endpoint is public and needs no API key.
+
+use async_trait::async_trait;
+use serde::Deserialize;
+use tauri::http::StatusCode;
+use tauri_plugin_http::reqwest::{self, Client};
+
+/// Errors that can occur when querying ProtonDB.
+#[derive(Debug, thiserror::Error)]
+pub enum ProtonDbError {
+    /// An HTTP request to ProtonDB failed.
+    #[error("http request failed: {0}")]
+    Request(#[from] reqwest::Error),
+
+    /// The response body could not be deserialized into the expected type.
+    #[error("unable to parse protondb data: {0}")]
+    InvalidData(#[from] serde_json::Error),
+}
+
+/// Report summary of a game, as returned by ProtonDB.
+#[derive(Deserialize)]
+struct ReportSummary {
+    /// Compatibility tier: `platinum`, `gold`, `silver`, `bronze`, `borked`,
+    /// or `pending` while there are too few reports.
+    tier: String,
+}
+
+/// ProtonDB requests made by the commands.
+///
+/// Implemented by [`ProtonDbClient`]; commands get it from the Tauri state
+/// as a `Box<dyn ProtonDbApi>`, so another implementation can stand in for
+/// it.
+#[async_trait]
+pub trait ProtonDbApi: Send + Sync {
+    /// Fetches the compatibility tier of a Steam game. Returns `None` if
+    /// nobody reported on the game yet.
+    async fn get_tier(&self, appid: &str) -> Result<Option<String>, ProtonDbError>;
+}
+
+/// Client for the ProtonDB report summaries.
+pub struct ProtonDbClient {
+    client: Client,
+}
+
+impl ProtonDbClient {
+    /// Creates a new ProtonDB client.
+    pub fn new() -> Self {
+        ProtonDbClient {
+            client: Client::new(),
+        }
+    }
+}
+
+#[async_trait]
+impl ProtonDbApi for ProtonDbClient {
+    /// Fetches the compatibility tier of a Steam game.
+    ///
+    /// Returns `None` if nobody reported on the game yet.
+    async fn get_tier(&self, appid: &str) -> Result<Option<String>, ProtonDbError> {
+        let url = format!(
+            "https://www.protondb.com/api/v1/reports/summaries/{}.json",
+            appid
+        );
+        let res = self.client.get(url).send().await?;
+
+        if res.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+
+        let body = res.error_for_status()?.text().await?;
+
+        let parsed: ReportSummary = serde_json::from_str(&body)?;
+
+        Ok(Some(parsed.tier))
+    }
+}
+
+impl Default for ProtonDbClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod mock {
+    use async_trait::async_trait;
+
+    use super::*;
+
+    /// A [`ProtonDbApi`] for which no game has reports, without network
+    /// access.
+    #[derive(Default)]
+    pub struct MockProtonDbApi;
+
+    #[async_trait]
+    impl ProtonDbApi for MockProtonDbApi {
+        async fn get_tier(&self, _appid: &str) -> Result<Option<String>, ProtonDbError> {
+            Ok(None)
+        }
+    }
+}
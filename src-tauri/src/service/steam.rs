@@ -4,8 +4,12 @@
 //! user's owned games, along with the shared [`SteamError`] type used across
 //! the Steam integration.
 
+use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
-use tauri_plugin_http::reqwest::{self, Client};
+use tauri::http::StatusCode;
+use tauri_plugin_http::reqwest::{self, Client, RequestBuilder};
+
+use crate::retry::RetryPolicy;
 
 /// Errors that can occur when using Steam API or client operations.
 #[derive(Debug, thiserror::Error)]
@@ -21,6 +25,14 @@ pub enum SteamError {
     /// The response body could not be deserialized into the expected type.
     #[error("unable to parse steam data: {0}")]
     InvalidData(#[from] serde_json::Error),
+
+    /// The Steam Web API rejected the API key.
+    #[error("steam api key rejected")]
+    Unauthorized,
+
+    /// The profile does not exist or its game details are private.
+    #[error("steam profile unavailable: {0}")]
+    ProfileUnavailable(String),
 }
 
 /// A game entry as returned by the Steam `GetOwnedGames` endpoint.
@@ -28,20 +40,23 @@ pub enum SteamError {
 pub struct SteamGame {
     /// Steam App ID.
     pub appid: u64,
-    name: String,
+    pub name: String,
     /// Playtime in minutes over the last two weeks, if any.
-    playtime_2weeks: Option<u64>,
+    pub playtime_2weeks: Option<u64>,
     /// Total playtime in minutes.
-    playtime_forever: Option<u64>,
+    pub playtime_forever: Option<u64>,
+    /// Unix timestamp of the last time the game was played, `0` if never.
+    pub rtime_last_played: Option<i64>,
     img_icon_url: Option<String>,
     img_logo_url: Option<String>,
 }
 
-/// The inner payload of the `GetOwnedGames` response.
+/// The inner payload of the `GetOwnedGames` response. Empty when the
+/// profile's game details are private.
 #[derive(Serialize, Deserialize)]
 pub struct GameList {
-    game_count: u64,
-    games: Vec<SteamGame>,
+    game_count: Option<u64>,
+    games: Option<Vec<SteamGame>>,
 }
 
 /// Top-level wrapper for the `GetOwnedGames` JSON response.
@@ -50,6 +65,250 @@ pub struct GameListResponse {
     response: GameList,
 }
 
+/// A friend entry as returned by the `GetFriendList` endpoint.
+#[derive(Serialize, Deserialize)]
+pub struct Friend {
+    /// SteamID64 of the friend.
+    pub steamid: String,
+    /// Unix timestamp of when the friendship started.
+    pub friend_since: i64,
+}
+
+/// The inner payload of the `GetFriendList` response.
+#[derive(Serialize, Deserialize)]
+pub struct FriendList {
+    friends: Vec<Friend>,
+}
+
+/// Top-level wrapper for the `GetFriendList` JSON response.
+#[derive(Serialize, Deserialize)]
+pub struct FriendListResponse {
+    friendslist: FriendList,
+}
+
+/// Public profile information, as returned by `GetPlayerSummaries`.
+#[derive(Serialize, Deserialize)]
+pub struct PlayerSummary {
+    /// SteamID64 of the player.
+    pub steamid: String,
+    /// Display name of the player.
+    pub personaname: String,
+    /// URL of the medium-sized avatar.
+    pub avatarmedium: Option<String>,
+    /// URL of the full-sized avatar.
+    pub avatarfull: Option<String>,
+    /// Online state code, `0` (offline) when the profile is private.
+    #[serde(default)]
+    pub personastate: u8,
+}
+
+/// Online state of a Steam user.
+#[derive(Serialize, Debug, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum OnlineState {
+    Offline,
+    Online,
+    Busy,
+    Away,
+    Snooze,
+    LookingToTrade,
+    LookingToPlay,
+}
+
+impl From<u8> for OnlineState {
+    fn from(personastate: u8) -> Self {
+        match personastate {
+            1 => OnlineState::Online,
+            2 => OnlineState::Busy,
+            3 => OnlineState::Away,
+            4 => OnlineState::Snooze,
+            5 => OnlineState::LookingToTrade,
+            6 => OnlineState::LookingToPlay,
+            _ => OnlineState::Offline,
+        }
+    }
+}
+
+/// Profile of the configured Steam user, shown in the sidebar.
+#[derive(Serialize)]
+pub struct ProfileSummary {
+    /// SteamID64 of the user.
+    pub steam_id: String,
+    pub persona_name: String,
+    /// URL of the full-sized avatar.
+    pub avatar: Option<String>,
+    pub online_state: OnlineState,
+}
+
+/// The inner payload of the `GetPlayerSummaries` response.
+#[derive(Serialize, Deserialize)]
+pub struct PlayerSummaries {
+    players: Vec<PlayerSummary>,
+}
+
+/// Top-level wrapper for the `GetPlayerSummaries` JSON response.
+#[derive(Serialize, Deserialize)]
+pub struct PlayerSummariesResponse {
+    response: PlayerSummaries,
+}
+
+/// Maximum number of SteamIDs accepted by one `GetPlayerSummaries` call.
+const PLAYER_SUMMARIES_BATCH: usize = 100;
+
+/// A news item as returned by the `GetNewsForApp` endpoint.
+#[derive(Serialize, Deserialize)]
+pub struct NewsItem {
+    /// Unique ID of the news item.
+    pub gid: String,
+    pub title: String,
+    pub url: String,
+    pub author: Option<String>,
+    /// Body of the news item, in Steam's BBCode-like markup.
+    pub contents: String,
+    /// Display name of the feed, e.g. `Community Announcements`.
+    pub feedlabel: Option<String>,
+    /// Unix timestamp of the publication.
+    pub date: i64,
+}
+
+/// The inner payload of the `GetNewsForApp` response.
+#[derive(Serialize, Deserialize)]
+pub struct AppNews {
+    newsitems: Vec<NewsItem>,
+}
+
+/// Top-level wrapper for the `GetNewsForApp` JSON response.
+#[derive(Serialize, Deserialize)]
+pub struct AppNewsResponse {
+    appnews: AppNews,
+}
+
+/// A wishlist entry as returned by the `GetWishlist` endpoint.
+#[derive(Serialize, Deserialize)]
+pub struct WishlistItem {
+    /// Steam App ID.
+    pub appid: u64,
+}
+
+/// The inner payload of the `GetWishlist` response. `items` is missing when
+/// the wishlist is empty or private.
+#[derive(Serialize, Deserialize)]
+pub struct Wishlist {
+    items: Option<Vec<WishlistItem>>,
+}
+
+/// Top-level wrapper for the `GetWishlist` JSON response.
+#[derive(Serialize, Deserialize)]
+pub struct WishlistResponse {
+    response: Wishlist,
+}
+
+/// Unlock state of an achievement, as returned by `GetPlayerAchievements`.
+#[derive(Serialize, Deserialize)]
+pub struct PlayerAchievement {
+    /// Steam API name of the achievement.
+    pub apiname: String,
+    /// `1` if unlocked, `0` otherwise.
+    pub achieved: u8,
+    /// Unix timestamp of the unlock, `0` while locked.
+    pub unlocktime: i64,
+}
+
+/// The inner payload of the `GetPlayerAchievements` response.
+#[derive(Serialize, Deserialize)]
+pub struct PlayerStats {
+    success: bool,
+    error: Option<String>,
+    achievements: Option<Vec<PlayerAchievement>>,
+}
+
+/// Top-level wrapper for the `GetPlayerAchievements` JSON response.
+#[derive(Serialize, Deserialize)]
+pub struct PlayerStatsResponse {
+    playerstats: PlayerStats,
+}
+
+/// An achievement definition, as returned by `GetSchemaForGame`.
+#[derive(Serialize, Deserialize)]
+pub struct SchemaAchievement {
+    /// Steam API name of the achievement.
+    pub name: String,
+    #[serde(rename = "displayName")]
+    pub display_name: String,
+    pub description: Option<String>,
+    /// URL of the icon shown once unlocked.
+    pub icon: Option<String>,
+    /// URL of the icon shown while locked.
+    pub icongray: Option<String>,
+    /// `1` if the achievement is hidden until unlocked.
+    pub hidden: u8,
+}
+
+/// The stats section of the `GetSchemaForGame` response.
+#[derive(Serialize, Deserialize)]
+pub struct AvailableGameStats {
+    achievements: Option<Vec<SchemaAchievement>>,
+}
+
+/// The inner payload of the `GetSchemaForGame` response. Empty for games
+/// without stats.
+#[derive(Serialize, Deserialize)]
+pub struct GameSchema {
+    #[serde(rename = "availableGameStats")]
+    available_game_stats: Option<AvailableGameStats>,
+}
+
+/// Top-level wrapper for the `GetSchemaForGame` JSON response.
+#[derive(Serialize, Deserialize)]
+pub struct GameSchemaResponse {
+    game: GameSchema,
+}
+
+/// Error message returned by `GetPlayerAchievements` for games without
+/// achievements.
+const NO_STATS_ERROR: &str = "Requested app has no stats";
+
+/// Steam Web API requests made by the commands.
+///
+/// Implemented by [`SteamApiClient`]; commands get it from the Tauri state
+/// as a `Box<dyn SteamApi>`, so another implementation can stand in for it.
+#[async_trait]
+pub trait SteamApi: Send + Sync {
+    /// Fetches all games owned by the configured profile.
+    async fn get_games(&self) -> Result<Vec<SteamGame>, SteamError>;
+
+    /// Fetches all games owned by any profile, `None` if they are private.
+    async fn get_owned_games(&self, steam_id: &str) -> Result<Option<Vec<SteamGame>>, SteamError>;
+
+    /// Fetches the friends of the configured profile.
+    async fn get_friends(&self) -> Result<Vec<Friend>, SteamError>;
+
+    /// Fetches the public summary of the configured profile.
+    async fn get_profile_summary(&self) -> Result<ProfileSummary, SteamError>;
+
+    /// Fetches the public summaries of several profiles.
+    async fn get_player_summaries(
+        &self,
+        steam_ids: &[String],
+    ) -> Result<Vec<PlayerSummary>, SteamError>;
+
+    /// Fetches the latest `count` news items of a game.
+    async fn get_game_news(&self, appid: u64, count: u32) -> Result<Vec<NewsItem>, SteamError>;
+
+    /// Fetches the achievement schema of a game.
+    async fn get_game_achievements(&self, appid: u64)
+        -> Result<Vec<SchemaAchievement>, SteamError>;
+
+    /// Fetches the configured profile's progress on a game's achievements.
+    async fn get_player_achievements(
+        &self,
+        appid: u64,
+    ) -> Result<Vec<PlayerAchievement>, SteamError>;
+
+    /// Fetches the App IDs on the configured profile's wishlist.
+    async fn get_wishlist(&self) -> Result<Vec<u64>, SteamError>;
+}
+
 /// Async client for the Steam Web API.
 ///
 /// Requires a Steam Web API `key` and the target user's 64-bit `profile_id`
@@ -59,6 +318,7 @@ pub struct SteamApiClient {
     key: String,
     profile_id: String,
     client: Client,
+    retry_policy: RetryPolicy,
 }
 
 impl SteamApiClient {
@@ -73,25 +333,37 @@ impl SteamApiClient {
             key,
             profile_id,
             client: tauri_plugin_http::reqwest::Client::new(),
+            retry_policy: RetryPolicy::default(),
         }
     }
 
+    /// Sets the policy applied to requests failing with a transient error.
+    pub fn set_retry_policy(&mut self, retry_policy: RetryPolicy) {
+        self.retry_policy = retry_policy;
+    }
+}
+
+#[async_trait]
+impl SteamApi for SteamApiClient {
     /// Fetches all games owned by the configured Steam profile.
     ///
     /// Calls the `IPlayerService/GetOwnedGames` endpoint with `include_appinfo`
     /// enabled so that each entry includes the game name and icon URLs.
-    pub async fn get_games(&self) -> Result<Vec<SteamGame>, SteamError> {
-        let url = format!("https://api.steampowered.com/IPlayerService/GetOwnedGames/v0001");
+    #[tracing::instrument(skip(self), err)]
+    async fn get_games(&self) -> Result<Vec<SteamGame>, SteamError> {
+        self.get_owned_games(&self.profile_id)
+            .await?
+            .ok_or_else(|| SteamError::ProfileUnavailable(self.profile_id.clone()))
+    }
+
+    /// Fetches all games owned by any Steam profile.
+    ///
+    /// Returns `None` if the profile's game details are private.
+    #[tracing::instrument(skip_all, err)]
+    async fn get_owned_games(&self, steam_id: &str) -> Result<Option<Vec<SteamGame>>, SteamError> {
         let res = self
-            .client
-            .get(url)
-            .query(&[
-                ("key", &self.key),
-                ("steamid", &self.profile_id),
-                ("include_appinfo", &"1".to_string()),
-                ("format", &"json".to_string()),
-            ])
-            .send()
+            .retry_policy
+            .send(self.owned_games_request(steam_id))
             .await?;
 
         let body = res.text().await?;
@@ -100,4 +372,348 @@ impl SteamApiClient {
 
         Ok(parsed.response.games)
     }
+
+    /// Fetches the SteamID64s of the configured profile's friends.
+    ///
+    /// Calls the `ISteamUser/GetFriendList` endpoint, which requires the
+    /// profile's friend list to be public.
+    #[tracing::instrument(skip(self), err)]
+    async fn get_friends(&self) -> Result<Vec<Friend>, SteamError> {
+        let url = "https://api.steampowered.com/ISteamUser/GetFriendList/v0001";
+        let res = self
+            .retry_policy
+            .send(self.client.get(url).query(&[
+                ("key", self.key.as_str()),
+                ("steamid", self.profile_id.as_str()),
+                ("relationship", "friend"),
+            ]))
+            .await?;
+
+        if res.status() == StatusCode::UNAUTHORIZED {
+            return Err(SteamError::ProfileUnavailable(self.profile_id.clone()));
+        }
+
+        let body = res.error_for_status()?.text().await?;
+
+        let parsed: FriendListResponse = serde_json::from_str(&body)?;
+
+        Ok(parsed.friendslist.friends)
+    }
+
+    /// Fetches the profile of the configured Steam user.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SteamError::ProfileUnavailable`] if Steam does not know the
+    /// profile.
+    #[tracing::instrument(skip(self), err)]
+    async fn get_profile_summary(&self) -> Result<ProfileSummary, SteamError> {
+        let player = self
+            .get_player_summaries(std::slice::from_ref(&self.profile_id))
+            .await?
+            .into_iter()
+            .next()
+            .ok_or_else(|| SteamError::ProfileUnavailable(self.profile_id.clone()))?;
+
+        Ok(ProfileSummary {
+            steam_id: player.steamid,
+            persona_name: player.personaname,
+            avatar: player.avatarfull,
+            online_state: player.personastate.into(),
+        })
+    }
+
+    /// Fetches the public profile information of the given players.
+    ///
+    /// Calls the `ISteamUser/GetPlayerSummaries` endpoint in batches of 100
+    /// SteamIDs. Unknown players are left out.
+    #[tracing::instrument(skip_all, fields(count = steam_ids.len()), err)]
+    async fn get_player_summaries(
+        &self,
+        steam_ids: &[String],
+    ) -> Result<Vec<PlayerSummary>, SteamError> {
+        let url = "https://api.steampowered.com/ISteamUser/GetPlayerSummaries/v0002";
+        let mut players = Vec::new();
+
+        for batch in steam_ids.chunks(PLAYER_SUMMARIES_BATCH) {
+            let res = self
+                .retry_policy
+                .send(
+                    self.client
+                        .get(url)
+                        .query(&[("key", &self.key), ("steamids", &batch.join(","))]),
+                )
+                .await?;
+
+            let body = res.error_for_status()?.text().await?;
+
+            let parsed: PlayerSummariesResponse = serde_json::from_str(&body)?;
+
+            players.extend(parsed.response.players);
+        }
+
+        Ok(players)
+    }
+
+    /// Fetches the latest news items of a game, most recent first.
+    ///
+    /// Calls the `ISteamNews/GetNewsForApp` endpoint with full contents.
+    #[tracing::instrument(skip(self), err)]
+    async fn get_game_news(&self, appid: u64, count: u32) -> Result<Vec<NewsItem>, SteamError> {
+        let url = "https://api.steampowered.com/ISteamNews/GetNewsForApp/v0002";
+        let res = self
+            .retry_policy
+            .send(self.client.get(url).query(&[
+                ("appid", appid.to_string()),
+                ("count", count.to_string()),
+                ("maxlength", "0".to_string()),
+                ("format", "json".to_string()),
+            ]))
+            .await?;
+
+        let body = res.error_for_status()?.text().await?;
+
+        let parsed: AppNewsResponse = serde_json::from_str(&body)?;
+
+        Ok(parsed.appnews.newsitems)
+    }
+
+    /// Fetches the achievement definitions of a game.
+    ///
+    /// Calls the `ISteamUserStats/GetSchemaForGame` endpoint. Returns an
+    /// empty list for games without achievements.
+    #[tracing::instrument(skip(self), err)]
+    async fn get_game_achievements(
+        &self,
+        appid: u64,
+    ) -> Result<Vec<SchemaAchievement>, SteamError> {
+        let url = "https://api.steampowered.com/ISteamUserStats/GetSchemaForGame/v2";
+        let res = self
+            .retry_policy
+            .send(
+                self.client
+                    .get(url)
+                    .query(&[("key", &self.key), ("appid", &appid.to_string())]),
+            )
+            .await?;
+
+        let body = res.error_for_status()?.text().await?;
+
+        let parsed: GameSchemaResponse = serde_json::from_str(&body)?;
+
+        Ok(parsed
+            .game
+            .available_game_stats
+            .and_then(|stats| stats.achievements)
+            .unwrap_or_default())
+    }
+
+    /// Fetches the configured profile's unlock state for a game's
+    /// achievements.
+    ///
+    /// Calls the `ISteamUserStats/GetPlayerAchievements` endpoint. Returns an
+    /// empty list for games without achievements.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SteamError::InvalidResponse`] if Steam reports another
+    /// failure, e.g. private game details.
+    #[tracing::instrument(skip(self), err)]
+    async fn get_player_achievements(
+        &self,
+        appid: u64,
+    ) -> Result<Vec<PlayerAchievement>, SteamError> {
+        let url = "https://api.steampowered.com/ISteamUserStats/GetPlayerAchievements/v0001";
+        let res = self
+            .retry_policy
+            .send(self.client.get(url).query(&[
+                ("key", &self.key),
+                ("steamid", &self.profile_id),
+                ("appid", &appid.to_string()),
+            ]))
+            .await?;
+
+        // Failures such as missing stats come with an error status but a
+        // regular JSON body, so the body is parsed first.
+        let body = res.text().await?;
+
+        let parsed: PlayerStatsResponse = serde_json::from_str(&body)?;
+        let stats = parsed.playerstats;
+
+        match stats.error {
+            Some(error) if !stats.success && error != NO_STATS_ERROR => {
+                Err(SteamError::InvalidResponse(error))
+            }
+            _ => Ok(stats.achievements.unwrap_or_default()),
+        }
+    }
+
+    /// Fetches the App IDs on the configured profile's wishlist.
+    ///
+    /// Returns an empty list if the wishlist is private.
+    #[tracing::instrument(skip(self), err)]
+    async fn get_wishlist(&self) -> Result<Vec<u64>, SteamError> {
+        let url = "https://api.steampowered.com/IWishlistService/GetWishlist/v1";
+        let res = self
+            .retry_policy
+            .send(
+                self.client
+                    .get(url)
+                    .query(&[("key", &self.key), ("steamid", &self.profile_id)]),
+            )
+            .await?;
+
+        let body = res.error_for_status()?.text().await?;
+
+        let parsed: WishlistResponse = serde_json::from_str(&body)?;
+
+        Ok(parsed
+            .response
+            .items
+            .unwrap_or_default()
+            .into_iter()
+            .map(|item| item.appid)
+            .collect())
+    }
+}
+
+impl SteamApiClient {
+    /// Checks that the API key and profile ID are usable.
+    ///
+    /// Calls `GetOwnedGames` and inspects the response: a `401`/`403` status
+    /// means the key was rejected, and a response without a `game_count`
+    /// means the profile does not exist or its game details are private.
+    pub async fn check_credentials(&self) -> Result<(), SteamError> {
+        let res = self
+            .retry_policy
+            .send(self.owned_games_request(&self.profile_id))
+            .await?;
+
+        if matches!(
+            res.status(),
+            StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN
+        ) {
+            return Err(SteamError::Unauthorized);
+        }
+
+        let body = res.error_for_status()?.text().await?;
+
+        let parsed: serde_json::Value = serde_json::from_str(&body)?;
+
+        if parsed["response"].get("game_count").is_none() {
+            return Err(SteamError::ProfileUnavailable(self.profile_id.clone()));
+        }
+
+        Ok(())
+    }
+
+    /// Builds the `IPlayerService/GetOwnedGames` request for a profile.
+    fn owned_games_request(&self, steam_id: &str) -> RequestBuilder {
+        let url = "https://api.steampowered.com/IPlayerService/GetOwnedGames/v0001";
+        self.client.get(url).query(&[
+            ("key", &self.key),
+            ("steamid", &steam_id.to_string()),
+            ("include_appinfo", &"1".to_string()),
+            ("format", &"json".to_string()),
+        ])
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod mock {
+    use std::sync::{Arc, Mutex};
+
+    use async_trait::async_trait;
+
+    use super::*;
+
+    /// A [`SteamApi`] serving a library set by the test, without network
+    /// access. Clones share the library, so it can be changed after the mock
+    /// is managed by the app.
+    #[derive(Clone, Default)]
+    pub struct MockSteamApi {
+        library: Arc<Mutex<Vec<(u64, String)>>>,
+    }
+
+    impl MockSteamApi {
+        /// Replaces the owned games with the given App IDs and names.
+        pub fn set_library(&self, games: &[(u64, &str)]) {
+            *self.library.lock().unwrap() = games
+                .iter()
+                .map(|(appid, name)| (*appid, name.to_string()))
+                .collect();
+        }
+
+        fn games(&self) -> Vec<SteamGame> {
+            self.library
+                .lock()
+                .unwrap()
+                .iter()
+                .map(|(appid, name)| SteamGame {
+                    appid: *appid,
+                    name: name.clone(),
+                    playtime_2weeks: None,
+                    playtime_forever: Some(*appid),
+                    rtime_last_played: None,
+                    img_icon_url: None,
+                    img_logo_url: None,
+                })
+                .collect()
+        }
+    }
+
+    #[async_trait]
+    impl SteamApi for MockSteamApi {
+        async fn get_games(&self) -> Result<Vec<SteamGame>, SteamError> {
+            Ok(self.games())
+        }
+
+        async fn get_owned_games(
+            &self,
+            _steam_id: &str,
+        ) -> Result<Option<Vec<SteamGame>>, SteamError> {
+            Ok(Some(self.games()))
+        }
+
+        async fn get_friends(&self) -> Result<Vec<Friend>, SteamError> {
+            Ok(Vec::new())
+        }
+
+        async fn get_profile_summary(&self) -> Result<ProfileSummary, SteamError> {
+            Err(SteamError::ProfileUnavailable("mock".to_string()))
+        }
+
+        async fn get_player_summaries(
+            &self,
+            _steam_ids: &[String],
+        ) -> Result<Vec<PlayerSummary>, SteamError> {
+            Ok(Vec::new())
+        }
+
+        async fn get_game_news(
+            &self,
+            _appid: u64,
+            _count: u32,
+        ) -> Result<Vec<NewsItem>, SteamError> {
+            Ok(Vec::new())
+        }
+
+        async fn get_game_achievements(
+            &self,
+            _appid: u64,
+        ) -> Result<Vec<SchemaAchievement>, SteamError> {
+            Ok(Vec::new())
+        }
+
+        async fn get_player_achievements(
+            &self,
+            _appid: u64,
+        ) -> Result<Vec<PlayerAchievement>, SteamError> {
+            Ok(Vec::new())
+        }
+
+        async fn get_wishlist(&self) -> Result<Vec<u64>, SteamError> {
+            Ok(Vec::new())
+        }
+    }
 }
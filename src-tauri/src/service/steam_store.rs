@@ -0,0 +1,189 @@
+//! Steam storefront client.
+//!
+//! Queries the public `store.steampowered.com/api/appdetails` endpoint, which
+//! needs no API key, for store page metadata. Used as an alternative source
+//! for games IGDB has no match for.
+
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use serde::Deserialize;
+use tauri_plugin_http::reqwest::Client;
+
+use super::steam::SteamError;
+
+/// Store language used when none is configured.
+pub const DEFAULT_LANGUAGE: &str = "english";
+
+/// A game's Metacritic score, as shown on its store page.
+#[derive(Deserialize)]
+pub struct Metacritic {
+    pub score: i64,
+}
+
+/// A store category, such as co-op or controller support.
+#[derive(Deserialize)]
+pub struct StoreCategory {
+    /// Steam category ID, stable across languages.
+    pub id: i64,
+    /// Localized category name.
+    pub description: String,
+}
+
+/// A store genre.
+#[derive(Deserialize)]
+pub struct StoreGenre {
+    /// Localized genre name.
+    pub description: String,
+}
+
+/// The game a DLC or soundtrack belongs to.
+#[derive(Deserialize)]
+pub struct FullGame {
+    /// Steam App ID of the game.
+    pub appid: String,
+}
+
+/// Store page metadata of an app.
+#[derive(Deserialize)]
+pub struct AppDetails {
+    /// App type: `game`, `dlc`, `music` for soundtracks, ...
+    #[serde(rename = "type")]
+    pub app_type: Option<String>,
+    pub name: String,
+    /// Localized plain-text description.
+    pub short_description: Option<String>,
+    /// URL of the store page header image.
+    pub header_image: Option<String>,
+    pub metacritic: Option<Metacritic>,
+    pub categories: Option<Vec<StoreCategory>>,
+    pub genres: Option<Vec<StoreGenre>>,
+    /// App IDs of the game's DLC.
+    pub dlc: Option<Vec<u64>>,
+    /// Game the app belongs to, for DLC and soundtracks.
+    pub fullgame: Option<FullGame>,
+}
+
+impl AppDetails {
+    /// Returns the Steam App ID of the game this app is the soundtrack of,
+    /// or `None` if it is not a soundtrack.
+    pub fn soundtrack_of(&self) -> Option<&str> {
+        if self.app_type.as_deref() != Some("music") {
+            return None;
+        }
+
+        self.fullgame.as_ref().map(|game| game.appid.as_str())
+    }
+
+    /// Returns the Steam App ID of the game this app is a DLC of, or `None`
+    /// if it is not a DLC.
+    pub fn dlc_of(&self) -> Option<&str> {
+        if self.app_type.as_deref() != Some("dlc") {
+            return None;
+        }
+
+        self.fullgame.as_ref().map(|game| game.appid.as_str())
+    }
+}
+
+/// A single entry of the `appdetails` response. `data` is missing when
+/// `success` is `false`, e.g. for apps removed from the store.
+#[derive(Deserialize)]
+struct AppDetailsResult {
+    success: bool,
+    data: Option<AppDetails>,
+}
+
+/// Steam storefront requests made by the commands.
+///
+/// Implemented by [`SteamStoreClient`]; commands get it from the Tauri state
+/// as a `Box<dyn SteamStoreApi>`, so another implementation can stand in for
+/// it.
+#[async_trait]
+pub trait SteamStoreApi: Send + Sync {
+    /// Fetches the store page metadata of an app, localized in `language`
+    /// (e.g. `english`, `french`). Returns `None` if the app has no store
+    /// page.
+    async fn get_app_details(
+        &self,
+        appid: u64,
+        language: &str,
+    ) -> Result<Option<AppDetails>, SteamError>;
+}
+
+/// Client for the Steam storefront API.
+pub struct SteamStoreClient {
+    client: Client,
+}
+
+impl SteamStoreClient {
+    /// Creates a new storefront client.
+    pub fn new() -> Self {
+        SteamStoreClient {
+            client: Client::new(),
+        }
+    }
+}
+
+#[async_trait]
+impl SteamStoreApi for SteamStoreClient {
+    /// Fetches the store page metadata of an app, localized in `language`
+    /// (e.g. `english`, `french`).
+    ///
+    /// Returns `None` if the app has no store page.
+    ///
+    /// # Errors
+    ///
+    /// Fails on network errors and when the storefront rate-limits requests.
+    async fn get_app_details(
+        &self,
+        appid: u64,
+        language: &str,
+    ) -> Result<Option<AppDetails>, SteamError> {
+        let url = "https://store.steampowered.com/api/appdetails";
+        let res = self
+            .client
+            .get(url)
+            .query(&[("appids", appid.to_string()), ("l", language.to_string())])
+            .send()
+            .await?;
+
+        let body = res.error_for_status()?.text().await?;
+
+        let mut parsed: HashMap<String, AppDetailsResult> = serde_json::from_str(&body)?;
+
+        Ok(parsed
+            .remove(&appid.to_string())
+            .filter(|result| result.success)
+            .and_then(|result| result.data))
+    }
+}
+
+impl Default for SteamStoreClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod mock {
+    use async_trait::async_trait;
+
+    use super::*;
+
+    /// A [`SteamStoreApi`] for which no app has a store page, without
+    /// network access.
+    #[derive(Default)]
+    pub struct MockSteamStoreApi;
+
+    #[async_trait]
+    impl SteamStoreApi for MockSteamStoreApi {
+        async fn get_app_details(
+            &self,
+            _appid: u64,
+            _language: &str,
+        ) -> Result<Option<AppDetails>, SteamError> {
+            Ok(None)
+        }
+    }
+}
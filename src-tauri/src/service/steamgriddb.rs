@@ -0,0 +1,129 @@
+//! SteamGridDB client.
+//!
+//! SteamGridDB hosts community-made artwork for games, often of better
+//! quality than the IGDB covers of smaller titles. Games are looked up by
+//! Steam App ID. The API requires a key, which each user creates in their
+//! SteamGridDB account preferences.
+
+use serde::Deserialize;
+use tauri::http::StatusCode;
+use tauri_plugin_http::reqwest::{self, Client};
+
+/// Base URL of the SteamGridDB API.
+const API_URL: &str = "https://www.steamgriddb.com/api/v2";
+
+/// Dimensions of the vertical grids requested, matching the cover ratio.
+const GRID_DIMENSIONS: &str = "600x900";
+
+/// Errors that can occur when querying SteamGridDB.
+#[derive(Debug, thiserror::Error)]
+pub enum SteamGridDbError {
+    /// An HTTP request to SteamGridDB failed.
+    #[error("http request failed: {0}")]
+    Request(#[from] reqwest::Error),
+
+    /// The response body could not be deserialized into the expected type.
+    #[error("unable to parse steamgriddb data: {0}")]
+    InvalidData(#[from] serde_json::Error),
+
+    /// SteamGridDB rejected the API key.
+    #[error("steamgriddb api key rejected")]
+    Unauthorized,
+}
+
+/// Kind of artwork hosted by SteamGridDB.
+#[derive(Debug, Clone, Copy)]
+pub enum SteamGridDbImageKind {
+    /// Vertical grid, shown in place of the cover.
+    Grid,
+    /// Wide hero banner, shown at the top of the detail page.
+    Hero,
+    /// Game logo with a transparent background.
+    Logo,
+}
+
+impl SteamGridDbImageKind {
+    /// Returns the API path segment listing images of this kind.
+    fn endpoint(&self) -> &'static str {
+        match self {
+            SteamGridDbImageKind::Grid => "grids",
+            SteamGridDbImageKind::Hero => "heroes",
+            SteamGridDbImageKind::Logo => "logos",
+        }
+    }
+
+    /// Returns the name of this kind, used in local file names.
+    pub fn key(&self) -> &'static str {
+        match self {
+            SteamGridDbImageKind::Grid => "grid",
+            SteamGridDbImageKind::Hero => "hero",
+            SteamGridDbImageKind::Logo => "logo",
+        }
+    }
+}
+
+/// An image of the image list response.
+#[derive(Deserialize)]
+struct SteamGridDbImage {
+    /// Full-size image URL.
+    url: String,
+}
+
+/// Top-level wrapper of the image list response.
+#[derive(Deserialize)]
+struct ImagesResponse {
+    data: Vec<SteamGridDbImage>,
+}
+
+/// Client for the SteamGridDB API.
+pub struct SteamGridDbClient {
+    api_key: String,
+    client: Client,
+}
+
+impl SteamGridDbClient {
+    /// Creates a new SteamGridDB client authenticating with `api_key`.
+    pub fn new(api_key: String) -> Self {
+        SteamGridDbClient {
+            api_key,
+            client: Client::new(),
+        }
+    }
+
+    /// Fetches the URL of the top-rated image of a kind for a Steam game.
+    ///
+    /// Returns `None` if SteamGridDB does not know the game or has no image
+    /// of that kind for it.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SteamGridDbError::Unauthorized`] if the API key is invalid.
+    pub async fn get_image_url(
+        &self,
+        appid: &str,
+        kind: SteamGridDbImageKind,
+    ) -> Result<Option<String>, SteamGridDbError> {
+        let url = format!("{}/{}/steam/{}", API_URL, kind.endpoint(), appid);
+        let mut request = self.client.get(url).bearer_auth(&self.api_key);
+
+        if let SteamGridDbImageKind::Grid = kind {
+            request = request.query(&[("dimensions", GRID_DIMENSIONS)]);
+        }
+
+        let res = request.send().await?;
+
+        match res.status() {
+            StatusCode::NOT_FOUND => return Ok(None),
+            StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => {
+                return Err(SteamGridDbError::Unauthorized)
+            }
+            _ => {}
+        }
+
+        let body = res.error_for_status()?.text().await?;
+
+        let parsed: ImagesResponse = serde_json::from_str(&body)?;
+
+        Ok(parsed.data.into_iter().next().map(|image| image.url))
+    }
+}
@@ -0,0 +1,107 @@
+//! Work done when the app exits.
+//!
+//! Background tasks (refreshes, downloads, session watchers) are dropped
+//! with the async runtime, so anything they would have written is settled
+//! here instead: a running refresh is cancelled and waited for, running
+//! sessions are closed, partial images are removed, and the write-ahead log
+//! is merged into the database file.
+
+use std::{
+    sync::atomic::{AtomicBool, Ordering},
+    time::Duration,
+};
+
+use tauri::{AppHandle, Manager};
+use tokio::sync::{Mutex, MutexGuard};
+
+use crate::{
+    assets::AssetManager,
+    db::{session::SessionRepository, DatabaseState},
+    discord::DiscordPresence,
+};
+
+/// How long the exit waits for a running refresh. A refresh still fetching
+/// from Steam or IGDB has written nothing yet and is dropped instead.
+const REFRESH_WAIT: Duration = Duration::from_secs(10);
+
+/// Tracks the running library refresh, so the app waits for it to commit or
+/// roll back before exiting.
+#[derive(Default)]
+pub struct RefreshTracker {
+    running: Mutex<()>,
+    cancelled: AtomicBool,
+}
+
+impl RefreshTracker {
+    /// Creates a `RefreshTracker` with no refresh running.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Marks a refresh as running until the returned guard is dropped. Waits
+    /// for the refresh already running, if any.
+    pub async fn start(&self) -> MutexGuard<'_, ()> {
+        self.running.lock().await
+    }
+
+    /// Returns `true` once the app is exiting. The refresh then stops before
+    /// replacing the library.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    /// Cancels the running refresh and waits for it to finish, up to
+    /// [`REFRESH_WAIT`]. Returns `false` if it is still running.
+    pub(crate) async fn cancel(&self) -> bool {
+        self.cancelled.store(true, Ordering::Relaxed);
+
+        tokio::time::timeout(REFRESH_WAIT, self.running.lock())
+            .await
+            .is_ok()
+    }
+}
+
+/// Settles in-flight work before the process exits.
+///
+/// Every step is best effort: state that was never managed, e.g. after a
+/// failed setup, is skipped, and failures are logged.
+pub async fn flush(app: &AppHandle) {
+    if let Some(asset_manager) = app.try_state::<AssetManager>() {
+        asset_manager.cancel();
+    }
+
+    // A cancelled refresh rolls back its transaction and moves the previous
+    // images back; one interrupted anyway never committed, so the database
+    // is left as it was
+    if let Some(refresh_tracker) = app.try_state::<RefreshTracker>() {
+        if !refresh_tracker.cancel().await {
+            tracing::warn!("the running refresh did not finish before exiting");
+        }
+    }
+
+    if let Some(asset_manager) = app.try_state::<AssetManager>() {
+        if let Err(e) = asset_manager.remove_partial_files().await {
+            tracing::warn!("failed to remove partial assets: {e}");
+        }
+    }
+
+    // Session watchers stop with the app: the sessions end now rather than
+    // staying open forever
+    if let Some(session_repository) = app.try_state::<SessionRepository>() {
+        match session_repository.end_open_sessions().await {
+            Ok(0) => {}
+            Ok(closed) => tracing::info!("closed {closed} running play sessions"),
+            Err(e) => tracing::warn!("failed to close play sessions: {e}"),
+        }
+    }
+
+    if let Some(discord_presence) = app.try_state::<DiscordPresence>() {
+        discord_presence.clear();
+    }
+
+    if let Some(db_state) = app.try_state::<DatabaseState>() {
+        if let Err(e) = db_state.close().await {
+            tracing::warn!("failed to checkpoint the database: {e}");
+        }
+    }
+}
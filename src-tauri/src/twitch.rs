@@ -1,19 +1,50 @@
-use std::ops::Deref;
+use std::{
+    ops::Deref,
+    time::{SystemTime, UNIX_EPOCH},
+};
 
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use tauri::http::StatusCode;
 use tauri_plugin_http::reqwest::{self, Client};
 
+use crate::secrets::{Secret, SecretStore};
+
+/// Seconds before expiry from which a token is refreshed instead of used, so
+/// it does not lapse mid-refresh.
+const TOKEN_REFRESH_MARGIN: i64 = 60 * 60;
+
 #[derive(Debug)]
 pub struct TwitchApiClient {
     client_id: String,
     client_secret: String,
-    access_token: Option<String>,
+    access_token: Option<TwitchToken>,
+    /// Where the token is persisted across launches, if anywhere.
+    token_store: Option<SecretStore>,
     client: Client,
 }
 
 #[derive(Deserialize)]
 pub struct TwitchAuthResponse {
     access_token: String,
+    /// Lifetime of the token, in seconds.
+    expires_in: i64,
+}
+
+/// A Twitch access token with its expiry, as persisted in the keychain.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TwitchToken {
+    /// Client ID the token was issued to.
+    client_id: String,
+    access_token: String,
+    /// Unix timestamp after which Twitch rejects the token.
+    expires_at: i64,
+}
+
+impl TwitchToken {
+    /// Returns `true` if the token expires within [`TOKEN_REFRESH_MARGIN`].
+    fn is_expiring(&self) -> bool {
+        self.expires_at - now() <= TOKEN_REFRESH_MARGIN
+    }
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -23,6 +54,9 @@ pub enum TwitchError {
 
     #[error("unable to parse data: {0}")]
     InvalidData(#[from] serde_json::Error),
+
+    #[error("twitch rejected credentials: {0}")]
+    Unauthorized(String),
 }
 
 impl TwitchApiClient {
@@ -31,14 +65,35 @@ impl TwitchApiClient {
             client_id,
             client_secret,
             access_token: None,
+            token_store: None,
             client: tauri_plugin_http::reqwest::Client::new(),
         }
     }
 
+    /// Persists tokens in `store`, and reuses the stored token if it was
+    /// issued to the same client and is still valid.
+    pub fn set_token_store(&mut self, store: SecretStore) {
+        let stored = store
+            .get(Secret::TwitchAccessToken)
+            .ok()
+            .flatten()
+            .and_then(|token| serde_json::from_str::<TwitchToken>(&token).ok());
+
+        if let Some(token) = stored {
+            if token.client_id == self.client_id && !token.is_expiring() {
+                self.access_token = Some(token);
+            }
+        }
+
+        self.token_store = Some(store);
+    }
+
     pub fn get_client_id(&self) -> &str {
         self.client_id.deref()
     }
 
+    /// Requests a new access token with the client credentials and persists
+    /// it if a token store is set.
     pub async fn refresh_access_token(&mut self) -> Result<&str, TwitchError> {
         let url = "https://id.twitch.tv/oauth2/token";
         let res = self
@@ -52,16 +107,50 @@ impl TwitchApiClient {
             .send()
             .await?;
 
-        let body = res.text().await?;
+        // Twitch answers 400 to unknown client IDs and 403 to wrong secrets;
+        // other errors, like 429 or 5xx, say nothing of the credentials
+        if matches!(
+            res.status(),
+            StatusCode::BAD_REQUEST | StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN
+        ) {
+            return Err(TwitchError::Unauthorized(res.text().await?));
+        }
+
+        let body = res.error_for_status()?.text().await?;
 
         let parsed: TwitchAuthResponse = serde_json::from_str(&body)?;
 
-        self.access_token = Some(parsed.access_token);
+        let token = TwitchToken {
+            client_id: self.client_id.clone(),
+            access_token: parsed.access_token,
+            expires_at: now() + parsed.expires_in,
+        };
 
-        Ok(self.access_token.as_deref().unwrap())
+        if let Some(store) = &self.token_store {
+            // Best effort: without it, the next launch requests a new token
+            if let Ok(token) = serde_json::to_string(&token) {
+                let _ = store.set(Secret::TwitchAccessToken, &token);
+            }
+        }
+
+        let token = self.access_token.insert(token);
+
+        Ok(&token.access_token)
     }
 
+    /// Returns the current access token, or `None` if there is none or it
+    /// is about to expire and must be refreshed.
     pub fn get_access_token(&self) -> Option<&str> {
-        self.access_token.as_deref()
+        self.access_token
+            .as_ref()
+            .filter(|token| !token.is_expiring())
+            .map(|token| token.access_token.as_str())
     }
 }
+
+/// Returns the current Unix timestamp.
+fn now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |now| now.as_secs() as i64)
+}
@@ -0,0 +1,90 @@
+//! Running Windows games through Wine or Proton.
+//!
+//! Custom (non-Steam) Windows games are launched with a per-game runtime and
+//! prefix. The runtime is either a `wine` binary or a Proton `proton`
+//! script, detected from its file name.
+
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use tokio::process::{Child, Command};
+
+/// Errors that can occur while using a Wine or Proton runtime.
+#[derive(Debug, thiserror::Error)]
+pub enum WineError {
+    /// The runtime could not be started or the prefix directory created.
+    #[error("unable to run wine: {0}")]
+    Process(#[from] std::io::Error),
+
+    /// Prefix initialization exited with a failure status.
+    #[error("prefix creation failed: {0}")]
+    PrefixCreation(String),
+
+    /// The prefix folder could not be opened in the file manager.
+    #[error("unable to open prefix: {0}")]
+    OpenPrefix(String),
+}
+
+/// Wine configuration of a custom game.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct WineConfig {
+    /// Path to a `wine` binary or a Proton `proton` script.
+    pub runtime: String,
+    /// Path to the prefix directory.
+    pub prefix: String,
+}
+
+impl WineConfig {
+    /// Returns `true` if the runtime is a Proton script.
+    fn is_proton(&self) -> bool {
+        Path::new(&self.runtime)
+            .file_name()
+            .is_some_and(|name| name == "proton")
+    }
+
+    /// Builds a command running `program` with `args` inside the prefix.
+    ///
+    /// Proton expects its compatibility data directory rather than the
+    /// prefix itself; it keeps the actual prefix in a `pfx` subdirectory.
+    fn command(&self, program: &str, args: &[&str]) -> Command {
+        let mut command = Command::new(&self.runtime);
+
+        if self.is_proton() {
+            command
+                .arg("run")
+                .env("STEAM_COMPAT_DATA_PATH", &self.prefix)
+                .env("STEAM_COMPAT_CLIENT_INSTALL_PATH", "");
+        } else {
+            command.env("WINEPREFIX", &self.prefix);
+        }
+
+        command.arg(program).args(args);
+        command
+    }
+
+    /// Creates and initializes the prefix, waiting for Wine to finish.
+    pub async fn create_prefix(&self) -> Result<(), WineError> {
+        tokio::fs::create_dir_all(&self.prefix).await?;
+
+        let status = self.command("wineboot", &["--init"]).status().await?;
+
+        if !status.success() {
+            return Err(WineError::PrefixCreation(status.to_string()));
+        }
+
+        Ok(())
+    }
+
+    /// Starts `executable` inside the prefix and returns the running
+    /// process. The working directory is the executable's directory, as
+    /// many Windows games expect.
+    pub fn launch(&self, executable: &str) -> Result<Child, WineError> {
+        let mut command = self.command(executable, &[]);
+
+        if let Some(dir) = Path::new(executable).parent() {
+            command.current_dir(dir);
+        }
+
+        Ok(command.spawn()?)
+    }
+}